@@ -0,0 +1,639 @@
+use serenity::all::{
+    Colour,
+    CommandInteraction,
+    CommandOptionType,
+    CreateCommand,
+    CreateCommandOption,
+    CreateEmbed,
+    CreateEmbedFooter,
+    ResolvedOption,
+    ResolvedValue,
+    RoleId,
+    UserId,
+};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tracing::{info, warn};
+
+use crate::i18n;
+use crate::permissions::Permission;
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::settings::{Settings, SETTING_KEYS};
+use crate::{metrics, solver, stats};
+use crate::Bot;
+
+// /admin 配下のサブコマンドと必要な権限の対応表
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminCommand {
+    Reload,
+    ImportWords,
+    Jobs,
+    Dashboard,
+    ConfigShow,
+    ConfigSet,
+    RewardsAdd,
+    RewardsRemove,
+    RewardsList,
+    RewardsPreview,
+    RotateKey,
+    CompareStrategies,
+    SelfCheck,
+}
+
+// 戦略の比較で解かせる答えの数（既定値と上限）
+const DEFAULT_STRATEGY_SAMPLES: i64 = 100;
+const MAX_STRATEGY_SAMPLES: i64 = 500;
+
+// 整合性の確認で調べる単語の組の上限
+const MAX_CONSISTENCY_SAMPLES: i64 = 100_000;
+
+// サブコマンドグループとその説明
+const GROUPS: [(&str, &str); 2] = [
+    ("config", "実行時設定の表示・変更"),
+    ("rewards", "成績に応じたロール報酬"),
+];
+
+impl AdminCommand {
+    pub const ALL: [AdminCommand; 13] = [
+        AdminCommand::Reload,
+        AdminCommand::ImportWords,
+        AdminCommand::Jobs,
+        AdminCommand::Dashboard,
+        AdminCommand::ConfigShow,
+        AdminCommand::ConfigSet,
+        AdminCommand::RewardsAdd,
+        AdminCommand::RewardsRemove,
+        AdminCommand::RewardsList,
+        AdminCommand::RewardsPreview,
+        AdminCommand::RotateKey,
+        AdminCommand::CompareStrategies,
+        AdminCommand::SelfCheck,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AdminCommand::Reload => "reload",
+            AdminCommand::ImportWords => "import-words",
+            AdminCommand::Jobs => "jobs",
+            AdminCommand::Dashboard => "dashboard",
+            AdminCommand::ConfigShow => "show",
+            AdminCommand::ConfigSet => "set",
+            AdminCommand::RewardsAdd => "add",
+            AdminCommand::RewardsRemove => "remove",
+            AdminCommand::RewardsList => "list",
+            AdminCommand::RewardsPreview => "preview",
+            AdminCommand::RotateKey => "rotate-key",
+            AdminCommand::CompareStrategies => "compare-strategies",
+            AdminCommand::SelfCheck => "self-check",
+        }
+    }
+
+    // サブコマンドグループ（/admin config set など）
+    pub fn group(&self) -> Option<&'static str> {
+        match self {
+            AdminCommand::ConfigShow | AdminCommand::ConfigSet => Some("config"),
+            AdminCommand::RewardsAdd
+            | AdminCommand::RewardsRemove
+            | AdminCommand::RewardsList
+            | AdminCommand::RewardsPreview => Some("rewards"),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(group: Option<&str>, name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.group() == group && c.name() == name)
+    }
+
+    fn path(&self) -> String {
+        match self.group() {
+            Some(group) => format!("{} {}", group, self.name()),
+            None => self.name().to_string(),
+        }
+    }
+
+    pub fn permission(&self) -> Permission {
+        match self {
+            AdminCommand::Reload => Permission::Moderator,
+            AdminCommand::ImportWords => Permission::GuildAdmin,
+            AdminCommand::Jobs => Permission::Moderator,
+            AdminCommand::Dashboard => Permission::Moderator,
+            AdminCommand::ConfigShow => Permission::Moderator,
+            AdminCommand::ConfigSet => Permission::GuildAdmin,
+            AdminCommand::RewardsAdd => Permission::GuildAdmin,
+            AdminCommand::RewardsRemove => Permission::GuildAdmin,
+            AdminCommand::RewardsList => Permission::Moderator,
+            AdminCommand::RewardsPreview => Permission::Moderator,
+            AdminCommand::RotateKey => Permission::BotOwner,
+            AdminCommand::CompareStrategies => Permission::GuildAdmin,
+            AdminCommand::SelfCheck => Permission::Moderator,
+        }
+    }
+
+    fn option(&self) -> CreateCommandOption {
+        match self {
+            AdminCommand::Reload => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "単語・絵文字キャッシュを再読み込み",
+            ),
+            AdminCommand::ImportWords => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "単語をデータベースに追加",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "words",
+                    "追加する5文字の単語（カンマまたは空白区切り）",
+                )
+                .required(true),
+            ),
+            AdminCommand::Jobs => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "定期ジョブの実行状況を表示",
+            ),
+            AdminCommand::Dashboard => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "直近7日間の利用状況を表示",
+            ),
+            AdminCommand::ConfigShow => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "現在の設定を表示",
+            ),
+            AdminCommand::ConfigSet => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "設定を変更",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "key",
+                    format!("設定キー（{}）", SETTING_KEYS.join(", ")),
+                )
+                .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "value", "新しい値")
+                    .required(true),
+            ),
+            AdminCommand::RewardsAdd => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "節目に達したユーザーに付与するロールを追加",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "kind", "節目の種類")
+                    .add_string_choice("連続正解日数", "streak")
+                    .add_string_choice("正解数", "wins")
+                    .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "threshold", "必要な日数・回数")
+                    .min_int_value(1)
+                    .required(true),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::Role, "role", "付与するロール").required(true),
+            ),
+            AdminCommand::RewardsRemove => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "ロール報酬を削除",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::Role, "role", "削除するロール").required(true),
+            ),
+            AdminCommand::RewardsList => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "ロール報酬の一覧",
+            ),
+            AdminCommand::RewardsPreview => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "ユーザーに付け外しされるロールを確認（変更はしない）",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::User, "user", "確認するユーザー").required(true),
+            ),
+            AdminCommand::RotateKey => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "Supabaseのキーを再起動せずに差し替える",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "key", "新しいキー").required(true),
+            ),
+            AdminCommand::CompareStrategies => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "推測の選び方（entropy / minimax / heuristic）を同じ答えで解かせて比較",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "samples",
+                    format!("解かせる答えの数（既定: {}）", DEFAULT_STRATEGY_SAMPLES),
+                )
+                .min_int_value(1)
+                .max_int_value(MAX_STRATEGY_SAMPLES as u64),
+            ),
+            AdminCommand::SelfCheck => CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                self.name(),
+                "候補の絞り込みと結果の計算が食い違っていないか確認",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "samples",
+                    format!("確認する単語の組の数（既定: {}）", solver::DEFAULT_CONSISTENCY_SAMPLES),
+                )
+                .min_int_value(1)
+                .max_int_value(MAX_CONSISTENCY_SAMPLES as u64),
+            ),
+        }
+    }
+}
+
+pub fn create_command() -> CreateCommand {
+    let mut command = i18n::command("admin").dm_permission(false);
+
+    for sub in AdminCommand::ALL.iter().filter(|c| c.group().is_none()) {
+        command = command.add_option(sub.option());
+    }
+
+    for (name, description) in GROUPS {
+        let group = AdminCommand::ALL.iter()
+            .filter(|c| c.group() == Some(name))
+            .fold(
+                CreateCommandOption::new(CommandOptionType::SubCommandGroup, name, description),
+                |group, sub| group.add_sub_option(sub.option()),
+            );
+        command = command.add_option(group);
+    }
+
+    command
+}
+
+fn string_option<'a>(options: &[ResolvedOption<'a>], name: &str) -> &'a str {
+    options.iter()
+        .find_map(|o| match &o.value {
+            ResolvedValue::String(s) if o.name == name => Some(*s),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn integer_option(options: &[ResolvedOption<'_>], name: &str) -> i64 {
+    options.iter()
+        .find_map(|o| match o.value {
+            ResolvedValue::Integer(n) if o.name == name => Some(n),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn role_option(options: &[ResolvedOption<'_>], name: &str) -> Option<RoleId> {
+    options.iter().find_map(|o| match o.value {
+        ResolvedValue::Role(role) if o.name == name => Some(role.id),
+        _ => None,
+    })
+}
+
+fn user_option(options: &[ResolvedOption<'_>], name: &str) -> Option<UserId> {
+    options.iter().find_map(|o| match o.value {
+        ResolvedValue::User(user, _) if o.name == name => Some(user.id),
+        _ => None,
+    })
+}
+
+// 入力文字列から5文字の英単語を抽出（重複・不正な単語は除外）
+fn parse_word_list(input: &str) -> (Vec<String>, Vec<String>) {
+    let mut valid = Vec::new();
+    let mut invalid = Vec::new();
+
+    for token in input.split(|c: char| c == ',' || c.is_whitespace()).filter(|t| !t.is_empty()) {
+        let word = token.to_lowercase();
+        if word.len() == 5 && word.chars().all(|c| c.is_ascii_alphabetic()) {
+            if !valid.contains(&word) {
+                valid.push(word);
+            }
+        } else {
+            invalid.push(token.to_string());
+        }
+    }
+
+    (valid, invalid)
+}
+
+impl Bot {
+    pub(crate) async fn handle_admin_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let options = command.data.options();
+        let (group, name, sub_options) = match options.first() {
+            Some(ResolvedOption { name, value: ResolvedValue::SubCommand(sub_options), .. }) => (None, *name, sub_options),
+            Some(ResolvedOption { name: group, value: ResolvedValue::SubCommandGroup(subs), .. }) => match subs.first() {
+                Some(ResolvedOption { name, value: ResolvedValue::SubCommand(sub_options), .. }) => {
+                    (Some(*group), *name, sub_options)
+                }
+                _ => return,
+            },
+            _ => return,
+        };
+        let Some(admin_command) = AdminCommand::from_path(group, name) else {
+            return;
+        };
+        // /admin はサーバー内でのみ使える
+        let Some(guild_id) = command.guild_id else {
+            return;
+        };
+        let http = ctx.http();
+
+        let mut responder = self.responder(ctx, command, ReplyMode::Ephemeral);
+        if !self.permissions.require(command, &mut responder, admin_command.permission()).await {
+            return;
+        }
+
+        // キャッシュ再読み込みなどは3秒を超えることがあるため、必要に応じて自動で保留される
+        responder
+            .run(async {
+                let content = match admin_command {
+                    AdminCommand::Reload => self.admin_reload().await,
+                    AdminCommand::ImportWords => self.admin_import_words(string_option(sub_options, "words")).await,
+                    AdminCommand::Jobs => self.admin_jobs().await,
+                    AdminCommand::Dashboard => self.admin_dashboard(),
+                    AdminCommand::ConfigShow => format!("⚙️ **現在の設定**\n```\n{}```", self.settings().describe()),
+                    AdminCommand::ConfigSet => {
+                        self.admin_config_set(string_option(sub_options, "key"), string_option(sub_options, "value")).await
+                    }
+                    AdminCommand::RewardsAdd => {
+                        let kind = string_option(sub_options, "kind");
+                        let threshold = integer_option(sub_options, "threshold");
+                        self.admin_rewards_add(http, guild_id, kind, threshold, role_option(sub_options, "role")).await
+                    }
+                    AdminCommand::RewardsRemove => self.admin_rewards_remove(guild_id, role_option(sub_options, "role")).await,
+                    AdminCommand::RewardsList => self.admin_rewards_list(guild_id),
+                    AdminCommand::RewardsPreview => {
+                        self.admin_rewards_preview(http, guild_id, user_option(sub_options, "user")).await
+                    }
+                    AdminCommand::RotateKey => self.admin_rotate_key(string_option(sub_options, "key")).await,
+                    // 結果はEmbedで返す
+                    AdminCommand::CompareStrategies => {
+                        let samples = match integer_option(sub_options, "samples") {
+                            0 => DEFAULT_STRATEGY_SAMPLES,
+                            n => n.clamp(1, MAX_STRATEGY_SAMPLES),
+                        };
+                        info!("Admin command /admin {} by {} ({} samples)", admin_command.path(), command.user.id, samples);
+                        return self.admin_compare_strategies(samples as usize).await;
+                    }
+                    AdminCommand::SelfCheck => {
+                        let samples = match integer_option(sub_options, "samples") {
+                            0 => solver::DEFAULT_CONSISTENCY_SAMPLES,
+                            n => n.clamp(1, MAX_CONSISTENCY_SAMPLES) as usize,
+                        };
+                        self.admin_self_check(samples).await
+                    }
+                };
+
+                info!("Admin command /admin {} by {}: {}", admin_command.path(), command.user.id, content);
+                Reply::content(content)
+            })
+            .await;
+    }
+
+    async fn admin_reload(&self) -> String {
+        let emoji_result = self.load_emoji_cache().await;
+        let word_result = self.load_word_cache().await;
+
+        let word_count = self.word_cache.read().await.len();
+        let emoji_count = self.emoji_cache.read().await.len();
+
+        match (word_result, emoji_result) {
+            (Ok(()), Ok(())) => {
+                self.save_cache_snapshot().await;
+                format!("✅ 再読み込みしました（単語: {}件、絵文字: {}件）", word_count, emoji_count)
+            }
+            (word_result, emoji_result) => {
+                let mut message = String::from("⚠️ 再読み込み中にエラーが発生しました\n");
+                if let Err(e) = word_result {
+                    message.push_str(&format!("単語: {}\n", e));
+                }
+                if let Err(e) = emoji_result {
+                    message.push_str(&format!("絵文字: {}\n", e));
+                }
+                message
+            }
+        }
+    }
+
+    async fn admin_import_words(&self, input: &str) -> String {
+        let (words, invalid) = parse_word_list(input);
+
+        if words.is_empty() {
+            return "追加できる5文字の単語がありません。".to_string();
+        }
+
+        let mut message = match self.storage.insert_words(&words).await {
+            Ok(()) => {
+                if let Err(e) = self.load_word_cache().await {
+                    warn!("Failed to reload word cache after import: {:?}", e);
+                }
+                format!("✅ {}語を追加しました", words.len())
+            }
+            Err(e) => {
+                self.reporter.report("Failed to import words", &e).await;
+                format!("❌ 単語の追加に失敗しました: {}", e)
+            }
+        };
+
+        if !invalid.is_empty() {
+            message.push_str(&format!("\n無視した入力: {}", invalid.join(", ")));
+        }
+
+        message
+    }
+
+    // 新しいキーで設定を読めることを確かめてから切り替える（読めなければ元のキーに戻す）
+    async fn admin_rotate_key(&self, key: &str) -> String {
+        let Some(supabase) = &self.supabase else {
+            return "❌ Supabaseを使っていないため、キーを差し替えられません。".to_string();
+        };
+        let previous = match supabase.rotate_key(key) {
+            Ok(previous) => previous,
+            Err(e) => return format!("❌ {}", e),
+        };
+
+        match self.storage.load_settings().await {
+            Ok(_) => {
+                info!("Rotated Supabase key to {}", supabase.key_hint());
+                format!("✅ Supabaseのキーを差し替えました（{}）。`SUPABASE_KEY` シークレットも更新してください", supabase.key_hint())
+            }
+            Err(e) => {
+                supabase.restore_key(previous);
+                warn!("New Supabase key was rejected: {:?}", e);
+                format!("❌ 新しいキーでの接続に失敗したため、元のキーに戻しました: {}", e)
+            }
+        }
+    }
+
+    // 戦略ごとに別のスレッドで同時に解かせる
+    async fn admin_compare_strategies(&self, samples: usize) -> Reply {
+        let arena = self.word_cache.read().await.words_of_length(solver::WORD_LENGTH).cloned();
+        let Some(arena) = arena.filter(|arena| !arena.is_empty()) else {
+            return Reply::content("❌ 単語キャッシュに5文字の単語がないため比較できません。");
+        };
+        let answers = Arc::new(solver::sample_answers(&arena, samples));
+
+        let tasks = solver::Strategy::ALL.map(|strategy| {
+            let arena = Arc::clone(&arena);
+            let answers = Arc::clone(&answers);
+            tokio::task::spawn_blocking(move || solver::evaluate_strategy(strategy, &arena, &answers))
+        });
+        let mut reports = Vec::new();
+        for task in tasks {
+            match task.await {
+                Ok(report) => reports.push(report),
+                Err(e) => warn!("Strategy comparison task failed: {:?}", e),
+            }
+        }
+        info!("Compared strategies over {} answers: {:?}", answers.len(), reports);
+
+        let best = reports.iter()
+            .map(|r| r.average_guesses)
+            .fold(f64::INFINITY, f64::min);
+        let embed = reports.iter().fold(
+            CreateEmbed::new()
+                .title("🧪 戦略の比較")
+                .description(format!(
+                    "{}語の単語リストから等間隔に選んだ{}個の答えを、それぞれの戦略で最後まで解かせた結果",
+                    arena.len(),
+                    answers.len()
+                ))
+                .color(Colour::DARK_GREEN)
+                .footer(CreateEmbedFooter::new("最初の推測は答えによらないため、戦略ごとに1回だけ計算しています")),
+            |embed, report| {
+                let medal = if report.average_guesses == best { "🏆 " } else { "" };
+                embed.field(
+                    format!("{}{}", medal, report.strategy.name()),
+                    format!(
+                        "平均 **{:.2}** 回 / 最悪 {} 回
+{}回で解けなかった答え: {}
+最初の推測: `{}`
+実行時間: {:.1} 秒",
+                        report.average_guesses,
+                        report.worst_guesses,
+                        solver::MAX_GUESSES,
+                        report.failures,
+                        report.opener.as_deref().unwrap_or("-"),
+                        report.elapsed.as_secs_f64(),
+                    ),
+                    true,
+                )
+            },
+        );
+        Reply::embed(embed)
+    }
+
+    async fn admin_self_check(&self, samples: usize) -> String {
+        let report = match self.check_consistency(samples).await {
+            Ok(Some(report)) => report,
+            Ok(None) => return "❌ 単語キャッシュに5文字の単語がないため確認できません。".to_string(),
+            Err(e) => return format!("❌ 確認に失敗しました: {}", e),
+        };
+
+        if report.is_consistent() {
+            return format!("✅ {}組を確認し、食い違いはありませんでした（シード: `{}`）", report.checked, report.seed);
+        }
+        let mut message = format!(
+            "⚠️ {}組中{}組で食い違いがありました（シード: `{}`）\n",
+            report.checked,
+            report.divergence_count,
+            report.seed
+        );
+        for d in &report.divergences {
+            message.push_str(&format!(
+                "`{}` → 答え `{}`（{}）: 候補 `{}` は絞り込み {} / HashMap版 {} / 同じ結果 {}\n",
+                d.guess,
+                d.answer,
+                d.pattern,
+                d.candidate,
+                if d.matches { "残る" } else { "除外" },
+                if d.matches_by_map { "残る" } else { "除外" },
+                if d.reproduces { "なる" } else { "ならない" },
+            ));
+        }
+        message
+    }
+
+    async fn admin_config_set(&self, key: &str, value: &str) -> String {
+        // 値を確かめてから、最新の設定に対して書き換える
+        if let Err(message) = Settings::clone(&self.settings()).set(key, value) {
+            return format!("❌ {}", message);
+        }
+
+        match self.update_settings(|settings| settings.set(key, value).ok()).await {
+            Ok(_) => format!("✅ `{}` を `{}` に変更しました", key, value.trim()),
+            Err(e) => {
+                self.reporter.report("Failed to save settings", &e).await;
+                format!("❌ 設定の保存に失敗しました: {}", e)
+            }
+        }
+    }
+
+    fn admin_dashboard(&self) -> String {
+        let summary = self.metrics.summary(stats::today());
+
+        let mut message = format!("📈 **直近{}日間の利用状況**（起動後の記録のみ）\n", metrics::RETENTION_DAYS);
+        for day in &summary.days {
+            message.push_str(&format!(
+                "<t:{}:d> コマンド {}回 / プレイヤー {}人 / 最大同時セッション {} / ストレージ {}件（失敗 {}）\n",
+                day.day * 24 * 60 * 60,
+                day.commands,
+                day.players,
+                day.peak_sessions,
+                day.storage_calls,
+                day.storage_errors,
+            ));
+        }
+
+        message.push_str(&format!("\nプレイヤー（期間中）: {}人\n", summary.players));
+        message.push_str(&match summary.solver_latency_ms {
+            Some([p50, p90, p99]) => format!("単語提案の計算時間: p50 {} ms / p90 {} ms / p99 {} ms\n", p50, p90, p99),
+            None => "単語提案の計算時間: 記録なし\n".to_string(),
+        });
+        message.push_str(&match summary.storage_error_rate() {
+            Some(rate) => format!("ストレージのエラー率: {:.1}%\n", rate),
+            None => "ストレージのエラー率: 記録なし\n".to_string(),
+        });
+        message
+    }
+
+    async fn admin_jobs(&self) -> String {
+        let statuses = self.scheduler.statuses().await;
+        if statuses.is_empty() {
+            return "登録されているジョブはありません。".to_string();
+        }
+
+        let mut message = String::from("🗓️ **定期ジョブ**\n");
+        for (name, schedule, status) in statuses {
+            let last_run = match status.last_run.and_then(|t| t.duration_since(UNIX_EPOCH).ok()) {
+                Some(t) => format!("<t:{}:R>", t.as_secs()),
+                None => "未実行".to_string(),
+            };
+            message.push_str(&format!(
+                "**{}** ({:?}) - 最終実行: {} / 実行: {}回 / 失敗: {}回\n",
+                name, schedule, last_run, status.run_count, status.failure_count
+            ));
+            if let Some(error) = status.last_error {
+                message.push_str(&format!("  └ 直近のエラー: {}\n", error));
+            }
+        }
+
+        message
+    }
+}
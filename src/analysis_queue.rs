@@ -0,0 +1,179 @@
+// 分析（提案のスコア計算）の実行枠と順番待ち
+// 多くのユーザーが同時に確定すると重い計算がランタイムのスレッドを使い切るため、
+// 同時に計算する数を抑え、待っている分析は来た順に、ユーザーごとに1つずつ実行する
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::Notify;
+
+pub struct AnalysisQueue {
+    workers: usize,
+    state: Mutex<QueueState>,
+    // 実行枠が空いた、または順番待ちが変わった
+    changed: Notify,
+}
+
+#[derive(Default)]
+struct QueueState {
+    // 計算中のユーザー
+    running: Vec<u64>,
+    // 順番待ちのユーザーと、待っている分析の番号（ユーザーごとに1つまで）
+    waiting: VecDeque<(u64, u64)>,
+    next_ticket: u64,
+}
+
+// 計算が終わると実行枠を返す
+pub struct AnalysisSlot {
+    queue: Arc<AnalysisQueue>,
+    user: u64,
+}
+
+// 順番待ちをやめた（待っている間に取り消された）ときに列から外す
+struct Waiting<'a> {
+    queue: &'a AnalysisQueue,
+    ticket: u64,
+}
+
+// 既定で同時に計算する分析の数の上限
+const MAX_DEFAULT_WORKERS: usize = 4;
+
+// 計算はブロッキング用のスレッドプールで行うため、ランタイムのスレッドを残す必要はない（1コアでも応答は止まらない）
+// 1件の計算がコアの数だけスレッドを使うため、同時に計算する数はコアの数までとし、多くても MAX_DEFAULT_WORKERS 件にする
+pub fn default_workers() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get().min(MAX_DEFAULT_WORKERS))
+}
+
+impl AnalysisQueue {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            workers: workers.max(1),
+            state: Mutex::default(),
+            changed: Notify::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // 実行枠が空くまで待つ（待っている間は何番目かを queued に伝える）
+    // 同じユーザーの新しい分析が来た場合は、順番を譲って None を返す
+    pub async fn enter(self: &Arc<Self>, user: u64, mut queued: impl FnMut(usize)) -> Option<AnalysisSlot> {
+        let waiting = {
+            let mut state = self.lock();
+            let ticket = state.next_ticket;
+            state.next_ticket += 1;
+            // 待っている古い盤面の分析と置き換える（順番はそのまま引き継ぐ）
+            match state.waiting.iter_mut().find(|(waiting, _)| *waiting == user) {
+                Some(entry) => entry.1 = ticket,
+                None => state.waiting.push_back((user, ticket)),
+            }
+            Waiting { queue: self, ticket }
+        };
+        self.changed.notify_waiters();
+
+        let mut reported = None;
+        loop {
+            let changed = self.changed.notified();
+            let position = {
+                let mut state = self.lock();
+                let index = state.waiting.iter().position(|&(_, ticket)| ticket == waiting.ticket)?;
+                // 計算中のユーザーの分析は、前にいても枠を使えないので数えない
+                let ahead = state.waiting.iter()
+                    .take(index)
+                    .filter(|(waiting, _)| !state.running.contains(waiting))
+                    .count();
+                if ahead == 0 && state.running.len() < self.workers && !state.running.contains(&user) {
+                    state.waiting.remove(index);
+                    state.running.push(user);
+                    return Some(AnalysisSlot { queue: Arc::clone(self), user });
+                }
+                ahead + 1
+            };
+
+            if reported != Some(position) {
+                queued(position);
+                reported = Some(position);
+            }
+            changed.await;
+        }
+    }
+}
+
+impl Drop for AnalysisSlot {
+    fn drop(&mut self) {
+        let mut state = self.queue.lock();
+        if let Some(index) = state.running.iter().position(|&user| user == self.user) {
+            state.running.swap_remove(index);
+        }
+        drop(state);
+        self.queue.changed.notify_waiters();
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        // 実行枠を得た分析はすでに列から外れている
+        self.queue.lock().waiting.retain(|&(_, ticket)| ticket != self.ticket);
+        self.queue.changed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    #[tokio::test]
+    async fn waiting_users_run_in_order_as_slots_free_up() {
+        let queue = Arc::new(AnalysisQueue::new(1));
+        let first = queue.enter(1, |_| panic!("the first analysis should not wait")).await.unwrap();
+
+        let positions = Arc::new(Mutex::new(Vec::new()));
+        let spawn = |user: u64| {
+            let (queue, positions) = (Arc::clone(&queue), Arc::clone(&positions));
+            tokio::spawn(async move {
+                let slot = queue.enter(user, |position| positions.lock().unwrap().push((user, position))).await;
+                slot.is_some()
+            })
+        };
+        let second = spawn(2);
+        settle().await;
+        let third = spawn(3);
+        settle().await;
+        assert_eq!(*positions.lock().unwrap(), vec![(2, 1), (3, 2)]);
+
+        drop(first);
+        assert!(second.await.unwrap());
+        assert!(third.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn each_user_runs_one_analysis_and_newer_ones_replace_waiting_ones() {
+        let queue = Arc::new(AnalysisQueue::new(2));
+        let running = queue.enter(1, |_| {}).await.unwrap();
+
+        // 枠が空いていても、同じユーザーの分析は前の分析が終わるまで待つ
+        let older = tokio::spawn({
+            let queue = Arc::clone(&queue);
+            async move { queue.enter(1, |_| {}).await.is_some() }
+        });
+        settle().await;
+        let newer = tokio::spawn({
+            let queue = Arc::clone(&queue);
+            async move { queue.enter(1, |_| {}).await.is_some() }
+        });
+        assert!(!older.await.unwrap());
+
+        // ほかのユーザーは空いている枠で計算できる
+        let other = queue.enter(2, |_| {}).await;
+        assert!(other.is_some());
+
+        drop(running);
+        assert!(newer.await.unwrap());
+    }
+}
@@ -0,0 +1,213 @@
+// /analyze-file: 終わったゲームをまとめたJSONファイルを読み、ゲームごとに振り返って腕前と運を集計する
+// 読める形式（このボットのセッションや、ほかの記録ツールから書き出したもの）:
+//   [{"guesses": [...]}, ...] または {"games": [...]}。ゲームは推測の配列そのものでもよい
+//   推測は "CRANE BYXXG" / "CRANE:BYXXG" の文字列か、{"word": "CRANE", "results": [...] または "pattern": "BYXXG"}
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use serde_json::{json, Value};
+use serenity::all::{
+    Attachment,
+    CommandInteraction,
+    CommandOptionType,
+    CreateAttachment,
+    CreateCommand,
+    CreateCommandOption,
+    ResolvedValue,
+};
+use tracing::info;
+
+use crate::i18n::{self, Locale};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::solver::{self, GameAnalysis, LetterResult, WordleGuess};
+use crate::solve::parse_history;
+use crate::Bot;
+
+const MAX_FILE_BYTES: u32 = 1024 * 1024;
+const MAX_GAMES: usize = 500;
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("analyze-file").add_option(
+        CreateCommandOption::new(CommandOptionType::Attachment, "file", "終わったゲームをまとめたJSONファイル")
+            .required(true),
+    )
+}
+
+fn parse_result(value: &Value) -> Option<LetterResult> {
+    match value {
+        Value::String(s) => serde_json::from_value(value.clone()).ok()
+            .or_else(|| s.chars().next().filter(|_| s.chars().count() == 1).and_then(LetterResult::from_char)),
+        Value::Number(n) => n.as_u64().and_then(|n| char::from_digit(n as u32, 10)).and_then(LetterResult::from_char),
+        _ => None,
+    }
+}
+
+fn parse_guess(value: &Value) -> Option<WordleGuess> {
+    match value {
+        Value::String(entry) => parse_history(entry).ok().filter(|g| g.len() == 1)?.pop(),
+        Value::Object(guess) => {
+            let word = guess.get("word")?.as_str()?;
+            let pattern: String = match guess.get("results").or_else(|| guess.get("pattern"))? {
+                Value::String(pattern) => pattern.clone(),
+                Value::Array(results) => results.iter()
+                    .map(|r| parse_result(r).map(LetterResult::to_char))
+                    .collect::<Option<String>>()?,
+                _ => return None,
+            };
+            WordleGuess::parse(word, &pattern).filter(|g| solver::is_valid_word(&g.word))
+        }
+        _ => None,
+    }
+}
+
+// ゲームの一覧と、読めなかったゲームの数
+pub fn parse_games(value: &Value) -> (Vec<Vec<WordleGuess>>, usize) {
+    let games = match value {
+        Value::Object(root) => root.get("games").and_then(Value::as_array),
+        Value::Array(games) => Some(games),
+        _ => None,
+    };
+
+    let mut parsed = Vec::new();
+    let mut skipped = 0;
+    for game in games.into_iter().flatten() {
+        let guesses = match game {
+            Value::Object(game) => game.get("guesses").and_then(Value::as_array),
+            Value::Array(guesses) => Some(guesses),
+            _ => None,
+        };
+        match guesses.and_then(|g| g.iter().map(parse_guess).collect::<Option<Vec<_>>>()) {
+            Some(guesses) if !guesses.is_empty() => parsed.push(guesses),
+            _ => skipped += 1,
+        }
+    }
+    (parsed, skipped)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisSummary {
+    pub games: usize,
+    pub skipped: usize,
+    pub solved: usize,
+    pub average_guesses: Option<f64>,
+    // 推測の腕前（最も良い推測に対するエントロピーの割合の平均。0〜1）
+    pub average_skill: Option<f64>,
+    // 1ゲームあたりの運（期待より多く絞り込めたビット数の平均）
+    pub average_luck: Option<f64>,
+}
+
+pub fn summarize(analyses: &[GameAnalysis], skipped: usize) -> AnalysisSummary {
+    let solved: Vec<&GameAnalysis> = analyses.iter().filter(|a| a.solved).collect();
+    AnalysisSummary {
+        games: analyses.len(),
+        skipped,
+        solved: solved.len(),
+        average_guesses: solver::average(solved.iter().map(|a| a.guesses as f64)),
+        average_skill: solver::average(analyses.iter().filter_map(GameAnalysis::average_skill)),
+        average_luck: solver::average(analyses.iter().map(GameAnalysis::total_luck)),
+    }
+}
+
+fn report_json(summary: &AnalysisSummary, analyses: &[GameAnalysis]) -> Value {
+    let games: Vec<Value> = analyses.iter()
+        .map(|a| json!({
+            "guesses": a.guesses,
+            "solved": a.solved,
+            "skill": a.average_skill(),
+            "luck": a.total_luck(),
+            "steps": a.steps.iter().map(|s| json!({
+                "word": s.word,
+                "candidates_before": s.candidates_before,
+                "candidates_after": s.candidates_after,
+                "entropy": s.entropy,
+                "best_entropy": s.best_entropy,
+                "actual_bits": s.actual_bits,
+                "skill": s.skill(),
+                "luck": s.luck(),
+            })).collect::<Vec<_>>(),
+        }))
+        .collect();
+    json!({ "summary": summary, "games": games })
+}
+
+impl Bot {
+    pub(crate) async fn handle_analyze_file_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let file = command.data.options().into_iter().find_map(|o| match o.value {
+            ResolvedValue::Attachment(file) if o.name == "file" => Some(file),
+            _ => None,
+        });
+        let Some(file) = file else {
+            return;
+        };
+
+        let mut responder = self.responder(ctx, command, ReplyMode::Ephemeral);
+        if file.size > MAX_FILE_BYTES {
+            responder.send(Reply::content(locale.analyze_file_error(MAX_GAMES))).await;
+            return;
+        }
+
+        // ダウンロードと全ゲームの再生は時間がかかるため自動で保留される
+        responder
+            .run(async {
+                match self.analyze_file(locale, file).await {
+                    Ok(reply) => reply,
+                    Err(e) => {
+                        info!("Could not analyze games file from {}: {:?}", command.user.id, e);
+                        Reply::content(locale.analyze_file_error(MAX_GAMES))
+                    }
+                }
+            })
+            .await;
+    }
+
+    async fn analyze_file(&self, locale: Locale, file: &Attachment) -> anyhow::Result<Reply> {
+        let bytes = file.download().await.context("Failed to download attachment")?;
+        let value: Value = serde_json::from_slice(&bytes).context("Not a JSON file")?;
+        let (mut games, mut skipped) = parse_games(&value);
+        if games.is_empty() {
+            anyhow::bail!("No readable games");
+        }
+        if games.len() > MAX_GAMES {
+            skipped += games.len() - MAX_GAMES;
+            games.truncate(MAX_GAMES);
+        }
+
+        let arena = self.word_cache.read().await.words_of_length(solver::WORD_LENGTH).cloned().unwrap_or_default();
+        let games = Arc::new(games);
+        let analyses = {
+            let games = Arc::clone(&games);
+            tokio::task::spawn_blocking(move || solver::analyze_games(&arena, &games)).await?
+        };
+        let summary = summarize(&analyses, skipped);
+        info!("Analyzed {} games from a file ({} skipped)", summary.games, summary.skipped);
+
+        let report = serde_json::to_vec_pretty(&report_json(&summary, &analyses))?;
+        Ok(Reply::content(locale.analysis_summary(&summary))
+            .attachment(CreateAttachment::bytes(report, "wordle-analysis.json")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_games_in_several_shapes() {
+        let value = json!({
+            "games": [
+                { "guesses": ["crane BYXXG", "moist:GGGGG"] },
+                [{ "word": "slate", "results": ["Gray", "Gray", "Yellow", "Gray", "Green"] }, { "word": "crane", "pattern": "GGGGG" }],
+                { "guesses": [{ "word": "crane", "results": [0, 1, 2, 0, 0] }] },
+                { "guesses": ["not a guess"] },
+                { "guesses": [] },
+            ]
+        });
+        let (games, skipped) = parse_games(&value);
+        assert_eq!(games.len(), 3);
+        assert_eq!(skipped, 2);
+        assert_eq!(games[1][0].results[2], LetterResult::Yellow);
+        assert_eq!(games[2][0].results, [LetterResult::Gray, LetterResult::Yellow, LetterResult::Green, LetterResult::Gray, LetterResult::Gray]);
+    }
+}
@@ -0,0 +1,222 @@
+// ソルバーを外部から使うためのREST API（Webフロントエンドやスクリプト向け）
+// ボットと同じ単語キャッシュを参照し、APIキーで保護する
+use std::net::SocketAddr;
+
+use anyhow::Context as _;
+use axum::extract::{Query, Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::{solver, Bot, WordleGuess};
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 100;
+
+// /candidates で1回に返す単語の数
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1000;
+
+#[derive(Clone)]
+struct ApiState {
+    bot: Bot,
+    api_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SolveRequest {
+    #[serde(default)]
+    guesses: Vec<WordleGuess>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct SolveResponse {
+    candidate_count: usize,
+    suggestions: Vec<solver::WordScore>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CandidatesQuery {
+    // "CRANE:YGGBG,SLATE:BBBBB" の形式（省略時は制約なし）
+    guesses: Option<String>,
+    #[serde(default)]
+    offset: usize,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct CandidatesResponse {
+    // 条件に合う候補の総数（words はそのうち offset から limit 件）
+    count: usize,
+    offset: usize,
+    words: Vec<String>,
+}
+
+pub fn api_router(bot: Bot, api_key: String) -> Router {
+    let state = ApiState { bot, api_key };
+
+    Router::new()
+        .route("/solve", post(solve))
+        .route("/candidates", get(candidates))
+        .layer(middleware::from_fn_with_state(state.clone(), require_api_key))
+        .with_state(state)
+}
+
+pub async fn serve_api(bot: Bot, api_key: String, addr: SocketAddr) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await
+        .with_context(|| format!("Failed to bind {}", addr))?;
+    info!("Serving solver API on http://{}", addr);
+
+    axum::serve(listener, api_router(bot, api_key)).await?;
+    Ok(())
+}
+
+// Authorization: Bearer <キー> または X-API-Key: <キー> を要求する
+async fn require_api_key(State(state): State<ApiState>, headers: HeaderMap, request: Request, next: Next) -> Response {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let provided = header("X-API-Key")
+        .or_else(|| header("Authorization").and_then(|v| v.strip_prefix("Bearer ")));
+
+    match provided {
+        Some(key) if keys_match(key, &state.api_key) => next.run(request).await,
+        _ => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
+
+// 比較にかかる時間から一致した長さを推測されないようにする
+fn keys_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn solve(State(state): State<ApiState>, Json(request): Json<SolveRequest>) -> Response {
+    let guesses = match normalize_guesses(request.guesses) {
+        Ok(guesses) => guesses,
+        Err(error) => return (StatusCode::UNPROCESSABLE_ENTITY, error).into_response(),
+    };
+
+    let candidates = state.bot.api_candidates(&guesses).await;
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    // 順位付けは候補数の2乗の計算になるため、非同期のワーカーを止めないよう別スレッドで行う
+    let candidate_count = candidates.len();
+    let guess_count = guesses.len();
+    let ranked = tokio::task::spawn_blocking(move || {
+        let mut suggestions = solver::rank_words(&candidates, guess_count);
+        suggestions.truncate(limit);
+        suggestions
+    })
+    .await;
+
+    match ranked {
+        Ok(suggestions) => Json(SolveResponse { candidate_count, suggestions }).into_response(),
+        Err(e) => {
+            warn!("Failed to rank API suggestions: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+async fn candidates(State(state): State<ApiState>, Query(query): Query<CandidatesQuery>) -> Response {
+    let guesses = match query.guesses.as_deref().map(parse_guess_list).transpose() {
+        Ok(guesses) => guesses.unwrap_or_default(),
+        Err(error) => return (StatusCode::BAD_REQUEST, error).into_response(),
+    };
+
+    let words = state.bot.api_candidates(&guesses).await;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    Json(CandidatesResponse { count: words.len(), offset: query.offset, words: page(words, query.offset, limit) }).into_response()
+}
+
+fn page(words: Vec<String>, offset: usize, limit: usize) -> Vec<String> {
+    words.into_iter().skip(offset).take(limit).collect()
+}
+
+// JSONの推測を検証し、単語を大文字にそろえる（候補の絞り込みは大文字で比較する）
+fn normalize_guesses(guesses: Vec<WordleGuess>) -> Result<Vec<WordleGuess>, String> {
+    guesses.into_iter()
+        .map(|g| {
+            if !solver::is_valid_word(&g.word) || g.results.len() != solver::WORD_LENGTH {
+                return Err(format!("invalid guess: {:?}", g.word));
+            }
+            Ok(WordleGuess { word: g.word.to_uppercase(), results: g.results })
+        })
+        .collect()
+}
+
+fn parse_guess_list(input: &str) -> Result<Vec<WordleGuess>, String> {
+    input.split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            entry.trim()
+                .split_once(':')
+                .and_then(|(word, pattern)| WordleGuess::parse(word, pattern))
+                .ok_or_else(|| format!("invalid guess: {:?} (expected WORD:PATTERN, e.g. CRANE:YGGBG)", entry))
+        })
+        .collect()
+}
+
+impl Bot {
+    // 推測履歴に矛盾しない候補（単語キャッシュが空なら既定の開始単語）
+    async fn api_candidates(&self, guesses: &[WordleGuess]) -> Vec<String> {
+        let words = self.word_cache.read().await;
+        if words.is_empty() {
+            return solver::filter_candidates(solver::FALLBACK_WORDS.iter().copied(), guesses);
+        }
+        // サーバーが分からないため、全サーバー共通の使わない単語だけを除く
+        let settings = self.settings();
+        words.candidate_ids(guesses).without(|word| settings.is_banned(None, word)).to_strings()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LetterResult;
+
+    #[test]
+    fn parses_compact_guess_lists() {
+        let guesses = parse_guess_list("crane:YGGBG, slate:bbbbb").unwrap();
+        assert_eq!(guesses.len(), 2);
+        assert_eq!(guesses[0].word, "CRANE");
+        assert_eq!(guesses[1].results, vec![LetterResult::Gray; 5]);
+
+        assert!(parse_guess_list("CRANE").is_err());
+        assert!(parse_guess_list("CRANE:YGG").is_err());
+    }
+
+    #[tokio::test]
+    async fn lowercase_json_guesses_filter_like_uppercase_ones() {
+        let storage = std::sync::Arc::new(crate::storage::MemoryStorage::new(["CRANE", "TRACE", "SLATE"]));
+        let bot = Bot::new(serenity::all::GuildId::new(1), storage, Default::default());
+        bot.load_word_cache().await.unwrap();
+
+        let request: SolveRequest = serde_json::from_str(r#"{"guesses": [{"word": "trace", "results": ["Gray", "Green", "Green", "Yellow", "Green"]}]}"#).unwrap();
+        let guesses = normalize_guesses(request.guesses).unwrap();
+        assert_eq!(guesses[0].word, "TRACE");
+        assert_eq!(bot.api_candidates(&guesses).await, ["CRANE"]);
+
+        let invalid = vec![WordleGuess { word: "cr4ne".to_string(), results: vec![LetterResult::Gray; 5] }];
+        assert!(normalize_guesses(invalid).is_err());
+    }
+
+    #[test]
+    fn candidates_are_returned_a_page_at_a_time() {
+        let words: Vec<String> = ["CRANE", "SLATE", "TRACE"].map(String::from).to_vec();
+        assert_eq!(page(words.clone(), 0, 2), ["CRANE", "SLATE"]);
+        assert_eq!(page(words.clone(), 2, 2), ["TRACE"]);
+        assert!(page(words, 5, 2).is_empty());
+    }
+
+    #[test]
+    fn compares_keys_exactly() {
+        assert!(keys_match("secret", "secret"));
+        assert!(!keys_match("secreT", "secret"));
+        assert!(!keys_match("secret-longer", "secret"));
+    }
+}
@@ -0,0 +1,43 @@
+// Shuttleを使わずにセルフホストするためのエントリポイント（Docker / VPS向け）
+use discord_bot::{get_client_with, Bot, BotConfig};
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+
+    let config = BotConfig::from_env()?;
+    let bot = Bot::from_config(&config);
+
+    // API_KEY が設定されていれば、同じボットの状態を使ってソルバーAPIも提供する
+    #[cfg(feature = "api")]
+    if let Some(api_key) = config.api_key.clone() {
+        let addr = std::net::SocketAddr::from(([0, 0, 0, 0], config.api_port));
+        let bot = bot.clone();
+        tokio::spawn(async move {
+            if let Err(e) = discord_bot::serve_api(bot, api_key, addr).await {
+                error!("Solver API stopped: {:?}", e);
+            }
+        });
+    }
+
+    let mut client = get_client_with(&config, bot.clone()).await?;
+
+    // Ctrl+C / SIGTERM で未送信の書き込みを送り、シャードを停止して終了
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for shutdown signal: {:?}", e);
+            return;
+        }
+        info!("Shutdown signal received, stopping shards");
+        bot.flush_writes().await;
+        shard_manager.shutdown_all().await;
+    });
+
+    client.start().await?;
+    Ok(())
+}
@@ -0,0 +1,19 @@
+// ゲートウェイの代わりにHTTPのInteractions Endpointで動かすエントリポイント（サーバーレス向け）
+use std::net::SocketAddr;
+
+use discord_bot::{serve_webhook, BotConfig};
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+
+    let config = BotConfig::from_env()?;
+
+    let port = std::env::var("PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(8080);
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+
+    serve_webhook(config, addr).await
+}
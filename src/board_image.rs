@@ -0,0 +1,119 @@
+// 終了した盤面を文字入りのPNG画像にする（Discordの外で共有する用）
+// フォントを同梱しないよう、英大文字は5x7のビットマップで描く
+use std::io::Cursor;
+
+use anyhow::Context as _;
+use image::{ImageFormat, Rgb, RgbImage};
+
+use crate::{LetterResult, WordleGuess};
+
+const TILE: u32 = 60;
+const GAP: u32 = 6;
+const MARGIN: u32 = 12;
+// ビットマップの1ドットの大きさ
+const SCALE: u32 = 4;
+
+const BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const LETTER: Rgb<u8> = Rgb([255, 255, 255]);
+
+fn tile_color(result: LetterResult) -> Rgb<u8> {
+    match result {
+        LetterResult::Gray => Rgb([120, 124, 126]),
+        LetterResult::Yellow => Rgb([201, 180, 88]),
+        LetterResult::Green => Rgb([106, 170, 100]),
+    }
+}
+
+// A〜Z の 5x7 ビットマップ（各行の下位5ビット、上の行から）
+const GLYPHS: [[u8; 7]; 26] = [
+    [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+    [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+    [0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110],
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+    [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+    [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+    [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+    [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+    [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+    [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+    [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+    [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+    [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+    [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+    [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+    [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+    [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+    [0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100],
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+];
+
+fn fill(image: &mut RgbImage, x: u32, y: u32, w: u32, h: u32, color: Rgb<u8>) {
+    for dy in 0..h {
+        for dx in 0..w {
+            image.put_pixel(x + dx, y + dy, color);
+        }
+    }
+}
+
+// タイルの中央に文字を描く（英字以外は描かない）
+fn draw_letter(image: &mut RgbImage, x: u32, y: u32, letter: char) {
+    let letter = letter.to_ascii_uppercase();
+    if !letter.is_ascii_uppercase() {
+        return;
+    }
+    let glyph = GLYPHS[(letter as u8 - b'A') as usize];
+    let (left, top) = (x + (TILE - 5 * SCALE) / 2, y + (TILE - 7 * SCALE) / 2);
+    for (row, bits) in glyph.iter().enumerate() {
+        for col in 0..5 {
+            if bits & (0b10000 >> col) != 0 {
+                fill(image, left + col * SCALE, top + row as u32 * SCALE, SCALE, SCALE, LETTER);
+            }
+        }
+    }
+}
+
+// 推測ごとに1行のPNG画像
+pub fn render_board(guesses: &[WordleGuess]) -> anyhow::Result<Vec<u8>> {
+    let columns = guesses.iter().map(|g| g.results.len()).max().unwrap_or(0) as u32;
+    let rows = guesses.len() as u32;
+    let size = |n: u32| 2 * MARGIN + n * TILE + n.saturating_sub(1) * GAP;
+
+    let mut image = RgbImage::from_pixel(size(columns), size(rows), BACKGROUND);
+    for (row, guess) in guesses.iter().enumerate() {
+        for (col, (letter, result)) in guess.word.chars().zip(&guess.results).enumerate() {
+            let x = MARGIN + col as u32 * (TILE + GAP);
+            let y = MARGIN + row as u32 * (TILE + GAP);
+            fill(&mut image, x, y, TILE, TILE, tile_color(*result));
+            draw_letter(&mut image, x, y, letter);
+        }
+    }
+
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).context("Failed to encode board image")?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendered_board_reads_back_as_the_same_colors() {
+        let guesses = vec![
+            WordleGuess::parse("CRANE", "BYBBG").unwrap(),
+            WordleGuess::parse("TRUCE", "GGGGG").unwrap(),
+        ];
+
+        let bytes = render_board(&guesses).unwrap();
+        let rows = crate::screenshot::read_tile_colors(&bytes).unwrap();
+
+        assert_eq!(rows, guesses.iter().map(|g| g.results.clone()).collect::<Vec<_>>());
+    }
+}
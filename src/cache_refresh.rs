@@ -0,0 +1,154 @@
+// 単語・絵文字キャッシュの定期的な再読み込み
+// 間隔（cache_refresh_minutes）は設定で変更できるため、スケジューラーから毎分呼び、期限が来たときだけ読み込む
+// 複数のインスタンスが同じ時刻に問い合わせないよう間隔に揺らぎを加え、
+// テーブルの指紋（件数と最大のID）が前回と同じなら全件の取得を省く
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use arc_swap::ArcSwap;
+use tracing::{info, warn};
+
+use crate::settings::Settings;
+use crate::storage::{CachedTable, Storage};
+use crate::{word_cache, wordlist};
+
+// 間隔をこの割合までずらす
+const JITTER: f64 = 0.1;
+
+// 無作為な値から間隔に掛ける倍率（1 ± JITTER）を決める
+fn jitter_factor(random: u64) -> f64 {
+    let unit = random as f64 / u64::MAX as f64;
+    1.0 + JITTER * (unit * 2.0 - 1.0)
+}
+
+fn random_jitter() -> f64 {
+    jitter_factor(RandomState::new().hash_one(SystemTime::now()))
+}
+
+// 読み込み直したキャッシュ
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Refreshed {
+    pub words: bool,
+    pub emojis: bool,
+}
+
+struct RefreshState {
+    last_refresh: Instant,
+    // 次の再読み込みまでの間隔に掛ける倍率
+    jitter: f64,
+    // 最後に読み込んだときのテーブルの指紋
+    fingerprints: HashMap<CachedTable, String>,
+}
+
+pub struct CacheRefresher {
+    storage: Arc<dyn Storage>,
+    word_cache: Arc<tokio::sync::RwLock<word_cache::WordCache>>,
+    word_versions: Arc<wordlist::WordListVersions>,
+    emoji_cache: Arc<tokio::sync::RwLock<HashMap<String, String>>>,
+    settings: Arc<ArcSwap<Settings>>,
+    state: tokio::sync::Mutex<RefreshState>,
+}
+
+impl CacheRefresher {
+    pub fn new(
+        storage: Arc<dyn Storage>,
+        word_cache: Arc<tokio::sync::RwLock<word_cache::WordCache>>,
+        word_versions: Arc<wordlist::WordListVersions>,
+        emoji_cache: Arc<tokio::sync::RwLock<HashMap<String, String>>>,
+        settings: Arc<ArcSwap<Settings>>,
+    ) -> Self {
+        let state = RefreshState { last_refresh: Instant::now(), jitter: random_jitter(), fingerprints: HashMap::new() };
+        Self { storage, word_cache, word_versions, emoji_cache, settings, state: tokio::sync::Mutex::new(state) }
+    }
+
+    // 設定された間隔（揺らぎを含む）が経過していれば再読み込みする
+    pub async fn run_if_due(&self) -> anyhow::Result<()> {
+        let minutes = self.settings.load().cache_refresh_minutes;
+        if minutes == 0 {
+            return Ok(());
+        }
+
+        let mut state = self.state.lock().await;
+        let interval = Duration::from_secs(minutes * 60).mul_f64(state.jitter);
+        if state.last_refresh.elapsed() < interval {
+            return Ok(());
+        }
+        state.last_refresh = Instant::now();
+        state.jitter = random_jitter();
+
+        self.refresh_with(&mut state).await.map(|_| ())
+    }
+
+    // 間隔によらず、変わったテーブルだけ読み込み直す
+    pub async fn refresh(&self) -> anyhow::Result<Refreshed> {
+        let mut state = self.state.lock().await;
+        self.refresh_with(&mut state).await
+    }
+
+    async fn refresh_with(&self, state: &mut RefreshState) -> anyhow::Result<Refreshed> {
+        let mut refreshed = Refreshed::default();
+        let mut errors = Vec::new();
+
+        for table in [CachedTable::Words, CachedTable::Emojis] {
+            let Some(fingerprint) = self.changed_fingerprint(state, table).await else {
+                continue;
+            };
+            let result = match table {
+                CachedTable::Words => crate::load_words(self.storage.as_ref(), &self.word_cache, &self.word_versions).await,
+                CachedTable::Emojis => crate::load_emojis(self.storage.as_ref(), &self.emoji_cache).await,
+            };
+            match result {
+                Ok(()) => {
+                    match table {
+                        CachedTable::Words => refreshed.words = true,
+                        CachedTable::Emojis => refreshed.emojis = true,
+                    }
+                    match fingerprint {
+                        Some(fingerprint) => state.fingerprints.insert(table, fingerprint),
+                        None => state.fingerprints.remove(&table),
+                    };
+                }
+                Err(e) => errors.push(e.context(format!("Failed to refresh {:?}", table))),
+            }
+        }
+
+        if refreshed != Refreshed::default() {
+            info!("Refreshed caches (words: {}, emojis: {})", refreshed.words, refreshed.emojis);
+        }
+        match errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(refreshed),
+        }
+    }
+
+    // 読み込み直すべきなら Some（中身は読み込んだあとに覚える指紋）
+    // 指紋を取得できなければ、変わったものとして読み込む
+    async fn changed_fingerprint(&self, state: &RefreshState, table: CachedTable) -> Option<Option<String>> {
+        match self.storage.table_fingerprint(table).await {
+            Ok(Some(fingerprint)) if state.fingerprints.get(&table) == Some(&fingerprint) => None,
+            Ok(fingerprint) => Some(fingerprint),
+            Err(e) => {
+                warn!("Failed to check {:?} for changes: {:?}", table, e);
+                Some(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_stays_within_the_allowed_range() {
+        assert_eq!(jitter_factor(0), 1.0 - JITTER);
+        assert_eq!(jitter_factor(u64::MAX), 1.0 + JITTER);
+        for random in [1, u64::MAX / 3, u64::MAX / 2, u64::MAX - 1] {
+            let factor = jitter_factor(random);
+            assert!((1.0 - JITTER..=1.0 + JITTER).contains(&factor), "{}", factor);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+// /candidates: 提案は出さずに、残りの候補数だけを本人に伝える
+use serenity::all::{CommandInteraction, CreateCommand};
+
+use crate::i18n;
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::Bot;
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("candidates")
+}
+
+impl Bot {
+    pub(crate) async fn handle_candidates_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let content = match self.remaining_candidates(command.user.id.get()).await {
+            Ok(count) => locale.remaining(count),
+            Err(e) => locale.session_error(e).to_string(),
+        };
+
+        self.responder(ctx, command, ReplyMode::Ephemeral)
+            .send(Reply::content(content))
+            .await;
+    }
+}
@@ -0,0 +1,312 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use serenity::all::{RoleId, UserId};
+use tracing::warn;
+
+use crate::permissions::PermissionConfig;
+
+const DEFAULT_API_PORT: u16 = 3000;
+
+// 外部サービス（Supabase・辞書・Webhook）へのHTTP接続の設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpConfig {
+    // ホストごとに残しておく待機中の接続数と、待機中の接続を閉じるまでの時間
+    pub pool_max_idle_per_host: usize,
+    pub pool_idle_timeout: Duration,
+    // TCPとHTTP/2のキープアライブの間隔
+    pub keep_alive: Duration,
+    // 1リクエスト全体のタイムアウトと、接続確立のタイムアウト
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    // Supabaseへの同時リクエスト数の上限（一括処理で接続を使い切らないように）
+    pub max_concurrent_requests: usize,
+    // HTTP/2を使わない（HTTP/2に対応していないプロキシの内側で動かす場合）
+    pub http1_only: bool,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: 8,
+            pool_idle_timeout: Duration::from_secs(90),
+            keep_alive: Duration::from_secs(30),
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            max_concurrent_requests: 16,
+            http1_only: false,
+        }
+    }
+}
+
+impl HttpConfig {
+    // ボット全体で共有するクライアントを作る（作れなければ既定の設定で作る）
+    pub fn client(&self) -> reqwest::Client {
+        let mut builder = reqwest::Client::builder()
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.keep_alive)
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout);
+        builder = if self.http1_only {
+            builder.http1_only()
+        } else {
+            builder
+                .http2_adaptive_window(true)
+                .http2_keep_alive_interval(self.keep_alive)
+                .http2_keep_alive_while_idle(true)
+        };
+
+        builder.build().unwrap_or_else(|e| {
+            warn!("Could not build the HTTP client ({}); using defaults", e);
+            reqwest::Client::new()
+        })
+    }
+}
+
+// ボットの起動設定（Shuttleのシークレットまたは環境変数から読み込む）
+#[derive(Debug, Clone)]
+pub struct BotConfig {
+    pub discord_token: String,
+    pub discord_guild_id: u64,
+    pub supabase_url: String,
+    pub supabase_key: String,
+    // ソルバーAPIの認証キーと待ち受けポート（キーがなければAPIは起動しない）
+    pub api_key: Option<String>,
+    pub api_port: u16,
+    // Webhookモード（Interactions Endpoint）で署名の検証に使う公開鍵（任意）
+    pub discord_public_key: Option<String>,
+    pub permissions: PermissionConfig,
+    // 深刻なエラーを投稿するチャンネル（任意）
+    pub error_channel_id: Option<u64>,
+    // この時間（ミリ秒）を超えた操作を警告する（任意）
+    pub slow_interaction_ms: Option<u64>,
+    // 分析の計算を同時に行う数（任意。未設定ならCPU数から決める）
+    pub analysis_workers: Option<usize>,
+    // メモリに保持するセッション数の上限と、追い出したセッションをストレージに保存するか
+    pub max_sessions: usize,
+    pub persist_sessions: bool,
+    // 単語・絵文字キャッシュのスナップショットを保存するファイル（任意）
+    pub cache_snapshot_path: Option<String>,
+    // 一般向けのコマンドをユーザーインストールでも使えるようにグローバルに登録するか
+    pub user_install: bool,
+    pub http: HttpConfig,
+    // セッションと成績の書き込みをまとめて送る件数と間隔
+    pub write_batch_size: usize,
+    pub write_flush_interval: Duration,
+    // 読み込み時に無視した任意設定（validate で報告する）
+    ignored: Vec<String>,
+}
+
+impl BotConfig {
+    // キー名から値を引く関数を受け取り、設定を組み立てる
+    pub fn from_lookup(get: impl Fn(&str) -> Option<String>) -> anyhow::Result<Self> {
+        let require = |key: &str| get(key).with_context(|| format!("'{}' was not found", key));
+
+        let discord_token = require("DISCORD_TOKEN")?;
+
+        let discord_guild_id: u64 = require("DISCORD_GUILD_ID")?
+            .parse()
+            .context("'DISCORD_GUILD_ID' must be a numeric id")?;
+        // Discordのidは0にならない（0のまま GuildId を作ると起動時に panic する）
+        anyhow::ensure!(discord_guild_id != 0, "'DISCORD_GUILD_ID' must not be 0");
+
+        let supabase_url = require("SUPABASE_URL")?;
+        let supabase_key = require("SUPABASE_KEY")?;
+        let discord_public_key = get("DISCORD_PUBLIC_KEY");
+        let api_key = get("API_KEY");
+        let cache_snapshot_path = get("CACHE_SNAPSHOT_PATH");
+
+        // 任意のID設定（不正な値は無視して起動を続ける）
+        let mut ignored = Vec::new();
+        let mut optional_id = |key: &str| {
+            let value = get(key)?;
+            match value.parse::<u64>() {
+                Ok(id) if id != 0 => Some(id),
+                _ => {
+                    ignored.push(format!("'{}' is not a valid id ({:?}); ignoring it", key, value));
+                    None
+                }
+            }
+        };
+
+        // 管理コマンド用の権限設定（任意）
+        let permissions = PermissionConfig {
+            owner_id: optional_id("BOT_OWNER_ID").map(UserId::new),
+            moderator_role: optional_id("MODERATOR_ROLE_ID").map(RoleId::new),
+        };
+
+        let error_channel_id = optional_id("ERROR_CHANNEL_ID");
+
+        let mut optional_number = |key: &str| {
+            let value = get(key)?;
+            match value.parse::<u64>() {
+                Ok(n) => Some(n),
+                Err(_) => {
+                    ignored.push(format!("'{}' is not a number ({:?}); ignoring it", key, value));
+                    None
+                }
+            }
+        };
+
+        let slow_interaction_ms = optional_number("SLOW_INTERACTION_MS");
+        let analysis_workers = optional_number("ANALYSIS_WORKERS").map(|n| (n as usize).max(1));
+        let max_sessions = optional_number("MAX_SESSIONS")
+            .map_or(crate::DEFAULT_MAX_SESSIONS, |n| n as usize);
+
+        let api_port = optional_number("API_PORT")
+            .and_then(|port| u16::try_from(port).ok())
+            .unwrap_or(DEFAULT_API_PORT);
+
+        let defaults = HttpConfig::default();
+        let mut seconds = |key: &str, default: Duration| optional_number(key).map_or(default, Duration::from_secs);
+        let pool_idle_timeout = seconds("HTTP_POOL_IDLE_SECS", defaults.pool_idle_timeout);
+        let keep_alive = seconds("HTTP_KEEPALIVE_SECS", defaults.keep_alive);
+        let timeout = seconds("HTTP_TIMEOUT_SECS", defaults.timeout);
+        let connect_timeout = seconds("HTTP_CONNECT_TIMEOUT_SECS", defaults.connect_timeout);
+        let pool_max_idle_per_host = optional_number("HTTP_POOL_MAX_IDLE")
+            .map_or(defaults.pool_max_idle_per_host, |n| n as usize);
+        let max_concurrent_requests = optional_number("HTTP_MAX_CONCURRENT")
+            .map_or(defaults.max_concurrent_requests, |n| (n as usize).max(1));
+
+        let write_batch_size = optional_number("WRITE_BATCH_SIZE")
+            .map_or(crate::write_queue::DEFAULT_BATCH_SIZE, |n| (n as usize).max(1));
+        let write_flush_interval = optional_number("WRITE_FLUSH_SECS")
+            .map_or(crate::write_queue::DEFAULT_FLUSH_INTERVAL, Duration::from_secs);
+
+        let flag = |key: &str| get(key).is_some_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes"));
+        let persist_sessions = flag("PERSIST_SESSIONS");
+        let user_install = flag("USER_INSTALL");
+        let http = HttpConfig {
+            pool_max_idle_per_host,
+            pool_idle_timeout,
+            keep_alive,
+            timeout,
+            connect_timeout,
+            max_concurrent_requests,
+            http1_only: flag("HTTP1_ONLY"),
+        };
+
+        Ok(Self {
+            discord_token,
+            discord_guild_id,
+            supabase_url,
+            supabase_key,
+            api_key,
+            api_port,
+            discord_public_key,
+            permissions,
+            error_channel_id,
+            slow_interaction_ms,
+            analysis_workers,
+            max_sessions,
+            persist_sessions,
+            cache_snapshot_path,
+            user_install,
+            http,
+            write_batch_size,
+            write_flush_interval,
+            ignored,
+        })
+    }
+
+    // 起動を止めるほどではない設定の問題を列挙する
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = self.ignored.clone();
+
+        if self.discord_token.split('.').count() != 3 {
+            problems.push("'DISCORD_TOKEN' does not look like a bot token".to_string());
+        }
+        if !self.supabase_url.starts_with("https://") && !self.supabase_url.starts_with("http://") {
+            problems.push(format!("'SUPABASE_URL' should start with http(s):// ({:?})", self.supabase_url));
+        }
+        if self.max_sessions == 0 {
+            problems.push("'MAX_SESSIONS' must be at least 1; using 1".to_string());
+        }
+        if self.api_key.as_ref().is_some_and(|key| key.len() < 16) {
+            problems.push("'API_KEY' is shorter than 16 characters".to_string());
+        }
+        if self.http.timeout.is_zero() || self.http.connect_timeout.is_zero() {
+            problems.push("HTTP timeouts of 0 make every request fail immediately".to_string());
+        }
+        if self.permissions.owner_id.is_none() {
+            problems.push("'BOT_OWNER_ID' is not set; owner-only admin commands are unavailable".to_string());
+        }
+
+        problems
+    }
+
+    // 環境変数から読み込む（セルフホスト用）
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::from_lookup(|key| std::env::var(key).ok().filter(|value| !value.is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup<'a>(pairs: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |key| pairs.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string())
+    }
+
+    const REQUIRED: [(&str, &str); 4] = [
+        ("DISCORD_TOKEN", "aaa.bbb.ccc"),
+        ("DISCORD_GUILD_ID", "1234"),
+        ("SUPABASE_URL", "https://example.supabase.co"),
+        ("SUPABASE_KEY", "key"),
+    ];
+
+    #[test]
+    fn missing_required_key_is_an_error() {
+        let error = BotConfig::from_lookup(lookup(&REQUIRED[1..])).unwrap_err();
+        assert!(error.to_string().contains("DISCORD_TOKEN"));
+    }
+
+    #[test]
+    fn zero_guild_id_is_an_error() {
+        let mut pairs = REQUIRED.to_vec();
+        pairs[1] = ("DISCORD_GUILD_ID", "0");
+        let error = BotConfig::from_lookup(lookup(&pairs)).unwrap_err();
+        assert!(error.to_string().contains("DISCORD_GUILD_ID"), "{}", error);
+    }
+
+    #[test]
+    fn invalid_optional_ids_are_reported_not_fatal() {
+        let mut pairs = REQUIRED.to_vec();
+        pairs.push(("BOT_OWNER_ID", "42"));
+        pairs.push(("ERROR_CHANNEL_ID", "not-a-number"));
+
+        let config = BotConfig::from_lookup(lookup(&pairs)).unwrap();
+
+        assert_eq!(config.error_channel_id, None);
+        let problems = config.validate();
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert!(problems[0].contains("ERROR_CHANNEL_ID"));
+    }
+
+    #[test]
+    fn user_install_is_opt_in() {
+        assert!(!BotConfig::from_lookup(lookup(&REQUIRED)).unwrap().user_install);
+
+        let mut pairs = REQUIRED.to_vec();
+        pairs.push(("USER_INSTALL", "true"));
+        assert!(BotConfig::from_lookup(lookup(&pairs)).unwrap().user_install);
+    }
+
+    #[test]
+    fn http_settings_default_and_override() {
+        assert_eq!(BotConfig::from_lookup(lookup(&REQUIRED)).unwrap().http, HttpConfig::default());
+
+        let mut pairs = REQUIRED.to_vec();
+        pairs.push(("HTTP_TIMEOUT_SECS", "5"));
+        pairs.push(("HTTP_MAX_CONCURRENT", "0"));
+        pairs.push(("HTTP1_ONLY", "yes"));
+        let http = BotConfig::from_lookup(lookup(&pairs)).unwrap().http;
+
+        assert_eq!(http.timeout, Duration::from_secs(5));
+        assert_eq!(http.max_concurrent_requests, 1);
+        assert!(http.http1_only);
+        assert_eq!(http.keep_alive, HttpConfig::default().keep_alive);
+    }
+}
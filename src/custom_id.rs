@@ -0,0 +1,310 @@
+// ボタン・モーダルの custom_id の型付きエンコード/デコード
+// 形式: wht:<形式バージョン>:<所有者のユーザーID>:<リビジョン>:<アクション>[:<引数>]
+use std::fmt;
+
+use crate::settings::GuessLimit;
+use crate::solver::WORD_LENGTH;
+
+const PREFIX: &str = "wht";
+
+// custom_id の形式を変更したら上げる（古いメッセージのボタンは拒否される）
+pub const FORMAT_VERSION: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    // 新しい単語入力モーダルを開く
+    NewWord,
+    // 単語入力モーダルの送信
+    WordModal,
+    // 指定位置の文字の色を切り替える
+    Letter { index: usize },
+    // 色の入力メニューで選んだ色をまとめて設定する
+    LetterMenu,
+    // 色を確定して提案を計算する
+    Confirm,
+    // 確定済みの推測を色の入力に戻す（番号は0始まり）
+    EditGuess { index: usize },
+    // 読み込んだ盤面の修正する行を選ぶ
+    ImportRow { row: usize },
+    // 読み込んだ盤面の単語入力モーダルを開く
+    ImportWords,
+    // 読み込んだ盤面の単語入力モーダルの送信
+    ImportModal,
+    // 終了した盤面を画像で受け取る
+    ExportImage,
+    // 過去の推測のメニューで選んだ推測を色の入力に戻す（番号はメニューの値）
+    PickGuess,
+    // このセッションの盤面の言語を切り替える（サーバーの設定は変えない）
+    ToggleLocale,
+    // おすすめの単語を選んだ理由を本人にだけ表示する（番号はおすすめの順位、0始まり）
+    Explain { index: usize },
+    // 終了した盤面のSNS向けの共有用の文章を受け取る
+    ShareText,
+    // 実行中の分析を取り消し、確定前の色の入力に戻す
+    CancelAnalysis,
+    // /wordle の推測の入力モーダルを開く（ゲームIDから、再起動後は保存したゲームを読み込む）
+    PlayGuess { game: u64 },
+    // /wordle の推測の入力モーダルの送信
+    PlayModal { game: u64 },
+    // /wordle の次の段階のヒントを使う
+    PlayHint { game: u64 },
+    // /daily の推測の入力モーダルを開く（日とそれまでの推測から、再起動後も盤面を作り直せる）
+    DailyGuess { day: u64, played: PlayedWords },
+    // /daily の推測の入力モーダルの送信
+    DailyModal { day: u64, played: PlayedWords },
+}
+
+// custom_id に埋め込む推測した単語（ボタンがあるのは終わる前なので、一番多い上限の最後の1回を除いた数まで）
+const PLAYED_LETTERS: usize = WORD_LENGTH * (GuessLimit::MOST - 1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayedWords {
+    letters: [u8; PLAYED_LETTERS],
+    len: usize,
+}
+
+impl Default for PlayedWords {
+    fn default() -> Self {
+        Self { letters: [0; PLAYED_LETTERS], len: 0 }
+    }
+}
+
+impl PlayedWords {
+    // 入りきらない、または5文字の英大文字でない単語があれば None
+    pub fn new<'a>(words: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        let mut played = Self::default();
+        for word in words {
+            if word.len() != WORD_LENGTH || !word.bytes().all(|b| b.is_ascii_uppercase()) || played.len + WORD_LENGTH > PLAYED_LETTERS {
+                return None;
+            }
+            played.letters[played.len..played.len + WORD_LENGTH].copy_from_slice(word.as_bytes());
+            played.len += WORD_LENGTH;
+        }
+        Some(played)
+    }
+
+    pub fn words(&self) -> Vec<String> {
+        self.letters[..self.len].chunks(WORD_LENGTH)
+            .map(|word| String::from_utf8_lossy(word).into_owned())
+            .collect()
+    }
+
+    // 単語をつなげた文字列（CRANESLATE）
+    fn encode(&self) -> String {
+        String::from_utf8_lossy(&self.letters[..self.len]).into_owned()
+    }
+
+    fn decode(raw: &str) -> Result<Self, CustomIdError> {
+        if !raw.len().is_multiple_of(WORD_LENGTH) {
+            return Err(CustomIdError::Malformed);
+        }
+        let words: Vec<_> = raw.as_bytes().chunks(WORD_LENGTH).map(std::str::from_utf8).collect::<Result<_, _>>()
+            .map_err(|_| CustomIdError::Malformed)?;
+        Self::new(words).ok_or(CustomIdError::Malformed)
+    }
+}
+
+impl Action {
+    // 推測を確定する・確定前に戻す操作（共有した盤面では開始した人に限れる）
+    pub fn rewrites_history(self) -> bool {
+        matches!(self, Action::Confirm | Action::CancelAnalysis | Action::EditGuess { .. } | Action::PickGuess)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomId {
+    // セッションを所有するユーザー（チャンネルで共有した盤面ではチャンネル）
+    pub owner: u64,
+    // 盤面を描画した時点のセッションのリビジョン（古いボタンの検出に使う）
+    pub revision: u64,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CustomIdError {
+    // このボットの形式ではない（旧形式のボタンを含む）
+    Foreign,
+    // 形式バージョンが古い、または新しい
+    UnsupportedVersion(u8),
+    Malformed,
+}
+
+impl fmt::Display for CustomIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CustomIdError::Foreign => write!(f, "unrecognized custom_id"),
+            CustomIdError::UnsupportedVersion(v) => write!(f, "unsupported custom_id version {}", v),
+            CustomIdError::Malformed => write!(f, "malformed custom_id"),
+        }
+    }
+}
+
+impl std::error::Error for CustomIdError {}
+
+impl CustomId {
+    pub fn new(owner: u64, revision: u64, action: Action) -> Self {
+        Self { owner, revision, action }
+    }
+
+    pub fn encode(&self) -> String {
+        let action = match self.action {
+            Action::NewWord => "new".to_string(),
+            Action::WordModal => "word".to_string(),
+            Action::Letter { index } => format!("letter:{}", index),
+            Action::LetterMenu => "letters".to_string(),
+            Action::Confirm => "confirm".to_string(),
+            Action::EditGuess { index } => format!("edit:{}", index),
+            Action::ImportRow { row } => format!("import-row:{}", row),
+            Action::ImportWords => "import-words".to_string(),
+            Action::ImportModal => "import-modal".to_string(),
+            Action::ExportImage => "export".to_string(),
+            Action::PickGuess => "pick-guess".to_string(),
+            Action::ToggleLocale => "locale".to_string(),
+            Action::Explain { index } => format!("why:{}", index),
+            Action::ShareText => "share-text".to_string(),
+            Action::CancelAnalysis => "cancel".to_string(),
+            Action::PlayGuess { game } => format!("play:{}", game),
+            Action::PlayModal { game } => format!("play-word:{}", game),
+            Action::PlayHint { game } => format!("play-hint:{}", game),
+            Action::DailyGuess { day, played } => format!("daily:{}:{}", day, played.encode()),
+            Action::DailyModal { day, played } => format!("daily-word:{}:{}", day, played.encode()),
+        };
+        format!("{}:{}:{}:{}:{}", PREFIX, FORMAT_VERSION, self.owner, self.revision, action)
+    }
+
+    pub fn decode(raw: &str) -> Result<Self, CustomIdError> {
+        let mut parts = raw.split(':');
+
+        if parts.next() != Some(PREFIX) {
+            return Err(CustomIdError::Foreign);
+        }
+
+        let version: u8 = parts.next()
+            .and_then(|v| v.parse().ok())
+            .ok_or(CustomIdError::Malformed)?;
+        if version != FORMAT_VERSION {
+            return Err(CustomIdError::UnsupportedVersion(version));
+        }
+
+        let owner: u64 = parts.next()
+            .and_then(|s| s.parse().ok())
+            .ok_or(CustomIdError::Malformed)?;
+
+        let revision: u64 = parts.next()
+            .and_then(|s| s.parse().ok())
+            .ok_or(CustomIdError::Malformed)?;
+
+        let action = match (parts.next(), parts.next()) {
+            (Some("new"), None) => Action::NewWord,
+            (Some("word"), None) => Action::WordModal,
+            (Some("letters"), None) => Action::LetterMenu,
+            (Some("confirm"), None) => Action::Confirm,
+            (Some("import-words"), None) => Action::ImportWords,
+            (Some("import-modal"), None) => Action::ImportModal,
+            (Some("export"), None) => Action::ExportImage,
+            (Some("pick-guess"), None) => Action::PickGuess,
+            (Some("locale"), None) => Action::ToggleLocale,
+            (Some("share-text"), None) => Action::ShareText,
+            (Some("cancel"), None) => Action::CancelAnalysis,
+            (Some("letter"), Some(index)) => Action::Letter {
+                index: index.parse().map_err(|_| CustomIdError::Malformed)?,
+            },
+            (Some("edit"), Some(index)) => Action::EditGuess {
+                index: index.parse().map_err(|_| CustomIdError::Malformed)?,
+            },
+            (Some("import-row"), Some(row)) => Action::ImportRow {
+                row: row.parse().map_err(|_| CustomIdError::Malformed)?,
+            },
+            (Some("why"), Some(index)) => Action::Explain {
+                index: index.parse().map_err(|_| CustomIdError::Malformed)?,
+            },
+            (Some("play"), Some(game)) => Action::PlayGuess {
+                game: game.parse().map_err(|_| CustomIdError::Malformed)?,
+            },
+            (Some("play-word"), Some(game)) => Action::PlayModal {
+                game: game.parse().map_err(|_| CustomIdError::Malformed)?,
+            },
+            (Some("play-hint"), Some(game)) => Action::PlayHint {
+                game: game.parse().map_err(|_| CustomIdError::Malformed)?,
+            },
+            (Some("daily"), Some(day)) => Action::DailyGuess {
+                day: day.parse().map_err(|_| CustomIdError::Malformed)?,
+                played: PlayedWords::decode(parts.next().ok_or(CustomIdError::Malformed)?)?,
+            },
+            (Some("daily-word"), Some(day)) => Action::DailyModal {
+                day: day.parse().map_err(|_| CustomIdError::Malformed)?,
+                played: PlayedWords::decode(parts.next().ok_or(CustomIdError::Malformed)?)?,
+            },
+            _ => return Err(CustomIdError::Malformed),
+        };
+
+        if parts.next().is_some() {
+            return Err(CustomIdError::Malformed);
+        }
+
+        Ok(Self { owner, revision, action })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_every_action() {
+        let actions = [
+            Action::NewWord,
+            Action::WordModal,
+            Action::Letter { index: 3 },
+            Action::LetterMenu,
+            Action::Confirm,
+            Action::EditGuess { index: 1 },
+            Action::ImportRow { row: 2 },
+            Action::ImportWords,
+            Action::ImportModal,
+            Action::ExportImage,
+            Action::PickGuess,
+            Action::ToggleLocale,
+            Action::Explain { index: 2 },
+            Action::ShareText,
+            Action::CancelAnalysis,
+            Action::PlayGuess { game: 1700000000000 },
+            Action::PlayModal { game: 1700000000000 },
+            Action::PlayHint { game: u64::MAX },
+            Action::DailyGuess { day: 20000, played: PlayedWords::default() },
+            Action::DailyModal { day: 20000, played: PlayedWords::new(["CRANE", "SLATE"]).unwrap() },
+        ];
+        for action in actions {
+            let id = CustomId::new(123456789012345678, 1700000000000, action);
+            assert_eq!(CustomId::decode(&id.encode()), Ok(id));
+        }
+    }
+
+    #[test]
+    fn daily_ids_carry_the_played_words_within_the_discord_limit() {
+        let words = ["CRANE", "SLATE", "PIOUS", "GHOST", "NYMPH", "FJORD", "WALTZ"];
+        let played = PlayedWords::new(words).unwrap();
+        assert_eq!(played.words(), words);
+        assert_eq!(PlayedWords::new(["CRANE"; 8]), None);
+        assert_eq!(PlayedWords::new(["crane"]), None);
+
+        // custom_id は100文字まで
+        let id = CustomId::new(u64::MAX, u64::MAX, Action::DailyModal { day: 99999, played });
+        assert!(id.encode().len() <= 100, "{}", id.encode());
+        assert_eq!(CustomId::decode(&id.encode()), Ok(id));
+    }
+
+    #[test]
+    fn rejects_legacy_and_foreign_ids() {
+        assert_eq!(CustomId::decode("letter_0_A"), Err(CustomIdError::Foreign));
+        assert_eq!(CustomId::decode("confirm_result"), Err(CustomIdError::Foreign));
+        assert_eq!(CustomId::decode("wht:1:1:new"), Err(CustomIdError::UnsupportedVersion(1)));
+    }
+
+    #[test]
+    fn rejects_malformed_ids() {
+        for raw in ["wht", "wht:2", "wht:2:abc:1:new", "wht:2:1:new", "wht:2:1:1:letter", "wht:2:1:1:letter:x", "wht:2:1:1:new:extra", "wht:2:1:1:play", "wht:2:1:1:daily:1", "wht:2:1:1:daily:1:CRAN", "wht:2:1:1:daily:1:crane"] {
+            assert_eq!(CustomId::decode(raw), Err(CustomIdError::Malformed), "{}", raw);
+        }
+    }
+}
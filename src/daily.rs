@@ -0,0 +1,490 @@
+// /daily: サーバーの全員が同じ単語を当てるデイリー
+// 単語はその日に最初に遊んだときの答えの候補（ストレージに記録する）から、サーバーと日（UTC）で決める。
+// 共有を選んだサーバーは、全サーバー共通の候補から日だけで選んだ単語を使う。
+// 再起動や単語リストの更新があっても同じ単語になる。盤面は本人にだけ表示し、
+// 終えた結果はチャンネルにスポイラーで投稿する。日付が変わるとスケジューラーが答えとまとめを投稿する
+// ゲームとまとめ用の結果はメモリ上に持つ（再起動するとその日のまとめは投稿されない）
+// 終えたメンバーの結果はストレージにも記録し、再起動後に遊び直したり成績に二重に数えたりできないようにする
+// 推測のボタンには日とそれまでの推測を埋め込んでおき、再起動後はそこからその日の盤面を作り直す
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use arc_swap::ArcSwap;
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    ChannelId,
+    Colour,
+    CommandInteraction,
+    CreateAllowedMentions,
+    CreateCommand,
+    CreateEmbed,
+    CreateEmbedFooter,
+    CreateMessage,
+    Http,
+    ModalInteraction,
+    Timestamp,
+};
+use tracing::{info, warn};
+
+use crate::custom_id::{Action, CustomId, PlayedWords};
+use crate::i18n::{self, Locale, Text};
+use crate::play::{play_reply, PlayError, PlayGames, PlayKind, PlayStatus, PlayView};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::settings::Settings;
+use crate::solver::SecretWord;
+use crate::storage::Storage;
+use crate::{forum, results, stats, wordlist, Bot};
+
+// メンバーが終えたデイリーの結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub guesses: usize,
+    pub solved: bool,
+}
+
+// サーバーのその日のデイリー
+struct DailyPuzzle {
+    secret: SecretWord,
+    games: PlayGames,
+    results: BTreeMap<u64, DailyResult>,
+    // 最後に /daily を使ったチャンネル（結果チャンネルがなければここにまとめを投稿する）
+    channel: u64,
+    // 推測できる回数（その日の最初に決めたサーバーの設定）
+    limit: usize,
+}
+
+// 日付が変わって締め切ったデイリー
+pub struct DailySummary {
+    pub guild_id: u64,
+    pub day: u64,
+    pub answer: String,
+    pub channel: u64,
+    pub results: BTreeMap<u64, DailyResult>,
+    pub limit: usize,
+}
+
+#[derive(Default)]
+pub struct DailyPuzzles {
+    // (サーバー, 日) ごとのデイリー
+    puzzles: Mutex<HashMap<(u64, u64), DailyPuzzle>>,
+    http: OnceLock<Arc<Http>>,
+    // フォーラムの投稿を作る処理（同じ日の投稿を二重に作らない）
+    posts: tokio::sync::Mutex<()>,
+}
+
+// その日のサーバーの単語の番号（候補の数から選ぶ。同じサーバーと日なら、再起動しても常に同じ番号）
+pub fn daily_index(guild_id: u64, day: u64, count: usize) -> usize {
+    let hash = wordlist::fnv1a(guild_id.to_le_bytes().into_iter().chain(day.to_le_bytes()));
+    (hash % count.max(1) as u64) as usize
+}
+
+// その日のサーバーの答えの候補を記録する版の名前
+fn pool_version(guild_id: u64, day: u64) -> String {
+    format!("daily-{}-{}", guild_id, day)
+}
+
+// その日の全サーバー共通の答えの候補を記録する版の名前
+fn shared_pool_version(day: u64) -> String {
+    format!("daily-{}", day)
+}
+
+impl DailyPuzzles {
+    // 接続後にHTTPクライアントを登録（それまではまとめを投稿しない）
+    pub fn attach(&self, http: Arc<Http>) {
+        let _ = self.http.set(http);
+    }
+
+    pub(crate) async fn lock_posts(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.posts.lock().await
+    }
+
+    // その日のデイリーを始める（途中のゲームがあれば続きを返す。もう終えていれば None）
+    // secret と limit は今日のデイリーがまだなければ使う
+    pub fn start(&self, guild_id: u64, day: u64, channel: u64, user_id: u64, (secret, limit): (SecretWord, usize), revision: u64) -> Option<PlayView> {
+        let mut puzzles = self.puzzles.lock().unwrap_or_else(|e| e.into_inner());
+        let puzzle = puzzles.entry((guild_id, day)).or_insert_with(|| DailyPuzzle {
+            secret,
+            games: PlayGames::default(),
+            results: BTreeMap::new(),
+            channel,
+            limit,
+        });
+        puzzle.channel = channel;
+        if puzzle.results.contains_key(&user_id) {
+            return None;
+        }
+        // 始め直して推測の回数を増やせないよう、途中のゲームはそのまま続ける
+        if let Some(view) = puzzle.games.view(user_id).filter(|view| view.status == PlayStatus::InProgress) {
+            return Some(view);
+        }
+        Some(puzzle.games.start(user_id, puzzle.secret.clone(), revision, puzzle.limit))
+    }
+
+    // ゲームが残っているか（終えていれば結果が残っている）
+    pub fn has_game(&self, guild_id: u64, day: u64, user_id: u64) -> bool {
+        let puzzles = self.puzzles.lock().unwrap_or_else(|e| e.into_inner());
+        puzzles.get(&(guild_id, day))
+            .is_some_and(|puzzle| puzzle.results.contains_key(&user_id) || puzzle.games.view(user_id).is_some())
+    }
+
+    // 推測の色を判定して記録する（日付が変わったデイリーは NotFound）
+    pub fn guess(&self, guild_id: u64, day: u64, user_id: u64, revision: u64, word: &str, next_revision: u64) -> Result<PlayView, PlayError> {
+        let mut puzzles = self.puzzles.lock().unwrap_or_else(|e| e.into_inner());
+        let puzzle = puzzles.get_mut(&(guild_id, day)).ok_or(PlayError::NotFound)?;
+        let view = puzzle.games.guess(user_id, revision, word, next_revision)?;
+        if view.status != PlayStatus::InProgress {
+            let solved = view.status == PlayStatus::Won;
+            puzzle.results.insert(user_id, DailyResult { guesses: view.guesses.len(), solved });
+        }
+        Ok(view)
+    }
+
+    // 今日より前のデイリーを締め切って取り出す
+    pub fn take_finished(&self, today: u64) -> Vec<DailySummary> {
+        let mut puzzles = self.puzzles.lock().unwrap_or_else(|e| e.into_inner());
+        let finished: Vec<_> = puzzles.keys().filter(|(_, day)| *day < today).copied().collect();
+        finished.into_iter()
+            .filter_map(|key| puzzles.remove(&key).map(|puzzle| (key, puzzle)))
+            .map(|((guild_id, day), puzzle)| DailySummary {
+                guild_id,
+                day,
+                answer: puzzle.secret.reveal(),
+                channel: puzzle.channel,
+                results: puzzle.results,
+                limit: puzzle.limit,
+            })
+            .collect()
+    }
+}
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("daily")
+}
+
+// 正解した人の推測回数の分布（添字は推測回数 - 1。上限の回数までそろえる）
+pub(crate) fn distribution(results: &[DailyResult], limit: usize) -> Vec<u32> {
+    let mut distribution = vec![0; limit];
+    for result in results.iter().filter(|r| r.solved && r.guesses > 0) {
+        if distribution.len() < result.guesses {
+            distribution.resize(result.guesses, 0);
+        }
+        distribution[result.guesses - 1] += 1;
+    }
+    distribution
+}
+
+fn summary_embed(locale: Locale, summary: &DailySummary, results: &[DailyResult], shared: bool) -> CreateEmbed {
+    let solved: Vec<_> = results.iter().filter(|r| r.solved).collect();
+    let average = (!solved.is_empty()).then(|| solved.iter().map(|r| r.guesses).sum::<usize>() as f64 / solved.len() as f64);
+    let mut embed = CreateEmbed::new()
+        .title(locale.text(Text::DailySummaryTitle))
+        .color(Colour::DARK_GREEN)
+        .description(locale.daily_summary(&summary.answer, results.len(), solved.len(), average));
+    if let Ok(timestamp) = Timestamp::from_unix_timestamp((summary.day * 24 * 60 * 60) as i64) {
+        embed = embed.timestamp(timestamp);
+    }
+    if shared {
+        embed = embed.footer(CreateEmbedFooter::new(locale.text(Text::DailyShared)));
+    }
+    if !solved.is_empty() {
+        let chart = stats::distribution_chart(&distribution(results, summary.limit), summary.limit);
+        embed = embed.field(locale.text(Text::StatsDistribution), chart, false);
+    }
+    embed
+}
+
+// 日付が変わったら前日までのデイリーの答えとまとめを投稿する（スケジューラーから呼ぶ）
+// 結果チャンネルがフォーラムなら、その日の投稿に返信する
+pub async fn post_summaries(daily: Arc<DailyPuzzles>, settings: Arc<ArcSwap<Settings>>, storage: Arc<dyn Storage>) -> anyhow::Result<()> {
+    // 接続前は締め切らずに次の機会を待つ
+    let Some(http) = daily.http.get().cloned() else {
+        return Ok(());
+    };
+
+    let mut failed = 0;
+    for summary in daily.take_finished(stats::today()) {
+        let settings = settings.load();
+        let locale = settings.locale_for(Some(summary.guild_id));
+        // 成績を公開しないメンバーはまとめに含めない
+        let results: Vec<_> = summary.results.iter()
+            .filter(|(user_id, _)| !settings.is_private(**user_id))
+            .map(|(_, result)| *result)
+            .collect();
+
+        let shared = settings.is_shared_daily(summary.guild_id);
+        let message = CreateMessage::new().embed(summary_embed(locale, &summary, &results, shared));
+        let posted = match settings.results_forum(summary.guild_id) {
+            Some(forum) => {
+                let place = (summary.guild_id, summary.day, forum);
+                forum::post_reply(&daily, &http, storage.as_ref(), &settings, place, message).await
+            }
+            None => {
+                let channel = ChannelId::new(settings.results_channels.get(&summary.guild_id).copied().unwrap_or(summary.channel));
+                channel.send_message(&http, message).await.map(|_| channel).map_err(Into::into)
+            }
+        };
+        match posted {
+            Ok(channel) => info!("Posted the daily summary of guild {} to channel {}", summary.guild_id, channel),
+            Err(e) => {
+                warn!("Failed to post the daily summary of guild {}: {}", summary.guild_id, e);
+                failed += 1;
+            }
+        }
+    }
+    anyhow::ensure!(failed == 0, "failed to post {} daily summaries", failed);
+    Ok(())
+}
+
+impl Bot {
+    // その日のサーバーの答えの候補
+    // 最初に選んだときの候補をストレージに記録し、単語リストや禁止する単語が変わっても同じ候補から選ぶ
+    async fn daily_pool(&self, guild_id: u64, day: u64) -> Vec<String> {
+        let version = pool_version(guild_id, day);
+        match self.storage.load_wordlist_version(&version).await {
+            Ok(Some(pool)) if !pool.is_empty() => pool,
+            Ok(_) => {
+                let pool = self.fresh_daily_pool(guild_id, day).await;
+                self.pin_daily_pool(&version, pool).await
+            }
+            Err(e) => {
+                // 記録を読めなければ現在の候補から選ぶ（記録した候補と同じとは限らない）
+                warn!("Failed to load the daily answers of guild {}: {:?}", guild_id, e);
+                self.fresh_daily_pool(guild_id, day).await
+            }
+        }
+    }
+
+    // まだ記録していない日の候補
+    // 共有するサーバーは全サーバー共通の候補から日だけで選んだ1語を候補にする（設定を切り替えてもその日の単語は変わらない）
+    async fn fresh_daily_pool(&self, guild_id: u64, day: u64) -> Vec<String> {
+        if !self.settings().is_shared_daily(guild_id) {
+            return self.answer_pool(Some(guild_id)).await;
+        }
+        let version = shared_pool_version(day);
+        let shared = match self.storage.load_wordlist_version(&version).await {
+            Ok(Some(pool)) if !pool.is_empty() => pool,
+            _ => self.pin_daily_pool(&version, self.answer_pool(None).await).await,
+        };
+        shared.get(daily_index(0, day, shared.len())).cloned().into_iter().collect()
+    }
+
+    // 候補を記録して、記録した候補を返す（同時に記録したときは先に記録された候補を使う）
+    async fn pin_daily_pool(&self, version: &str, pool: Vec<String>) -> Vec<String> {
+        if pool.is_empty() {
+            return pool;
+        }
+        if let Err(e) = self.storage.record_wordlist_version(version, &pool).await {
+            warn!("Failed to record the daily answers {}: {:?}", version, e);
+            return pool;
+        }
+        match self.storage.load_wordlist_version(version).await {
+            Ok(Some(pinned)) if !pinned.is_empty() => pinned,
+            _ => pool,
+        }
+    }
+
+    // その日のサーバーの答えと推測できる回数
+    async fn daily_secret(&self, guild_id: u64, day: u64) -> Result<(SecretWord, usize), PlayError> {
+        let pool = self.daily_pool(guild_id, day).await;
+        let secret = pool.get(daily_index(guild_id, day, pool.len())).map(|word| SecretWord::new(word)).ok_or(PlayError::NotFound)?;
+        Ok((secret, self.settings().daily_guess_limit(guild_id, day).guesses()))
+    }
+
+    // 記録したその日の結果（読めなければ遊べるよう None として扱う）
+    async fn stored_daily_result(&self, guild_id: u64, day: u64, user_id: u64) -> Option<DailyResult> {
+        match self.storage.load_daily_result(guild_id, day, user_id).await {
+            Ok(result) => result.and_then(|value| serde_json::from_value(value).ok()),
+            Err(e) => {
+                warn!("Failed to load the daily result of {} in guild {}: {:?}", user_id, guild_id, e);
+                None
+            }
+        }
+    }
+
+    // 終えた結果を記録する（すでに記録があれば false。記録できなければ成績に数えるよう true）
+    async fn save_daily_result(&self, guild_id: u64, day: u64, user_id: u64, result: DailyResult) -> bool {
+        let value = serde_json::to_value(result).unwrap_or_default();
+        match self.storage.record_daily_result(guild_id, day, user_id, value).await {
+            Ok(recorded) => recorded,
+            Err(e) => {
+                warn!("Failed to record the daily result of {} in guild {}: {:?}", user_id, guild_id, e);
+                true
+            }
+        }
+    }
+
+    // 今日のデイリーを始める（単語リストがなければ Err、もう終えていれば Ok(None)）
+    pub async fn start_daily(&self, guild_id: u64, channel: u64, user_id: u64) -> Result<Option<PlayView>, PlayError> {
+        let day = stats::today();
+        // 再起動の前に終えていれば、メモリに残っていなくても遊び直せない
+        if !self.daily.has_game(guild_id, day, user_id) && self.stored_daily_result(guild_id, day, user_id).await.is_some() {
+            return Ok(None);
+        }
+        let secret = self.daily_secret(guild_id, day).await?;
+        Ok(self.daily.start(guild_id, day, channel, user_id, secret, self.next_revision()))
+    }
+
+    // 再起動などでゲームがなくなっていたら、ボタンに埋め込んだ推測からその日の盤面を作り直す
+    // （リビジョンはボタンのものに揃える。日付が変わったボタンは NotFound）
+    pub async fn restore_daily(&self, guild_id: u64, day: u64, channel: u64, user_id: u64, revision: u64, played: PlayedWords) -> Result<(), PlayError> {
+        if day != stats::today() {
+            return Err(PlayError::NotFound);
+        }
+        if self.daily.has_game(guild_id, day, user_id) {
+            return Ok(());
+        }
+        if self.stored_daily_result(guild_id, day, user_id).await.is_some() {
+            return Err(PlayError::Finished);
+        }
+        let secret = self.daily_secret(guild_id, day).await?;
+        if self.daily.start(guild_id, day, channel, user_id, secret, revision).is_some() {
+            for word in played.words() {
+                self.daily.guess(guild_id, day, user_id, revision, &word, revision)?;
+            }
+            info!("Restored the daily puzzle of guild {} for {} from {} guesses", guild_id, user_id, played.words().len());
+        }
+        Ok(())
+    }
+
+    // デイリーの推測を受け付ける（終えたゲームは結果を記録し、初めて記録したときだけ成績にも記録する）
+    pub async fn daily_guess(&self, guild_id: u64, user_id: u64, revision: u64, word: &str) -> Result<PlayView, PlayError> {
+        let word = word.trim().to_uppercase();
+        if !self.is_known_play_word(&word).await {
+            return Err(PlayError::UnknownWord);
+        }
+        let day = stats::today();
+        let view = self.daily.guess(guild_id, day, user_id, revision, &word, self.next_revision())?;
+        if view.status != PlayStatus::InProgress {
+            let result = DailyResult { guesses: view.guesses.len(), solved: view.status == PlayStatus::Won };
+            if self.save_daily_result(guild_id, day, user_id, result).await {
+                self.record_play_result(user_id, Some(guild_id), &view).await;
+            }
+        }
+        Ok(view)
+    }
+
+    pub(crate) async fn handle_daily_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let user_id = command.user.id.get();
+        let Some(guild_id) = command.guild_id else {
+            self.responder(ctx, command, ReplyMode::Ephemeral)
+                .send(Reply::content(locale.text(Text::DailyGuildOnly)))
+                .await;
+            return;
+        };
+
+        // ほかのメンバーに盤面が見えないよう、本人にだけ表示する
+        let reply = match self.start_daily(guild_id.get(), command.channel_id.get(), user_id).await {
+            Ok(Some(view)) => {
+                info!("Started the daily puzzle of guild {} for {}", guild_id, user_id);
+                play_reply(locale, &view, PlayKind::Daily { day: stats::today() })
+            }
+            Ok(None) => Reply::content(locale.text(Text::DailyAlreadyPlayed)),
+            Err(_) => Reply::content(locale.text(Text::PlayNoWords)),
+        };
+        self.responder(ctx, command, ReplyMode::Ephemeral).send(reply).await;
+    }
+
+    // 推測の色を判定して盤面を更新し、終えたらチャンネルに結果をスポイラーで投稿する
+    pub(crate) async fn submit_daily_guess(&self, ctx: &ReplyContext, modal: &ModalInteraction, locale: Locale, id: CustomId, word: &str) {
+        let Some(guild_id) = modal.guild_id else {
+            self.responder(ctx, modal, ReplyMode::Ephemeral)
+                .send(Reply::content(locale.text(Text::DailyGuildOnly)))
+                .await;
+            return;
+        };
+        let Action::DailyModal { day, played } = id.action else {
+            return;
+        };
+        let restored = self.restore_daily(guild_id.get(), day, modal.channel_id.get(), id.owner, id.revision, played).await;
+        let guessed = match restored {
+            Ok(()) => self.daily_guess(guild_id.get(), id.owner, id.revision, word).await,
+            Err(e) => Err(e),
+        };
+        let view = match guessed {
+            Ok(view) => view,
+            Err(e) => {
+                self.responder(ctx, modal, ReplyMode::Ephemeral)
+                    .send(Reply::content(locale.play_error(e)))
+                    .await;
+                return;
+            }
+        };
+        self.responder(ctx, modal, ReplyMode::UpdateMessage)
+            .send(play_reply(locale, &view, PlayKind::Daily { day }))
+            .await;
+
+        if view.status == PlayStatus::InProgress || self.settings().is_private(id.owner) {
+            return;
+        }
+        let solved = view.status == PlayStatus::Won;
+        let spoilers: Vec<String> = view.guesses.iter()
+            .zip(results::pattern_rows(&view.guesses))
+            .map(|(guess, row)| format!("||{} `{}`||", row, guess.word))
+            .collect();
+        let message = CreateMessage::new()
+            .content(format!("{}\n{}", locale.daily_result(id.owner, solved.then_some(view.guesses.len()), view.limit), spoilers.join("\n")))
+            .allowed_mentions(CreateAllowedMentions::new());
+        // 結果チャンネルがフォーラムなら、その日の投稿に返信してスコアボードを更新する
+        let settings = self.settings();
+        let posted = match settings.results_forum(guild_id.get()) {
+            Some(forum) => {
+                let place = (guild_id.get(), day, forum);
+                match forum::post_reply(&self.daily, ctx.http(), self.storage.as_ref(), &settings, place, message).await {
+                    Ok(thread) => forum::update_scoreboard(ctx.http(), self.storage.as_ref(), &settings, guild_id.get(), day, thread).await,
+                    Err(e) => Err(e),
+                }
+            }
+            None => modal.channel_id.send_message(ctx.http(), message).await.map(|_| ()).map_err(Into::into),
+        };
+        if let Err(e) = posted {
+            warn!("Failed to post the daily result of {}: {:?}", id.owner, e);
+        }
+        self.forward_daily_result(guild_id, id.owner, day, &view).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_member_plays_the_same_word_once_a_day() {
+        let daily = DailyPuzzles::default();
+        // 同じサーバーと日なら、プロセスやRustの版によらず常に同じ番号になる
+        assert_eq!(daily_index(1, 20000, 100), 38);
+        assert_eq!(daily_index(1, 20000, 7), 4);
+
+        daily.start(1, 10, 5, 100, (SecretWord::new("CRANE"), 6), 0).unwrap();
+        // 2人目は渡した単語・上限ではなく、その日のサーバーの単語・上限で遊ぶ
+        assert_eq!(daily.start(1, 10, 5, 200, (SecretWord::new("SLATE"), 4), 0).unwrap().limit, 6);
+        assert_eq!(daily.guess(1, 10, 200, 0, "CRANE", 1).unwrap().status, PlayStatus::Won);
+
+        // 途中のゲームは始め直さず、終えたら次の日まで遊べない
+        daily.guess(1, 10, 100, 0, "SLATE", 1).unwrap();
+        assert_eq!(daily.start(1, 10, 5, 100, (SecretWord::new("CRANE"), 6), 2).unwrap().guesses.len(), 1);
+        assert!(daily.start(1, 10, 5, 200, (SecretWord::new("CRANE"), 6), 2).is_none());
+        // 日付が変わったデイリーには推測できない
+        assert_eq!(daily.guess(1, 11, 100, 1, "CRANE", 2), Err(PlayError::NotFound));
+
+        assert!(daily.take_finished(10).is_empty());
+        let summaries = daily.take_finished(11);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!((summaries[0].answer.as_str(), summaries[0].limit), ("CRANE", 6));
+        assert_eq!(summaries[0].results.get(&200), Some(&DailyResult { guesses: 1, solved: true }));
+        assert!(!summaries[0].results.contains_key(&100));
+        assert!(daily.take_finished(11).is_empty());
+    }
+
+    #[test]
+    fn distribution_counts_only_solved_results() {
+        let results = [
+            DailyResult { guesses: 3, solved: true },
+            DailyResult { guesses: 3, solved: true },
+            DailyResult { guesses: 6, solved: false },
+            DailyResult { guesses: 5, solved: true },
+        ];
+        assert_eq!(distribution(&results, 6), vec![0, 0, 2, 0, 1, 0]);
+        assert_eq!(distribution(&results, 8), vec![0, 0, 2, 0, 1, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,227 @@
+// /define: 単語の意味・発音・品詞を表示する（進行中のゲームとは独立）
+use anyhow::Context as _;
+use serde::Deserialize;
+use serenity::all::{
+    AutocompleteChoice,
+    CommandInteraction,
+    CommandOptionType,
+    Colour,
+    CreateAutocompleteResponse,
+    CreateCommand,
+    CreateCommandOption,
+    CreateEmbed,
+    ResolvedValue,
+};
+use tracing::info;
+
+use crate::i18n::{self, Locale, Text};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::Bot;
+
+const DICTIONARY_API_URL: &str = "https://api.dictionaryapi.dev/api/v2/entries/en";
+
+// Discordの候補表示の上限
+pub(crate) const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+// 品詞ごとに表示する意味の数と、表示する品詞の数
+const DEFINITIONS_PER_PART: usize = 3;
+const MAX_PARTS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub word: String,
+    pub phonetic: Option<String>,
+    pub meanings: Vec<Meaning>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Meaning {
+    pub part_of_speech: String,
+    pub definitions: Vec<String>,
+}
+
+// Free Dictionary API の応答（必要な項目のみ）
+#[derive(Deserialize)]
+struct Entry {
+    word: String,
+    phonetic: Option<String>,
+    #[serde(default)]
+    phonetics: Vec<Phonetic>,
+    #[serde(default)]
+    meanings: Vec<EntryMeaning>,
+}
+
+#[derive(Deserialize)]
+struct Phonetic {
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EntryMeaning {
+    part_of_speech: String,
+    #[serde(default)]
+    definitions: Vec<EntryDefinition>,
+}
+
+#[derive(Deserialize)]
+struct EntryDefinition {
+    definition: String,
+}
+
+// 複数の項目に分かれている場合は1つにまとめ、同じ品詞の意味は合わせる
+fn parse_entries(entries: Vec<Entry>) -> Option<Definition> {
+    let word = entries.first()?.word.clone();
+    let phonetic = entries.iter()
+        .flat_map(|e| e.phonetic.iter().chain(e.phonetics.iter().filter_map(|p| p.text.as_ref())))
+        .find(|text| !text.is_empty())
+        .cloned();
+
+    let mut meanings: Vec<Meaning> = Vec::new();
+    for meaning in entries.into_iter().flat_map(|e| e.meanings) {
+        let definitions = meaning.definitions.into_iter().map(|d| d.definition);
+        match meanings.iter_mut().find(|m| m.part_of_speech == meaning.part_of_speech) {
+            Some(existing) => existing.definitions.extend(definitions),
+            None => meanings.push(Meaning { part_of_speech: meaning.part_of_speech, definitions: definitions.collect() }),
+        }
+    }
+
+    Some(Definition { word, phonetic, meanings })
+}
+
+pub struct Dictionary {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl Dictionary {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client, url: DICTIONARY_API_URL.to_string() }
+    }
+
+    // 見つからない単語は None
+    pub async fn lookup(&self, word: &str) -> anyhow::Result<Option<Definition>> {
+        let url = format!("{}/{}", self.url, word);
+        let response = self.client.get(&url)
+            .send()
+            .await
+            .context("Failed to send request to the dictionary API")?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Dictionary API returned {}", response.status()));
+        }
+
+        let entries: Vec<Entry> = response.json().await.context("Failed to parse dictionary response")?;
+        Ok(parse_entries(entries))
+    }
+}
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("define")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "word", "調べる単語")
+                .required(true)
+                .set_autocomplete(true),
+        )
+}
+
+fn render(locale: Locale, definition: &Definition) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title(format!("📖 {}", definition.word))
+        .color(Colour::BLUE);
+    if let Some(phonetic) = &definition.phonetic {
+        embed = embed.description(format!("{}: {}", locale.text(Text::Pronunciation), phonetic));
+    }
+
+    for meaning in definition.meanings.iter().take(MAX_PARTS) {
+        let lines: Vec<String> = meaning.definitions.iter()
+            .take(DEFINITIONS_PER_PART)
+            .enumerate()
+            .map(|(i, d)| format!("{}. {}", i + 1, d))
+            .collect();
+        // フィールドの値は1024文字まで
+        let value: String = lines.join("\n").chars().take(1024).collect();
+        embed = embed.field(&meaning.part_of_speech, value, false);
+    }
+    embed
+}
+
+impl Bot {
+    pub(crate) async fn handle_define_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let word = command.data.options().iter()
+            .find_map(|o| match o.value {
+                ResolvedValue::String(s) if o.name == "word" => Some(s.trim().to_lowercase()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let mut responder = self.responder(ctx, command, ReplyMode::NewMessage);
+        if word.is_empty() || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+            responder.send(Reply::content(locale.text(Text::DefinitionNotFound))).await;
+            return;
+        }
+
+        // 辞書APIの応答が遅い場合は自動で保留される
+        responder
+            .run(async {
+                match self.dictionary.lookup(&word).await {
+                    Ok(Some(definition)) => Reply::embed(render(locale, &definition)),
+                    Ok(None) => {
+                        info!("No definition found for {:?}", word);
+                        Reply::content(locale.text(Text::DefinitionNotFound))
+                    }
+                    Err(e) => {
+                        self.reporter.report("Failed to look up definition", &e).await;
+                        Reply::content(locale.text(Text::DefinitionFailed))
+                    }
+                }
+            })
+            .await;
+    }
+
+    // 入力中の文字で始まる単語を辞書（単語キャッシュ）から候補として返す
+    pub(crate) async fn handle_define_autocomplete(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let prefix = command.data.autocomplete().map_or(String::new(), |o| o.value.trim().to_lowercase());
+
+        let choices: Vec<AutocompleteChoice> = {
+            let words = self.word_cache.read().await;
+            words.iter()
+                .filter(|w| w.starts_with(&prefix))
+                .take(MAX_AUTOCOMPLETE_CHOICES)
+                .map(|w| AutocompleteChoice::new(w, w))
+                .collect()
+        };
+
+        self.responder(ctx, command, ReplyMode::NewMessage)
+            .autocomplete(CreateAutocompleteResponse::new().set_choices(choices))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_merged_by_part_of_speech() {
+        let json = r#"[
+            {"word": "crane", "phonetics": [{"audio": ""}, {"text": "/kɹeɪn/"}],
+             "meanings": [{"partOfSpeech": "noun", "definitions": [{"definition": "A large bird."}]}]},
+            {"word": "crane", "meanings": [
+                {"partOfSpeech": "noun", "definitions": [{"definition": "A lifting machine."}]},
+                {"partOfSpeech": "verb", "definitions": [{"definition": "To stretch one's neck."}]}
+            ]}
+        ]"#;
+
+        let definition = parse_entries(serde_json::from_str(json).unwrap()).unwrap();
+
+        assert_eq!(definition.phonetic.as_deref(), Some("/kɹeɪn/"));
+        assert_eq!(definition.meanings.len(), 2);
+        assert_eq!(definition.meanings[0].definitions, vec!["A large bird.", "A lifting machine."]);
+        assert_eq!(definition.meanings[1].part_of_speech, "verb");
+    }
+}
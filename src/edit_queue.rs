@@ -0,0 +1,114 @@
+// 盤面メッセージの編集をメッセージごとにまとめて送る
+// 色の切り替えを連打すると編集が立て続けに発生してレート制限に当たるため、
+// 短い待ち時間の間に届いた編集は最新のものだけを送り、429は待ってから再送する
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serenity::all::MessageId;
+use serenity::http::HttpError;
+use tracing::{info, warn, Instrument};
+
+use crate::respond::{Reply, Responder};
+
+// 同じメッセージへの編集をまとめる待ち時間
+pub const DEFAULT_EDIT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+// レート制限時の再送回数と最初の待ち時間（以降は倍にする）
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+struct PendingEdit {
+    responder: Responder,
+    reply: Reply,
+}
+
+pub struct EditQueue {
+    debounce: Duration,
+    // 送信待ちの編集（キーがあれば送信タスクが動いている）
+    pending: Mutex<HashMap<MessageId, Option<PendingEdit>>>,
+}
+
+impl EditQueue {
+    pub fn new(debounce: Duration) -> Self {
+        Self { debounce, pending: Mutex::new(HashMap::new()) }
+    }
+
+    // 編集を予約する（応答済みの Responder を渡す）
+    // 送信前に同じメッセージへの編集が届いた場合、古い編集は破棄される
+    pub fn submit(self: &Arc<Self>, message: MessageId, responder: Responder, reply: Reply) {
+        let edit = PendingEdit { responder, reply };
+        let start_worker = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            match pending.insert(message, Some(edit)) {
+                Some(Some(_)) => {
+                    info!("Coalesced pending edit for message {}", message);
+                    false
+                }
+                Some(None) => false,
+                None => true,
+            }
+        };
+
+        if start_worker {
+            let queue = Arc::clone(self);
+            tokio::spawn(async move { queue.flush(message).await }.instrument(tracing::Span::current()));
+        }
+    }
+
+    fn take(&self, message: MessageId) -> Option<PendingEdit> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let edit = pending.get_mut(&message).and_then(Option::take);
+        if edit.is_none() {
+            pending.remove(&message);
+        }
+        edit
+    }
+
+    // 待ち時間ごとに最新の編集を1つずつ送り、送るものがなくなったら終了する
+    async fn flush(&self, message: MessageId) {
+        loop {
+            tokio::time::sleep(self.debounce).await;
+            let Some(edit) = self.take(message) else {
+                return;
+            };
+            self.send(message, edit).await;
+        }
+    }
+
+    async fn send(&self, message: MessageId, mut edit: PendingEdit) {
+        let mut delay = INITIAL_RETRY_DELAY;
+        let mut retries = 0;
+
+        loop {
+            let error = match edit.responder.try_send(edit.reply.clone()).await {
+                Ok(()) => return,
+                Err(e) => e,
+            };
+
+            if !is_rate_limited(&error) || retries >= MAX_RATE_LIMIT_RETRIES {
+                edit.responder.report("Cannot edit board message", &error).await;
+                return;
+            }
+
+            // 429の応答から待ち時間は取得できないため、段階的に待ち時間を延ばす
+            warn!("Rate limited while editing message {}; retrying in {:?}", message, delay);
+            tokio::time::sleep(delay).await;
+            retries += 1;
+            delay *= 2;
+
+            // 待っている間に新しい編集が届いていれば、そちらを送る
+            let newer = self.pending.lock().unwrap_or_else(|e| e.into_inner()).get_mut(&message).and_then(Option::take);
+            if let Some(newer) = newer {
+                edit = newer;
+            }
+        }
+    }
+}
+
+fn is_rate_limited(error: &serenity::Error) -> bool {
+    matches!(
+        error,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response)) if response.status_code.as_u16() == 429
+    )
+}
@@ -0,0 +1,172 @@
+// 盤面のマスの表示方法と、その段階的なフォールバック
+// サーバーごとに順番を決め、先頭から順に使えるものを使う
+// （カスタム絵文字 → アプリケーション絵文字 → Unicodeの色のマス → テキスト）
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::LetterResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmojiTier {
+    // Supabaseの emojis テーブルに登録したサーバーのカスタム絵文字
+    Custom,
+    // Developer Portalでアプリケーションに登録した絵文字（どのサーバーでも使える）
+    Application,
+    // ⬜🟨🟩 と文字
+    Unicode,
+    // 絵文字を使わない表記（[A] は緑、(A) は黄、小文字は灰）
+    Text,
+}
+
+pub const DEFAULT_CHAIN: [EmojiTier; 4] = [EmojiTier::Custom, EmojiTier::Application, EmojiTier::Unicode, EmojiTier::Text];
+
+impl EmojiTier {
+    pub const ALL: [EmojiTier; 4] = DEFAULT_CHAIN;
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EmojiTier::Custom => "custom",
+            EmojiTier::Application => "application",
+            EmojiTier::Unicode => "unicode",
+            EmojiTier::Text => "text",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tier| tier.name() == value.trim().to_lowercase())
+    }
+}
+
+// "custom,unicode" のような指定を読む（テキストはどの環境でも表示できるため、指定がなくても最後に加える）
+pub fn parse_chain(input: &str) -> Result<Vec<EmojiTier>, String> {
+    let mut chain = Vec::new();
+    for name in input.split(|c: char| c == ',' || c == '>' || c.is_whitespace()).filter(|s| !s.is_empty()) {
+        let Some(tier) = EmojiTier::parse(name) else {
+            let names: Vec<_> = EmojiTier::ALL.iter().map(|t| format!("`{}`", t.name())).collect();
+            return Err(format!("`{}` は指定できません（{} をカンマ区切りで指定してください）", name, names.join(", ")));
+        };
+        if !chain.contains(&tier) {
+            chain.push(tier);
+        }
+    }
+    if chain.is_empty() {
+        return Err("1つ以上指定してください".to_string());
+    }
+    if !chain.contains(&EmojiTier::Text) {
+        chain.push(EmojiTier::Text);
+    }
+    Ok(chain)
+}
+
+pub fn describe_chain(chain: &[EmojiTier]) -> String {
+    chain.iter().map(|tier| tier.name()).collect::<Vec<_>>().join(" → ")
+}
+
+fn color_name(result: &LetterResult) -> &'static str {
+    match result {
+        LetterResult::Gray => "gray",
+        LetterResult::Yellow => "yellow",
+        LetterResult::Green => "green",
+    }
+}
+
+fn square(result: &LetterResult) -> char {
+    match result {
+        LetterResult::Gray => '⬜',
+        LetterResult::Yellow => '🟨',
+        LetterResult::Green => '🟩',
+    }
+}
+
+// 絵文字の名前からDiscordの表記への対応（カスタム絵文字とアプリケーション絵文字）
+pub struct EmojiSources<'a> {
+    pub custom: &'a HashMap<String, String>,
+    pub application: &'a HashMap<String, String>,
+}
+
+impl EmojiSources<'_> {
+    fn lookup(&self, tier: EmojiTier, name: &str) -> Option<String> {
+        match tier {
+            EmojiTier::Custom => self.custom.get(name).cloned(),
+            EmojiTier::Application => self.application.get(name).cloned(),
+            EmojiTier::Unicode | EmojiTier::Text => None,
+        }
+    }
+
+    // 文字入りのマス（絵文字の名前は "a_green" の形式）
+    pub fn letter_tile(&self, chain: &[EmojiTier], letter: char, result: &LetterResult) -> String {
+        let name = format!("{}_{}", letter.to_ascii_lowercase(), color_name(result));
+        for tier in chain {
+            match tier {
+                EmojiTier::Custom | EmojiTier::Application => {
+                    if let Some(emoji) = self.lookup(*tier, &name) {
+                        return emoji;
+                    }
+                }
+                EmojiTier::Unicode => return format!("{}{}", square(result), letter),
+                EmojiTier::Text => return text_tile(letter, result),
+            }
+        }
+        text_tile(letter, result)
+    }
+
+    // 文字のないマス（共有用の結果。絵文字の名前は "tile_green" の形式）
+    pub fn blank_tile(&self, chain: &[EmojiTier], result: &LetterResult) -> String {
+        let name = format!("tile_{}", color_name(result));
+        for tier in chain {
+            match tier {
+                EmojiTier::Custom | EmojiTier::Application => {
+                    if let Some(emoji) = self.lookup(*tier, &name) {
+                        return emoji;
+                    }
+                }
+                EmojiTier::Unicode => return square(result).to_string(),
+                EmojiTier::Text => return text_blank(result).to_string(),
+            }
+        }
+        text_blank(result).to_string()
+    }
+}
+
+fn text_tile(letter: char, result: &LetterResult) -> String {
+    let letter = letter.to_ascii_uppercase();
+    match result {
+        LetterResult::Gray => format!(" {} ", letter.to_ascii_lowercase()),
+        LetterResult::Yellow => format!("({})", letter),
+        LetterResult::Green => format!("[{}]", letter),
+    }
+}
+
+fn text_blank(result: &LetterResult) -> char {
+    match result {
+        LetterResult::Gray => '-',
+        LetterResult::Yellow => 'Y',
+        LetterResult::Green => 'G',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_through_the_chain() {
+        let custom = HashMap::from([("a_green".to_string(), "<:a_green:1>".to_string())]);
+        let application = HashMap::from([("b_green".to_string(), "<:b_green:2>".to_string())]);
+        let sources = EmojiSources { custom: &custom, application: &application };
+
+        assert_eq!(sources.letter_tile(&DEFAULT_CHAIN, 'A', &LetterResult::Green), "<:a_green:1>");
+        assert_eq!(sources.letter_tile(&DEFAULT_CHAIN, 'B', &LetterResult::Green), "<:b_green:2>");
+        assert_eq!(sources.letter_tile(&DEFAULT_CHAIN, 'C', &LetterResult::Yellow), "🟨C");
+
+        let chain = parse_chain("application, text").unwrap();
+        assert_eq!(chain, vec![EmojiTier::Application, EmojiTier::Text]);
+        assert_eq!(sources.letter_tile(&chain, 'A', &LetterResult::Green), "[A]");
+        assert_eq!(sources.blank_tile(&chain, &LetterResult::Yellow), "Y");
+
+        assert_eq!(parse_chain("custom").unwrap(), vec![EmojiTier::Custom, EmojiTier::Text]);
+        assert!(parse_chain("sparkles").is_err());
+    }
+}
@@ -0,0 +1,143 @@
+// 結果チャンネルがフォーラムのサーバーの /daily
+// 日ごとに1つの投稿を作ってメンバーの結果を返信として集め、投稿の最初のメッセージをスコアボードとして固定し、結果が届くたびに更新する
+// 投稿はストレージに記録し、再起動後やほかのインスタンスからも同じ投稿を使う
+use std::sync::Arc;
+
+use anyhow::Context;
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateForumPost, CreateMessage, EditMessage, Http, MessageId, Timestamp};
+use tracing::{info, warn};
+
+use crate::daily::{self, DailyPuzzles, DailyResult};
+use crate::i18n::{Locale, Text};
+use crate::settings::Settings;
+use crate::stats;
+use crate::storage::Storage;
+
+// スコアボードに並べる人数の上限（Embedの説明の文字数に収める）
+const SCOREBOARD_PLAYERS: usize = 50;
+
+// 成績を公開するメンバーの記録した結果（正解した人を推測回数の少ない順に、当てられなかった人はその後に）
+async fn visible_results(storage: &dyn Storage, settings: &Settings, guild_id: u64, day: u64) -> anyhow::Result<Vec<(u64, DailyResult)>> {
+    let mut results: Vec<(u64, DailyResult)> = storage.load_daily_results(guild_id, day).await?
+        .into_iter()
+        .filter(|(user_id, _)| !settings.is_private(*user_id))
+        .filter_map(|(user_id, result)| serde_json::from_value(result).ok().map(|result| (user_id, result)))
+        .collect();
+    rank(&mut results);
+    Ok(results)
+}
+
+fn rank(results: &mut [(u64, DailyResult)]) {
+    results.sort_by_key(|(user_id, result)| (!result.solved, result.guesses, *user_id));
+}
+
+// スコアボードの1人1行（上限を超えた分は人数だけ）
+fn scoreboard_lines(locale: Locale, results: &[(u64, DailyResult)], limit: usize) -> Vec<String> {
+    let mut lines: Vec<String> = results.iter()
+        .take(SCOREBOARD_PLAYERS)
+        .map(|(user_id, result)| {
+            let score = if result.solved { result.guesses.to_string() } else { "X".to_string() };
+            format!("<@{}> {}/{}", user_id, score, limit)
+        })
+        .collect();
+    if results.len() > SCOREBOARD_PLAYERS {
+        lines.push(locale.more_players(results.len() - SCOREBOARD_PLAYERS));
+    }
+    if lines.is_empty() {
+        lines.push(locale.text(Text::DailyScoreboardEmpty).to_string());
+    }
+    lines
+}
+
+fn scoreboard_embed(locale: Locale, day: u64, results: &[(u64, DailyResult)], limit: usize) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title(locale.text(Text::DailyScoreboardTitle))
+        .color(Colour::DARK_GREEN)
+        .description(scoreboard_lines(locale, results, limit).join("\n"));
+    if let Ok(timestamp) = Timestamp::from_unix_timestamp((day * 24 * 60 * 60) as i64) {
+        embed = embed.timestamp(timestamp);
+    }
+    if results.iter().any(|(_, result)| result.solved) {
+        let results: Vec<DailyResult> = results.iter().map(|(_, result)| *result).collect();
+        let chart = stats::distribution_chart(&daily::distribution(&results, limit), limit);
+        embed = embed.field(locale.text(Text::StatsDistribution), chart, false);
+    }
+    embed
+}
+
+async fn scoreboard(storage: &dyn Storage, settings: &Settings, guild_id: u64, day: u64) -> anyhow::Result<CreateEmbed> {
+    let locale = settings.locale_for(Some(guild_id));
+    let limit = settings.daily_guess_limit(guild_id, day).guesses();
+    let results = visible_results(storage, settings, guild_id, day).await?;
+    Ok(scoreboard_embed(locale, day, &results, limit))
+}
+
+// その日の投稿（まだなければスコアボードを最初のメッセージにして作り、固定する）
+async fn daily_post(daily: &DailyPuzzles, http: &Arc<Http>, storage: &dyn Storage, settings: &Settings, guild_id: u64, day: u64, forum: u64) -> anyhow::Result<ChannelId> {
+    // このインスタンスで同時に2つ作らないよう、確認から記録までを1つずつ行う
+    let _posting = daily.lock_posts().await;
+    if let Some(thread) = storage.load_daily_post(guild_id, day).await? {
+        return Ok(ChannelId::new(thread));
+    }
+
+    let starter = CreateMessage::new().embed(scoreboard(storage, settings, guild_id, day).await?);
+    let thread = ChannelId::new(forum)
+        .create_forum_post(http, CreateForumPost::new(format!("Daily Wordle {}", day), starter))
+        .await?;
+    if !storage.record_daily_post(guild_id, day, thread.id.get()).await? {
+        // ほかのインスタンスが先に作っていれば、そちらを使って作った投稿は消す
+        if let Err(e) = thread.id.delete(http).await {
+            warn!("Failed to delete the duplicate daily post of guild {}: {}", guild_id, e);
+        }
+        let existing = storage.load_daily_post(guild_id, day).await?.context("the recorded daily post is missing")?;
+        return Ok(ChannelId::new(existing));
+    }
+
+    // 投稿の最初のメッセージのIDはスレッドと同じ
+    if let Err(e) = thread.id.pin(http, MessageId::new(thread.id.get())).await {
+        warn!("Failed to pin the daily scoreboard of guild {}: {}", guild_id, e);
+    }
+    info!("Created the daily post of guild {} in forum {}", guild_id, forum);
+    Ok(thread.id)
+}
+
+// その日の投稿に返信を送る（投稿がなければ作る）
+pub(crate) async fn post_reply(
+    daily: &DailyPuzzles,
+    http: &Arc<Http>,
+    storage: &dyn Storage,
+    settings: &Settings,
+    (guild_id, day, forum): (u64, u64, u64),
+    message: CreateMessage,
+) -> anyhow::Result<ChannelId> {
+    let thread = daily_post(daily, http, storage, settings, guild_id, day, forum).await?;
+    thread.send_message(http, message).await?;
+    Ok(thread)
+}
+
+// スコアボードを記録した結果で書き換える
+pub(crate) async fn update_scoreboard(http: &Arc<Http>, storage: &dyn Storage, settings: &Settings, guild_id: u64, day: u64, thread: ChannelId) -> anyhow::Result<()> {
+    let embed = scoreboard(storage, settings, guild_id, day).await?;
+    thread.edit_message(http, MessageId::new(thread.get()), EditMessage::new().embed(embed)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoreboard_lists_solvers_first_and_counts_the_rest() {
+        let result = |guesses, solved| DailyResult { guesses, solved };
+        let mut results = [(3, result(6, false)), (1, result(4, true)), (2, result(2, true))];
+        rank(&mut results);
+
+        assert_eq!(scoreboard_lines(Locale::En, &results, 6), ["<@2> 2/6", "<@1> 4/6", "<@3> X/6"]);
+        assert_eq!(scoreboard_lines(Locale::En, &[], 6), [Locale::En.text(Text::DailyScoreboardEmpty)]);
+
+        let many: Vec<_> = (0..SCOREBOARD_PLAYERS as u64 + 2).map(|user_id| (user_id, result(3, true))).collect();
+        let lines = scoreboard_lines(Locale::En, &many, 6);
+        assert_eq!(lines.len(), SCOREBOARD_PLAYERS + 1);
+        assert_eq!(lines.last().unwrap(), &Locale::En.more_players(2));
+    }
+}
@@ -0,0 +1,352 @@
+// /config: サーバー管理者が変更できるサーバー単位の設定
+use serenity::all::{
+    ChannelType,
+    CommandInteraction,
+    CommandOptionType,
+    CreateCommand,
+    CreateCommandOption,
+    GuildId,
+    ResolvedOption,
+    ResolvedValue,
+};
+use tracing::info;
+
+use crate::emoji;
+use crate::i18n::{self, Locale};
+use crate::permissions::Permission;
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::settings::{parse_banned_words, DailyGuessLimit, GuessLimit, Settings};
+use crate::{stats, Bot};
+
+pub fn create_command() -> CreateCommand {
+    let language = Locale::ALL.iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, "language", "使用する言語").required(true),
+        |option, locale| option.add_string_choice(locale.name(), locale.code()),
+    );
+
+    i18n::command("config")
+        .dm_permission(false)
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "language", "このサーバーで使う言語を設定")
+                .add_sub_option(language),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "results-channel",
+                "正解した結果（単語は伏せる）を投稿するチャンネルを設定",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::Channel, "channel", "投稿先（省略すると投稿を止める）")
+                    .channel_types(vec![ChannelType::Text, ChannelType::Forum]),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "results-webhook",
+                "正解した結果をJSONで送るURLを設定",
+            )
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "url", "送信先のURL（https、省略すると送信を止める）")),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "emoji-fallback",
+                "盤面のマスに使う絵文字の順番を設定",
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::String,
+                "order",
+                "custom, application, unicode, text をカンマ区切りで（省略すると既定の順番に戻す）",
+            )),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "banned-words",
+                "このサーバーで提案・答え・候補に使わない単語を管理（モデレーター以上）",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "action", "操作")
+                    .required(true)
+                    .add_string_choice("add", "add")
+                    .add_string_choice("remove", "remove")
+                    .add_string_choice("list", "list"),
+            )
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "words", "単語（カンマ・空白区切り）")),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "strict-words",
+                "単語リストにない推測を受け付けず、近い単語を案内する",
+            )
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "有効にするか").required(true)),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "shared-daily",
+                "/daily でほかのサーバーと同じ単語を使う",
+            )
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "有効にするか").required(true)),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "daily-guesses",
+                "/daily で推測できる回数を設定（翌日から）",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "limit", "推測できる回数")
+                    .required(true)
+                    .add_string_choice("classic（6回）", GuessLimit::Classic.code())
+                    .add_string_choice("relaxed（8回）", GuessLimit::Relaxed.code())
+                    .add_string_choice("brutal（4回）", GuessLimit::Brutal.code()),
+            ),
+        )
+}
+
+impl Bot {
+    pub(crate) async fn handle_config_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let options = command.data.options();
+        let Some(ResolvedOption { name, value: ResolvedValue::SubCommand(sub_options), .. }) = options.first() else {
+            return;
+        };
+        let Some(guild_id) = command.guild_id else {
+            return;
+        };
+
+        // 使わない単語の管理はモデレーターにも任せる
+        let required = if *name == "banned-words" { Permission::Moderator } else { Permission::GuildAdmin };
+        let mut responder = self.responder(ctx, command, ReplyMode::Ephemeral);
+        if !self.permissions.require(command, &mut responder, required).await {
+            return;
+        }
+
+        let content = match *name {
+            "language" => self.config_language(guild_id, sub_options).await,
+            "results-channel" => self.config_results_channel(guild_id, sub_options).await,
+            "results-webhook" => self.config_results_webhook(guild_id, sub_options).await,
+            "emoji-fallback" => self.config_emoji_fallback(guild_id, sub_options).await,
+            "banned-words" => self.config_banned_words(guild_id, sub_options).await,
+            "strict-words" => self.config_strict_words(guild_id, sub_options).await,
+            "shared-daily" => self.config_shared_daily(guild_id, sub_options).await,
+            "daily-guesses" => self.config_daily_guesses(guild_id, sub_options).await,
+            _ => return,
+        };
+
+        // URLのトークンをログに残さない
+        if *name == "results-webhook" {
+            info!("Config command /config {} by {}", name, command.user.id);
+        } else {
+            info!("Config command /config {} by {}: {}", name, command.user.id, content);
+        }
+        responder.send(Reply::content(content)).await;
+    }
+
+    async fn config_language(&self, guild_id: GuildId, options: &[ResolvedOption<'_>]) -> String {
+        let value = options.iter()
+            .find_map(|o| match o.value {
+                ResolvedValue::String(s) if o.name == "language" => Some(s),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let Some(locale) = Locale::parse(value) else {
+            return format!("❌ {}", crate::settings::locale_error());
+        };
+
+        self.update_guild_settings(|settings| {
+            settings.guild_locales.insert(guild_id.get(), locale);
+            match locale {
+                Locale::Ja => "✅ このサーバーの言語を日本語に設定しました".to_string(),
+                Locale::En => "✅ This server's language is now English".to_string(),
+            }
+        })
+        .await
+    }
+
+    async fn config_results_channel(&self, guild_id: GuildId, options: &[ResolvedOption<'_>]) -> String {
+        let channel = options.iter().find_map(|o| match &o.value {
+            ResolvedValue::Channel(channel) if o.name == "channel" => Some((channel.id, channel.kind == ChannelType::Forum)),
+            _ => None,
+        });
+
+        self.update_guild_settings(|settings| match channel {
+            Some((channel, true)) => {
+                settings.results_channels.insert(guild_id.get(), channel.get());
+                settings.results_forums.insert(guild_id.get());
+                format!("✅ 正解した結果を <#{}> の日ごとの投稿に返信します（/daily の結果はスコアボードにまとめます）", channel)
+            }
+            Some((channel, false)) => {
+                settings.results_channels.insert(guild_id.get(), channel.get());
+                settings.results_forums.remove(&guild_id.get());
+                format!("✅ 正解した結果を <#{}> に投稿します", channel)
+            }
+            None => {
+                settings.results_channels.remove(&guild_id.get());
+                settings.results_forums.remove(&guild_id.get());
+                "✅ 結果の投稿を止めました".to_string()
+            }
+        })
+        .await
+    }
+
+    async fn config_emoji_fallback(&self, guild_id: GuildId, options: &[ResolvedOption<'_>]) -> String {
+        let order = options.iter().find_map(|o| match o.value {
+            ResolvedValue::String(s) if o.name == "order" => Some(s),
+            _ => None,
+        });
+
+        let chain = match order.map(emoji::parse_chain).transpose() {
+            Ok(chain) => chain,
+            Err(message) => return format!("❌ {}", message),
+        };
+
+        self.update_guild_settings(|settings| match chain {
+            Some(chain) => {
+                let done = format!("✅ 盤面のマスは {} の順に使える絵文字で表示します", emoji::describe_chain(&chain));
+                settings.emoji_chains.insert(guild_id.get(), chain);
+                done
+            }
+            None => {
+                settings.emoji_chains.remove(&guild_id.get());
+                format!("✅ 既定の順番（{}）に戻しました", emoji::describe_chain(&emoji::DEFAULT_CHAIN))
+            }
+        })
+        .await
+    }
+
+    async fn config_banned_words(&self, guild_id: GuildId, options: &[ResolvedOption<'_>]) -> String {
+        let option = |name: &str| options.iter().find_map(|o| match o.value {
+            ResolvedValue::String(s) if o.name == name => Some(s),
+            _ => None,
+        });
+        let words = match parse_banned_words(option("words").unwrap_or_default()) {
+            Ok(words) => words,
+            Err(message) => return format!("❌ {}", message),
+        };
+
+        let action = option("action").unwrap_or_default();
+        match action {
+            "list" => {
+                let settings = self.settings();
+                let global = settings.banned_words.len();
+                return match settings.guild_banned_words.get(&guild_id.get()).filter(|banned| !banned.is_empty()) {
+                    None => format!("このサーバーで使わない単語はありません（全サーバー共通: {}語）", global),
+                    Some(banned) => {
+                        let words: Vec<&str> = banned.iter().map(String::as_str).collect();
+                        format!("このサーバーで使わない単語（{}語、全サーバー共通: {}語）: ||{}||", words.len(), global, words.join(", "))
+                    }
+                };
+            }
+            _ if words.is_empty() => return "❌ 単語を指定してください".to_string(),
+            "add" | "remove" => {}
+            _ => return "❌ add / remove / list のいずれかを指定してください".to_string(),
+        }
+
+        self.update_guild_settings(|settings| {
+            let banned = settings.guild_banned_words.entry(guild_id.get()).or_default();
+            let done = if action == "add" {
+                banned.extend(words.iter().cloned());
+                format!("✅ {}語を使わない単語に追加しました（{}語）", words.len(), banned.len())
+            } else {
+                let removed = words.iter().filter(|w| banned.remove(*w)).count();
+                format!("✅ {}語を使わない単語から外しました（{}語）", removed, banned.len())
+            };
+            if banned.is_empty() {
+                settings.guild_banned_words.remove(&guild_id.get());
+            }
+            done
+        })
+        .await
+    }
+
+    async fn config_strict_words(&self, guild_id: GuildId, options: &[ResolvedOption<'_>]) -> String {
+        let enabled = options.iter().any(|o| o.name == "enabled" && matches!(o.value, ResolvedValue::Boolean(true)));
+
+        self.update_guild_settings(|settings| {
+            if enabled {
+                settings.strict_guilds.insert(guild_id.get());
+                "✅ 単語リストにない推測は受け付けず、近い単語を案内します".to_string()
+            } else {
+                settings.strict_guilds.remove(&guild_id.get());
+                "✅ 単語リストにない推測も受け付けます".to_string()
+            }
+        })
+        .await
+    }
+
+    async fn config_shared_daily(&self, guild_id: GuildId, options: &[ResolvedOption<'_>]) -> String {
+        let enabled = options.iter().any(|o| o.name == "enabled" && matches!(o.value, ResolvedValue::Boolean(true)));
+
+        // その日の単語はサーバーごとに記録するため、切り替えはまだ誰も遊んでいない日から反映される
+        self.update_guild_settings(|settings| {
+            if enabled {
+                settings.shared_daily_guilds.insert(guild_id.get());
+                "✅ 次のデイリーから、ほかのサーバーと同じ単語を使います（このサーバーで使わない単語も答えになります）".to_string()
+            } else {
+                settings.shared_daily_guilds.remove(&guild_id.get());
+                "✅ 次のデイリーから、このサーバーだけの単語を使います".to_string()
+            }
+        })
+        .await
+    }
+
+    async fn config_daily_guesses(&self, guild_id: GuildId, options: &[ResolvedOption<'_>]) -> String {
+        let limit = options.iter().find_map(|o| match o.value {
+            ResolvedValue::String(s) if o.name == "limit" => GuessLimit::parse(s),
+            _ => None,
+        });
+        let Some(limit) = limit else {
+            return "❌ classic / relaxed / brutal のいずれかを指定してください".to_string();
+        };
+
+        // 今日のデイリーはメンバーの結果をそろえるため、今の上限のまま続ける
+        let today = stats::today();
+        self.update_guild_settings(|settings| {
+            let previous = settings.daily_guess_limit(guild_id.get(), today);
+            settings.daily_guess_limits.insert(guild_id.get(), DailyGuessLimit { limit, since: today + 1, previous });
+            format!("✅ 明日（UTC）のデイリーから、推測できる回数を{}回にします", limit.guesses())
+        })
+        .await
+    }
+
+    async fn config_results_webhook(&self, guild_id: GuildId, options: &[ResolvedOption<'_>]) -> String {
+        let url = options.iter().find_map(|o| match o.value {
+            ResolvedValue::String(s) if o.name == "url" => Some(s.trim()),
+            _ => None,
+        });
+
+        if let Some(url) = url {
+            match reqwest::Url::parse(url) {
+                Ok(parsed) if parsed.scheme() == "https" => {}
+                _ => return "❌ https:// で始まるURLを指定してください".to_string(),
+            }
+        }
+
+        self.update_guild_settings(|settings| match url {
+            Some(url) => {
+                settings.result_webhooks.insert(guild_id.get(), url.to_string());
+                "✅ 正解した結果を指定のURLへ送ります".to_string()
+            }
+            None => {
+                settings.result_webhooks.remove(&guild_id.get());
+                "✅ 結果の送信を止めました".to_string()
+            }
+        })
+        .await
+    }
+
+    // 設定を書き換えて保存し、結果のメッセージを返す
+    async fn update_guild_settings(&self, update: impl FnOnce(&mut Settings) -> String) -> String {
+        match self.update_settings(|settings| Some(update(settings))).await {
+            Ok(done) => done.unwrap_or_default(),
+            Err(e) => {
+                self.reporter.report("Failed to save settings", &e).await;
+                format!("❌ 設定の保存に失敗しました: {}", e)
+            }
+        }
+    }
+}
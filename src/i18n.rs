@@ -0,0 +1,925 @@
+// 表示文言の多言語化（サーバーごとの言語設定に従う）
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serenity::all::CreateCommand;
+
+use crate::leaderboard::{LeaderboardPeriod, LeaderboardSort};
+use crate::settings::InputStyle;
+use crate::play::PlayError;
+use crate::session::{BoardSection, SessionError};
+use crate::stats::UserStats;
+use crate::solver::{Conflict, GuessExplanation, HardModeViolation, Hint, HintTier, OpenerScore};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Ja,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::Ja, Locale::En];
+
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Ja => "ja",
+            Locale::En => "en",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::Ja => "日本語",
+            Locale::En => "English",
+        }
+    }
+
+    // 盤面の切り替えボタンで表示するもう一方の言語
+    pub fn toggled(self) -> Self {
+        match self {
+            Locale::Ja => Locale::En,
+            Locale::En => Locale::Ja,
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+// 引数のない文言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    EmptyBoard,
+    History,
+    Keyboard,
+    CurrentWord,
+    CandidateCount,
+    Elimination,
+    ColorHint,
+    ColorMenuHint,
+    LetterMenuPlaceholder,
+    ConfirmButton,
+    NewWordButton,
+    CancelAnalysisButton,
+    WordModalTitle,
+    WordInputPlaceholder,
+    Analyzing,
+    NoCandidates,
+    Suggestions,
+    SuggestionFailed,
+    UnknownCommand,
+    NotOwner,
+    HostOnly,
+    SharedBoard,
+    SharedBoardHostOnly,
+    OutdatedButton,
+    GenericError,
+    Pronunciation,
+    DefinitionNotFound,
+    DefinitionFailed,
+    ImportUnreadable,
+    ImportNoImage,
+    ImportReview,
+    ImportWordsButton,
+    ImportWordsTitle,
+    ImportWordsLabel,
+    ExportImageButton,
+    ShareTextButton,
+    ShareTextIntro,
+    PrivacyEnabled,
+    PrivacyDisabled,
+    ExportImageFailed,
+    LeaderboardEmpty,
+    JumpToMe,
+    OpenersTitle,
+    OpenersEmpty,
+    EvaluateInvalidWord,
+    EditPastGuess,
+    PlayGuessButton,
+    PlayNoWords,
+    StatsEmpty,
+    StatsDistribution,
+    DailyIntro,
+    DailyGuildOnly,
+    DailyAlreadyPlayed,
+    DailySummaryTitle,
+    DailyShared,
+    DailyScoreboardTitle,
+    DailyScoreboardEmpty,
+    HardModeSuggestions,
+    QuordleSolved,
+}
+
+impl Locale {
+    pub fn text(self, text: Text) -> &'static str {
+        use Locale::*;
+        use Text::*;
+        match (text, self) {
+            (EmptyBoard, Ja) => "まだ推測がありません。新しい単語を入力してください！",
+            (EmptyBoard, En) => "No guesses yet. Enter a new word!",
+            (History, Ja) => "推測履歴",
+            (History, En) => "Guesses",
+            (Keyboard, Ja) => "キーボード",
+            (Keyboard, En) => "Keyboard",
+            (CurrentWord, Ja) => "現在の単語",
+            (CurrentWord, En) => "Current word",
+            (CandidateCount, Ja) => "候補数",
+            (CandidateCount, En) => "Candidates",
+            (Elimination, Ja) => "一番のおすすめでの絞り込み",
+            (Elimination, En) => "How the top pick splits",
+            (ColorHint, Ja) => "⬇️ 各文字をクリックして色を変更し、確定ボタンを押してください",
+            (ColorHint, En) => "⬇️ Click each letter to change its color, then press Confirm",
+            (ColorMenuHint, Ja) => "⬇️ メニューで黄と緑の文字を選び（選ばなかった文字は灰）、確定ボタンを押してください",
+            (ColorMenuHint, En) => "⬇️ Pick the yellow and green letters in the menu (the rest are gray), then press Confirm",
+            (LetterMenuPlaceholder, Ja) => "黄と緑の文字を選ぶ",
+            (LetterMenuPlaceholder, En) => "Pick the yellow and green letters",
+            (ConfirmButton, Ja) => "✅ 確定",
+            (ConfirmButton, En) => "✅ Confirm",
+            (NewWordButton, Ja) => "📝 新しい単語を入力",
+            (NewWordButton, En) => "📝 Enter a new word",
+            (CancelAnalysisButton, Ja) => "キャンセル",
+            (CancelAnalysisButton, En) => "Cancel",
+            (WordModalTitle, Ja) => "単語を入力",
+            (WordModalTitle, En) => "Enter a word",
+            (WordInputPlaceholder, Ja) => "5文字の英単語を入力してください",
+            (WordInputPlaceholder, En) => "Type a five-letter English word",
+            (Analyzing, Ja) => "⏳ 最適な単語を分析中...",
+            (Analyzing, En) => "⏳ Analyzing the best words...",
+            (NoCandidates, Ja) => "候補となる単語が見つかりませんでした。制約を見直してください。",
+            (NoCandidates, En) => "No candidate words found. Please check the colors you entered.",
+            (Suggestions, Ja) => "おすすめ",
+            (Suggestions, En) => "Suggestions",
+            (SuggestionFailed, Ja) => "単語の提案を取得できませんでした。データベースの接続を確認してください。",
+            (SuggestionFailed, En) => "Could not get word suggestions. Please check the database connection.",
+            (UnknownCommand, Ja) => "このコマンドは現在利用できません。",
+            (UnknownCommand, En) => "This command is currently unavailable.",
+            (NotOwner, Ja) => "このボードは他のユーザーのものです。`/wht` で自分のボードを開始してください。",
+            (NotOwner, En) => "This board belongs to another user. Start your own with `/wht`.",
+            (HostOnly, Ja) => "この共有ボードの確定とやり直しは、開始した人だけができます。",
+            (HostOnly, En) => "Only the person who started this shared board can confirm or undo guesses.",
+            (SharedBoard, Ja) => "👥 このチャンネルの誰でも単語と色を入力できます。",
+            (SharedBoard, En) => "👥 Anyone in this channel can enter words and colors.",
+            (SharedBoardHostOnly, Ja) => "👥 このチャンネルの誰でも単語と色を入力できます（確定とやり直しは開始した人だけ）。",
+            (SharedBoardHostOnly, En) => "👥 Anyone in this channel can enter words and colors (only the host can confirm or undo).",
+            (OutdatedButton, Ja) => "このボタンは古いメッセージのものです。`/wht` で新しく始めてください。",
+            (OutdatedButton, En) => "This button belongs to an old message. Start again with `/wht`.",
+            (GenericError, Ja) => "エラーが発生しました",
+            (GenericError, En) => "Something went wrong",
+            (Pronunciation, Ja) => "発音",
+            (Pronunciation, En) => "Pronunciation",
+            (DefinitionNotFound, Ja) => "その単語の意味は見つかりませんでした。",
+            (DefinitionNotFound, En) => "No definition found for that word.",
+            (DefinitionFailed, Ja) => "辞書を検索できませんでした。しばらくしてからもう一度お試しください。",
+            (DefinitionFailed, En) => "Could not reach the dictionary. Please try again later.",
+            (ImportUnreadable, Ja) => "画像から盤面を読み取れませんでした。盤面全体が写ったスクリーンショットを送ってください。",
+            (ImportUnreadable, En) => "Could not find a board in the image. Please send a screenshot showing the whole board.",
+            (ImportNoImage, Ja) => "このメッセージには画像が添付されていません。",
+            (ImportNoImage, En) => "This message has no image attached.",
+            (ImportReview, Ja) => "📷 画像から読み取った色です。違うタイルは行を選んで押すと色が変わります。合っていれば単語を入力して確定してください。",
+            (ImportReview, En) => "📷 These are the colors read from the image. Select a row and click a tile to fix it, then enter your words to confirm.",
+            (ImportWordsButton, Ja) => "📝 単語を入力して確定",
+            (ImportWordsButton, En) => "📝 Enter words and confirm",
+            (ImportWordsTitle, Ja) => "推測した単語を入力",
+            (ImportWordsTitle, En) => "Enter your guesses",
+            (ImportWordsLabel, Ja) => "上から順にスペース区切りで入力",
+            (ImportWordsLabel, En) => "Top to bottom, separated by spaces",
+            (ExportImageButton, Ja) => "🖼️ 画像で保存",
+            (ExportImageButton, En) => "🖼️ Save as image",
+            (ShareTextButton, Ja) => "📋 共有用の文章",
+            (ShareTextButton, En) => "📋 Share text",
+            (ShareTextIntro, Ja) => "SNSなどに貼るときは、次の文章をコピーしてください。",
+            (ShareTextIntro, En) => "Copy the text below to post it outside Discord.",
+            (PrivacyEnabled, Ja) => "🔒 成績を記録しない設定です。ランキング・結果の投稿・ロール報酬にも表示されません。",
+            (PrivacyEnabled, En) => "🔒 Your results are not recorded, and you are left out of leaderboards, result posts and role rewards.",
+            (PrivacyDisabled, Ja) => "🔓 成績を記録する設定です。ランキングや結果の投稿に表示されます。",
+            (PrivacyDisabled, En) => "🔓 Your results are recorded and may appear on leaderboards and in result posts.",
+            (ExportImageFailed, Ja) => "画像を作成できませんでした。",
+            (ExportImageFailed, En) => "Could not create the image.",
+            (LeaderboardEmpty, Ja) => "まだ誰も正解していません。",
+            (LeaderboardEmpty, En) => "Nobody has solved a game yet.",
+            (JumpToMe, Ja) => "📍 自分の順位",
+            (JumpToMe, En) => "📍 Jump to me",
+            (OpenersTitle, Ja) => "🚀 おすすめの開始単語",
+            (OpenersTitle, En) => "🚀 Best opening words",
+            (OpenersEmpty, Ja) => "単語リストが読み込まれていません。",
+            (OpenersEmpty, En) => "The word list has not been loaded.",
+            (EvaluateInvalidWord, Ja) => "5文字の英単語を入力してください。",
+            (EvaluateInvalidWord, En) => "Please enter a five-letter English word.",
+            (EditPastGuess, Ja) => "過去の推測を編集",
+            (EditPastGuess, En) => "Edit a past guess",
+            (PlayGuessButton, Ja) => "📝 推測する",
+            (PlayGuessButton, En) => "📝 Guess",
+            (PlayNoWords, Ja) => "単語リストが読み込まれていないため、出題できません。",
+            (PlayNoWords, En) => "The word list has not been loaded, so no game can be started.",
+            (StatsEmpty, Ja) => "まだ成績がありません。ゲームを最後まで遊ぶと記録されます。",
+            (StatsEmpty, En) => "No stats yet. Finish a game to start recording them.",
+            (StatsDistribution, Ja) => "推測回数の分布",
+            (StatsDistribution, En) => "Guess distribution",
+            (QuordleSolved, Ja) => "🎉 4つの盤面をすべて解きました！",
+            (QuordleSolved, En) => "🎉 All four boards are solved!",
+            (DailyIntro, Ja) => "今日のデイリーです。サーバーのメンバー全員が同じ単語に挑戦しています。",
+            (DailyIntro, En) => "Today's daily puzzle. Everyone in this server is guessing the same word.",
+            (DailyGuildOnly, Ja) => "デイリーはサーバーでだけ遊べます。",
+            (DailyGuildOnly, En) => "The daily puzzle can only be played in a server.",
+            (DailyAlreadyPlayed, Ja) => "今日のデイリーはもう遊びました。日付が変わると（UTC）答えとまとめが投稿されます。",
+            (DailyAlreadyPlayed, En) => "You have already played today's daily. The answer and a summary are posted when the day rolls over (UTC).",
+            (DailySummaryTitle, Ja) => "📅 デイリーの結果",
+            (DailySummaryTitle, En) => "📅 Daily results",
+            (DailyShared, Ja) => "🌐 ほかのサーバーと同じお題です",
+            (DailyShared, En) => "🌐 Same word as the other servers sharing the daily",
+            (DailyScoreboardTitle, Ja) => "📋 今日のスコアボード",
+            (DailyScoreboardTitle, En) => "📋 Today's scoreboard",
+            (DailyScoreboardEmpty, Ja) => "まだ誰も終えていません。",
+            (DailyScoreboardEmpty, En) => "Nobody has finished yet.",
+            (HardModeSuggestions, Ja) => "🔒 ハードモード: 判明したヒントをすべて使う単語だけを提案します",
+            (HardModeSuggestions, En) => "🔒 Hard mode: only words that use every revealed hint are suggested",
+        }
+    }
+
+    // 盤面のEmbedの欄の見出し
+    pub fn section_name(self, section: BoardSection) -> &'static str {
+        self.text(match section {
+            BoardSection::History => Text::History,
+            BoardSection::Keyboard => Text::Keyboard,
+            BoardSection::CurrentWord => Text::CurrentWord,
+            BoardSection::Candidates => Text::CandidateCount,
+            BoardSection::Suggestions => Text::Suggestions,
+            BoardSection::Elimination => Text::Elimination,
+        })
+    }
+
+    pub fn guess_label(self, number: usize) -> String {
+        match self {
+            Locale::Ja => format!("**{}回目:** ", number),
+            Locale::En => format!("**Guess {}:** ", number),
+        }
+    }
+
+    pub fn solved(self, guesses: usize) -> String {
+        match self {
+            Locale::Ja => format!("🎉 **{}回で正解しました！**", guesses),
+            Locale::En => format!("🎉 **Solved in {} guesses!**", guesses),
+        }
+    }
+
+    pub fn share_header(self, guesses: usize) -> String {
+        match self {
+            Locale::Ja => format!("🎯 Wordle Helper {}回で正解", guesses),
+            Locale::En => format!("🎯 Wordle Helper solved in {}", guesses),
+        }
+    }
+
+    pub fn candidate_count(self, count: usize) -> String {
+        match self {
+            Locale::Ja => format!("💡 **{}語**", count),
+            Locale::En => format!("💡 **{}**", count),
+        }
+    }
+
+    // 計算の実行枠の順番待ち（1なら次に始まる）
+    pub fn queue_position(self, position: usize) -> String {
+        match self {
+            Locale::Ja => format!("⌛ 順番待ち: {}番目", position),
+            Locale::En => format!("⌛ Waiting in line: #{}", position),
+        }
+    }
+
+    // 候補が多い分析の途中経過（例: 3,200/9,800語）
+    pub fn scoring_progress(self, scored: usize, total: usize) -> String {
+        match self {
+            Locale::Ja => format!("スコア計算中… {}/{}語", grouped(scored), grouped(total)),
+            Locale::En => format!("Scoring words… {}/{}", grouped(scored), grouped(total)),
+        }
+    }
+
+    pub fn remaining(self, count: usize) -> String {
+        match self {
+            Locale::Ja => format!("残り {}語", count),
+            Locale::En => format!("{} words left", count),
+        }
+    }
+
+    // おすすめの単語に添える情報量と、推測したあとに残る候補数の期待値
+    pub fn suggestion_note(self, bits: f64, remaining: f64) -> String {
+        match self {
+            Locale::Ja => format!(" — {:.2} ビット / 候補を平均 {:.1} 語まで絞れます", bits, remaining),
+            Locale::En => format!(" — {:.2} bits / narrows the list to {:.1} words on average", bits, remaining),
+        }
+    }
+
+    // 位置ごとの色の内訳（例: `2` R: 🟩40 🟨22 ⬜5）
+    pub fn split_map(self, explanation: &GuessExplanation) -> String {
+        let header = match self {
+            Locale::Ja => format!("**{}** を推測すると、残り{}語は文字ごとに次のように分かれます", explanation.word, explanation.candidates),
+            Locale::En => format!("Guessing **{}** splits the {} remaining words by letter like this", explanation.word, explanation.candidates),
+        };
+        let lines = explanation.splits.iter()
+            .enumerate()
+            .map(|(i, s)| format!("`{}` {}: 🟩{} 🟨{} ⬜{}", i + 1, s.letter, s.green, s.yellow, s.gray));
+        std::iter::once(header).chain(lines).collect::<Vec<_>>().join("\n")
+    }
+
+    pub fn explain_label(self, word: &str) -> String {
+        match self {
+            Locale::Ja => format!("❓ なぜ {}？", word),
+            Locale::En => format!("❓ Why {}?", word),
+        }
+    }
+
+    pub fn explanation(self, explanation: &GuessExplanation) -> String {
+        let letters: Vec<String> = explanation.untested_letters.iter().map(char::to_string).collect();
+        match self {
+            Locale::Ja => format!(
+                "💡 **{}** を選んだ理由\n- まだ試していない文字: {}\n- 推測したあとに残る候補: 平均 {:.1} 語（現在 {} 語、情報量 {:.2} ビット）\n- この単語自体が答えの可能性: {}",
+                explanation.word,
+                if letters.is_empty() { "なし（試した文字だけで絞り込む）".to_string() } else { letters.join(", ") },
+                explanation.expected_remaining,
+                explanation.candidates,
+                explanation.entropy,
+                if explanation.can_be_answer { "あり" } else { "なし（絞り込むための単語）" },
+            ),
+            Locale::En => format!(
+                "💡 Why **{}**\n- Untested letters: {}\n- Candidates left after guessing: {:.1} on average (now {}, {:.2} bits)\n- Could be the answer itself: {}",
+                explanation.word,
+                if letters.is_empty() { "none (narrows down with tested letters only)".to_string() } else { letters.join(", ") },
+                explanation.expected_remaining,
+                explanation.candidates,
+                explanation.entropy,
+                if explanation.can_be_answer { "yes" } else { "no (a probe to narrow things down)" },
+            ),
+        }
+    }
+
+    pub fn input_style(self, style: InputStyle) -> &'static str {
+        match (style, self) {
+            (InputStyle::Buttons, Locale::Ja) => "🔘 色は文字ごとのボタンで入力します（押すたびに 灰→黄→緑 と切り替わります）。",
+            (InputStyle::Buttons, Locale::En) => "🔘 Colors are entered with a button per letter (each press cycles gray → yellow → green).",
+            (InputStyle::Select, Locale::Ja) => "📋 色はメニューで黄と緑の文字をまとめて選んで入力します。",
+            (InputStyle::Select, Locale::En) => "📋 Colors are entered by picking the yellow and green letters in one menu.",
+        }
+    }
+
+    // /wordle の盤面の見出し
+    pub fn play_header(self, guesses: usize, max: usize) -> String {
+        match self {
+            Locale::Ja => format!("ボットが選んだ5文字の英単語を当ててください（{}/{}）", guesses, max),
+            Locale::En => format!("Guess the five-letter word the bot picked ({}/{})", guesses, max),
+        }
+    }
+
+    // 上限が6回でないゲームの推測回数の分布の見出し
+    pub fn limited_distribution(self, limit: usize) -> String {
+        match self {
+            Locale::Ja => format!("推測回数の分布（上限{}回）", limit),
+            Locale::En => format!("Guess distribution ({} guesses)", limit),
+        }
+    }
+
+    pub fn play_won(self, guesses: usize) -> String {
+        match self {
+            Locale::Ja => format!("🎉 {}回目で正解しました！", guesses),
+            Locale::En => format!("🎉 Solved in {} guesses!", guesses),
+        }
+    }
+
+    // ヒントのボタン（使うと引かれる点数を添える）
+    pub fn play_hint_button(self, cost: u32) -> String {
+        match self {
+            Locale::Ja => format!("💡 ヒント（-{}点）", cost),
+            Locale::En => format!("💡 Hint (-{} pts)", cost),
+        }
+    }
+
+    // 使ったヒント（位置は1始まりで表示する）
+    pub fn play_hint(self, hint: &Hint) -> String {
+        match (hint.tier, hint.position, self) {
+            (HintTier::Absent, _, Locale::Ja) => format!("💡 **{}** は答えに含まれません", hint.letter),
+            (HintTier::Absent, _, Locale::En) => format!("💡 **{}** is not in the answer", hint.letter),
+            (HintTier::Present, _, Locale::Ja) => format!("💡 **{}** が答えに含まれます", hint.letter),
+            (HintTier::Present, _, Locale::En) => format!("💡 **{}** is in the answer", hint.letter),
+            (HintTier::Position, position, Locale::Ja) => format!("💡 {}文字目は **{}** です", position.unwrap_or(0) + 1, hint.letter),
+            (HintTier::Position, position, Locale::En) => format!("💡 Letter {} is **{}**", position.unwrap_or(0) + 1, hint.letter),
+        }
+    }
+
+    pub fn play_score(self, score: u32, penalty: u32) -> String {
+        match (penalty, self) {
+            (0, Locale::Ja) => format!("得点: **{}**", score),
+            (0, Locale::En) => format!("Score: **{}**", score),
+            (_, Locale::Ja) => format!("得点: **{}**（ヒント -{}）", score, penalty),
+            (_, Locale::En) => format!("Score: **{}** (hints -{})", score, penalty),
+        }
+    }
+
+    pub fn play_lost(self, answer: &str) -> String {
+        match self {
+            Locale::Ja => format!("😢 答えは **{}** でした。", answer),
+            Locale::En => format!("😢 The answer was **{}**.", answer),
+        }
+    }
+
+    // 終えたデイリーの結果の投稿の1行目（当てられなかったら guesses は None）
+    pub fn daily_result(self, user_id: u64, guesses: Option<usize>, max: usize) -> String {
+        let score = guesses.map_or("X".to_string(), |n| n.to_string());
+        match self {
+            Locale::Ja => format!("<@{}> が今日のデイリーを終えました（{}/{}）", user_id, score, max),
+            Locale::En => format!("<@{}> finished today's daily ({}/{})", user_id, score, max),
+        }
+    }
+
+    pub fn daily_summary(self, answer: &str, players: usize, solved: usize, average: Option<f64>) -> String {
+        let average = average.map_or("-".to_string(), |average| format!("{:.2}", average));
+        match self {
+            Locale::Ja => format!("答えは **{}** でした。\n参加: **{}**人 / 正解: **{}**人 / 平均推測回数: **{}**", answer, players, solved, average),
+            Locale::En => format!("The answer was **{}**.\nPlayers: **{}** / Solved: **{}** / Average guesses: **{}**", answer, players, solved, average),
+        }
+    }
+
+    pub fn play_error(self, error: PlayError) -> &'static str {
+        match (error, self) {
+            (PlayError::NotFound, Locale::Ja) => "このゲームは見つかりませんでした。`/wordle` で新しいゲームを始めてください。",
+            (PlayError::NotFound, Locale::En) => "This game could not be found. Start a new one with `/wordle`.",
+            (PlayError::Stale, _) => self.text(Text::OutdatedButton),
+            (PlayError::Finished, Locale::Ja) => "このゲームはもう終わっています。",
+            (PlayError::Finished, Locale::En) => "This game is already over.",
+            (PlayError::UnknownWord, Locale::Ja) => "単語リストにない単語です。別の単語を入力してください。",
+            (PlayError::UnknownWord, Locale::En) => "That word is not in the word list. Try another one.",
+            (PlayError::NoHints, Locale::Ja) => "これ以上出せるヒントはありません。",
+            (PlayError::NoHints, Locale::En) => "There are no more hints to give.",
+        }
+    }
+
+    pub fn more_players(self, count: usize) -> String {
+        match self {
+            Locale::Ja => format!("... 他{}人", count),
+            Locale::En => format!("... and {} more", count),
+        }
+    }
+
+    pub fn more_words(self, count: usize) -> String {
+        match self {
+            Locale::Ja => format!("... 他{}語\n", count),
+            Locale::En => format!("... and {} more\n", count),
+        }
+    }
+
+    // 推測の番号は0始まりで受け取り、表示は1始まり
+    pub fn conflict(self, conflict: Conflict) -> String {
+        let reason = match (conflict, self) {
+            (Conflict::Position { position, first, second }, Locale::Ja) => {
+                format!("{}回目と{}回目で、{}文字目の緑が別の文字になっています。", first + 1, second + 1, position + 1)
+            }
+            (Conflict::Position { position, first, second }, Locale::En) => {
+                format!("Guesses {} and {} have different green letters at position {}.", first + 1, second + 1, position + 1)
+            }
+            (Conflict::Letter { letter, first, second }, Locale::Ja) => {
+                format!("{}回目と{}回目で、{} の色が矛盾しています。", first + 1, second + 1, letter)
+            }
+            (Conflict::Letter { letter, first, second }, Locale::En) => {
+                format!("Guesses {} and {} disagree about the letter {}.", first + 1, second + 1, letter)
+            }
+            (Conflict::NoMatchingWord { guess }, Locale::Ja) => {
+                format!("{}回目の結果を加えると、当てはまる単語がなくなります。", guess + 1)
+            }
+            (Conflict::NoMatchingWord { guess }, Locale::En) => {
+                format!("No word in the list fits once guess {} is included.", guess + 1)
+            }
+        };
+        match self {
+            Locale::Ja => format!("⚠️ **候補が見つかりません**\n{}\n下のボタンから{}回目の色を修正できます。", reason, conflict.suspect() + 1),
+            Locale::En => format!("⚠️ **No candidates left**\n{}\nUse the button below to fix the colors of guess {}.", reason, conflict.suspect() + 1),
+        }
+    }
+
+    pub fn stats_title(self, name: &str) -> String {
+        match self {
+            Locale::Ja => format!("📊 {} さんの成績", name),
+            Locale::En => format!("📊 Stats for {}", name),
+        }
+    }
+
+    // /stats の数値の欄（正解の記録がないあいだは平均を「-」にする）
+    pub fn stats_summary(self, stats: &UserStats, today: u64) -> String {
+        let win_rate = stats.win_rate().map_or("-".to_string(), |rate| format!("{:.0}%", rate));
+        let average = stats.average_guesses().map_or("-".to_string(), |average| format!("{:.2}", average));
+        match self {
+            Locale::Ja => format!(
+                "遊んだ数: **{}**\n正解数: **{}**（正解率 {}）\n平均推測回数: **{}**\n連続正解日数: **{}**（最長 {}）",
+                stats.games_played(), stats.wins, win_rate, average,
+                self.streak_days(stats.streak_on(today)), self.streak_days(stats.best_streak),
+            ),
+            Locale::En => format!(
+                "Played: **{}**\nWins: **{}** ({} win rate)\nAverage guesses: **{}**\nCurrent streak: **{}** (best {})",
+                stats.games_played(), stats.wins, win_rate, average,
+                self.streak_days(stats.streak_on(today)), self.streak_days(stats.best_streak),
+            ),
+        }
+    }
+
+    pub fn leaderboard_sort(self, sort: LeaderboardSort) -> &'static str {
+        match (sort, self) {
+            (LeaderboardSort::Streak, Locale::Ja) => "連続正解日数",
+            (LeaderboardSort::Streak, Locale::En) => "Current streak",
+            (LeaderboardSort::Average, Locale::Ja) => "平均推測回数",
+            (LeaderboardSort::Average, Locale::En) => "Average guesses",
+            (LeaderboardSort::Wins, Locale::Ja) => "正解数",
+            (LeaderboardSort::Wins, Locale::En) => "Total wins",
+            (LeaderboardSort::WinRate, Locale::Ja) => "正解率",
+            (LeaderboardSort::WinRate, Locale::En) => "Win rate",
+        }
+    }
+
+    pub fn leaderboard_period(self, period: LeaderboardPeriod) -> &'static str {
+        match (period, self) {
+            (LeaderboardPeriod::Week, Locale::Ja) => "直近7日",
+            (LeaderboardPeriod::Week, Locale::En) => "Last 7 days",
+            (LeaderboardPeriod::Month, Locale::Ja) => "直近30日",
+            (LeaderboardPeriod::Month, Locale::En) => "Last 30 days",
+            (LeaderboardPeriod::AllTime, Locale::Ja) => "すべての期間",
+            (LeaderboardPeriod::AllTime, Locale::En) => "All time",
+        }
+    }
+
+    pub fn streak_days(self, days: u32) -> String {
+        match self {
+            Locale::Ja => format!("{}日", days),
+            Locale::En => format!("{} days", days),
+        }
+    }
+
+    pub fn page_label(self, page: usize, pages: usize) -> String {
+        match self {
+            Locale::Ja => format!("{} / {} ページ", page, pages),
+            Locale::En => format!("Page {} of {}", page, pages),
+        }
+    }
+
+    pub fn opener_line(self, rank: usize, score: &OpenerScore) -> String {
+        match self {
+            Locale::Ja => format!(
+                "{}. **{}** — {:.2} ビット / 残り {:.1}語（期待値）",
+                rank, score.word, score.entropy, score.expected_remaining
+            ),
+            Locale::En => format!(
+                "{}. **{}** — {:.2} bits / {:.1} words left on average",
+                rank, score.word, score.entropy, score.expected_remaining
+            ),
+        }
+    }
+
+    pub fn evaluate_title(self, word: &str) -> String {
+        match self {
+            Locale::Ja => format!("🧪 開始単語の評価: {}", word),
+            Locale::En => format!("🧪 Opener evaluation: {}", word),
+        }
+    }
+
+    pub fn evaluate_description(self, score: &OpenerScore, rank: usize, total: usize, percentile: f64) -> String {
+        match self {
+            Locale::Ja => format!(
+                "エントロピー: **{:.2}** ビット\n残り候補数の期待値: **{:.1}**語\n順位: {}語中 **{}**位（{:.1}パーセンタイル）",
+                score.entropy, score.expected_remaining, total, rank, percentile
+            ),
+            Locale::En => format!(
+                "Entropy: **{:.2}** bits\nExpected words left: **{:.1}**\nRank: **#{}** of {} ({:.1} percentile)",
+                score.entropy, score.expected_remaining, rank, total, percentile
+            ),
+        }
+    }
+
+    // ボットのステータスに表示する文言
+    pub fn presence(self, sessions: usize) -> String {
+        match (self, sessions) {
+            (Locale::Ja, 0) => "/wht でWordleをお手伝い".to_string(),
+            (Locale::Ja, n) => format!("{}件のWordleをお手伝い中", n),
+            (Locale::En, 0) => "Type /wht for Wordle help".to_string(),
+            (Locale::En, 1) => "Helping 1 Wordle game".to_string(),
+            (Locale::En, n) => format!("Helping {} Wordle games", n),
+        }
+    }
+
+    pub fn reaction_prompt(self, position: usize, letter: char) -> String {
+        match self {
+            Locale::Ja => format!("👉 {}文字目の **{}** の色をリアクションで選んでください（⬜ 灰 / 🟨 黄 / 🟩 緑）", position, letter),
+            Locale::En => format!("👉 React with the color of letter {} (**{}**): ⬜ gray / 🟨 yellow / 🟩 green", position, letter),
+        }
+    }
+
+    pub fn openers_footer(self, word_count: usize) -> String {
+        match self {
+            Locale::Ja => format!("単語リスト {}語で計算", word_count),
+            Locale::En => format!("Computed over {} words", word_count),
+        }
+    }
+
+    pub fn import_row_label(self, number: usize) -> String {
+        match self {
+            Locale::Ja => format!("{}行目", number),
+            Locale::En => format!("Row {}", number),
+        }
+    }
+
+    pub fn edit_guess_label(self, number: usize) -> String {
+        match self {
+            Locale::Ja => format!("✏️ {}回目を修正", number),
+            Locale::En => format!("✏️ Fix guess {}", number),
+        }
+    }
+
+    pub fn quordle_invalid_entry(self, entry: &str) -> String {
+        match self {
+            Locale::Ja => format!(
+                "`{}` を読み取れませんでした。「crane BYXXG GBBBY XXYXX YBBBG, moist …」のように、5文字の単語と4つの盤面の結果（G: 緑、Y: 黄、B / X: 灰。解けた盤面は _）をカンマ区切りで入力してください。",
+                entry
+            ),
+            Locale::En => format!(
+                "Could not read `{}`. Enter each five-letter word and its colors on the four boards (G: green, Y: yellow, B / X: gray, _ for a solved board) separated by commas, e.g. `crane BYXXG GBBBY XXYXX YBBBG, moist …`.",
+                entry
+            ),
+        }
+    }
+
+    // Quordle の盤面の見出し（1始まり）
+    pub fn quordle_board(self, number: usize) -> String {
+        match self {
+            Locale::Ja => format!("盤面 {}", number),
+            Locale::En => format!("Board {}", number),
+        }
+    }
+
+    // Quordle のおすすめに添える、まだ解けていない盤面に対する情報量の合計
+    pub fn quordle_bits(self, bits: f64) -> String {
+        match self {
+            Locale::Ja => format!(" — 合計 {:.2} ビット", bits),
+            Locale::En => format!(" — {:.2} bits in total", bits),
+        }
+    }
+
+    pub fn solve_invalid_entry(self, entry: &str) -> String {
+        match self {
+            Locale::Ja => format!(
+                "`{}` を読み取れませんでした。「crane BYXXG, moist XXGYB」のように、5文字の単語と結果（G: 緑、Y: 黄、B / X: 灰）をカンマ区切りで入力してください。",
+                entry
+            ),
+            Locale::En => format!(
+                "Could not read `{}`. Enter each five-letter word and its colors (G: green, Y: yellow, B / X: gray) separated by commas, e.g. `crane BYXXG, moist XXGYB`.",
+                entry
+            ),
+        }
+    }
+
+    pub fn analyze_file_error(self, max_games: usize) -> String {
+        match self {
+            Locale::Ja => format!(
+                "ファイルを読み取れませんでした。1MBまでのJSONファイルに、ゲームの推測と結果（例: `[{{\"guesses\": [\"crane BYXXG\", \"moist GGGGG\"]}}]`）を{}件まで入れてください。",
+                max_games
+            ),
+            Locale::En => format!(
+                "Could not read the file. Upload a JSON file (up to 1 MB) with up to {} games of guesses and colors, e.g. `[{{\"guesses\": [\"crane BYXXG\", \"moist GGGGG\"]}}]`.",
+                max_games
+            ),
+        }
+    }
+
+    pub fn analysis_summary(self, summary: &crate::analyze_file::AnalysisSummary) -> String {
+        let number = |value: Option<f64>, digits: usize| value.map_or("-".to_string(), |v| format!("{:.*}", digits, v));
+        let skill = summary.average_skill.map(|s| s * 100.0);
+        match self {
+            Locale::Ja => format!(
+                "📊 **{}ゲームを振り返りました**（読めなかったゲーム: {}）\n正解: {} / 平均推測回数: {}\n腕前: {}%（最も良い推測に対するエントロピーの割合）\n運: 1ゲームあたり {} ビット（期待より多く絞り込めた量）\n詳細は添付のJSONを見てください。",
+                summary.games, summary.skipped, summary.solved, number(summary.average_guesses, 2), number(skill, 1), number(summary.average_luck, 2)
+            ),
+            Locale::En => format!(
+                "📊 **Analyzed {} games** ({} unreadable)\nSolved: {} / Average guesses: {}\nSkill: {}% (entropy of your guesses vs. the best guess)\nLuck: {} bits per game (narrowed down beyond expectation)\nSee the attached JSON for details.",
+                summary.games, summary.skipped, summary.solved, number(summary.average_guesses, 2), number(skill, 1), number(summary.average_luck, 2)
+            ),
+        }
+    }
+
+    pub fn import_word_mismatch(self, rows: usize) -> String {
+        match self {
+            Locale::Ja => format!("画像から{}行を読み取りました。推測した単語を上から順に{}個入力してください。", rows, rows),
+            Locale::En => format!("Found {} rows in the image. Please enter the {} words you guessed, top to bottom.", rows, rows),
+        }
+    }
+
+    // 厳格なサーバーで単語リストにない推測を受け付けなかったとき（近い単語があれば案内する）
+    pub fn unknown_word(self, word: &str, nearest: &[String]) -> String {
+        let mut message = match self {
+            Locale::Ja => format!("❌ **{}** は単語リストにないため、推測に使えません。", word),
+            Locale::En => format!("❌ **{}** is not in the word list, so it can't be used as a guess.", word),
+        };
+        if !nearest.is_empty() {
+            message.push_str(&match self {
+                Locale::Ja => format!("\n近い単語: {}", nearest.join(", ")),
+                Locale::En => format!("\nDid you mean: {}", nearest.join(", ")),
+            });
+        }
+        message
+    }
+
+    pub fn hard_mode_warning(self, violations: &[HardModeViolation]) -> String {
+        let header = match self {
+            Locale::Ja => "⚠️ この単語はハードモードのルールに反しています",
+            Locale::En => "⚠️ This word breaks the hard mode rules",
+        };
+        let lines = violations.iter().map(|v| match (*v, self) {
+            (HardModeViolation::MissingGreen { letter, position }, Locale::Ja) => format!("- {}文字目は {} にしてください", position + 1, letter),
+            (HardModeViolation::MissingGreen { letter, position }, Locale::En) => format!("- Letter {} must be {}", position + 1, letter),
+            (HardModeViolation::MissingLetter { letter, count: 1 }, Locale::Ja) => format!("- {} を含めてください", letter),
+            (HardModeViolation::MissingLetter { letter, count: 1 }, Locale::En) => format!("- The word must contain {}", letter),
+            (HardModeViolation::MissingLetter { letter, count }, Locale::Ja) => format!("- {} を{}つ含めてください", letter, count),
+            (HardModeViolation::MissingLetter { letter, count }, Locale::En) => format!("- The word must contain {} {} times", letter, count),
+        });
+        std::iter::once(header.to_string()).chain(lines).collect::<Vec<_>>().join("\n")
+    }
+
+    pub fn session_error(self, error: SessionError) -> &'static str {
+        match (error, self) {
+            (_, Locale::Ja) => error.message(),
+            (SessionError::NotFound, Locale::En) => "No game found. Start a new one with `/wht`.",
+            (SessionError::Stale, Locale::En) => "This board is outdated. Please use the latest message.",
+            (SessionError::NoWordToConfirm, Locale::En) => "There is no word to confirm. Enter a word first.",
+            (SessionError::NotAwaitingColors, Locale::En) => "There is no word to color. Enter a word first.",
+            (SessionError::AlreadySolved, Locale::En) => "This game is already solved. Start a new one with `/wht`.",
+            (SessionError::ImportPending, Locale::En) => "Please confirm the imported board first.",
+            (SessionError::ImportWordMismatch, Locale::En) => "Please enter one five-letter word for each imported row, top to bottom.",
+            (SessionError::AnalysisRunning, Locale::En) => "Still analyzing the best words. Please wait a moment.",
+        }
+    }
+}
+
+// コマンドの名前と説明（Discordの既定は英語で、日本語のクライアントには日本語で表示される）
+// 名前を訳すと入力する名前も変わるため、管理者向けのコマンドは名前を訳さない
+struct CommandText {
+    name: &'static str,
+    ja_name: Option<&'static str>,
+    en: &'static str,
+    ja: &'static str,
+}
+
+const COMMANDS: [CommandText; 21] = [
+    CommandText { name: "ping", ja_name: None, en: "Pong", ja: "Pong" },
+    CommandText { name: "wht", ja_name: Some("ワードル"), en: "Wordle Helper Tool", ja: "Wordleの次の一手を提案" },
+    CommandText { name: "status", ja_name: Some("稼働状況"), en: "Show the bot's status", ja: "ボットの稼働状況を表示" },
+    CommandText { name: "admin", ja_name: None, en: "Administrator commands", ja: "管理者向けコマンド" },
+    CommandText { name: "config", ja_name: None, en: "Server settings", ja: "サーバーの設定" },
+    CommandText { name: "define", ja_name: Some("辞書"), en: "Look up an English word", ja: "英単語の意味を調べる" },
+    CommandText {
+        name: "candidates",
+        ja_name: Some("残り候補"),
+        en: "Show only how many words are left in your game",
+        ja: "現在のゲームの残り候補数だけを表示",
+    },
+    CommandText {
+        name: "import",
+        ja_name: Some("画像から読み込む"),
+        en: "Load a board from a Wordle screenshot",
+        ja: "Wordleのスクリーンショットから盤面を読み込む",
+    },
+    CommandText {
+        name: "leaderboard",
+        ja_name: Some("ランキング"),
+        en: "Show the leaderboard for this server",
+        ja: "このサーバーの成績のランキングを表示",
+    },
+    CommandText {
+        name: "stats",
+        ja_name: Some("成績"),
+        en: "Show your stats and guess distribution",
+        ja: "自分の成績と推測回数の分布を表示",
+    },
+    CommandText {
+        name: "openers",
+        ja_name: Some("開始単語"),
+        en: "Show the best opening words for the current word list",
+        ja: "現在の単語リストで最も良い開始単語を表示",
+    },
+    CommandText {
+        name: "evaluate",
+        ja_name: Some("開始単語の評価"),
+        en: "Score a word as an opening guess",
+        ja: "単語を開始単語として評価",
+    },
+    CommandText {
+        name: "guess",
+        ja_name: Some("推測"),
+        en: "Enter a guess and set its colors with reactions",
+        ja: "単語を入力し、色をリアクションで入力する",
+    },
+    CommandText {
+        name: "solve",
+        ja_name: Some("まとめて入力"),
+        en: "Paste your guesses so far and get suggestions",
+        ja: "これまでの推測と結果をまとめて入力して提案を受け取る",
+    },
+    CommandText {
+        name: "qht",
+        ja_name: Some("クワードル"),
+        en: "Quordle helper: paste your guesses on four boards and get suggestions",
+        ja: "Quordle（4つの盤面を同時に解く）の推測と結果をまとめて入力して提案を受け取る",
+    },
+    CommandText {
+        name: "analyze-file",
+        ja_name: Some("まとめて振り返る"),
+        en: "Analyze many finished games from a JSON file",
+        ja: "終わったゲームをまとめたJSONファイルを振り返る",
+    },
+    CommandText {
+        name: "privacy",
+        ja_name: Some("成績の非公開"),
+        en: "Stop recording your results and hide them from leaderboards",
+        ja: "成績を記録せず、ランキングや結果の投稿に表示しない",
+    },
+    CommandText {
+        name: "wordle",
+        ja_name: Some("ワードルで遊ぶ"),
+        en: "Play Wordle with a word picked by the bot",
+        ja: "ボットが選んだ単語を当てるWordleを遊ぶ",
+    },
+    CommandText {
+        name: "daily",
+        ja_name: Some("デイリー"),
+        en: "Play today's puzzle, the same word for everyone in this server",
+        ja: "サーバーの全員が同じ単語に挑戦する今日のデイリーを遊ぶ",
+    },
+    CommandText {
+        name: "input-style",
+        ja_name: Some("色の入力方法"),
+        en: "Choose how you enter letter colors on the board",
+        ja: "盤面の色の入力方法（ボタンかメニュー）を選ぶ",
+    },
+    // メッセージコマンドには説明をつけられない
+    CommandText { name: crate::screenshot::MESSAGE_COMMAND_NAME, ja_name: Some("盤面の画像を読み込む"), en: "", ja: "" },
+];
+
+// 3桁ごとにカンマで区切る
+pub(crate) fn grouped(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// 名前と説明を設定したコマンド（未登録の名前はそのまま使う）
+pub fn command(name: &str) -> CreateCommand {
+    let mut command = CreateCommand::new(name);
+    let Some(text) = COMMANDS.iter().find(|c| c.name == name) else {
+        return command;
+    };
+    if let Some(ja_name) = text.ja_name {
+        command = command.name_localized("ja", ja_name);
+    }
+    if !text.en.is_empty() {
+        command = command.description(text.en).description_localized("ja", text.ja);
+    }
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_known_codes_only() {
+        assert_eq!(Locale::parse("en"), Some(Locale::En));
+        assert_eq!(Locale::parse(" JA "), Some(Locale::Ja));
+        assert_eq!(Locale::parse("fr"), None);
+    }
+
+    #[test]
+    fn progress_counts_are_grouped_by_thousands() {
+        assert_eq!(Locale::Ja.scoring_progress(3200, 9800), "スコア計算中… 3,200/9,800語");
+        assert_eq!(grouped(0), "0");
+        assert_eq!(grouped(999), "999");
+        assert_eq!(grouped(1234567), "1,234,567");
+    }
+
+    #[test]
+    fn command_names_are_unique_and_valid() {
+        let mut names: Vec<&str> = COMMANDS.iter().map(|c| c.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), COMMANDS.len());
+
+        // Discordの制限: 名前は32文字、説明は100文字まで
+        for text in &COMMANDS {
+            assert!(text.ja_name.into_iter().chain([text.name]).all(|n| n.chars().count() <= 32));
+            assert!(text.en.chars().count() <= 100 && text.ja.chars().count() <= 100);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+// 実行中の分析（セッションごとに、分析している盤面のリビジョン）
+// 確定ボタンの連打や応答の再送で、同じ盤面の重い計算が二重に走らないようにする
+// 「キャンセル」ボタンや新しい盤面の分析からは、実行中の分析の計算を途中で止める
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+// 分析の進み具合（分析中の盤面に表示する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnalysisProgress {
+    // 分析している盤面のリビジョン
+    pub revision: u64,
+    pub stage: ProgressStage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStage {
+    // 計算の実行枠の順番待ち（1なら次に始まる）
+    Queued { position: usize },
+    // 提案のスコア計算中
+    Scoring { scored: usize, total: usize },
+}
+
+#[derive(Default)]
+pub struct InFlightAnalyses {
+    running: Mutex<HashMap<u64, (u64, CancellationToken)>>,
+}
+
+// 分析が終わる（途中で失敗する）と実行中の印を外す
+pub struct AnalysisGuard {
+    analyses: Arc<InFlightAnalyses>,
+    session: u64,
+    revision: u64,
+    token: CancellationToken,
+    progress: Option<Arc<watch::Sender<Option<AnalysisProgress>>>>,
+}
+
+// 別スレッドで行う計算に渡す、取り消しの合図と進み具合の送り先
+#[derive(Clone)]
+pub struct AnalysisProbe {
+    revision: u64,
+    token: CancellationToken,
+    progress: Option<Arc<watch::Sender<Option<AnalysisProgress>>>>,
+}
+
+impl InFlightAnalyses {
+    // 同じ盤面か、より新しい盤面の分析が実行中なら None（新しい盤面の分析は古い分析を待たずに始める）
+    pub fn begin(self: &Arc<Self>, session: u64, revision: u64) -> Option<AnalysisGuard> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if matches!(running.get(&session), Some((current, _)) if *current >= revision) {
+            return None;
+        }
+        let token = CancellationToken::new();
+        // 古い盤面の分析の結果は使われないため止める
+        if let Some((_, older)) = running.insert(session, (revision, token.clone())) {
+            older.cancel();
+        }
+        Some(AnalysisGuard { analyses: Arc::clone(self), session, revision, token, progress: None })
+    }
+
+    // その盤面の分析が実行中なら計算を止める（止めたかどうかを返す）
+    pub fn cancel(&self, session: u64, revision: u64) -> bool {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        match running.get(&session) {
+            Some((current, token)) if *current == revision => {
+                token.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl AnalysisGuard {
+    // 分析の進み具合を送る先
+    pub fn report_to(mut self, progress: watch::Sender<Option<AnalysisProgress>>) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    pub fn probe(&self) -> AnalysisProbe {
+        AnalysisProbe { revision: self.revision, token: self.token.clone(), progress: self.progress.clone() }
+    }
+
+    // 取り消されるまで待つ（順番待ちをやめるのに使う）
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+
+    pub fn report(&self, stage: ProgressStage) {
+        self.probe().report(stage);
+    }
+}
+
+impl AnalysisProbe {
+    // 計算の途中で確かめる取り消しの合図
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    // 送り先がなければ何もしない
+    pub fn report(&self, stage: ProgressStage) {
+        if let Some(progress) = &self.progress {
+            progress.send_replace(Some(AnalysisProgress { revision: self.revision, stage }));
+        }
+    }
+}
+
+impl Drop for AnalysisGuard {
+    fn drop(&mut self) {
+        let mut running = self.analyses.running.lock().unwrap_or_else(|e| e.into_inner());
+        // 後から始まった新しい盤面の印は残す
+        if matches!(running.get(&self.session), Some((revision, _)) if *revision == self.revision) {
+            running.remove(&self.session);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicates_are_rejected_until_the_first_finishes() {
+        let analyses = Arc::new(InFlightAnalyses::default());
+
+        let first = analyses.begin(1, 10).unwrap();
+        assert!(analyses.begin(1, 10).is_none());
+        assert!(analyses.begin(2, 10).is_some());
+        drop(first);
+        assert!(analyses.begin(1, 10).is_some());
+
+        // 新しい盤面の分析が始まると古い分析は止まり、古い分析が終わっても新しい分析中の印は外れない
+        let old = analyses.begin(1, 11).unwrap();
+        let new = analyses.begin(1, 12).unwrap();
+        assert!(old.probe().is_cancelled() && !new.probe().is_cancelled());
+        assert!(analyses.begin(1, 11).is_none());
+        drop(old);
+        assert!(analyses.begin(1, 12).is_none());
+        drop(new);
+    }
+
+    #[test]
+    fn cancel_stops_only_the_matching_board() {
+        let analyses = Arc::new(InFlightAnalyses::default());
+
+        let running = analyses.begin(1, 10).unwrap();
+        assert!(!analyses.cancel(1, 9));
+        assert!(!analyses.cancel(2, 10));
+        assert!(!running.probe().is_cancelled());
+
+        assert!(analyses.cancel(1, 10));
+        assert!(running.probe().is_cancelled());
+        drop(running);
+        assert!(!analyses.cancel(1, 10));
+    }
+
+    #[test]
+    fn progress_is_reported_with_the_board_revision() {
+        let analyses = Arc::new(InFlightAnalyses::default());
+        let (sender, receiver) = watch::channel(None);
+
+        let running = analyses.begin(1, 10).unwrap().report_to(sender);
+        running.report(ProgressStage::Scoring { scored: 300, total: 9800 });
+        assert_eq!(
+            *receiver.borrow(),
+            Some(AnalysisProgress { revision: 10, stage: ProgressStage::Scoring { scored: 300, total: 9800 } }),
+        );
+
+        // 分析が終わると送る側がなくなる
+        drop(running);
+        assert!(receiver.has_changed().is_err());
+    }
+}
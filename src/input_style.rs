@@ -0,0 +1,145 @@
+// /input-style: 色の入力方法をユーザーごとに選ぶ
+// メニューを選んだユーザーには、文字ごとのボタンの代わりに、黄と緑の文字をまとめて選ぶメニューを表示する
+// （ボタンを何度も押して色を切り替えずに、1回の操作で全部の文字の色を決められる）
+use serenity::all::{
+    ButtonStyle,
+    CommandInteraction,
+    CommandOptionType,
+    CreateActionRow,
+    CreateButton,
+    CreateCommand,
+    CreateCommandOption,
+    CreateSelectMenu,
+    CreateSelectMenuKind,
+    CreateSelectMenuOption,
+    ResolvedValue,
+};
+
+use crate::custom_id::{Action, CustomId};
+use crate::i18n::{self, Text};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::session::BoardView;
+use crate::settings::InputStyle;
+use crate::{Bot, LetterResult};
+
+// メニューの選択肢は25個まで（1文字につき黄と緑の2つ）
+const MAX_MENU_LETTERS: usize = 12;
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("input-style").add_option(
+        CreateCommandOption::new(CommandOptionType::String, "style", "色の入力方法（省略すると現在の設定を表示）")
+            .add_string_choice("ボタン / Buttons", InputStyle::Buttons.code())
+            .add_string_choice("メニュー / Menu", InputStyle::Select.code()),
+    )
+}
+
+// メニューの選択肢の値（<文字の位置>:<y または g>）
+fn option_value(index: usize, result: LetterResult) -> String {
+    let color = if result == LetterResult::Green { "g" } else { "y" };
+    format!("{}:{}", index, color)
+}
+
+// メニューで選ばれた値を文字ごとの色にする（選ばれなかった文字は灰、黄と緑の両方なら緑）
+pub fn menu_colors(values: &[String], length: usize) -> Option<Vec<LetterResult>> {
+    let mut colors = vec![LetterResult::Gray; length];
+    for value in values {
+        let (index, color) = value.split_once(':')?;
+        let slot = colors.get_mut(index.parse::<usize>().ok()?)?;
+        match color {
+            "g" => *slot = LetterResult::Green,
+            "y" if *slot != LetterResult::Green => *slot = LetterResult::Yellow,
+            "y" => {}
+            _ => return None,
+        }
+    }
+    Some(colors)
+}
+
+impl Bot {
+    // 色の入力方法を変更する（既定のボタンに戻す場合は設定を消す。変更がなければ保存しない）
+    pub async fn set_input_style(&self, user_id: u64, style: InputStyle) -> anyhow::Result<()> {
+        self.update_settings(|settings| {
+            let previous = if style == InputStyle::default() {
+                settings.input_styles.remove(&user_id)
+            } else {
+                settings.input_styles.insert(user_id, style)
+            };
+            (previous.unwrap_or_default() != style).then_some(())
+        })
+        .await
+        .map(|_| ())
+    }
+
+    // 黄と緑の文字をまとめて選ぶメニューと確定ボタン（今の色を選択済みにしておく）
+    pub(crate) fn create_letter_menu(&self, view: &BoardView, word: &str, current_results: &[LetterResult]) -> Vec<CreateActionRow> {
+        let (owner, revision) = (view.session, view.revision);
+        let mut options = Vec::new();
+        for (i, letter) in word.chars().enumerate().take(MAX_MENU_LETTERS) {
+            let current = current_results.get(i).copied().unwrap_or(LetterResult::Gray);
+            for result in [LetterResult::Yellow, LetterResult::Green] {
+                let label = format!("{}. {} {}", i + 1, letter, self.get_letter_emoji_for_button(&result));
+                options.push(CreateSelectMenuOption::new(label, option_value(i, result)).default_selection(current == result));
+            }
+        }
+
+        // 1文字に黄と緑の両方を選んだ場合は緑として扱うため、選べる数は文字数まで
+        let max_values = (options.len() / 2) as u8;
+        let menu = CreateSelectMenu::new(
+            CustomId::new(owner, revision, Action::LetterMenu).encode(),
+            CreateSelectMenuKind::String { options },
+        )
+        .placeholder(view.locale.text(Text::LetterMenuPlaceholder))
+        .min_values(0)
+        .max_values(max_values);
+
+        let confirm_button = CreateButton::new(CustomId::new(owner, revision, Action::Confirm).encode())
+            .label(view.locale.text(Text::ConfirmButton))
+            .style(ButtonStyle::Success);
+
+        vec![CreateActionRow::SelectMenu(menu), CreateActionRow::Buttons(vec![confirm_button])]
+    }
+
+    pub(crate) async fn handle_input_style_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let user_id = command.user.id.get();
+        let style = command.data.options().into_iter().find_map(|o| match o.value {
+            ResolvedValue::String(value) if o.name == "style" => InputStyle::parse(value),
+            _ => None,
+        });
+
+        let content = match style {
+            Some(style) => match self.set_input_style(user_id, style).await {
+                Ok(()) => locale.input_style(style),
+                Err(e) => {
+                    self.reporter.report("Failed to save input style", &e).await;
+                    locale.text(Text::GenericError)
+                }
+            },
+            None => locale.input_style(self.settings().input_style(user_id)),
+        };
+
+        self.responder(ctx, command, ReplyMode::Ephemeral)
+            .send(Reply::content(content))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu_values_become_colors_for_every_letter() {
+        use LetterResult::*;
+        let values = ["0:y", "2:g", "4:y", "4:g"].map(String::from);
+        assert_eq!(menu_colors(&values, 5), Some(vec![Yellow, Gray, Green, Gray, Green]));
+        assert_eq!(menu_colors(&[], 5), Some(vec![Gray; 5]));
+
+        for value in [option_value(3, Yellow), option_value(1, Green)] {
+            assert!(menu_colors(&[value], 5).is_some());
+        }
+        for invalid in ["5:g", "x:y", "1:b", "1"] {
+            assert_eq!(menu_colors(&[invalid.to_string()], 5), None, "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// ユーザーインストール: サーバーに招待しなくても、個人がどのサーバーやDMでも使えるようにする
+// セッションはユーザーごとなので、どこで始めても同じゲームの続きになる
+use serenity::all::{
+    AuthorizingIntegrationOwner,
+    AuthorizingIntegrationOwners,
+    CreateCommand,
+    GuildId,
+    InstallationContext,
+    InteractionContext,
+};
+
+// サーバーへのインストールとユーザーへのインストールの両方で、サーバー・DM・グループDMから使える
+pub fn user_installable(command: CreateCommand) -> CreateCommand {
+    command
+        .integration_types(vec![InstallationContext::Guild, InstallationContext::User])
+        .contexts(vec![InteractionContext::Guild, InteractionContext::BotDm, InteractionContext::PrivateChannel])
+}
+
+// ボットがサーバーにインストールされている場合だけ、そのサーバーを返す
+// （ユーザーインストールだけで使われたサーバーではロールの付与やチャンネルへの投稿ができない）
+pub fn installed_guild(owners: &AuthorizingIntegrationOwners, guild_id: Option<GuildId>) -> Option<GuildId> {
+    let guild_id = guild_id?;
+    // 古い形式のインタラクションには情報がないため、サーバーにインストールされているとみなす
+    if owners.0.is_empty() {
+        return Some(guild_id);
+    }
+    owners.0.iter()
+        .any(|owner| matches!(owner, AuthorizingIntegrationOwner::GuildInstall(Some(id)) if *id == guild_id))
+        .then_some(guild_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::all::UserId;
+
+    #[test]
+    fn guild_features_need_a_guild_install() {
+        let guild = GuildId::new(1);
+        let user_only = AuthorizingIntegrationOwners(vec![AuthorizingIntegrationOwner::UserInstall(UserId::new(2))]);
+        let both = AuthorizingIntegrationOwners(vec![
+            AuthorizingIntegrationOwner::GuildInstall(Some(guild)),
+            AuthorizingIntegrationOwner::UserInstall(UserId::new(2)),
+        ]);
+
+        assert_eq!(installed_guild(&user_only, Some(guild)), None);
+        assert_eq!(installed_guild(&both, Some(guild)), Some(guild));
+        assert_eq!(installed_guild(&both, None), None);
+        assert_eq!(installed_guild(&AuthorizingIntegrationOwners::default(), Some(guild)), Some(guild));
+    }
+}
@@ -0,0 +1,368 @@
+// /leaderboard: 成績のランキング（本人にだけ表示し、並び替え・期間の切り替え・ページ送り・自分の順位へ移動できる）
+// サーバーで使うとそのサーバーで遊んだユーザーだけを載せる
+// 盤面とは別の custom_id 形式: lb:<並び順>:<期間>:<ページ>[:<ボタンの区別>] / lb:<並び順>:<期間>:me / lb:sort:<期間> / lb:period:<並び順>
+use std::cmp::Ordering;
+
+use serenity::all::{
+    ButtonStyle,
+    Colour,
+    CommandInteraction,
+    CommandOptionType,
+    ComponentInteraction,
+    ComponentInteractionDataKind,
+    CreateActionRow,
+    CreateButton,
+    CreateCommand,
+    CreateCommandOption,
+    CreateEmbed,
+    CreateEmbedFooter,
+    CreateSelectMenu,
+    CreateSelectMenuKind,
+    CreateSelectMenuOption,
+    ResolvedValue,
+};
+use tracing::info;
+
+use crate::i18n::{self, Locale, Text};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::stats::{self, Tally, UserStats};
+use crate::Bot;
+
+const PREFIX: &str = "lb";
+const PAGE_SIZE: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardSort {
+    // 現在の連続正解日数
+    Streak,
+    // 平均推測回数（少ない順）
+    Average,
+    // 正解数
+    Wins,
+    // 正解率（遊んだゲームのうち正解した割合）
+    WinRate,
+}
+
+impl LeaderboardSort {
+    pub const ALL: [LeaderboardSort; 4] = [LeaderboardSort::Streak, LeaderboardSort::Average, LeaderboardSort::Wins, LeaderboardSort::WinRate];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            LeaderboardSort::Streak => "streak",
+            LeaderboardSort::Average => "average",
+            LeaderboardSort::Wins => "wins",
+            LeaderboardSort::WinRate => "rate",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.code() == code)
+    }
+}
+
+// 集計する期間（週間・月間は今日を含む直近の日数）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardPeriod {
+    Week,
+    Month,
+    AllTime,
+}
+
+impl LeaderboardPeriod {
+    pub const ALL: [LeaderboardPeriod; 3] = [LeaderboardPeriod::Week, LeaderboardPeriod::Month, LeaderboardPeriod::AllTime];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            LeaderboardPeriod::Week => "week",
+            LeaderboardPeriod::Month => "month",
+            LeaderboardPeriod::AllTime => "all",
+        }
+    }
+
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.code() == code)
+    }
+
+    // 集計を始める日（すべての期間なら None）
+    fn first_day(self, today: u64) -> Option<u64> {
+        match self {
+            LeaderboardPeriod::Week => Some(today.saturating_sub(6)),
+            LeaderboardPeriod::Month => Some(today.saturating_sub(29)),
+            LeaderboardPeriod::AllTime => None,
+        }
+    }
+}
+
+// 表示するページ（Me は呼び出したユーザーがいるページ）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Page {
+    Number(usize),
+    Me,
+}
+
+pub fn is_leaderboard_id(custom_id: &str) -> bool {
+    custom_id.split(':').next() == Some(PREFIX)
+}
+
+fn page_id(sort: LeaderboardSort, period: LeaderboardPeriod, page: usize, button: &str) -> String {
+    format!("{}:{}:{}:{}:{}", PREFIX, sort.code(), period.code(), page, button)
+}
+
+fn decode(custom_id: &str, values: &[String]) -> Option<(LeaderboardSort, LeaderboardPeriod, Page)> {
+    let parts: Vec<&str> = custom_id.split(':').collect();
+    match parts.as_slice() {
+        // 並び順や期間を変えたら最初のページに戻る
+        [PREFIX, "sort", period] => Some((LeaderboardSort::parse(values.first()?)?, LeaderboardPeriod::parse(period)?, Page::Number(0))),
+        [PREFIX, "period", sort] => Some((LeaderboardSort::parse(sort)?, LeaderboardPeriod::parse(values.first()?)?, Page::Number(0))),
+        [PREFIX, sort, period, "me"] => Some((LeaderboardSort::parse(sort)?, LeaderboardPeriod::parse(period)?, Page::Me)),
+        [PREFIX, sort, period, page, ..] => {
+            Some((LeaderboardSort::parse(sort)?, LeaderboardPeriod::parse(period)?, Page::Number(page.parse().ok()?)))
+        }
+        _ => None,
+    }
+}
+
+// 期間内に遊んだユーザーを並び順に並べる（同じ値なら期間内の正解数、次にユーザーIDの順）
+// 正解率以外の並び順では、期間内に正解していないユーザーは載せない
+pub fn rank(
+    entries: Vec<(u64, UserStats)>,
+    sort: LeaderboardSort,
+    period: LeaderboardPeriod,
+    today: u64,
+) -> Vec<(u64, UserStats, Tally)> {
+    let first_day = period.first_day(today);
+    let mut entries: Vec<_> = entries.into_iter()
+        .map(|(id, stats)| {
+            let tally = stats.tally_since(first_day);
+            (id, stats, tally)
+        })
+        .filter(|(_, _, tally)| match sort {
+            LeaderboardSort::WinRate => tally.played() > 0,
+            LeaderboardSort::Average => tally.average_guesses().is_some(),
+            LeaderboardSort::Streak | LeaderboardSort::Wins => tally.wins > 0,
+        })
+        .collect();
+
+    entries.sort_by(|(a_id, a, a_tally), (b_id, b, b_tally)| {
+        let primary = match sort {
+            LeaderboardSort::Streak => b.streak_on(today).cmp(&a.streak_on(today)),
+            LeaderboardSort::Average => a_tally.average_guesses().partial_cmp(&b_tally.average_guesses()).unwrap_or(Ordering::Equal),
+            LeaderboardSort::Wins => Ordering::Equal,
+            LeaderboardSort::WinRate => b_tally.win_rate().partial_cmp(&a_tally.win_rate()).unwrap_or(Ordering::Equal),
+        };
+        primary.then(b_tally.wins.cmp(&a_tally.wins)).then(a_id.cmp(b_id))
+    });
+    entries
+}
+
+pub fn create_command() -> CreateCommand {
+    let mut period = CreateCommandOption::new(CommandOptionType::String, "period", "集計する期間（省略するとすべての期間）");
+    for (name, value) in [("週間 / Weekly", LeaderboardPeriod::Week), ("月間 / Monthly", LeaderboardPeriod::Month), ("すべて / All time", LeaderboardPeriod::AllTime)] {
+        period = period.add_string_choice(name, value.code());
+    }
+    i18n::command("leaderboard").add_option(period)
+}
+
+fn value_text(locale: Locale, sort: LeaderboardSort, stats: &UserStats, tally: &Tally, today: u64) -> String {
+    match sort {
+        LeaderboardSort::Streak => locale.streak_days(stats.streak_on(today)),
+        LeaderboardSort::Average => format!("{:.2}", tally.average_guesses().unwrap_or_default()),
+        LeaderboardSort::Wins => tally.wins.to_string(),
+        LeaderboardSort::WinRate => format!("{:.0}% ({}/{})", tally.win_rate().unwrap_or_default(), tally.wins, tally.played()),
+    }
+}
+
+fn render(
+    locale: Locale,
+    sort: LeaderboardSort,
+    period: LeaderboardPeriod,
+    page: Page,
+    ranking: &[(u64, UserStats, Tally)],
+    caller: u64,
+    today: u64,
+) -> Reply {
+    let pages = ranking.len().div_ceil(PAGE_SIZE).max(1);
+    let page = match page {
+        Page::Me => ranking.iter().position(|(id, _, _)| *id == caller).map_or(0, |i| i / PAGE_SIZE),
+        Page::Number(n) => n.min(pages - 1),
+    };
+
+    let lines: Vec<String> = ranking.iter()
+        .enumerate()
+        .skip(page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .map(|(i, (id, stats, tally))| {
+            let line = format!("{}. <@{}> — {}", i + 1, id, value_text(locale, sort, stats, tally, today));
+            if *id == caller { format!("👉 **{}**", line) } else { line }
+        })
+        .collect();
+    let description = if lines.is_empty() { locale.text(Text::LeaderboardEmpty).to_string() } else { lines.join("\n") };
+
+    let embed = CreateEmbed::new()
+        .title(format!("🏆 {} — {}", locale.leaderboard_sort(sort), locale.leaderboard_period(period)))
+        .description(description)
+        .color(Colour::GOLD)
+        .footer(CreateEmbedFooter::new(locale.page_label(page + 1, pages)));
+
+    let options = LeaderboardSort::ALL.iter()
+        .map(|s| CreateSelectMenuOption::new(locale.leaderboard_sort(*s), s.code()).default_selection(*s == sort))
+        .collect();
+    let sort_menu = CreateSelectMenu::new(format!("{}:sort:{}", PREFIX, period.code()), CreateSelectMenuKind::String { options });
+    let options = LeaderboardPeriod::ALL.iter()
+        .map(|p| CreateSelectMenuOption::new(locale.leaderboard_period(*p), p.code()).default_selection(*p == period))
+        .collect();
+    let period_menu = CreateSelectMenu::new(format!("{}:period:{}", PREFIX, sort.code()), CreateSelectMenuKind::String { options });
+
+    let buttons = vec![
+        CreateButton::new(page_id(sort, period, page.saturating_sub(1), "prev"))
+            .label("◀")
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 0),
+        CreateButton::new(page_id(sort, period, page + 1, "next"))
+            .label("▶")
+            .style(ButtonStyle::Secondary)
+            .disabled(page + 1 >= pages),
+        CreateButton::new(format!("{}:{}:{}:me", PREFIX, sort.code(), period.code()))
+            .label(locale.text(Text::JumpToMe))
+            .style(ButtonStyle::Primary)
+            .disabled(!ranking.iter().any(|(id, _, _)| *id == caller)),
+    ];
+
+    Reply::embed(embed).components(vec![
+        CreateActionRow::SelectMenu(sort_menu),
+        CreateActionRow::SelectMenu(period_menu),
+        CreateActionRow::Buttons(buttons),
+    ])
+}
+
+impl Bot {
+    // サーバーで使った場合は、そのサーバーで遊んだユーザーだけを載せる（DMではすべてのユーザー）
+    async fn ranking(&self, guild_id: Option<u64>, sort: LeaderboardSort, period: LeaderboardPeriod) -> anyhow::Result<Vec<(u64, UserStats, Tally)>> {
+        // 成績を公開しないユーザーは、切り替える前の記録が残っていても表示しない
+        let settings = self.settings();
+        let home_guild_id = self.discord_guild_id.get();
+        let entries = self.storage.list_user_stats().await?
+            .into_iter()
+            .filter(|(id, _)| !settings.is_private(*id))
+            .filter_map(|(id, value)| serde_json::from_value::<UserStats>(value).ok().map(|stats| (id, stats)))
+            .filter(|(_, stats)| guild_id.is_none_or(|guild_id| stats.played_in(guild_id, home_guild_id)))
+            .collect();
+        Ok(rank(entries, sort, period, stats::today()))
+    }
+
+    async fn leaderboard_reply(&self, locale: Locale, guild_id: Option<u64>, sort: LeaderboardSort, period: LeaderboardPeriod, page: Page, caller: u64) -> Reply {
+        match self.ranking(guild_id, sort, period).await {
+            Ok(ranking) => render(locale, sort, period, page, &ranking, caller, stats::today()),
+            Err(e) => {
+                self.reporter.report("Failed to load leaderboard", &e).await;
+                Reply::content(locale.text(Text::GenericError))
+            }
+        }
+    }
+
+    pub(crate) async fn handle_leaderboard_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let caller = command.user.id.get();
+        let period = command.data.options().into_iter()
+            .find_map(|o| match o.value {
+                ResolvedValue::String(value) if o.name == "period" => LeaderboardPeriod::parse(value),
+                _ => None,
+            })
+            .unwrap_or(LeaderboardPeriod::AllTime);
+
+        let guild_id = command.guild_id.map(|g| g.get());
+        self.responder(ctx, command, ReplyMode::Ephemeral)
+            .run(self.leaderboard_reply(locale, guild_id, LeaderboardSort::Streak, period, Page::Number(0), caller))
+            .await;
+    }
+
+    // ランキングの操作（本人にだけ表示されたメッセージを書き換える）
+    pub(crate) async fn handle_leaderboard_component(&self, ctx: &ReplyContext, component: &ComponentInteraction) {
+        let locale = self.locale_for(component.guild_id);
+        let values = match &component.data.kind {
+            ComponentInteractionDataKind::StringSelect { values } => values.as_slice(),
+            _ => &[],
+        };
+        let Some((sort, period, page)) = decode(&component.data.custom_id, values) else {
+            info!("Rejected leaderboard custom_id {:?}", component.data.custom_id);
+            self.responder(ctx, component, ReplyMode::Ephemeral)
+                .send(Reply::content(locale.text(Text::GenericError)))
+                .await;
+            return;
+        };
+
+        self.responder(ctx, component, ReplyMode::UpdateMessage)
+            .run(self.leaderboard_reply(locale, component.guild_id.map(|g| g.get()), sort, period, page, component.user.id.get()))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(wins: u32, streak: u32, total_guesses: u32) -> UserStats {
+        UserStats {
+            wins,
+            current_streak: streak,
+            best_streak: streak,
+            last_win_day: Some(100),
+            counted_wins: wins,
+            total_guesses,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ranking_orders_by_the_selected_column() {
+        let entries = vec![
+            (1, stats(10, 1, 50)),
+            (2, stats(3, 3, 9)),
+            (3, stats(5, 3, 20)),
+            (4, UserStats::default()),
+        ];
+        let ids = |sort| rank(entries.clone(), sort, LeaderboardPeriod::AllTime, 100).into_iter().map(|(id, _, _)| id).collect::<Vec<_>>();
+
+        // 正解したことのないユーザーは載せない。同じ値なら正解数の多い順
+        assert_eq!(ids(LeaderboardSort::Streak), vec![3, 2, 1]);
+        assert_eq!(ids(LeaderboardSort::Average), vec![2, 3, 1]);
+        assert_eq!(ids(LeaderboardSort::Wins), vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn periods_count_only_recent_games() {
+        let mut recent = UserStats::default();
+        recent.record_win(98, 3);
+        recent.record_loss(99);
+        let mut old = stats(20, 0, 80);
+        old.record_win(80, 4);
+        let mut perfect = UserStats::default();
+        perfect.record_win(100, 5);
+        let entries = vec![(1, recent), (2, old), (3, perfect)];
+        let ids = |sort, period| rank(entries.clone(), sort, period, 100).into_iter().map(|(id, _, _)| id).collect::<Vec<_>>();
+
+        // 週間では期間内に遊んでいないユーザーは載せない
+        assert_eq!(ids(LeaderboardSort::Wins, LeaderboardPeriod::Week), vec![1, 3]);
+        assert_eq!(ids(LeaderboardSort::WinRate, LeaderboardPeriod::Week), vec![3, 1]);
+        assert_eq!(ids(LeaderboardSort::Average, LeaderboardPeriod::Month), vec![1, 2, 3]);
+        assert_eq!(ids(LeaderboardSort::Wins, LeaderboardPeriod::AllTime), vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn guilds_without_records_belong_to_the_home_guild() {
+        let mut stats = UserStats::default();
+        assert!(stats.played_in(1, 1) && !stats.played_in(2, 1));
+        stats.guilds.insert(2);
+        assert!(stats.played_in(2, 1) && !stats.played_in(1, 1));
+    }
+
+    #[test]
+    fn custom_ids_round_trip() {
+        use LeaderboardPeriod::*;
+        assert_eq!(decode(&page_id(LeaderboardSort::Wins, Week, 2, "next"), &[]), Some((LeaderboardSort::Wins, Week, Page::Number(2))));
+        assert_eq!(decode("lb:average:month:me", &[]), Some((LeaderboardSort::Average, Month, Page::Me)));
+        assert_eq!(decode("lb:sort:all", &["rate".to_string()]), Some((LeaderboardSort::WinRate, AllTime, Page::Number(0))));
+        assert_eq!(decode("lb:period:wins", &["week".to_string()]), Some((LeaderboardSort::Wins, Week, Page::Number(0))));
+        assert!(is_leaderboard_id("lb:sort:all") && !is_leaderboard_id("wht:2:1:1:new"));
+    }
+}
@@ -0,0 +1,2004 @@
+use serenity::{all::{
+        ChannelId,
+        CreateActionRow,
+        CreateAttachment,
+        CreateButton,
+        Command,
+        CreateCommand,
+        CreateCommandOption,
+        CommandOptionType,
+        CreateEmbed,
+        CreateInputText,
+        CreateModal,
+        Interaction,
+        ModalInteraction,
+        ButtonStyle,
+        GuildId,
+        InputTextStyle,
+        ComponentInteraction,
+        ComponentInteractionDataKind,
+        CreateSelectMenu,
+        CreateSelectMenuKind,
+        CreateSelectMenuOption,
+        Colour,
+        MessageId,
+        UserId,
+        Http,
+        Reaction,
+        ResolvedValue,
+    },
+    async_trait};
+use serenity::model::gateway::Ready;
+use serenity::prelude::*;
+use tracing::{error, field::Empty, info, info_span, warn, Instrument};
+use arc_swap::ArcSwap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+
+mod admin;
+mod analysis_queue;
+mod analyze_file;
+mod board_image;
+mod cache_refresh;
+mod candidates;
+#[cfg(feature = "api")]
+mod api;
+pub mod config;
+pub mod custom_id;
+mod daily;
+mod define;
+mod edit_queue;
+mod forum;
+pub mod emoji;
+mod guild_config;
+pub mod i18n;
+mod in_flight;
+mod input_style;
+mod install;
+mod leaderboard;
+mod metrics;
+mod openers;
+pub mod permissions;
+mod play;
+mod presence;
+mod privacy;
+pub mod quordle;
+mod reactions;
+mod reporting;
+mod respond;
+mod results;
+mod rewards;
+mod scheduler;
+mod screenshot;
+pub mod session;
+pub mod settings;
+mod snapshot;
+mod solve;
+pub mod stats;
+mod status;
+pub mod storage;
+mod suggestion_cache;
+pub mod supabase;
+#[cfg(feature = "webhook")]
+mod webhook;
+mod word_cache;
+mod wordlist;
+mod write_queue;
+
+#[cfg(feature = "api")]
+pub use api::{api_router, serve_api};
+pub use config::BotConfig;
+#[cfg(feature = "webhook")]
+pub use webhook::serve_webhook;
+use custom_id::{Action, CustomId, CustomIdError};
+use edit_queue::{EditQueue, DEFAULT_EDIT_DEBOUNCE};
+use i18n::{Locale, Text};
+pub use wordle_solver as solver;
+pub use wordle_solver::{LetterResult, WordleGuess};
+use permissions::PermissionConfig;
+use reporting::ErrorReporter;
+use respond::{Reply, ReplyContext, ReplyMode, Responder, Target};
+use scheduler::{Schedule, Scheduler};
+use session::{BoardControls, BoardField, BoardSection, BoardView, SessionError};
+use settings::{Difficulty, Settings};
+use storage::{Storage, SupabaseStorage};
+
+// フォールバック：一般的な開始単語
+fn fallback_words() -> Vec<String> {
+    solver::FALLBACK_WORDS.iter().map(|w| w.to_string()).collect()
+}
+
+// 最後の操作からこの時間が経過したセッションは破棄する
+const SESSION_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+// メモリに保持するセッション数の既定の上限
+pub const DEFAULT_MAX_SESSIONS: usize = 1000;
+
+const DEFAULT_SLOW_INTERACTION_THRESHOLD: std::time::Duration = std::time::Duration::from_millis(1500);
+
+// 「なぜこの単語？」ボタンをつけるおすすめの数
+const MAX_EXPLAINED: usize = 3;
+
+// 分析中の盤面に進み具合を表示する間隔（これより早く終わる分析では表示しない）
+const PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+// スコア計算の進み具合を送る単語数の区切り
+const PROGRESS_REPORT_STEP: usize = 100;
+
+// セッションの進行段階（遷移は session.rs の GameState のメソッドでのみ行う）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Phase {
+    AwaitingWord,
+    AwaitingColors { word: String, results: Vec<LetterResult> },
+    // スクリーンショットから読み取った色の確認中（selected は修正中の行）
+    Importing { rows: Vec<Vec<LetterResult>>, selected: usize },
+    Completed,
+}
+
+// チャンネルで共有した盤面（開始した人と、確定とやり直しを開始した人に限るか）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedBoard {
+    pub host: u64,
+    pub host_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameState {
+    pub guesses: Vec<WordleGuess>,
+    pub phase: Phase,
+    // 状態が変わるたびに更新され、ボタンの custom_id に埋め込まれる
+    pub revision: u64,
+    pub last_suggestion: String,
+    // 盤面を表示する言語（保存済みのセッションには無いため既定値を使う）
+    #[serde(default)]
+    pub locale: Locale,
+    // 入力した単語がハードモードのルールに反していれば警告し、提案もルールに合う単語だけにする
+    #[serde(default)]
+    pub hard_mode: bool,
+    // 提案の難易度（開始したときのサーバー全体の既定値、または /wht で指定したもの）
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    // 最後に提案したおすすめの上位の単語を選んだ理由（提案と同時に計算する）
+    #[serde(default)]
+    pub explanations: Vec<solver::GuessExplanation>,
+    // 開始したサーバー（盤面の絵文字の設定に使う。DMや古い保存データでは None）
+    #[serde(default)]
+    pub guild_id: Option<u64>,
+    // 開始したときの単語リストの版（分析はこの版の単語で行う。古い保存データには無い）
+    #[serde(default)]
+    pub word_list_version: Option<String>,
+    // チャンネルで共有した盤面（セッションはチャンネルのIDで持つ。個人の盤面では None）
+    #[serde(default)]
+    pub shared: Option<SharedBoard>,
+    // 推測の並びごとの候補（推測を戻して確定し直したときに使い回す。複製とは共有する）
+    #[serde(skip)]
+    pub(crate) candidate_cache: word_cache::CandidateCache,
+    // 推測の並びごとに計算したおすすめの単語（描き直すときや確定し直したときに使い回す。複製とは共有する）
+    #[serde(skip)]
+    pub(crate) suggestion_cache: suggestion_cache::SuggestionCache,
+    #[serde(skip, default = "std::time::Instant::now")]
+    pub last_active: std::time::Instant,
+}
+
+// セッションごとに個別のロックを持たせ、他のユーザーの操作を待たないようにする
+// （外側のロックはエントリの取得・追加の間だけ保持する）
+pub(crate) type SessionMap = Arc<tokio::sync::RwLock<HashMap<u64, Arc<tokio::sync::Mutex<GameState>>>>>;
+
+// 共有状態はすべて Arc で保持し、バックグラウンドタスクには clone() して渡す
+#[derive(Clone)]
+pub struct Bot {
+    storage: Arc<dyn Storage>,
+    discord_guild_id: GuildId,
+    game_states: SessionMap,
+    emoji_cache: Arc<tokio::sync::RwLock<HashMap<String, String>>>,
+    // アプリケーションに登録した絵文字（名前 → Discordの表記）
+    application_emojis: Arc<tokio::sync::RwLock<HashMap<String, String>>>,
+    word_cache: Arc<tokio::sync::RwLock<word_cache::WordCache>>,
+    permissions: PermissionConfig,
+    settings: Arc<ArcSwap<Settings>>,
+    // 設定の書き換えを1つずつ行うためのロック
+    settings_writes: Arc<tokio::sync::Mutex<()>>,
+    scheduler: Arc<Scheduler>,
+    reporter: Arc<ErrorReporter>,
+    // /define で使う辞書
+    dictionary: Arc<define::Dictionary>,
+    // 外部サービスへのリクエスト（結果の転送など）
+    http_client: reqwest::Client,
+    // 盤面メッセージの編集をまとめて送るキュー
+    edits: Arc<EditQueue>,
+    // 実行中の分析（同じ盤面の分析を二重に始めない）
+    analyses: Arc<in_flight::InFlightAnalyses>,
+    // 分析の計算の実行枠（同時に計算する数を抑え、ユーザーごとに順番に実行する）
+    analysis_queue: Arc<analysis_queue::AnalysisQueue>,
+    // 盤面のリビジョン採番（再起動前のボタンと衝突しないよう起動時刻から始める）
+    revisions: Arc<AtomicU64>,
+    // これ以上かかった操作を警告としてログに残す
+    slow_interaction_threshold: std::time::Duration,
+    // 上限を超えたら最も古いセッションを追い出す（persist_sessions なら変更のたびにストレージに保存し、追い出したものも退避する）
+    max_sessions: usize,
+    persist_sessions: bool,
+    // 単語・絵文字キャッシュのスナップショットの保存先（任意）
+    cache_snapshot: Option<std::path::PathBuf>,
+    // 利用状況の集計（/admin dashboard）
+    metrics: Arc<metrics::Metrics>,
+    // /openers のランキング（単語キャッシュが変わるまで使い回す）
+    openers: Arc<openers::OpenerCache>,
+    // ボットのステータスに表示するセッション数
+    presence: Arc<presence::Presence>,
+    // 一般向けのコマンドをユーザーインストールでも使えるようにする
+    user_install: bool,
+    // リアクションで色を入力中の盤面
+    reaction_prompts: Arc<reactions::ReactionPrompts>,
+    // /wordle で遊んでいるゲーム
+    play_games: Arc<play::PlayGames>,
+    // サーバーごとの /daily
+    daily: Arc<daily::DailyPuzzles>,
+    // Supabaseのクライアント（/admin rotate-key でキーを差し替える。テストなどでは None）
+    supabase: Option<Arc<supabase::SupabaseClient>>,
+    // セッションと成績の未送信の書き込み（終了時に flush_writes で送る）
+    writes: Arc<write_queue::WriteBehindStorage>,
+    // 単語リストの現在の版と、置き換えられた過去の版
+    word_versions: Arc<wordlist::WordListVersions>,
+    // 単語・絵文字キャッシュの定期的な再読み込み
+    cache_refresher: Arc<cache_refresh::CacheRefresher>,
+}
+
+impl Bot {
+    pub fn new(discord_guild_id: GuildId, storage: Arc<dyn Storage>, permissions: PermissionConfig) -> Self {
+        let game_states = Arc::new(tokio::sync::RwLock::new(HashMap::new()));
+        let http_client = reqwest::Client::new();
+        // ストレージの呼び出しはすべて成否を集計する
+        let metrics = Arc::new(metrics::Metrics::default());
+        let storage: Arc<dyn Storage> = Arc::new(metrics::MeteredStorage::new(storage, Arc::clone(&metrics)));
+        // セッションと成績の保存はまとめて送る
+        let writes = Arc::new(write_queue::WriteBehindStorage::new(storage));
+        let storage: Arc<dyn Storage> = Arc::clone(&writes) as Arc<dyn Storage>;
+
+        let word_cache = Arc::new(tokio::sync::RwLock::new(word_cache::WordCache::default()));
+        let word_versions = Arc::new(wordlist::WordListVersions::default());
+        let settings = Arc::new(ArcSwap::from_pointee(Settings::default()));
+
+        let presence = Arc::new(presence::Presence::default());
+        let daily = Arc::new(daily::DailyPuzzles::default());
+        let play_games = Arc::new(play::PlayGames::default());
+
+        let mut scheduler = Scheduler::new();
+        scheduler.register("session-expiry", Schedule::Every(std::time::Duration::from_secs(60 * 60)), {
+            let game_states = Arc::clone(&game_states);
+            move || expire_sessions(Arc::clone(&game_states))
+        });
+        scheduler.register("play-game-expiry", Schedule::Every(std::time::Duration::from_secs(60 * 60)), {
+            let play_games = Arc::clone(&play_games);
+            let storage = Arc::clone(&storage);
+            move || play::expire_games(Arc::clone(&play_games), Arc::clone(&storage))
+        });
+        // 間隔は設定で変更できるため、毎分確認して操作のない盤面にだけヒントを出す
+        scheduler.register("auto-hints", Schedule::Every(std::time::Duration::from_secs(60)), {
+            let play_games = Arc::clone(&play_games);
+            let settings = Arc::clone(&settings);
+            let storage = Arc::clone(&storage);
+            move || play::give_auto_hints(Arc::clone(&play_games), Arc::clone(&settings), Arc::clone(&storage))
+        });
+        scheduler.register("presence", Schedule::Every(std::time::Duration::from_secs(60)), {
+            let presence = Arc::clone(&presence);
+            let game_states = Arc::clone(&game_states);
+            let settings = Arc::clone(&settings);
+            move || presence::refresh_presence(Arc::clone(&presence), Arc::clone(&game_states), Arc::clone(&settings))
+        });
+        // 送る間隔は with_write_batching で変更できるため、毎秒確認して必要なときだけ送る
+        scheduler.register("write-flush", Schedule::Every(std::time::Duration::from_secs(1)), {
+            let writes = Arc::clone(&writes);
+            move || {
+                let writes = Arc::clone(&writes);
+                async move { writes.flush_if_due().await }
+            }
+        });
+        let emoji_cache = Arc::new(tokio::sync::RwLock::new(HashMap::new()));
+        let cache_refresher = Arc::new(cache_refresh::CacheRefresher::new(
+            Arc::clone(&storage),
+            Arc::clone(&word_cache),
+            Arc::clone(&word_versions),
+            Arc::clone(&emoji_cache),
+            Arc::clone(&settings),
+        ));
+        // 間隔は設定で変更できるため、毎分確認して必要なときだけ再読み込みする
+        scheduler.register("cache-refresh", Schedule::Every(std::time::Duration::from_secs(60)), {
+            let cache_refresher = Arc::clone(&cache_refresher);
+            move || {
+                let cache_refresher = Arc::clone(&cache_refresher);
+                async move { cache_refresher.run_if_due().await }
+            }
+        });
+
+        // 日付が変わったら前日のデイリーの答えとまとめを投稿する
+        scheduler.register("daily-summary", Schedule::DailyAt { hour: 0, minute: 0 }, {
+            let daily = Arc::clone(&daily);
+            let settings = Arc::clone(&settings);
+            let storage = Arc::clone(&storage);
+            move || daily::post_summaries(Arc::clone(&daily), Arc::clone(&settings), Arc::clone(&storage))
+        });
+
+        Self {
+            storage,
+            discord_guild_id,
+            game_states,
+            emoji_cache,
+            application_emojis: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            word_cache,
+            permissions,
+            settings,
+            settings_writes: Arc::new(tokio::sync::Mutex::new(())),
+            scheduler: Arc::new(scheduler),
+            reporter: Arc::new(ErrorReporter::new(None)),
+            dictionary: Arc::new(define::Dictionary::new(http_client.clone())),
+            http_client,
+            edits: Arc::new(EditQueue::new(DEFAULT_EDIT_DEBOUNCE)),
+            analyses: Arc::new(in_flight::InFlightAnalyses::default()),
+            analysis_queue: Arc::new(analysis_queue::AnalysisQueue::new(analysis_queue::default_workers())),
+            revisions: Arc::new(AtomicU64::new(startup_millis())),
+            slow_interaction_threshold: DEFAULT_SLOW_INTERACTION_THRESHOLD,
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            persist_sessions: false,
+            cache_snapshot: None,
+            metrics,
+            openers: Arc::new(openers::OpenerCache::default()),
+            presence,
+            user_install: false,
+            reaction_prompts: Arc::new(reactions::ReactionPrompts::default()),
+            play_games,
+            daily,
+            supabase: None,
+            writes,
+            word_versions,
+            cache_refresher,
+        }
+    }
+
+    // 深刻なエラーを投稿するチャンネルを設定
+    pub fn with_error_channel(mut self, channel: ChannelId) -> Self {
+        self.reporter = Arc::new(ErrorReporter::new(Some(channel)));
+        self
+    }
+
+    // メモリに保持するセッション数の上限と、追い出したセッションを保存するかを設定
+    pub fn with_session_limit(mut self, max_sessions: usize, persist_evicted: bool) -> Self {
+        self.max_sessions = max_sessions.max(1);
+        self.persist_sessions = persist_evicted;
+        self
+    }
+
+    // 分析の計算を同時にいくつまで行うか
+    pub fn with_analysis_workers(mut self, workers: usize) -> Self {
+        self.analysis_queue = Arc::new(analysis_queue::AnalysisQueue::new(workers));
+        self
+    }
+
+    // 遅い操作として警告するしきい値を設定
+    pub fn with_slow_interaction_threshold(mut self, threshold: std::time::Duration) -> Self {
+        self.slow_interaction_threshold = threshold;
+        self
+    }
+
+    // セッションと成績の書き込みを、何件たまったら・何秒経ったらまとめて送るか
+    pub fn with_write_batching(self, batch_size: usize, flush_interval: std::time::Duration) -> Self {
+        self.writes.configure(batch_size, flush_interval);
+        self
+    }
+
+    // 未送信の書き込みをすべて送る（終了する前に呼ぶ）
+    pub async fn flush_writes(&self) {
+        let pending = self.writes.pending_count();
+        if pending == 0 {
+            return;
+        }
+        info!("Flushing {} queued writes", pending);
+        if let Err(e) = self.writes.flush().await {
+            self.reporter.report("Failed to flush queued writes", &e).await;
+        }
+    }
+
+    // 外部サービスへのリクエストに使うクライアント（接続プールの設定済みのもの）
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.dictionary = Arc::new(define::Dictionary::new(client.clone()));
+        self.http_client = client;
+        self
+    }
+
+    // キーの差し替えに使うSupabaseのクライアント（ストレージと同じものを渡す）
+    pub fn with_supabase(mut self, client: Arc<supabase::SupabaseClient>) -> Self {
+        self.supabase = Some(client);
+        self
+    }
+
+    // 一般向けのコマンドを、ボットを招待していないサーバーやDMでも使えるように登録する
+    pub fn with_user_install(mut self, enabled: bool) -> Self {
+        self.user_install = enabled;
+        self
+    }
+
+    // ストレージから単語リストを取得してキャッシュ
+    pub async fn load_word_cache(&self) -> anyhow::Result<()> {
+        load_words(self.storage.as_ref(), &self.word_cache, &self.word_versions).await
+    }
+
+    // 候補の絞り込みと結果の計算の整合性を、現在の単語リストの無作為な組で確かめる（5文字の単語がなければ None）
+    pub async fn check_consistency(&self, samples: usize) -> anyhow::Result<Option<solver::ConsistencyReport>> {
+        let arena = self.word_cache.read().await.words_of_length(solver::WORD_LENGTH).cloned();
+        let Some(arena) = arena.filter(|arena| !arena.is_empty()) else {
+            return Ok(None);
+        };
+        let seed = startup_millis();
+        Ok(Some(tokio::task::spawn_blocking(move || solver::check_consistency(&arena, samples, seed)).await?))
+    }
+
+    // ストレージから絵文字情報を取得してキャッシュ
+    pub async fn load_emoji_cache(&self) -> anyhow::Result<()> {
+        load_emojis(self.storage.as_ref(), &self.emoji_cache).await
+    }
+
+    // 単語・絵文字のうち、ストレージで変わったものだけ読み込み直す
+    pub async fn refresh_caches(&self) -> anyhow::Result<cache_refresh::Refreshed> {
+        self.cache_refresher.refresh().await
+    }
+
+    // 制約に基づいて可能な単語をフィルタリング
+    // 使わない単語（全サーバー共通とセッションを開始したサーバーのもの）は除く
+    fn filter_words_by_constraints(&self, words: &word_cache::WordCache, game_state: &GameState) -> word_cache::Candidates {
+        let candidates = game_state.candidate_cache.candidates(words, &game_state.guesses);
+        let settings = self.settings();
+        if !settings.has_banned_words(game_state.guild_id) {
+            return candidates;
+        }
+        candidates.without(|word| settings.is_banned(game_state.guild_id, word))
+    }
+
+    // 候補が0件のときだけ、食い違っている推測を探す（単語リストが空なら判断しない）
+    async fn find_conflict(&self, game_state: &GameState) -> Option<solver::Conflict> {
+        let words = self.words_for(game_state).await;
+        if words.is_empty() || !self.filter_words_by_constraints(&words, game_state).is_empty() {
+            return None;
+        }
+
+        let conflict = solver::find_conflict(words.iter(), &game_state.guesses);
+        info!("Constraints have no candidates: {:?}", conflict);
+        conflict
+    }
+
+    // 高度な単語提案システム
+    async fn get_optimal_words(&self, game_state: &GameState, analysis: &in_flight::AnalysisGuard) -> anyhow::Result<Vec<String>> {
+        {
+            let words = self.word_cache.read().await;
+            info!("Total words in cache: {}", words.len());
+
+            if words.is_empty() {
+                info!("Word cache is empty, attempting to reload");
+                drop(words); // ロックを解放
+
+                if let Err(e) = self.load_word_cache().await {
+                    warn!("Failed to reload word cache: {:?}", e);
+                    return Ok(fallback_words());
+                }
+            }
+        }
+
+        // 再度ロックを取得してフィルタリング（ゲームを始めたときの版の単語を使う）
+        let words = self.words_for(game_state).await;
+        if words.is_empty() {
+            info!("Word cache still empty after reload");
+            return Ok(fallback_words());
+        }
+
+        let possible_words = self.filter_words_by_constraints(&words, game_state);
+        info!("Possible words after filtering: {}", possible_words.len());
+
+        // フィルタリング結果の詳細ログ
+        if possible_words.is_empty() {
+            info!("No possible words found. Game state constraints:");
+            for (i, guess) in game_state.guesses.iter().enumerate() {
+                info!("  Guess {}: {} -> {:?}", i + 1, guess.word, guess.results);
+            }
+
+            // 制約なしで5文字の単語があるかチェック
+            let five_letter_words: Vec<_> = words.iter()
+                .filter(|w| solver::is_valid_word(w))
+                .take(10)
+                .collect();
+            info!("Sample 5-letter words in database: {:?}", 
+                five_letter_words);
+
+            // フォールバック：一般的な開始単語
+            return Ok(fallback_words());
+        }
+
+        let difficulty = game_state.difficulty;
+        let key = self.suggestion_key(game_state, possible_words.clone());
+        if let Some(words) = game_state.suggestion_cache.get(&key) {
+            info!("Reusing cached suggestions");
+            return Ok(words);
+        }
+        if possible_words.len() <= key.limit && difficulty == Difficulty::Standard {
+            let words = possible_words.to_strings();
+            game_state.suggestion_cache.insert(key, words.clone());
+            return Ok(words);
+        }
+
+        let (arena, ids) = (&possible_words.arena, &possible_words.ids);
+        // advanced では答えの候補でない単語も、候補を分ける推測として順位をつける（使わない単語は除く）
+        // ハードモードでは判明したヒントを使わない単語は推測できないため除く（答えの候補は常にルールに合う）
+        let probes: Vec<solver::WordId> = if difficulty == Difficulty::Advanced {
+            let settings = self.settings();
+            let banned = settings.has_banned_words(game_state.guild_id);
+            arena.iter()
+                .filter(|(_, word)| !banned || !settings.is_banned(game_state.guild_id, word))
+                .filter(|(_, word)| !game_state.hard_mode || solver::hard_mode_violations(word, &game_state.guesses).is_empty())
+                .map(|(id, _)| id)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let total = if difficulty == Difficulty::Advanced { probes.len() } else { ids.len() };
+        let (arena, ids) = (Arc::clone(arena), Arc::clone(ids));
+        let (guess_count, limit) = (game_state.guesses.len(), key.limit);
+        let probe = analysis.probe();
+        // スコアは複数のスレッドで計算し、表示する分だけを残す（非同期のワーカーを止めないよう別スレッドで行う）
+        let scored_words = tokio::task::spawn_blocking(move || {
+            // 進み具合は一定の単語数ごとに送る
+            let cancelled = |scored: usize| {
+                if scored.is_multiple_of(PROGRESS_REPORT_STEP) {
+                    probe.report(in_flight::ProgressStage::Scoring { scored, total });
+                }
+                probe.is_cancelled()
+            };
+            match difficulty {
+                Difficulty::Standard => solver::top_word_ids_until(&arena, &ids, guess_count, limit, cancelled),
+                // よく使われる単語を先に並べる（スコアの順番はそのまま。上位に入らない単語も並べ替えに使うため全体を順位づけする）
+                Difficulty::Beginner => solver::rank_word_ids_until(&arena, &ids, guess_count, cancelled).map(|mut ranked| {
+                    ranked.sort_by_key(|r| !solver::is_common_word(arena.word(r.id)));
+                    ranked
+                }),
+                Difficulty::Advanced => solver::top_probes_until(&arena, &probes, &ids, limit, cancelled),
+            }
+        })
+        .await?;
+        // 取り消された分析の結果は使われない（盤面はすでに確定前に戻っている）
+        let Some(scored_words) = scored_words else {
+            info!("Analysis was cancelled during scoring");
+            return Ok(Vec::new());
+        };
+
+        // 表示する分だけ単語に戻す
+        let words: Vec<String> = scored_words.into_iter().take(key.limit).map(|ranked| possible_words.arena.word(ranked.id).to_string()).collect();
+        game_state.suggestion_cache.insert(key, words.clone());
+        Ok(words)
+    }
+
+    // 提案の順位を決める条件（同じなら覚えている提案を使える）
+    fn suggestion_key(&self, game_state: &GameState, candidates: word_cache::Candidates) -> suggestion_cache::SuggestionKey {
+        let difficulty = game_state.difficulty;
+        let limit = difficulty.suggestion_limit(self.settings().suggestion_count).max(10);
+        suggestion_cache::SuggestionKey::new(&game_state.guesses, candidates, difficulty, game_state.hard_mode, limit)
+    }
+
+    // 今の盤面の提案を覚えているか（覚えていれば計算を待たずに描き直せる）
+    pub(crate) async fn has_cached_suggestions(&self, game_state: &GameState) -> bool {
+        let candidates = {
+            let words = self.words_for(game_state).await;
+            self.filter_words_by_constraints(&words, game_state)
+        };
+        game_state.suggestion_cache.contains(&self.suggestion_key(game_state, candidates))
+    }
+
+    // アプリケーションに登録した絵文字を読み込む（絵文字の名前はカスタム絵文字と同じ形式）
+    pub(crate) async fn load_application_emojis(&self, http: &Http) -> anyhow::Result<()> {
+        let emojis = http.get_application_emojis().await?;
+
+        let mut cache = self.application_emojis.write().await;
+        cache.clear();
+        for emoji in emojis {
+            cache.insert(emoji.name.clone(), emoji.to_string());
+        }
+
+        info!("Loaded {} application emojis", cache.len());
+        Ok(())
+    }
+
+    // サーバーで設定したマスの表示方法の順番（未設定なら既定の順番）
+    fn emoji_chain(&self, guild_id: Option<u64>) -> Vec<emoji::EmojiTier> {
+        guild_id
+            .and_then(|guild| self.settings().emoji_chains.get(&guild).cloned())
+            .unwrap_or_else(|| emoji::DEFAULT_CHAIN.to_vec())
+    }
+
+    async fn get_letter_emoji(&self, chain: &[emoji::EmojiTier], letter: char, result: &LetterResult) -> String {
+        let custom = self.emoji_cache.read().await;
+        let application = self.application_emojis.read().await;
+        emoji::EmojiSources { custom: &custom, application: &application }.letter_tile(chain, letter, result)
+    }
+
+    // 文字のないマス（読み込んだ盤面の確認や共有用の結果）
+    async fn get_blank_emoji(&self, chain: &[emoji::EmojiTier], result: &LetterResult) -> String {
+        let custom = self.emoji_cache.read().await;
+        let application = self.application_emojis.read().await;
+        emoji::EmojiSources { custom: &custom, application: &application }.blank_tile(chain, result)
+    }
+
+    // 単語を伏せた共有用の結果（サーバーで設定した絵文字の順番を使う）
+    pub(crate) async fn share_grid(&self, state: &GameState) -> String {
+        let chain = self.emoji_chain(state.guild_id);
+        let mut rows = Vec::new();
+        for guess in &state.guesses {
+            let mut row = String::new();
+            for result in &guess.results {
+                row.push_str(&self.get_blank_emoji(&chain, result).await);
+            }
+            rows.push(row);
+        }
+        format!("{}\n{}", state.locale.share_header(state.guesses.len()), rows.join("\n"))
+    }
+
+    fn get_letter_emoji_for_button(&self, result: &LetterResult) -> String {
+        match result {
+            LetterResult::Gray => "⬜".to_string(),
+            LetterResult::Yellow => "🟨".to_string(),
+            LetterResult::Green => "🟩".to_string(),
+        }
+    }
+
+    fn create_base_embed() -> CreateEmbed {
+        CreateEmbed::new()
+            .title("🎯 Wordle Helper Tool")
+            .color(Colour::BLUE)
+    }
+
+    // ゲーム状態に応じてEmbedの案内と欄（推測履歴・現在の単語）を作る
+    async fn update_embed_content(&self, game_state: &GameState) -> (String, Vec<BoardField>) {
+        let locale = game_state.locale;
+        let chain = self.emoji_chain(game_state.guild_id);
+        if game_state.guesses.is_empty() && game_state.phase == Phase::AwaitingWord {
+            (locale.text(Text::EmptyBoard).to_string(), Vec::new())
+        } else {
+            let mut description = String::new();
+
+            // 過去の推測を1行ずつ表示
+            let mut history = Vec::new();
+            for (i, guess) in game_state.guesses.iter().enumerate() {
+                let mut line = locale.guess_label(i + 1);
+                for (j, letter) in guess.word.chars().enumerate() {
+                    if j < guess.results.len() {
+                        let emoji = self.get_letter_emoji(&chain, letter, &guess.results[j]).await;
+                        line.push_str(&emoji);
+                    } else {
+                        line.push_str(&format!("🔤{}", letter));
+                    }
+                }
+                history.push(line);
+            }
+            let mut fields = session::board_fields(BoardSection::History, history);
+
+            // 確定した推測でわかった色をキーボードの並びで表示する（まだ使っていない文字はそのまま）
+            if !game_state.guesses.is_empty() {
+                let colors = session::keyboard_colors(&game_state.guesses);
+                let mut rows = Vec::new();
+                for row in session::KEYBOARD_ROWS {
+                    let mut keys = Vec::new();
+                    for letter in row.chars() {
+                        keys.push(match &colors[(letter as u8 - b'A') as usize] {
+                            Some(result) => self.get_letter_emoji(&chain, letter, result).await,
+                            None => format!("`{}`", letter),
+                        });
+                    }
+                    rows.push(keys.join(" "));
+                }
+                fields.push(BoardField { section: BoardSection::Keyboard, value: rows.join("\n") });
+            }
+
+            match &game_state.phase {
+                // 現在入力中の単語を表示
+                Phase::AwaitingColors { word, results } => {
+                    let mut current = String::new();
+                    for (letter, result) in word.chars().zip(results) {
+                        current.push_str(&self.get_letter_emoji(&chain, letter, result).await);
+                    }
+                    // 色の入力の案内は入力方法に合わせて letter_view でつける
+                    fields.push(BoardField { section: BoardSection::CurrentWord, value: current });
+                }
+                // 読み取った色を行ごとに表示し、修正中の行に印を付ける
+                Phase::Importing { rows, selected } => {
+                    description.push_str(locale.text(Text::ImportReview));
+                    description.push_str("\n\n");
+                    for (i, row) in rows.iter().enumerate() {
+                        for result in row {
+                            description.push_str(&self.get_blank_emoji(&chain, result).await);
+                        }
+                        if i == *selected {
+                            description.push_str(" ◀");
+                        }
+                        description.push('\n');
+                    }
+                }
+                Phase::Completed => {
+                    description.push_str(&locale.solved(game_state.guesses.len()));
+                }
+                Phase::AwaitingWord => {}
+            }
+
+            (description, fields)
+        }
+    }
+
+    // 盤面のEmbed（案内の下に欄を並べる）
+    fn board_embed(view: &BoardView) -> CreateEmbed {
+        let mut embed = Self::create_base_embed();
+        if !view.description.is_empty() {
+            embed = embed.description(&view.description);
+        }
+        let mut previous = None;
+        for field in &view.fields {
+            // 分かれた欄の続きには見出しを付けない
+            let name = if previous == Some(field.section) {
+                "\u{200b}"
+            } else {
+                view.locale.section_name(field.section)
+            };
+            embed = embed.field(name, &field.value, field.section.inline());
+            previous = Some(field.section);
+        }
+        embed
+    }
+
+    fn create_result_buttons(&self, view: &BoardView, word: &str, current_results: &[LetterResult]) -> Vec<CreateActionRow> {
+        let (owner, revision) = (view.session, view.revision);
+        let mut buttons = Vec::new();
+
+        // 各文字のボタン
+        for (i, letter) in word.chars().enumerate() {
+            let (emoji, style) = if i < current_results.len() {
+                let emoji = self.get_letter_emoji_for_button(&current_results[i]);
+                let style = match current_results[i] {
+                    LetterResult::Gray => ButtonStyle::Secondary,
+                    LetterResult::Yellow => ButtonStyle::Primary,
+                    LetterResult::Green => ButtonStyle::Success,
+                };
+                (emoji, style)
+            } else {
+                (self.get_letter_emoji_for_button(&LetterResult::Gray), ButtonStyle::Secondary)
+            };
+
+            let button = CreateButton::new(CustomId::new(owner, revision, Action::Letter { index: i }).encode())
+                .label(format!("{} {}", emoji, letter))
+                .style(style);
+            buttons.push(button);
+        }
+
+        // 確定ボタン
+        let confirm_button = CreateButton::new(CustomId::new(owner, revision, Action::Confirm).encode())
+            .label(view.locale.text(Text::ConfirmButton))
+            .style(ButtonStyle::Success);
+        buttons.push(confirm_button);
+
+        // 5つずつのボタンで行を作成
+        let mut rows = Vec::new();
+        for chunk in buttons.chunks(5) {
+            rows.push(CreateActionRow::Buttons(chunk.to_vec()));
+        }
+
+        rows
+    }
+
+    // 新しい単語入力ボタンを作成
+    fn create_new_word_button(&self, view: &BoardView) -> Vec<CreateActionRow> {
+        let button = CreateButton::new(CustomId::new(view.session, view.revision, Action::NewWord).encode())
+            .label(view.locale.text(Text::NewWordButton))
+            .style(ButtonStyle::Primary);
+
+        vec![CreateActionRow::Buttons(vec![button])]
+    }
+
+    // 分析中の新しい単語入力ボタンに、分析を取り消すボタンを並べる
+    fn create_analyzing_buttons(&self, view: &BoardView) -> Vec<CreateActionRow> {
+        let new_word = CreateButton::new(CustomId::new(view.session, view.revision, Action::NewWord).encode())
+            .label(view.locale.text(Text::NewWordButton))
+            .style(ButtonStyle::Primary);
+        let cancel = CreateButton::new(CustomId::new(view.session, view.revision, Action::CancelAnalysis).encode())
+            .label(view.locale.text(Text::CancelAnalysisButton))
+            .style(ButtonStyle::Danger);
+
+        vec![CreateActionRow::Buttons(vec![new_word, cancel])]
+    }
+
+    // 新しい単語入力ボタンに、疑わしい推測の修正ボタンを並べる
+    fn create_edit_guess_buttons(&self, view: &BoardView, index: usize) -> Vec<CreateActionRow> {
+        let edit = CreateButton::new(CustomId::new(view.session, view.revision, Action::EditGuess { index }).encode())
+            .label(view.locale.edit_guess_label(index + 1))
+            .style(ButtonStyle::Danger);
+        let new_word = CreateButton::new(CustomId::new(view.session, view.revision, Action::NewWord).encode())
+            .label(view.locale.text(Text::NewWordButton))
+            .style(ButtonStyle::Secondary);
+
+        vec![CreateActionRow::Buttons(vec![edit, new_word])]
+    }
+
+    // 読み込んだ盤面の選択中の行のタイルと、行の切り替え・確定ボタン
+    fn create_import_buttons(&self, view: &BoardView, rows: &[Vec<LetterResult>], selected: usize) -> Vec<CreateActionRow> {
+        let (owner, revision) = (view.session, view.revision);
+
+        let tiles = rows[selected].iter().enumerate()
+            .map(|(i, result)| {
+                let style = match result {
+                    LetterResult::Gray => ButtonStyle::Secondary,
+                    LetterResult::Yellow => ButtonStyle::Primary,
+                    LetterResult::Green => ButtonStyle::Success,
+                };
+                CreateButton::new(CustomId::new(owner, revision, Action::Letter { index: i }).encode())
+                    .label(format!("{} {}", self.get_letter_emoji_for_button(result), i + 1))
+                    .style(style)
+            })
+            .collect();
+
+        let previous = CreateButton::new(CustomId::new(owner, revision, Action::ImportRow { row: selected.saturating_sub(1) }).encode())
+            .label(format!("◀ {}", view.locale.import_row_label(selected.max(1))))
+            .style(ButtonStyle::Secondary)
+            .disabled(selected == 0);
+        let next = CreateButton::new(CustomId::new(owner, revision, Action::ImportRow { row: selected + 1 }).encode())
+            .label(format!("{} ▶", view.locale.import_row_label(selected + 2)))
+            .style(ButtonStyle::Secondary)
+            .disabled(selected + 1 >= rows.len());
+        let confirm = CreateButton::new(CustomId::new(owner, revision, Action::ImportWords).encode())
+            .label(view.locale.text(Text::ImportWordsButton))
+            .style(ButtonStyle::Success);
+
+        vec![
+            CreateActionRow::Buttons(tiles),
+            CreateActionRow::Buttons(vec![previous, next, confirm]),
+        ]
+    }
+
+    // 過去の推測を選んで色を修正するメニューを加える（推測がなければ何もしない）
+    fn with_history_menu(&self, view: &BoardView, mut rows: Vec<CreateActionRow>) -> Vec<CreateActionRow> {
+        if view.history.is_empty() {
+            return rows;
+        }
+
+        // メニューの選択肢は25個まで（それ以上は新しい推測を優先する）
+        let skip = view.history.len().saturating_sub(25);
+        let options = view.history.iter()
+            .enumerate()
+            .skip(skip)
+            .map(|(i, guess)| {
+                let pattern: String = guess.results.iter().map(|r| self.get_letter_emoji_for_button(r)).collect();
+                CreateSelectMenuOption::new(format!("{}. {} {}", i + 1, guess.word, pattern), i.to_string())
+            })
+            .collect();
+        let menu = CreateSelectMenu::new(
+            CustomId::new(view.session, view.revision, Action::PickGuess).encode(),
+            CreateSelectMenuKind::String { options },
+        )
+        .placeholder(view.locale.text(Text::EditPastGuess));
+
+        rows.push(CreateActionRow::SelectMenu(menu));
+        rows
+    }
+
+    fn create_export_button(&self, view: &BoardView) -> Vec<CreateActionRow> {
+        let button = CreateButton::new(CustomId::new(view.session, view.revision, Action::ExportImage).encode())
+            .label(view.locale.text(Text::ExportImageButton))
+            .style(ButtonStyle::Secondary);
+        let share = CreateButton::new(CustomId::new(view.session, view.revision, Action::ShareText).encode())
+            .label(view.locale.text(Text::ShareTextButton))
+            .style(ButtonStyle::Secondary);
+
+        vec![CreateActionRow::Buttons(vec![button, share])]
+    }
+
+    // おすすめの上位の単語の「なぜこの単語？」ボタンの行を加える
+    fn with_explain_buttons(&self, view: &BoardView, mut rows: Vec<CreateActionRow>) -> Vec<CreateActionRow> {
+        if view.explained.is_empty() {
+            return rows;
+        }
+        let buttons = view.explained.iter()
+            .enumerate()
+            .map(|(index, word)| {
+                CreateButton::new(CustomId::new(view.session, view.revision, Action::Explain { index }).encode())
+                    .label(view.locale.explain_label(word))
+                    .style(ButtonStyle::Secondary)
+            })
+            .collect();
+        rows.push(CreateActionRow::Buttons(buttons));
+        rows
+    }
+
+    // 最後のボタンの行に言語の切り替えボタンを加える（行が埋まっていれば新しい行にする）
+    fn with_locale_toggle(&self, view: &BoardView, mut rows: Vec<CreateActionRow>) -> Vec<CreateActionRow> {
+        let button = CreateButton::new(CustomId::new(view.session, view.revision, Action::ToggleLocale).encode())
+            .label(format!("🌐 {}", view.locale.toggled().name()))
+            .style(ButtonStyle::Secondary);
+
+        match rows.last_mut() {
+            Some(CreateActionRow::Buttons(buttons)) if buttons.len() < 5 => buttons.push(button),
+            _ => rows.push(CreateActionRow::Buttons(vec![button])),
+        }
+        rows
+    }
+
+    // 盤面の操作ボタンをDiscordのコンポーネントに変換
+    fn render_controls(&self, view: &BoardView) -> Vec<CreateActionRow> {
+        match &view.controls {
+            BoardControls::Unchanged => Vec::new(),
+            BoardControls::Finished => self.with_locale_toggle(view, self.create_export_button(view)),
+            BoardControls::NewWord => {
+                let rows = self.with_locale_toggle(view, self.create_new_word_button(view));
+                self.with_history_menu(view, self.with_explain_buttons(view, rows))
+            }
+            BoardControls::Analyzing { cancellable } => {
+                let rows = if *cancellable { self.create_analyzing_buttons(view) } else { self.create_new_word_button(view) };
+                self.with_history_menu(view, self.with_locale_toggle(view, rows))
+            }
+            BoardControls::LetterResults { word, results } => self.with_locale_toggle(view, self.create_result_buttons(view, word, results)),
+            BoardControls::LetterMenu { word, results } => self.with_locale_toggle(view, self.create_letter_menu(view, word, results)),
+            BoardControls::EditGuess { index } => {
+                self.with_history_menu(view, self.with_locale_toggle(view, self.create_edit_guess_buttons(view, *index)))
+            }
+            BoardControls::Importing { rows, selected } => self.with_locale_toggle(view, self.create_import_buttons(view, rows, *selected)),
+        }
+    }
+
+    // 候補数とおすすめの欄と、上位のおすすめを選んだ理由（提案できなければその理由）
+    async fn suggest_words(&self, game_state: &GameState, analysis: &in_flight::AnalysisGuard) -> Result<(Vec<BoardField>, Vec<solver::GuessExplanation>), String> {
+        let locale = game_state.locale;
+        match self.get_optimal_words(game_state, analysis).await {
+            Ok(words) => {
+                if words.is_empty() {
+                    Err(locale.text(Text::NoCandidates).to_string())
+                } else {
+                    // 候補数の欄を先に表示
+                    let possible = {
+                        let all_words = self.words_for(game_state).await;
+                        self.filter_words_by_constraints(&all_words, game_state)
+                    };
+                    let mut fields = vec![BoardField {
+                        section: BoardSection::Candidates,
+                        value: locale.candidate_count(possible.len()),
+                    }];
+
+                    // 単語リストを表示（ハードモードではルールに合う単語だけを提案していることを示す）
+                    let mut suggestion = String::new();
+                    if game_state.hard_mode {
+                        suggestion.push_str(locale.text(Text::HardModeSuggestions));
+                        suggestion.push('\n');
+                    }
+                    let mut explanations = Vec::new();
+                    let difficulty = game_state.difficulty;
+                    let shown = difficulty.suggestion_limit(self.settings().suggestion_count);
+                    for (i, word) in words.iter().enumerate() {
+                        let medal = match i {
+                            0 => "🥇",
+                            1 => "🥈", 
+                            2 => "🥉",
+                            _ => "📝",
+                        };
+                        // 情報量と、推測したあとに残る候補数の期待値を添える
+                        let note = if possible.is_empty() {
+                            String::new()
+                        } else {
+                            let (entropy, remaining) = solver::guess_metrics(&possible.arena, word, &possible.ids);
+                            locale.suggestion_note(entropy, remaining)
+                        };
+                        suggestion.push_str(&format!("{} **{}**{}\n", medal, word, note));
+                        if i < MAX_EXPLAINED && !possible.is_empty() {
+                            explanations.push(solver::explain_guess(&possible.arena, word, &possible.ids, &game_state.guesses));
+                        }
+
+                        // 設定された数まで表示
+                        if i + 1 >= shown {
+                            break;
+                        }
+                    }
+
+                    // 多くの候補がある場合はその旨を表示
+                    if words.len() > shown {
+                        suggestion.push_str(&locale.more_words(words.len() - shown));
+                    }
+
+                    fields.extend(session::board_fields(BoardSection::Suggestions, suggestion.lines().map(str::to_string)));
+                    if let Some(top) = explanations.first().filter(|e| !e.splits.is_empty()) {
+                        fields.push(BoardField { section: BoardSection::Elimination, value: locale.split_map(top) });
+                    }
+                    Ok((fields, explanations))
+                }
+            }
+            Err(e) => {
+                self.reporter.report("Error getting optimal words", &e).await;
+                Err(locale.text(Text::SuggestionFailed).to_string())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for Bot {
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        info!("{} is connected!", ready.user.name);
+
+        self.presence.attach(ctx.shard_id.0, ctx.shard.clone());
+        self.initialize(&ctx.http).await;
+        // 次の定期更新を待たずに表示する
+        self.presence.set(self.settings().default_locale.presence(self.game_states.read().await.len()));
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        self.handle_interaction(ReplyContext::gateway(Arc::clone(&ctx.http)), interaction).await;
+    }
+
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        self.handle_reaction(&ctx.http, &reaction).await;
+    }
+}
+
+impl Bot {
+    // 接続後（Webhookモードでは起動時）にキャッシュ・ジョブ・コマンドを準備する
+    pub(crate) async fn initialize(&self, http: &Arc<Http>) {
+        self.reporter.attach(Arc::clone(http));
+        self.daily.attach(Arc::clone(http));
+        self.play_games.attach(Arc::clone(http));
+
+        if let Err(e) = self.load_application_emojis(http).await {
+            warn!("Failed to load application emojis: {:?}", e);
+        }
+
+        // スナップショットから復元できれば、最新データの取得を待たずに起動を続ける
+        if self.restore_cache_snapshot().await {
+            let bot = self.clone();
+            tokio::spawn(async move { bot.load_caches().await });
+        } else {
+            self.load_caches().await;
+        }
+
+        if let Err(e) = self.load_settings().await {
+            self.reporter.report("Failed to load settings", &e).await;
+        }
+
+        self.scheduler.start();
+
+        // 管理用のコマンドはサーバーの設定を変えるため、ホームのサーバーにだけ登録する
+        let guild_commands = vec![admin::create_command(), guild_config::create_command()];
+        let helper_commands = vec![
+            i18n::command("ping"),
+            i18n::command("wht")
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "hard_mode",
+                    "ハードモードのルールに反する単語を警告し、提案もルールに合う単語だけにする",
+                ))
+                .add_option(
+                    CreateCommandOption::new(CommandOptionType::String, "difficulty", "提案の補助の多さ（省略するとサーバー全体の既定値）")
+                        .add_string_choice("beginner（よく使う単語を3つ、説明つき）", Difficulty::Beginner.code())
+                        .add_string_choice("standard", Difficulty::Standard.code())
+                        .add_string_choice("advanced（エントロピー順にすべて）", Difficulty::Advanced.code()),
+                )
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "shared",
+                    "盤面をこのチャンネルで共有し、誰でも色を入力できるようにする",
+                ))
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "host_only",
+                    "共有した盤面の確定とやり直しを、開始した人だけに限る",
+                )),
+            status::create_command(),
+            define::create_command(),
+            candidates::create_command(),
+            screenshot::create_command(),
+            screenshot::create_message_command(),
+            leaderboard::create_command(),
+            stats::create_command(),
+            openers::create_command(),
+            openers::create_evaluate_command(),
+            reactions::create_command(),
+            solve::create_command(),
+            quordle::create_command(),
+            analyze_file::create_command(),
+            privacy::create_command(),
+            play::create_command(),
+            daily::create_command(),
+            input_style::create_command(),
+        ];
+        self.register_commands(http, guild_commands, helper_commands).await;
+    }
+
+    // ユーザーインストールに対応する場合は、一般向けのコマンドをグローバルに登録する
+    // （切り替えたときに同じコマンドが二重に表示されないよう、もう一方の登録は置き換える）
+    async fn register_commands(&self, http: &Arc<Http>, mut guild_commands: Vec<CreateCommand>, helper_commands: Vec<CreateCommand>) {
+        let global_commands = if self.user_install {
+            helper_commands.into_iter().map(install::user_installable).collect()
+        } else {
+            guild_commands.extend(helper_commands);
+            Vec::new()
+        };
+
+        match Command::set_global_commands(http, global_commands).await {
+            Ok(commands) => info!("Registered {} global commands", commands.len()),
+            Err(e) => self.reporter.report("Failed to register global commands", &e).await,
+        }
+        match self.discord_guild_id.set_commands(http, guild_commands).await {
+            Ok(commands) => info!("Registered {} commands in guild {}", commands.len(), self.discord_guild_id),
+            Err(e) => self.reporter.report("Failed to register slash commands", &e).await,
+        }
+    }
+
+    // ストレージから絵文字・単語キャッシュを読み込み、成功したらスナップショットを更新する
+    async fn load_caches(&self) {
+        let emoji_result = self.load_emoji_cache().await;
+        if let Err(e) = &emoji_result {
+            self.reporter.report("Failed to load emoji cache", e).await;
+        } else {
+            let emoji_count = self.emoji_cache.read().await.len();
+            info!("Successfully loaded {} emojis", emoji_count);
+        }
+
+        let word_result = self.load_word_cache().await;
+        if let Err(e) = &word_result {
+            self.reporter.report("Failed to load word cache", e).await;
+            warn!("Will use fallback words for suggestions");
+        } else {
+            let word_count = self.word_cache.read().await.len();
+            info!("Successfully loaded {} words", word_count);
+        }
+
+        if emoji_result.is_ok() && word_result.is_ok() {
+            self.save_cache_snapshot().await;
+        }
+    }
+
+    // インタラクションごとにspanを作り、処理時間を記録して振り分ける
+    pub(crate) async fn handle_interaction(&self, ctx: ReplyContext, interaction: Interaction) {
+        let span = match &interaction {
+            Interaction::Command(command) => info_span!(
+                "command",
+                name = %command.data.name,
+                user_id = %command.user.id,
+                guild_id = ?command.guild_id.map(|g| g.get()),
+                duration_ms = Empty,
+            ),
+            Interaction::Autocomplete(command) => info_span!(
+                "autocomplete",
+                name = %command.data.name,
+                user_id = %command.user.id,
+                guild_id = ?command.guild_id.map(|g| g.get()),
+                duration_ms = Empty,
+            ),
+            Interaction::Component(component) => info_span!(
+                "component",
+                custom_id = %component.data.custom_id,
+                user_id = %component.user.id,
+                guild_id = ?component.guild_id.map(|g| g.get()),
+                duration_ms = Empty,
+            ),
+            Interaction::Modal(modal) => info_span!(
+                "modal",
+                custom_id = %modal.data.custom_id,
+                user_id = %modal.user.id,
+                guild_id = ?modal.guild_id.map(|g| g.get()),
+                duration_ms = Empty,
+            ),
+            _ => info_span!("interaction", duration_ms = Empty),
+        };
+
+        if let Interaction::Command(command) = &interaction {
+            self.metrics.record_command(command.user.id.get());
+        }
+
+        let started = std::time::Instant::now();
+        self.dispatch_interaction(ctx, interaction).instrument(span.clone()).await;
+
+        // バックグラウンドで続く処理（単語提案の計算など）は含まない
+        let elapsed = started.elapsed();
+        span.record("duration_ms", elapsed.as_millis() as u64);
+        if elapsed >= self.slow_interaction_threshold {
+            warn!(parent: &span, "Slow interaction: handled in {} ms", elapsed.as_millis());
+        }
+    }
+
+    fn responder(&self, ctx: &ReplyContext, target: impl Into<Target>, mode: ReplyMode) -> Responder {
+        Responder::new(ctx.clone(), Arc::clone(&self.reporter), target.into(), mode)
+    }
+
+    // 操作したサーバーの言語
+    fn locale_for(&self, guild_id: Option<GuildId>) -> Locale {
+        self.settings().locale_for(guild_id.map(|g| g.get()))
+    }
+
+    // 盤面をEmbedとボタンの応答に変換（操作できない場合は理由のみ）
+    fn board_reply(&self, locale: Locale, view: Result<BoardView, SessionError>) -> Reply {
+        match view {
+            // 終了した盤面のボタン（画像の保存など）も render_controls が選ぶ
+            Ok(view) => {
+                let components = self.render_controls(&view);
+                Reply::embed(Self::board_embed(&view)).components(components)
+            }
+            Err(e) => Reply::embed(Self::create_base_embed().description(locale.session_error(e))),
+        }
+    }
+
+    // 盤面で応答する（古いボタンからの操作は何も変更せずに受け付けるだけにする）
+    async fn send_board(&self, responder: &mut Responder, locale: Locale, view: Result<BoardView, SessionError>) -> bool {
+        if view.as_ref().err() == Some(&SessionError::Stale) {
+            info!("Ignored interaction from an outdated board");
+            responder.defer().await;
+            return false;
+        }
+
+        responder.send(self.board_reply(locale, view)).await
+    }
+
+    // 盤面の編集をキューに入れる（先に応答を保留し、連続した編集は最新のものだけを送る）
+    async fn queue_board(&self, mut responder: Responder, message: MessageId, locale: Locale, view: Result<BoardView, SessionError>) {
+        if view.as_ref().err() == Some(&SessionError::Stale) {
+            self.send_board(&mut responder, locale, view).await;
+            return;
+        }
+
+        if responder.defer().await {
+            self.edits.submit(message, responder, self.board_reply(locale, view));
+        }
+    }
+
+    async fn dispatch_interaction(&self, ctx: ReplyContext, interaction: Interaction) {
+        match interaction {
+            Interaction::Command(command) => {
+                match command.data.name.as_str() {
+                    "ping" => {
+                        self.responder(&ctx, &command, ReplyMode::NewMessage)
+                            .send(Reply::content("Pong"))
+                            .await;
+                    }
+                    "wht" => {
+                        let user_id = command.user.id.get();
+                        let options = command.data.options();
+                        let flag = |name: &str| options.iter().any(|o| o.name == name && matches!(o.value, ResolvedValue::Boolean(true)));
+                        let guild_id = command.guild_id.map(|g| g.get());
+                        // 共有した盤面はチャンネルのIDでセッションを持つ
+                        let (session, view) = if flag("shared") {
+                            let channel_id = command.channel_id.get();
+                            let board = SharedBoard { host: user_id, host_only: flag("host_only") };
+                            (channel_id, self.start_shared_session(channel_id, guild_id, board).await)
+                        } else {
+                            (user_id, self.start_session(user_id, guild_id).await)
+                        };
+                        if flag("hard_mode") {
+                            self.set_hard_mode(session, true).await;
+                        }
+                        let difficulty = options.iter().find_map(|o| match o.value {
+                            ResolvedValue::String(code) if o.name == "difficulty" => Difficulty::parse(code),
+                            _ => None,
+                        });
+                        if let Some(difficulty) = difficulty {
+                            self.set_difficulty(session, difficulty).await;
+                        }
+
+                        self.responder(&ctx, &command, ReplyMode::NewMessage)
+                            .send(self.board_reply(view.locale, Ok(view)))
+                            .await;
+                    }
+                    "status" => {
+                        self.handle_status_command(&ctx, &command).await;
+                    }
+                    "admin" => {
+                        self.handle_admin_command(&ctx, &command).await;
+                    }
+                    "config" => {
+                        self.handle_config_command(&ctx, &command).await;
+                    }
+                    "candidates" => {
+                        self.handle_candidates_command(&ctx, &command).await;
+                    }
+                    "openers" => {
+                        self.handle_openers_command(&ctx, &command).await;
+                    }
+                    "evaluate" => {
+                        self.handle_evaluate_command(&ctx, &command).await;
+                    }
+                    "guess" => {
+                        self.handle_guess_command(&ctx, &command).await;
+                    }
+                    "define" => {
+                        self.handle_define_command(&ctx, &command).await;
+                    }
+                    "leaderboard" => {
+                        self.handle_leaderboard_command(&ctx, &command).await;
+                    }
+                    "stats" => {
+                        self.handle_stats_command(&ctx, &command).await;
+                    }
+                    "import" => {
+                        self.handle_import_command(&ctx, &command).await;
+                    }
+                    "solve" => {
+                        self.handle_solve_command(&ctx, &command).await;
+                    }
+                    "qht" => {
+                        self.handle_qht_command(&ctx, &command).await;
+                    }
+                    "analyze-file" => {
+                        self.handle_analyze_file_command(&ctx, &command).await;
+                    }
+                    "privacy" => {
+                        self.handle_privacy_command(&ctx, &command).await;
+                    }
+                    "wordle" => {
+                        self.handle_wordle_command(&ctx, &command).await;
+                    }
+                    "daily" => {
+                        self.handle_daily_command(&ctx, &command).await;
+                    }
+                    "input-style" => {
+                        self.handle_input_style_command(&ctx, &command).await;
+                    }
+                    screenshot::MESSAGE_COMMAND_NAME => {
+                        self.handle_import_message_command(&ctx, &command).await;
+                    }
+                    // 登録を解除したコマンドが残っている場合など
+                    name => {
+                        warn!("Received unknown command: /{}", name);
+                        self.responder(&ctx, &command, ReplyMode::Ephemeral)
+                            .send(Reply::content(self.locale_for(command.guild_id).text(Text::UnknownCommand)))
+                            .await;
+                    }
+                }
+            }
+            Interaction::Autocomplete(command) if command.data.name == "define" => {
+                self.handle_define_autocomplete(&ctx, &command).await;
+            }
+            Interaction::Autocomplete(command) if command.data.name == "guess" => {
+                self.handle_guess_autocomplete(&ctx, &command).await;
+            }
+            Interaction::Modal(modal) => {
+                self.handle_modal_interaction(ctx, modal).await;
+            }
+            Interaction::Component(component) if leaderboard::is_leaderboard_id(&component.data.custom_id) => {
+                self.handle_leaderboard_component(&ctx, &component).await;
+            }
+            Interaction::Component(component) => {
+                self.handle_component_interaction(ctx, component).await;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Bot {
+    async fn handle_modal_interaction(&self, ctx: ReplyContext, modal: ModalInteraction) {
+        let locale = self.locale_for(modal.guild_id);
+        let id = match CustomId::decode(&modal.data.custom_id) {
+            Ok(id) if matches!(id.action, Action::WordModal | Action::ImportModal | Action::PlayModal { .. } | Action::DailyModal { .. }) => id,
+            Ok(_) | Err(_) => {
+                self.reject_custom_id(&ctx, &modal, locale, &modal.data.custom_id).await;
+                return;
+            }
+        };
+
+        let word = if let Some(row) = modal.data.components.first() {
+            if let Some(component) = row.components.first() {
+                match component {
+                    serenity::all::ActionRowComponent::InputText(input) => {
+                        input.value.clone().unwrap_or_default().to_uppercase()
+                    }
+                    _ => "ERROR".to_string(),
+                }
+            } else {
+                "ERROR".to_string()
+            }
+        } else {
+            "ERROR".to_string()
+        };
+
+        if !self.ensure_owner(&ctx, &modal, locale, modal.user.id, id).await {
+            return;
+        }
+
+        if id.action == Action::ImportModal {
+            self.confirm_import(&ctx, &modal, locale, id, &word).await;
+            return;
+        }
+
+        if let Action::PlayModal { game } = id.action {
+            self.submit_play_guess(&ctx, &modal, locale, id, game, &word).await;
+            return;
+        }
+
+        if matches!(id.action, Action::DailyModal { .. }) {
+            self.submit_daily_guess(&ctx, &modal, locale, id, &word).await;
+            return;
+        }
+
+        // 単語リストにない単語は盤面に入れず、本人にだけ近い単語を案内する
+        if let Some(nearest) = self.unknown_word(id.owner, &word).await {
+            self.responder(&ctx, &modal, ReplyMode::Ephemeral)
+                .send(Reply::content(locale.unknown_word(word.trim(), &nearest)))
+                .await;
+            return;
+        }
+
+        // 単語リストの読み込み直しなどで3秒の期限に間に合わないときは、先に保留してから盤面を編集する
+        let mut responder = self.responder(&ctx, &modal, ReplyMode::UpdateMessage);
+        let (violations, view) = responder.defer_if_slow(async {
+            // 盤面を更新する前の推測に対して確認する
+            let violations = self.hard_mode_violations(id.owner, &word).await;
+            (violations, self.submit_word(id.owner, id.revision, &word).await)
+        }).await;
+        if self.send_board(&mut responder, locale, view).await && !violations.is_empty() {
+            responder.follow_up(Reply::content(locale.hard_mode_warning(&violations))).await;
+        }
+    }
+
+    async fn handle_component_interaction(&self, ctx: ReplyContext, component: ComponentInteraction) {
+        let locale = self.locale_for(component.guild_id);
+        let id = match CustomId::decode(&component.data.custom_id) {
+            Ok(id) => id,
+            Err(_) => {
+                self.reject_custom_id(&ctx, &component, locale, &component.data.custom_id).await;
+                return;
+            }
+        };
+
+        if !self.ensure_owner(&ctx, &component, locale, component.user.id, id).await {
+            return;
+        }
+
+        match id.action {
+            Action::NewWord => self.open_word_modal(&ctx, &component, locale, id).await,
+            Action::Letter { index } => {
+                let view = self.cycle_letter(id.owner, id.revision, index).await;
+                let responder = self.responder(&ctx, &component, ReplyMode::UpdateMessage);
+                self.queue_board(responder, component.message.id, locale, view).await;
+            }
+            Action::LetterMenu => {
+                let values = match &component.data.kind {
+                    ComponentInteractionDataKind::StringSelect { values } => Some(values.as_slice()),
+                    _ => None,
+                };
+                // 選ばれた位置が入力中の単語の範囲内かを確かめる
+                let length = self.session(id.owner).await.and_then(|state| match state.phase {
+                    Phase::AwaitingColors { word, .. } => Some(word.chars().count()),
+                    _ => None,
+                });
+                let colors = values.zip(length).and_then(|(values, length)| input_style::menu_colors(values, length));
+                let Some(colors) = colors else {
+                    self.reject_custom_id(&ctx, &component, locale, &component.data.custom_id).await;
+                    return;
+                };
+                let view = self.set_letters(id.owner, id.revision, colors).await;
+                let responder = self.responder(&ctx, &component, ReplyMode::UpdateMessage);
+                self.queue_board(responder, component.message.id, locale, view).await;
+            }
+            Action::Confirm => self.confirm_and_analyze(&ctx, &component, locale, id).await,
+            Action::EditGuess { index } => {
+                let view = self.reopen_guess(id.owner, id.revision, index).await;
+                self.send_board(&mut self.responder(&ctx, &component, ReplyMode::UpdateMessage), locale, view).await;
+            }
+            Action::ImportRow { row } => {
+                let view = self.select_import_row(id.owner, id.revision, row).await;
+                self.send_board(&mut self.responder(&ctx, &component, ReplyMode::UpdateMessage), locale, view).await;
+            }
+            Action::ImportWords => self.open_import_modal(&ctx, &component, locale, id).await,
+            Action::ExportImage => self.export_board_image(&ctx, &component, locale, id).await,
+            Action::ShareText => {
+                // 本人にだけ、コピーしやすいようにコードブロックで返す
+                let reply = match self.finished_guesses(id.owner, id.revision).await {
+                    Ok(guesses) => Reply::content(format!(
+                        "{}\n```\n{}\n```",
+                        locale.text(Text::ShareTextIntro),
+                        results::social_share_text(locale, &guesses, stats::today(), solver::MAX_GUESSES)
+                    )),
+                    Err(_) => Reply::content(locale.text(Text::OutdatedButton)),
+                };
+                self.responder(&ctx, &component, ReplyMode::Ephemeral).send(reply).await;
+            }
+            Action::PickGuess => {
+                let index = match &component.data.kind {
+                    ComponentInteractionDataKind::StringSelect { values } => values.first().and_then(|v| v.parse().ok()),
+                    _ => None,
+                };
+                let Some(index) = index else {
+                    self.reject_custom_id(&ctx, &component, locale, &component.data.custom_id).await;
+                    return;
+                };
+                let view = self.reopen_guess(id.owner, id.revision, index).await;
+                self.send_board(&mut self.responder(&ctx, &component, ReplyMode::UpdateMessage), locale, view).await;
+            }
+            Action::ToggleLocale => self.toggle_board_locale(&ctx, &component, locale, id).await,
+            Action::CancelAnalysis => {
+                let view = self.cancel_analysis(id.owner, id.revision).await;
+                self.send_board(&mut self.responder(&ctx, &component, ReplyMode::UpdateMessage), locale, view).await;
+            }
+            Action::Explain { index } => {
+                // 盤面は変えず、本人にだけ説明を返す
+                let reply = match self.explain_suggestion(id.owner, id.revision, index).await {
+                    Ok(explanation) if explanation.splits.is_empty() => Reply::content(locale.explanation(&explanation)),
+                    Ok(explanation) => Reply::content(format!("{}\n\n{}", locale.explanation(&explanation), locale.split_map(&explanation))),
+                    Err(e) => Reply::content(locale.session_error(e)),
+                };
+                self.responder(&ctx, &component, ReplyMode::Ephemeral).send(reply).await;
+            }
+            Action::PlayGuess { .. } | Action::DailyGuess { .. } => self.open_play_modal(&ctx, &component, locale, id).await,
+            Action::PlayHint { game } => self.use_play_hint(&ctx, &component, locale, id, game).await,
+            Action::WordModal | Action::ImportModal | Action::PlayModal { .. } | Action::DailyModal { .. } => self.reject_custom_id(&ctx, &component, locale, &component.data.custom_id).await,
+        }
+    }
+
+    // 他のユーザーのボードは操作させない（本人にだけ案内を返す）
+    async fn ensure_owner(&self, ctx: &ReplyContext, target: impl Into<Target>, locale: Locale, user: UserId, id: CustomId) -> bool {
+        if user.get() == id.owner {
+            return true;
+        }
+
+        // チャンネルで共有した盤面は誰でも操作できる（開始した人に限った操作を除く）
+        let shared = self.session(id.owner).await.and_then(|state| state.shared);
+        let reason = match shared {
+            Some(board) if user.get() == board.host || !(board.host_only && id.action.rewrites_history()) => return true,
+            Some(_) => Text::HostOnly,
+            None => Text::NotOwner,
+        };
+
+        info!("User {} tried to operate a board owned by {}", user, id.owner);
+        self.responder(ctx, target, ReplyMode::Ephemeral)
+            .send(Reply::content(locale.text(reason)))
+            .await;
+        false
+    }
+
+    // 解釈できない・古い custom_id には本人にだけ案内を返す
+    async fn reject_custom_id(&self, ctx: &ReplyContext, target: impl Into<Target>, locale: Locale, raw: &str) {
+        let reason = CustomId::decode(raw).err();
+        info!("Rejected custom_id {:?} ({:?})", raw, reason);
+
+        let message = match reason {
+            Some(CustomIdError::Foreign) | Some(CustomIdError::UnsupportedVersion(_)) => Text::OutdatedButton,
+            _ => Text::GenericError,
+        };
+
+        self.responder(ctx, target, ReplyMode::Ephemeral)
+            .send(Reply::content(locale.text(message)))
+            .await;
+    }
+
+    // 新しい単語入力モーダルを表示
+    async fn open_word_modal(&self, ctx: &ReplyContext, component: &ComponentInteraction, locale: Locale, id: CustomId) {
+        let word_input = CreateInputText::new(InputTextStyle::Short, "word", locale.text(Text::WordModalTitle))
+            .placeholder(locale.text(Text::WordInputPlaceholder))
+            .min_length(5)
+            .max_length(5)
+            .required(true);
+
+        let modal = CreateModal::new(CustomId::new(id.owner, id.revision, Action::WordModal).encode(), locale.text(Text::WordModalTitle))
+            .components(vec![CreateActionRow::InputText(word_input)]);
+
+        self.responder(ctx, component, ReplyMode::UpdateMessage)
+            .open_modal(modal)
+            .await;
+    }
+
+    // 終了した盤面を文字入りの画像にして本人にだけ送る
+    async fn export_board_image(&self, ctx: &ReplyContext, component: &ComponentInteraction, locale: Locale, id: CustomId) {
+        let guesses = match self.finished_guesses(id.owner, id.revision).await {
+            Ok(guesses) => guesses,
+            Err(e) => {
+                info!("Ignored image export from an outdated board: {:?}", e);
+                self.responder(ctx, component, ReplyMode::Ephemeral)
+                    .send(Reply::content(locale.text(Text::OutdatedButton)))
+                    .await;
+                return;
+            }
+        };
+
+        self.responder(ctx, component, ReplyMode::Ephemeral)
+            .run(async {
+                let image = tokio::task::spawn_blocking(move || board_image::render_board(&guesses)).await;
+                match image.map_err(anyhow::Error::from).and_then(|image| image) {
+                    Ok(bytes) => Reply::default().attachment(CreateAttachment::bytes(bytes, "wordle.png")),
+                    Err(e) => {
+                        self.reporter.report("Failed to render board image", &e).await;
+                        Reply::content(locale.text(Text::ExportImageFailed))
+                    }
+                }
+            })
+            .await;
+    }
+
+    // 読み込んだ盤面に合わせる単語の入力モーダルを表示
+    async fn open_import_modal(&self, ctx: &ReplyContext, component: &ComponentInteraction, locale: Locale, id: CustomId) {
+        let words_input = CreateInputText::new(InputTextStyle::Short, "words", locale.text(Text::ImportWordsLabel))
+            .placeholder("CRANE SLATE ...")
+            .required(true);
+
+        let modal = CreateModal::new(CustomId::new(id.owner, id.revision, Action::ImportModal).encode(), locale.text(Text::ImportWordsTitle))
+            .components(vec![CreateActionRow::InputText(words_input)]);
+
+        self.responder(ctx, component, ReplyMode::UpdateMessage)
+            .open_modal(modal)
+            .await;
+    }
+
+    async fn confirm_and_analyze(&self, ctx: &ReplyContext, component: &ComponentInteraction, locale: Locale, id: CustomId) {
+        let mut responder = self.responder(ctx, component, ReplyMode::UpdateMessage);
+
+        let view = match self.confirm_guess(id.owner, id.revision).await {
+            Ok(view) => view,
+            Err(SessionError::Stale) => {
+                self.send_board(&mut responder, locale, Err(SessionError::Stale)).await;
+                return;
+            }
+            Err(e) => {
+                // 盤面はそのまま残し、本人にだけ理由を伝える
+                self.responder(ctx, component, ReplyMode::Ephemeral)
+                    .send(Reply::content(locale.session_error(e)))
+                    .await;
+                return;
+            }
+        };
+
+        let finished = view.controls == BoardControls::Finished;
+        if finished {
+            // ボットがインストールされていないサーバーでは行わない
+            if let Some(guild_id) = install::installed_guild(&component.authorizing_integration_owners, component.guild_id) {
+                self.spawn_win_tasks(ctx.http(), guild_id, id.owner);
+            }
+        }
+        if !responder.send(self.board_reply(locale, Ok(view))).await || finished {
+            return;
+        }
+
+        self.analyze_in_background(responder, component.message.id, locale, id.owner);
+    }
+
+    // 盤面の言語を切り替える（提案を計算し終えていない盤面は切り替えた言語で分析を続ける）
+    async fn toggle_board_locale(&self, ctx: &ReplyContext, component: &ComponentInteraction, locale: Locale, id: CustomId) {
+        let mut responder = self.responder(ctx, component, ReplyMode::UpdateMessage);
+        let view = self.toggle_locale(id.owner, id.revision).await;
+        let analyzing = matches!(&view, Ok(view) if matches!(view.controls, BoardControls::Analyzing { .. }));
+        if !self.send_board(&mut responder, locale, view).await || !analyzing {
+            return;
+        }
+
+        self.analyze_in_background(responder, component.message.id, locale, id.owner);
+    }
+
+    // 読み込んだ盤面を確定する（単語の数が合わなければ盤面はそのまま残す）
+    async fn confirm_import(&self, ctx: &ReplyContext, modal: &ModalInteraction, locale: Locale, id: CustomId, words: &str) {
+        let mut responder = self.responder(ctx, modal, ReplyMode::UpdateMessage);
+
+        // 時間がかかるときは先に保留し、あとから盤面を編集する
+        let view = match responder.defer_if_slow(self.finish_import(id.owner, id.revision, words)).await {
+            Ok(view) => view,
+            Err(SessionError::Stale) => {
+                self.send_board(&mut responder, locale, Err(SessionError::Stale)).await;
+                return;
+            }
+            // 保留したあとは新しく応答できないため、フォローアップで本人にだけ伝える
+            Err(e) if responder.is_acknowledged() => {
+                responder.follow_up(Reply::content(locale.session_error(e))).await;
+                return;
+            }
+            Err(e) => {
+                self.responder(ctx, modal, ReplyMode::Ephemeral)
+                    .send(Reply::content(locale.session_error(e)))
+                    .await;
+                return;
+            }
+        };
+
+        let finished = view.controls == BoardControls::Finished;
+        if !responder.send(self.board_reply(locale, Ok(view))).await || finished {
+            return;
+        }
+        if let Some(message) = &modal.message {
+            self.analyze_in_background(responder, message.id, locale, id.owner);
+        }
+    }
+
+    // 正解した場合は成績に応じたロール報酬の更新と、結果チャンネルへの投稿を行う
+    fn spawn_win_tasks(&self, http: &Arc<Http>, guild_id: GuildId, owner: u64) {
+        let bot = self.clone();
+        let http = Arc::clone(http);
+        tokio::spawn(async move {
+            // 共有した盤面は個人の成績として扱わない
+            if bot.session(owner).await.is_some_and(|state| state.shared.is_some()) {
+                return;
+            }
+            bot.apply_role_rewards(&http, guild_id, owner).await;
+            bot.post_result(&http, guild_id, owner).await;
+            bot.forward_result(guild_id, owner).await;
+        }.instrument(tracing::Span::current()));
+    }
+
+    // バックグラウンドで単語提案を生成
+    fn analyze_in_background(&self, responder: Responder, message: MessageId, locale: Locale, owner: u64) {
+        let bot = self.clone();
+
+        tokio::spawn(async move {
+            let (progress, updates) = tokio::sync::watch::channel(None);
+            let reporter = bot.report_progress(responder.clone(), message, locale, owner, updates);
+            let view = bot.finish_analysis_reporting(owner, Some(progress)).await;
+            // 途中経過の表示が最終的な盤面を上書きしないよう、先に止める
+            reporter.abort();
+            let _ = reporter.await;
+
+            // 単語提案を生成して最終的な表示を更新
+            // 分析中に盤面が操作された場合は、新しい盤面を上書きしない
+            match view {
+                Err(SessionError::Stale) => info!("Board changed during analysis; skipping update"),
+                Err(SessionError::AnalysisRunning) => info!("Analysis for {} is already running; skipping duplicate", owner),
+                view => bot.edits.submit(message, responder, bot.board_reply(locale, view)),
+            }
+        }.instrument(tracing::Span::current()));
+    }
+
+    // 分析が長引いている間、一定の間隔で順番待ちやスコア計算の進み具合を分析中の盤面に表示する
+    fn report_progress(
+        &self,
+        responder: Responder,
+        message: MessageId,
+        locale: Locale,
+        owner: u64,
+        mut updates: tokio::sync::watch::Receiver<Option<in_flight::AnalysisProgress>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let bot = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(PROGRESS_INTERVAL).await;
+                // 送る側がなくなったら分析は終わっている
+                match updates.has_changed() {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(_) => return,
+                }
+                let progress = *updates.borrow_and_update();
+                let Some(progress) = progress else {
+                    continue;
+                };
+                // 盤面が操作された後は表示しない
+                match bot.progress_view(owner, progress).await {
+                    Ok(view) => bot.edits.submit(message, responder.clone(), bot.board_reply(locale, Ok(view))),
+                    Err(_) => return,
+                }
+            }
+        }.instrument(tracing::Span::current()))
+    }
+}
+
+fn startup_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+async fn load_words(
+    storage: &dyn Storage,
+    word_cache: &tokio::sync::RwLock<word_cache::WordCache>,
+    word_versions: &wordlist::WordListVersions,
+) -> anyhow::Result<()> {
+    let all_words = storage.fetch_words().await?;
+
+    let mut cache = word_cache.write().await;
+    let previous = std::mem::replace(&mut *cache, word_cache::WordCache::new(all_words));
+    let changed = word_versions.replace(previous, &cache);
+    let cache = cache.downgrade();
+
+    info!("Successfully loaded {} word records in total", cache.len());
+    // 内容が変わったときだけ新しい版を記録し、整合性を確かめる
+    if let Some(version) = changed {
+        wordlist::record_version(storage, &version, &cache).await;
+        if let Some(arena) = cache.words_of_length(solver::WORD_LENGTH).cloned() {
+            tokio::spawn(check_consistency_in_background(arena, version));
+        }
+    }
+    Ok(())
+}
+
+async fn load_emojis(
+    storage: &dyn Storage,
+    emoji_cache: &tokio::sync::RwLock<HashMap<String, String>>,
+) -> anyhow::Result<()> {
+    let emojis: HashMap<String, String> = storage.fetch_emojis().await?
+        .into_iter()
+        .map(|emoji| (emoji.emoji_name, emoji.discord_format))
+        .collect();
+
+    let mut cache = emoji_cache.write().await;
+    *cache = emojis;
+    info!("Loaded {} emoji records", cache.len());
+    Ok(())
+}
+
+// 単語リストを読み込んだあとの整合性の確認（食い違いがあればログに残す）
+async fn check_consistency_in_background(arena: Arc<solver::WordArena>, version: String) {
+    let seed = startup_millis();
+    let samples = solver::DEFAULT_CONSISTENCY_SAMPLES;
+    match tokio::task::spawn_blocking(move || solver::check_consistency(&arena, samples, seed)).await {
+        Ok(report) if report.is_consistent() => {
+            info!("Consistency self-check passed for word list {} ({} pairs)", version, report.checked);
+        }
+        Ok(report) => error!(
+            "Consistency self-check found {} divergences in {} pairs for word list {} (seed {}): {:?}",
+            report.divergence_count, report.checked, version, report.seed, report.divergences
+        ),
+        Err(e) => warn!("Consistency self-check failed to run: {:?}", e),
+    }
+}
+
+// 一定時間操作のないセッションを破棄
+async fn expire_sessions(game_states: SessionMap) -> anyhow::Result<()> {
+    let mut states = game_states.write().await;
+    let before = states.len();
+    // 操作中（ロック中）のセッションは使用中とみなして残す
+    states.retain(|_, state| {
+        state.try_lock().map_or(true, |state| state.last_active.elapsed() < SESSION_TTL)
+    });
+
+    let expired = before - states.len();
+    if expired > 0 {
+        info!("Expired {} idle sessions ({} remaining)", expired, states.len());
+    }
+    Ok(())
+}
+
+impl Bot {
+    // 設定からボットを組み立てる（ゲートウェイ・Webhook・APIサーバー共通）
+    pub fn from_config(config: &BotConfig) -> Self {
+        for problem in config.validate() {
+            warn!("Configuration: {}", problem);
+        }
+
+        // Supabase・辞書・Webhookで同じ接続プールを使う
+        let http_client = config.http.client();
+        let supabase = Arc::new(
+            supabase::SupabaseClient::new(http_client.clone(), config.supabase_url.clone(), config.supabase_key.clone())
+                .with_max_concurrent_requests(config.http.max_concurrent_requests),
+        );
+        let storage = SupabaseStorage::new(Arc::clone(&supabase));
+        let mut bot = Bot::new(GuildId::new(config.discord_guild_id), Arc::new(storage), config.permissions.clone())
+            .with_http_client(http_client)
+            .with_supabase(supabase)
+            .with_write_batching(config.write_batch_size, config.write_flush_interval);
+        if let Some(channel) = config.error_channel_id {
+            bot = bot.with_error_channel(ChannelId::new(channel));
+        }
+        bot = bot.with_session_limit(config.max_sessions, config.persist_sessions);
+        if let Some(path) = &config.cache_snapshot_path {
+            bot = bot.with_cache_snapshot(path);
+        }
+        if let Some(ms) = config.slow_interaction_ms {
+            bot = bot.with_slow_interaction_threshold(std::time::Duration::from_millis(ms));
+        }
+        if let Some(workers) = config.analysis_workers {
+            bot = bot.with_analysis_workers(workers);
+        }
+        bot.with_user_install(config.user_install)
+    }
+}
+
+pub async fn get_client(config: BotConfig) -> anyhow::Result<Client> {
+    get_client_with(&config, Bot::from_config(&config)).await
+}
+
+// 組み立て済みのボットでクライアントを作る（APIサーバーなどと状態を共有する場合）
+pub async fn get_client_with(config: &BotConfig, bot: Bot) -> anyhow::Result<Client> {
+    // リアクションでの色の入力に使う（特権インテントではない）
+    let intents = GatewayIntents::GUILD_MESSAGE_REACTIONS | GatewayIntents::DIRECT_MESSAGE_REACTIONS;
+
+    let client = Client::builder(&config.discord_token, intents)
+        .event_handler(bot)
+        .await?;
+
+    Ok(client)
+}
+
+// custom_id の解釈から応答までの振り分けを、Discordに接続せずに検証する
+// 最初の応答は Webhook と同じく受信側へ引き渡されるため、送る前の内容をそのまま確かめられる
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use settings::GuessLimit;
+    use storage::MemoryStorage;
+
+    const OWNER: u64 = 42;
+    const OTHER: u64 = 43;
+    const EPHEMERAL: u64 = 64;
+
+    async fn bot() -> Bot {
+        let storage = Arc::new(MemoryStorage::new(["crane", "slate", "trace", "moist"]));
+        let bot = Bot::new(GuildId::new(1), storage, PermissionConfig::default());
+        let _ = bot.load_word_cache().await;
+        bot
+    }
+
+    fn user(id: u64) -> Value {
+        json!({ "id": id.to_string(), "username": "player", "discriminator": "0", "global_name": null, "avatar": null })
+    }
+
+    // ボタンが付いていた盤面のメッセージ
+    fn board_message() -> Value {
+        json!({
+            "id": "10", "channel_id": "20", "author": user(1), "content": "", "timestamp": "2026-01-01T00:00:00Z",
+            "edited_timestamp": null, "tts": false, "mention_everyone": false, "mentions": [], "mention_roles": [],
+            "attachments": [], "embeds": [], "pinned": false, "type": 0,
+        })
+    }
+
+    fn interaction(kind: u8, user_id: u64, data: Value) -> Interaction {
+        serde_json::from_value(json!({
+            "id": "1", "application_id": "2", "type": kind, "data": data, "channel_id": "20", "user": user(user_id),
+            "token": "token", "version": 1, "message": board_message(), "locale": "ja", "entitlements": [],
+        }))
+        .unwrap()
+    }
+
+    fn button(user_id: u64, custom_id: &str) -> Interaction {
+        interaction(3, user_id, json!({ "custom_id": custom_id, "component_type": 2 }))
+    }
+
+    fn modal(user_id: u64, custom_id: &str, word: &str) -> Interaction {
+        let input = json!({ "type": 4, "custom_id": "word", "value": word });
+        interaction(5, user_id, json!({ "custom_id": custom_id, "components": [{ "type": 1, "components": [input] }] }))
+    }
+
+    // 最初の応答（Webhookなら HTTP レスポンスとして返す内容）
+    async fn respond(bot: &Bot, interaction: Interaction) -> Value {
+        let (ctx, initial) = ReplyContext::webhook(Arc::new(Http::new("")));
+        bot.handle_interaction(ctx, interaction).await;
+        serde_json::to_value(initial.await.unwrap()).unwrap()
+    }
+
+    fn assert_ephemeral(response: &Value, text: Text) {
+        assert_eq!(response["type"], 4);
+        assert_eq!(response["data"]["flags"], EPHEMERAL);
+        assert_eq!(response["data"]["content"], Locale::Ja.text(text));
+    }
+
+    #[tokio::test]
+    async fn unreadable_and_foreign_custom_ids_are_rejected() {
+        let bot = bot().await;
+
+        let malformed = format!("wht:{}:42", custom_id::FORMAT_VERSION);
+        assert_ephemeral(&respond(&bot, button(OWNER, &malformed)).await, Text::GenericError);
+        assert_ephemeral(&respond(&bot, button(OWNER, "someone-else:1")).await, Text::OutdatedButton);
+        assert_ephemeral(&respond(&bot, button(OWNER, "wht:0:42:1:new")).await, Text::OutdatedButton);
+        // ボタンのアクションはモーダルからは受け付けない
+        let guess = CustomId::new(OWNER, 1, Action::PlayGuess { game: 1 }).encode();
+        assert_ephemeral(&respond(&bot, modal(OWNER, &guess, "crane")).await, Text::GenericError);
+    }
+
+    #[tokio::test]
+    async fn only_the_owner_can_press_the_buttons() {
+        let bot = bot().await;
+        let view = bot.start_play(OWNER, None, GuessLimit::Classic).await.unwrap();
+        let guess = CustomId::new(OWNER, view.revision, Action::PlayGuess { game: view.game }).encode();
+
+        assert_ephemeral(&respond(&bot, button(OTHER, &guess)).await, Text::NotOwner);
+
+        // 本人なら推測を入力するモーダルが開き、同じゲームを指す
+        let response = respond(&bot, button(OWNER, &guess)).await;
+        assert_eq!(response["type"], 9);
+        let id = CustomId::decode(response["data"]["custom_id"].as_str().unwrap()).unwrap();
+        assert_eq!((id.owner, id.revision, id.action), (OWNER, view.revision, Action::PlayModal { game: view.game }));
+    }
+
+    #[tokio::test]
+    async fn guesses_update_the_board_and_old_buttons_are_stale() {
+        let bot = bot().await;
+        let view = bot.start_play(OWNER, None, GuessLimit::Classic).await.unwrap();
+        let submit = CustomId::new(OWNER, view.revision, Action::PlayModal { game: view.game }).encode();
+        let hint = CustomId::new(OWNER, view.revision, Action::PlayHint { game: view.game }).encode();
+
+        let response = respond(&bot, modal(OWNER, &submit, "moist")).await;
+        assert_eq!(response["type"], 7);
+        assert!(response["data"]["embeds"][0]["description"].as_str().unwrap().contains("MOIST"));
+
+        // 盤面が進んだあとは、前のリビジョンのボタンは使えない
+        assert_ephemeral(&respond(&bot, button(OWNER, &hint)).await, Text::OutdatedButton);
+        assert_ephemeral(&respond(&bot, modal(OWNER, &submit, "slate")).await, Text::OutdatedButton);
+    }
+}
@@ -0,0 +1,317 @@
+// 利用状況の集計（/admin dashboard 用）
+// 日ごとにメモリ上で数え、直近の日数分だけ残す（再起動すると消える）
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serenity::async_trait;
+use serde_json::Value;
+
+use crate::stats;
+use crate::storage::{CachedTable, EmojiRecord, StatsUpdate, Storage, WordRecord};
+
+// 保持する日数
+pub const RETENTION_DAYS: usize = 7;
+// 1日に保持する処理時間のサンプル数（超えた分は数えない）
+const MAX_LATENCY_SAMPLES: usize = 10_000;
+
+#[derive(Debug, Clone, Default)]
+struct DayMetrics {
+    day: u64,
+    commands: u64,
+    players: HashSet<u64>,
+    peak_sessions: usize,
+    solver_latencies_ms: Vec<u64>,
+    storage_calls: u64,
+    storage_errors: u64,
+}
+
+// 1日分の集計結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaySummary {
+    pub day: u64,
+    pub commands: u64,
+    pub players: usize,
+    pub peak_sessions: usize,
+    pub storage_calls: u64,
+    pub storage_errors: u64,
+}
+
+// 期間全体の集計結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    // 古い日から順（記録のない日も含む）
+    pub days: Vec<DaySummary>,
+    pub players: usize,
+    // 単語提案の計算時間の p50 / p90 / p99（記録がなければ None）
+    pub solver_latency_ms: Option<[u64; 3]>,
+}
+
+impl Summary {
+    // ストレージ呼び出しの失敗率（%）
+    pub fn storage_error_rate(&self) -> Option<f64> {
+        let calls: u64 = self.days.iter().map(|d| d.storage_calls).sum();
+        let errors: u64 = self.days.iter().map(|d| d.storage_errors).sum();
+        (calls > 0).then(|| errors as f64 * 100.0 / calls as f64)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    days: Mutex<VecDeque<DayMetrics>>,
+}
+
+// 最も近い順位の値（samples は昇順）
+fn percentile(samples: &[u64], p: usize) -> u64 {
+    let rank = (samples.len() * p).div_ceil(100).max(1);
+    samples[rank - 1]
+}
+
+impl Metrics {
+    // 指定した日のバケットを更新する（古いバケットは捨てる）
+    fn update(&self, day: u64, f: impl FnOnce(&mut DayMetrics)) {
+        let mut days = self.days.lock().unwrap_or_else(|e| e.into_inner());
+        if days.back().is_none_or(|d| d.day < day) {
+            days.push_back(DayMetrics { day, ..Default::default() });
+        }
+        while days.front().is_some_and(|d| d.day + (RETENTION_DAYS as u64) <= day) {
+            days.pop_front();
+        }
+        // 日付が戻った場合（時計の調整など）は最新の日に含める
+        if let Some(bucket) = days.back_mut() {
+            f(bucket);
+        }
+    }
+
+    pub fn record_command_on(&self, day: u64, user_id: u64) {
+        self.update(day, |d| {
+            d.commands += 1;
+            d.players.insert(user_id);
+        });
+    }
+
+    pub fn record_command(&self, user_id: u64) {
+        self.record_command_on(stats::today(), user_id);
+    }
+
+    pub fn record_sessions_on(&self, day: u64, sessions: usize) {
+        self.update(day, |d| d.peak_sessions = d.peak_sessions.max(sessions));
+    }
+
+    pub fn record_sessions(&self, sessions: usize) {
+        self.record_sessions_on(stats::today(), sessions);
+    }
+
+    pub fn record_solver_latency_on(&self, day: u64, elapsed: Duration) {
+        self.update(day, |d| {
+            if d.solver_latencies_ms.len() < MAX_LATENCY_SAMPLES {
+                d.solver_latencies_ms.push(elapsed.as_millis() as u64);
+            }
+        });
+    }
+
+    pub fn record_solver_latency(&self, elapsed: Duration) {
+        self.record_solver_latency_on(stats::today(), elapsed);
+    }
+
+    pub fn record_storage_on(&self, day: u64, ok: bool) {
+        self.update(day, |d| {
+            d.storage_calls += 1;
+            if !ok {
+                d.storage_errors += 1;
+            }
+        });
+    }
+
+    pub fn record_storage(&self, ok: bool) {
+        self.record_storage_on(stats::today(), ok);
+    }
+
+    // today を含む直近 RETENTION_DAYS 日分の集計
+    pub fn summary(&self, today: u64) -> Summary {
+        let days = self.days.lock().unwrap_or_else(|e| e.into_inner());
+        let first = today.saturating_sub(RETENTION_DAYS as u64 - 1);
+        let recorded: Vec<&DayMetrics> = days.iter().filter(|d| d.day >= first && d.day <= today).collect();
+
+        let summaries = (first..=today)
+            .map(|day| match recorded.iter().find(|d| d.day == day) {
+                Some(d) => DaySummary {
+                    day,
+                    commands: d.commands,
+                    players: d.players.len(),
+                    peak_sessions: d.peak_sessions,
+                    storage_calls: d.storage_calls,
+                    storage_errors: d.storage_errors,
+                },
+                None => DaySummary { day, commands: 0, players: 0, peak_sessions: 0, storage_calls: 0, storage_errors: 0 },
+            })
+            .collect();
+
+        let players = recorded.iter().flat_map(|d| d.players.iter()).collect::<HashSet<_>>().len();
+
+        let mut latencies: Vec<u64> = recorded.iter().flat_map(|d| d.solver_latencies_ms.iter().copied()).collect();
+        latencies.sort_unstable();
+        let solver_latency_ms = (!latencies.is_empty())
+            .then(|| [percentile(&latencies, 50), percentile(&latencies, 90), percentile(&latencies, 99)]);
+
+        Summary { days: summaries, players, solver_latency_ms }
+    }
+}
+
+// ストレージの呼び出しごとに成否を記録する
+pub struct MeteredStorage {
+    inner: Arc<dyn Storage>,
+    metrics: Arc<Metrics>,
+}
+
+impl MeteredStorage {
+    pub fn new(inner: Arc<dyn Storage>, metrics: Arc<Metrics>) -> Self {
+        Self { inner, metrics }
+    }
+
+    fn record<T>(&self, result: anyhow::Result<T>) -> anyhow::Result<T> {
+        self.metrics.record_storage(result.is_ok());
+        result
+    }
+}
+
+#[async_trait]
+impl Storage for MeteredStorage {
+    async fn fetch_words(&self) -> anyhow::Result<Vec<WordRecord>> {
+        self.record(self.inner.fetch_words().await)
+    }
+
+    async fn fetch_emojis(&self) -> anyhow::Result<Vec<EmojiRecord>> {
+        self.record(self.inner.fetch_emojis().await)
+    }
+
+    async fn table_fingerprint(&self, table: CachedTable) -> anyhow::Result<Option<String>> {
+        self.record(self.inner.table_fingerprint(table).await)
+    }
+
+    async fn insert_words(&self, words: &[String]) -> anyhow::Result<()> {
+        self.record(self.inner.insert_words(words).await)
+    }
+
+    async fn save_session(&self, user_id: u64, state: Value) -> anyhow::Result<()> {
+        self.record(self.inner.save_session(user_id, state).await)
+    }
+
+    async fn take_session(&self, user_id: u64) -> anyhow::Result<Option<Value>> {
+        self.record(self.inner.take_session(user_id).await)
+    }
+
+    async fn load_settings(&self) -> anyhow::Result<Option<Value>> {
+        self.record(self.inner.load_settings().await)
+    }
+
+    async fn save_settings(&self, settings: Value) -> anyhow::Result<()> {
+        self.record(self.inner.save_settings(settings).await)
+    }
+
+    async fn load_user_stats(&self, user_id: u64) -> anyhow::Result<Option<Value>> {
+        self.record(self.inner.load_user_stats(user_id).await)
+    }
+
+    async fn save_user_stats(&self, user_id: u64, stats: Value) -> anyhow::Result<()> {
+        self.record(self.inner.save_user_stats(user_id, stats).await)
+    }
+
+    async fn list_user_stats(&self) -> anyhow::Result<Vec<(u64, Value)>> {
+        self.record(self.inner.list_user_stats().await)
+    }
+
+    async fn record_wordlist_version(&self, version: &str, words: &[String]) -> anyhow::Result<()> {
+        self.record(self.inner.record_wordlist_version(version, words).await)
+    }
+
+    async fn load_wordlist_version(&self, version: &str) -> anyhow::Result<Option<Vec<String>>> {
+        self.record(self.inner.load_wordlist_version(version).await)
+    }
+
+    async fn record_daily_result(&self, guild_id: u64, day: u64, user_id: u64, result: Value) -> anyhow::Result<bool> {
+        self.record(self.inner.record_daily_result(guild_id, day, user_id, result).await)
+    }
+
+    async fn load_daily_result(&self, guild_id: u64, day: u64, user_id: u64) -> anyhow::Result<Option<Value>> {
+        self.record(self.inner.load_daily_result(guild_id, day, user_id).await)
+    }
+
+    async fn load_daily_results(&self, guild_id: u64, day: u64) -> anyhow::Result<Vec<(u64, Value)>> {
+        self.record(self.inner.load_daily_results(guild_id, day).await)
+    }
+
+    async fn record_daily_post(&self, guild_id: u64, day: u64, thread_id: u64) -> anyhow::Result<bool> {
+        self.record(self.inner.record_daily_post(guild_id, day, thread_id).await)
+    }
+
+    async fn load_daily_post(&self, guild_id: u64, day: u64) -> anyhow::Result<Option<u64>> {
+        self.record(self.inner.load_daily_post(guild_id, day).await)
+    }
+
+    async fn save_play_game(&self, game_id: u64, started_at: u64, state: Value) -> anyhow::Result<()> {
+        self.record(self.inner.save_play_game(game_id, started_at, state).await)
+    }
+
+    async fn load_play_game(&self, game_id: u64) -> anyhow::Result<Option<Value>> {
+        self.record(self.inner.load_play_game(game_id).await)
+    }
+
+    async fn delete_play_game(&self, game_id: u64) -> anyhow::Result<()> {
+        self.record(self.inner.delete_play_game(game_id).await)
+    }
+
+    async fn delete_play_games_before(&self, started_before: u64) -> anyhow::Result<()> {
+        self.record(self.inner.delete_play_games_before(started_before).await)
+    }
+
+    async fn update_user_stats(&self, user_id: u64, update: StatsUpdate<'_>) -> anyhow::Result<Value> {
+        self.record(self.inner.update_user_stats(user_id, update).await)
+    }
+
+    async fn save_sessions(&self, sessions: &[(u64, Value)]) -> anyhow::Result<()> {
+        self.record(self.inner.save_sessions(sessions).await)
+    }
+
+    async fn save_user_stats_batch(&self, stats: &[(u64, Value)]) -> anyhow::Result<()> {
+        self.record(self.inner.save_user_stats_batch(stats).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_the_last_seven_days() {
+        let metrics = Metrics::default();
+        metrics.record_command_on(1, 10);
+        metrics.record_command_on(5, 10);
+        metrics.record_command_on(5, 20);
+        metrics.record_command_on(5, 20);
+        metrics.record_sessions_on(5, 3);
+        metrics.record_sessions_on(5, 2);
+        metrics.record_storage_on(5, true);
+        metrics.record_storage_on(5, false);
+        for ms in 1..=100 {
+            metrics.record_solver_latency_on(8, Duration::from_millis(ms));
+        }
+
+        let summary = metrics.summary(8);
+        // 1日目は範囲外（2〜8日目）
+        assert_eq!(summary.days.len(), RETENTION_DAYS);
+        assert_eq!(summary.days.first().unwrap().day, 2);
+        assert_eq!(summary.days.iter().map(|d| d.commands).sum::<u64>(), 3);
+        let day5 = summary.days.iter().find(|d| d.day == 5).unwrap();
+        assert_eq!((day5.players, day5.peak_sessions), (2, 3));
+        assert_eq!(summary.players, 2);
+        assert_eq!(summary.storage_error_rate(), Some(50.0));
+        assert_eq!(summary.solver_latency_ms, Some([50, 90, 99]));
+
+        // 8日以上前のバケットは捨てられる
+        metrics.record_command_on(20, 30);
+        let summary = metrics.summary(20);
+        assert_eq!(summary.players, 1);
+        assert_eq!(summary.solver_latency_ms, None);
+    }
+}
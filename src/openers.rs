@@ -0,0 +1,193 @@
+// /openers: 単語リスト全体から求めた開始単語のランキング
+// 計算は単語数の2乗に比例するため、単語キャッシュが変わったときだけやり直す
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use serenity::all::{
+    Colour,
+    CommandInteraction,
+    CommandOptionType,
+    CreateCommand,
+    CreateCommandOption,
+    CreateEmbed,
+    CreateEmbedFooter,
+    ResolvedValue,
+};
+use tracing::info;
+
+use crate::i18n::{self, Text};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::solver::{self, OpenerScore};
+use crate::Bot;
+
+// 表示する開始単語の数
+const OPENER_COUNT: usize = 15;
+
+// 計算済みのランキングと、計算に使った単語リストの指紋
+#[derive(Default)]
+pub struct OpenerCache {
+    ranking: tokio::sync::Mutex<Option<(u64, Arc<Vec<OpenerScore>>)>>,
+}
+
+fn fingerprint(words: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    words.hash(&mut hasher);
+    hasher.finish()
+}
+
+// ランキング中の位置（自分よりエントロピーの高い単語の数 + 1）と、それ以下の単語の割合（%）
+pub fn standing(ranking: &[OpenerScore], score: &OpenerScore) -> (usize, f64) {
+    let better = ranking.iter().filter(|other| other.entropy > score.entropy).count();
+    let percentile = if ranking.is_empty() { 100.0 } else { (ranking.len() - better) as f64 * 100.0 / ranking.len() as f64 };
+    (better + 1, percentile)
+}
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("openers")
+}
+
+pub fn create_evaluate_command() -> CreateCommand {
+    i18n::command("evaluate")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "word", "評価する5文字の英単語")
+                .min_length(5)
+                .max_length(5)
+                .required(true),
+        )
+}
+
+impl Bot {
+    // 単語リスト全体の開始単語ランキング（単語リストが変わっていれば計算し直す）
+    pub(crate) async fn opener_ranking(&self) -> anyhow::Result<Arc<Vec<OpenerScore>>> {
+        let words: Vec<String> = self.word_cache.read().await.iter().map(str::to_uppercase).collect();
+        let key = fingerprint(&words);
+
+        // 同時に呼ばれても計算は1回だけにする
+        let mut cache = self.openers.ranking.lock().await;
+        if let Some((cached_key, ranking)) = cache.as_ref() {
+            if *cached_key == key {
+                return Ok(Arc::clone(ranking));
+            }
+        }
+
+        let started = std::time::Instant::now();
+        let count = words.len();
+        let ranking = Arc::new(tokio::task::spawn_blocking(move || solver::rank_openers(&words)).await?);
+        info!("Ranked {} openers in {} ms", count, started.elapsed().as_millis());
+
+        *cache = Some((key, Arc::clone(&ranking)));
+        Ok(ranking)
+    }
+
+    pub(crate) async fn handle_openers_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+
+        // 初回や単語リストの更新後は計算に時間がかかるため自動で保留される
+        self.responder(ctx, command, ReplyMode::NewMessage)
+            .run(async {
+                let ranking = match self.opener_ranking().await {
+                    Ok(ranking) => ranking,
+                    Err(e) => {
+                        self.reporter.report("Failed to rank openers", &e).await;
+                        return Reply::content(locale.text(Text::GenericError));
+                    }
+                };
+                if ranking.is_empty() {
+                    return Reply::content(locale.text(Text::OpenersEmpty));
+                }
+
+                let lines: Vec<String> = ranking.iter()
+                    .take(OPENER_COUNT)
+                    .enumerate()
+                    .map(|(i, score)| locale.opener_line(i + 1, score))
+                    .collect();
+
+                let embed = CreateEmbed::new()
+                    .title(locale.text(Text::OpenersTitle))
+                    .description(lines.join("\n"))
+                    .color(Colour::BLUE)
+                    .footer(CreateEmbedFooter::new(locale.openers_footer(ranking.len())));
+                Reply::embed(embed)
+            })
+            .await;
+    }
+
+    // 単語リストにない単語も、正しい形なら評価する
+    pub(crate) async fn handle_evaluate_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let word = command.data.options().into_iter()
+            .find_map(|o| match o.value {
+                ResolvedValue::String(value) if o.name == "word" => Some(value.trim().to_uppercase()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        if !solver::is_valid_word(&word) {
+            self.responder(ctx, command, ReplyMode::Ephemeral)
+                .send(Reply::content(locale.text(Text::EvaluateInvalidWord)))
+                .await;
+            return;
+        }
+
+        self.responder(ctx, command, ReplyMode::NewMessage)
+            .run(async {
+                let ranking = match self.opener_ranking().await {
+                    Ok(ranking) => ranking,
+                    Err(e) => {
+                        self.reporter.report("Failed to rank openers", &e).await;
+                        return Reply::content(locale.text(Text::GenericError));
+                    }
+                };
+                if ranking.is_empty() {
+                    return Reply::content(locale.text(Text::OpenersEmpty));
+                }
+
+                // ランキングにない単語は同じ答えの候補に対して計算する
+                let score = match ranking.iter().find(|s| s.word == word) {
+                    Some(score) => score.clone(),
+                    None => {
+                        let answers: Vec<String> = ranking.iter().map(|s| s.word.clone()).collect();
+                        let word = word.clone();
+                        match tokio::task::spawn_blocking(move || solver::score_opener(&word, &answers)).await {
+                            Ok(score) => score,
+                            Err(e) => {
+                                self.reporter.report("Failed to evaluate opener", &e).await;
+                                return Reply::content(locale.text(Text::GenericError));
+                            }
+                        }
+                    }
+                };
+                let (rank, percentile) = standing(&ranking, &score);
+                info!("Evaluated opener {}: rank {} of {}", score.word, rank, ranking.len());
+
+                let embed = CreateEmbed::new()
+                    .title(locale.evaluate_title(&score.word))
+                    .description(locale.evaluate_description(&score, rank, ranking.len(), percentile))
+                    .color(Colour::BLUE)
+                    .footer(CreateEmbedFooter::new(locale.openers_footer(ranking.len())));
+                Reply::embed(embed)
+            })
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(word: &str, entropy: f64) -> OpenerScore {
+        OpenerScore { word: word.to_string(), entropy, expected_remaining: 0.0 }
+    }
+
+    #[test]
+    fn standing_counts_strictly_better_openers() {
+        let ranking = vec![score("SLATE", 5.0), score("CRANE", 4.0), score("TRACE", 4.0), score("FUZZY", 1.0)];
+
+        assert_eq!(standing(&ranking, &ranking[0]), (1, 100.0));
+        // 同点は同じ順位
+        assert_eq!(standing(&ranking, &ranking[2]), (2, 75.0));
+        // ランキングにない単語
+        assert_eq!(standing(&ranking, &score("XYLYL", 0.5)), (5, 0.0));
+    }
+}
@@ -0,0 +1,73 @@
+use serenity::all::{CommandInteraction, Permissions, RoleId, UserId};
+use tracing::info;
+
+use crate::respond::{Reply, Responder};
+
+// 管理コマンドに必要な権限レベル（上位の権限は下位の権限を包含する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Permission {
+    Moderator,
+    GuildAdmin,
+    BotOwner,
+}
+
+impl Permission {
+    fn label(&self) -> &'static str {
+        match self {
+            Permission::Moderator => "モデレーター",
+            Permission::GuildAdmin => "サーバー管理者",
+            Permission::BotOwner => "ボットオーナー",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PermissionConfig {
+    pub owner_id: Option<UserId>,
+    pub moderator_role: Option<RoleId>,
+}
+
+impl PermissionConfig {
+    // 実行ユーザーが持つ最上位の権限レベルを判定
+    pub fn level_of(&self, command: &CommandInteraction) -> Option<Permission> {
+        if self.owner_id == Some(command.user.id) {
+            return Some(Permission::BotOwner);
+        }
+
+        let member = command.member.as_ref()?;
+
+        let is_admin = member.permissions.is_some_and(|p| {
+            p.contains(Permissions::ADMINISTRATOR) || p.contains(Permissions::MANAGE_GUILD)
+        });
+        if is_admin {
+            return Some(Permission::GuildAdmin);
+        }
+
+        match self.moderator_role {
+            Some(role) if member.roles.contains(&role) => Some(Permission::Moderator),
+            _ => None,
+        }
+    }
+
+    pub fn allows(&self, command: &CommandInteraction, required: Permission) -> bool {
+        self.level_of(command).is_some_and(|level| level >= required)
+    }
+
+    // 権限を確認し、不足している場合はエフェメラルで拒否メッセージを返す
+    pub async fn require(&self, command: &CommandInteraction, responder: &mut Responder, required: Permission) -> bool {
+        if self.allows(command, required) {
+            return true;
+        }
+
+        info!(
+            "Permission denied: user {} tried /{} (requires {:?})",
+            command.user.id, command.data.name, required
+        );
+
+        responder
+            .send(Reply::content(format!("🚫 このコマンドを実行する権限がありません（必要な権限: {}）", required.label())))
+            .await;
+
+        false
+    }
+}
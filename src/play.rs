@@ -0,0 +1,797 @@
+// ボットが出題する遊べるWordle（/wordle）
+// 単語キャッシュから答えを選び、推測の色はボットが判定する。上限の回数（/wordle は開始時に6・8・4回から選ぶ）で
+// 当てられなければ答えを明かして終わる
+// 終わったゲームは /stats の成績に記録する
+// /wordle ではヒントのボタンで段階的なヒントを受け取れ、使ったヒントの分だけ得点が下がる
+// auto_hint_minutes を設定すると、その分だけ操作のない /wordle の盤面にボットが次のヒントを出す（得点はボタンと同じく下がる）
+// ゲームはメモリ上に持ち、答えは SecretWord で伏せておく（ログに答えを残さない）
+// /wordle のゲームは盤面が変わるたびにストレージにも保存し、再起動後はボタンに埋め込んだゲームIDから読み込んで続ける
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use arc_swap::ArcSwap;
+use serde::{Deserialize, Serialize};
+
+use serenity::all::{
+    ButtonStyle,
+    ChannelId,
+    Colour,
+    CommandInteraction,
+    CommandOptionType,
+    ComponentInteraction,
+    CreateActionRow,
+    CreateButton,
+    CreateCommand,
+    CreateCommandOption,
+    CreateEmbed,
+    CreateInputText,
+    CreateModal,
+    Http,
+    InputTextStyle,
+    ModalInteraction,
+    ResolvedValue,
+};
+use tracing::{info, warn};
+
+use crate::custom_id::{Action, CustomId, PlayedWords};
+use crate::i18n::{self, Locale, Text};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::results;
+use crate::settings::{GuessLimit, Settings};
+use crate::solver::{self, HintState, HintTier, LetterResult, SecretWord, WordleGuess};
+use crate::storage::Storage;
+use crate::Bot;
+
+// 終わっていないゲームを覚えておく時間（これを過ぎたゲームは捨てる）
+const GAME_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+// 正解したときの得点（残った推測の回数ごとに加点し、使ったヒントの点数を引く）
+const WIN_POINTS: u32 = 10;
+const POINTS_PER_SPARE_GUESS: u32 = 2;
+
+struct PlayGame {
+    // ゲームID（始めたときのリビジョン）
+    id: u64,
+    secret: SecretWord,
+    guesses: Vec<WordleGuess>,
+    hints: HintState,
+    revision: u64,
+    started: SystemTime,
+    // 推測できる回数
+    limit: usize,
+    // 最後に推測・ヒントを使った時刻（自動のヒントもここから数える）
+    last_action: SystemTime,
+    // 自動のヒントで更新する盤面のメッセージ（わからなければ自動のヒントを出さない）
+    board: Option<PlayBoard>,
+}
+
+// 盤面のメッセージの場所
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayBoard {
+    pub channel: u64,
+    pub message: u64,
+    pub guild: Option<u64>,
+}
+
+// 推測したあとのゲームの進み具合
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayStatus {
+    InProgress,
+    Won,
+    // 答えは当てられずに終わったときにだけ明かす
+    Lost { answer: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayView {
+    pub owner: u64,
+    pub revision: u64,
+    pub guesses: Vec<WordleGuess>,
+    pub status: PlayStatus,
+    // 使ったヒントと、次に出せるヒントの段階（出せるものがなければ None）
+    pub hints: HintState,
+    pub next_hint: Option<HintTier>,
+    pub limit: usize,
+    pub game: u64,
+}
+
+// ストレージに保存する /wordle のゲーム
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredPlayGame {
+    pub owner: u64,
+    pub answer: String,
+    pub guesses: Vec<WordleGuess>,
+    pub hints: HintState,
+    pub revision: u64,
+    pub limit: usize,
+    // 始めた時刻（UNIX秒。期限切れの判定に使う）
+    pub started_at: u64,
+    #[serde(default)]
+    pub last_action_at: u64,
+    #[serde(default)]
+    pub board: Option<PlayBoard>,
+}
+
+impl PlayView {
+    // 終わったゲームの得点（当てられなければ0）
+    pub fn score(&self) -> Option<u32> {
+        match self.status {
+            PlayStatus::InProgress => None,
+            PlayStatus::Won => {
+                let spare = self.limit.saturating_sub(self.guesses.len()) as u32;
+                Some((WIN_POINTS + spare * POINTS_PER_SPARE_GUESS).saturating_sub(self.hints.penalty()))
+            }
+            PlayStatus::Lost { .. } => Some(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayError {
+    // 期限切れや再起動でゲームがなくなった
+    NotFound,
+    // 古い盤面のボタンからの推測
+    Stale,
+    Finished,
+    // 5文字の英単語でない、または単語リストにない
+    UnknownWord,
+    // もう出せるヒントがない
+    NoHints,
+}
+
+#[derive(Default)]
+pub struct PlayGames {
+    games: Mutex<HashMap<u64, PlayGame>>,
+    http: OnceLock<Arc<Http>>,
+}
+
+// 盤面の種類（見出しと、推測のボタン・モーダルの custom_id が変わる）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PlayKind {
+    // /wordle（ユーザーごとに別の答え）
+    Free,
+    // /daily（サーバーの全員が同じ答え。その日のうちなら再起動後もボタンから続けられる）
+    Daily { day: u64 },
+}
+
+impl PlayKind {
+    fn title(self) -> &'static str {
+        match self {
+            PlayKind::Free => "🎲 Wordle",
+            PlayKind::Daily { .. } => "📅 Daily Wordle",
+        }
+    }
+
+    fn guess_action(self, view: &PlayView) -> Action {
+        match self {
+            PlayKind::Free => Action::PlayGuess { game: view.game },
+            PlayKind::Daily { day } => Action::DailyGuess {
+                day,
+                played: PlayedWords::new(view.guesses.iter().map(|g| g.word.as_str())).unwrap_or_default(),
+            },
+        }
+    }
+}
+
+// 推測のボタンから開くモーダルのアクション（ゲームIDやデイリーの埋め込んだ推測を引き継ぐ）
+fn modal_action(action: Action) -> Action {
+    match action {
+        Action::DailyGuess { day, played } => Action::DailyModal { day, played },
+        Action::PlayGuess { game } => Action::PlayModal { game },
+        other => other,
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn is_fresh(started: SystemTime) -> bool {
+    started.elapsed().unwrap_or_default() < GAME_TTL
+}
+
+// 推測の色（同じ文字が複数ある場合も公式と同じ判定）
+fn colors(guess: &str, answer: &str) -> Vec<LetterResult> {
+    solver::simulate_guess_pattern(guess, answer).into_iter()
+        .map(|code| match code {
+            2 => LetterResult::Green,
+            1 => LetterResult::Yellow,
+            _ => LetterResult::Gray,
+        })
+        .collect()
+}
+
+impl PlayGames {
+    // 新しいゲームを始める（同じユーザーの前のゲームは置き換える）
+    // ゲームIDは始めたときのリビジョン
+    pub fn start(&self, owner: u64, secret: SecretWord, revision: u64, limit: usize) -> PlayView {
+        let mut games = self.games.lock().unwrap_or_else(|e| e.into_inner());
+        // 期限切れのゲームはここで掃除する
+        games.retain(|_, game| is_fresh(game.started));
+        let game = PlayGame {
+            id: revision,
+            secret,
+            guesses: Vec::new(),
+            hints: HintState::default(),
+            revision,
+            started: SystemTime::now(),
+            limit,
+            last_action: SystemTime::now(),
+            board: None,
+        };
+        let view = Self::view_of(owner, &game);
+        games.insert(owner, game);
+        view
+    }
+
+    // 保存する形のゲームとゲームID（ゲームがなければ None）
+    pub fn stored(&self, owner: u64) -> Option<(u64, StoredPlayGame)> {
+        let games = self.games.lock().unwrap_or_else(|e| e.into_inner());
+        games.get(&owner).map(|game| (game.id, StoredPlayGame {
+            owner,
+            answer: game.secret.reveal(),
+            guesses: game.guesses.clone(),
+            hints: game.hints.clone(),
+            revision: game.revision,
+            limit: game.limit,
+            started_at: unix_secs(game.started),
+            last_action_at: unix_secs(game.last_action),
+            board: game.board,
+        }))
+    }
+
+    // 保存したゲームを戻す（そのユーザーのゲームがメモリにある、または期限切れなら戻さずに false）
+    pub fn restore(&self, id: u64, stored: StoredPlayGame) -> bool {
+        let started = UNIX_EPOCH + Duration::from_secs(stored.started_at);
+        let mut games = self.games.lock().unwrap_or_else(|e| e.into_inner());
+        if games.contains_key(&stored.owner) || !is_fresh(started) {
+            return false;
+        }
+        games.insert(stored.owner, PlayGame {
+            id,
+            secret: SecretWord::new(&stored.answer),
+            guesses: stored.guesses,
+            hints: stored.hints,
+            revision: stored.revision,
+            started,
+            limit: stored.limit,
+            last_action: UNIX_EPOCH + Duration::from_secs(stored.last_action_at.max(stored.started_at)),
+            board: stored.board,
+        });
+        true
+    }
+
+    // 接続後にHTTPクライアントを登録（それまでは自動のヒントを出さない）
+    pub fn attach(&self, http: Arc<Http>) {
+        let _ = self.http.set(http);
+    }
+
+    // 盤面のメッセージを記録する（置き換えられた前のゲームの盤面なら記録しない）
+    pub fn set_board(&self, owner: u64, game: u64, board: PlayBoard) {
+        let mut games = self.games.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(playing) = games.get_mut(&owner).filter(|playing| playing.id == game) {
+            playing.board = Some(board);
+        }
+    }
+
+    // 最後の操作から idle 以上経った進行中のゲームに次のヒントを出す（盤面のメッセージがわかるゲームだけ）
+    // ボタンはそのまま使えるよう、リビジョンは変えない
+    pub fn take_idle_hints(&self, idle: Duration) -> Vec<(PlayView, PlayBoard)> {
+        let mut games = self.games.lock().unwrap_or_else(|e| e.into_inner());
+        let mut hinted = Vec::new();
+        for (&owner, game) in games.iter_mut() {
+            let Some(board) = game.board else {
+                continue;
+            };
+            if game.last_action.elapsed().unwrap_or_default() < idle || Self::status(game) != PlayStatus::InProgress {
+                continue;
+            }
+            if game.hints.take(&game.secret.reveal(), &game.guesses).is_some() {
+                game.last_action = SystemTime::now();
+                hinted.push((Self::view_of(owner, game), board));
+            }
+        }
+        hinted
+    }
+
+    // 期限切れのゲームを捨てる
+    pub fn expire(&self) {
+        let mut games = self.games.lock().unwrap_or_else(|e| e.into_inner());
+        games.retain(|_, game| is_fresh(game.started));
+    }
+
+    // 遊んでいるゲームの今の盤面（ゲームがなければ None）
+    pub fn view(&self, owner: u64) -> Option<PlayView> {
+        let games = self.games.lock().unwrap_or_else(|e| e.into_inner());
+        games.get(&owner).map(|game| Self::view_of(owner, game))
+    }
+
+    // 推測の色を判定して記録する（単語リストにあるかは呼び出し側で確かめる）
+    pub fn guess(&self, owner: u64, revision: u64, word: &str, next_revision: u64) -> Result<PlayView, PlayError> {
+        let mut games = self.games.lock().unwrap_or_else(|e| e.into_inner());
+        let game = games.get_mut(&owner).ok_or(PlayError::NotFound)?;
+        if game.revision != revision {
+            return Err(PlayError::Stale);
+        }
+        if Self::status(game) != PlayStatus::InProgress {
+            return Err(PlayError::Finished);
+        }
+
+        let word = word.to_uppercase();
+        let results = colors(&word, &game.secret.reveal());
+        game.guesses.push(WordleGuess { word, results });
+        game.revision = next_revision;
+        game.last_action = SystemTime::now();
+        Ok(Self::view_of(owner, game))
+    }
+
+    // 次の段階のヒントを使う（使ったヒントは盤面に表示する）
+    pub fn hint(&self, owner: u64, revision: u64, next_revision: u64) -> Result<PlayView, PlayError> {
+        let mut games = self.games.lock().unwrap_or_else(|e| e.into_inner());
+        let game = games.get_mut(&owner).ok_or(PlayError::NotFound)?;
+        if game.revision != revision {
+            return Err(PlayError::Stale);
+        }
+        if Self::status(game) != PlayStatus::InProgress {
+            return Err(PlayError::Finished);
+        }
+
+        game.hints.take(&game.secret.reveal(), &game.guesses).ok_or(PlayError::NoHints)?;
+        game.revision = next_revision;
+        game.last_action = SystemTime::now();
+        Ok(Self::view_of(owner, game))
+    }
+
+    fn view_of(owner: u64, game: &PlayGame) -> PlayView {
+        let status = Self::status(game);
+        let next_hint = (status == PlayStatus::InProgress)
+            .then(|| game.hints.next(&game.secret.reveal(), &game.guesses))
+            .flatten()
+            .map(|hint| hint.tier);
+        PlayView {
+            owner,
+            revision: game.revision,
+            guesses: game.guesses.clone(),
+            status,
+            hints: game.hints.clone(),
+            next_hint,
+            limit: game.limit,
+            game: game.id,
+        }
+    }
+
+    fn status(game: &PlayGame) -> PlayStatus {
+        match game.guesses.last() {
+            Some(last) if game.secret.matches(&last.word) => PlayStatus::Won,
+            _ if game.guesses.len() >= game.limit => PlayStatus::Lost { answer: game.secret.reveal() },
+            _ => PlayStatus::InProgress,
+        }
+    }
+}
+
+// 期限切れのゲームをメモリとストレージから消す（スケジューラーから呼ぶ）
+pub async fn expire_games(games: Arc<PlayGames>, storage: Arc<dyn Storage>) -> anyhow::Result<()> {
+    games.expire();
+    let cutoff = unix_secs(SystemTime::now()).saturating_sub(GAME_TTL.as_secs());
+    storage.delete_play_games_before(cutoff).await
+}
+
+// 盤面が変わったゲームを保存する（保存に失敗してもメモリ上では続けられる）
+async fn save_game(games: &PlayGames, storage: &dyn Storage, owner: u64) {
+    let Some((game, stored)) = games.stored(owner) else {
+        return;
+    };
+    let saved = match serde_json::to_value(&stored) {
+        Ok(state) => storage.save_play_game(game, stored.started_at, state).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = saved {
+        warn!("Failed to save playable game {} of {}: {:?}", game, owner, e);
+    }
+}
+
+// 操作のない /wordle の盤面に次のヒントを出して、盤面のメッセージを更新する（スケジューラーから毎分呼ぶ）
+pub async fn give_auto_hints(games: Arc<PlayGames>, settings: Arc<ArcSwap<Settings>>, storage: Arc<dyn Storage>) -> anyhow::Result<()> {
+    let minutes = settings.load().auto_hint_minutes;
+    // 接続前はヒントを出しても盤面を更新できないため待つ
+    let (Some(http), true) = (games.http.get().cloned(), minutes > 0) else {
+        return Ok(());
+    };
+
+    let mut failed = 0;
+    for (view, board) in games.take_idle_hints(Duration::from_secs(minutes * 60)) {
+        save_game(&games, storage.as_ref(), view.owner).await;
+        let locale = settings.load().locale_for(board.guild);
+        let edit = play_reply(locale, &view, PlayKind::Free).into_message_edit();
+        // 更新できなくてもリビジョンは変えていないため、ヒントは次の操作で盤面に表示される
+        match ChannelId::new(board.channel).edit_message(&http, board.message, edit).await {
+            Ok(_) => info!("Gave an automatic hint to {}", view.owner),
+            Err(e) => {
+                warn!("Failed to show an automatic hint to {}: {}", view.owner, e);
+                failed += 1;
+            }
+        }
+    }
+    anyhow::ensure!(failed == 0, "failed to show {} automatic hints", failed);
+    Ok(())
+}
+
+// 盤面（色のマスと推測した単語）と、終わっていなければ推測のボタン
+pub(crate) fn play_reply(locale: Locale, view: &PlayView, kind: PlayKind) -> Reply {
+    let mut description = locale.play_header(view.guesses.len(), view.limit);
+    if matches!(kind, PlayKind::Daily { .. }) {
+        description = format!("{}\n{}", locale.text(Text::DailyIntro), description);
+    }
+    for (guess, row) in view.guesses.iter().zip(results::pattern_rows(&view.guesses)) {
+        description.push_str(&format!("\n{} `{}`", row, guess.word));
+    }
+    if !view.hints.used.is_empty() {
+        let hints: Vec<String> = view.hints.used.iter().map(|hint| locale.play_hint(hint)).collect();
+        description.push_str(&format!("\n\n{}", hints.join("\n")));
+    }
+    let footer = match &view.status {
+        PlayStatus::InProgress => None,
+        PlayStatus::Won => Some(locale.play_won(view.guesses.len())),
+        PlayStatus::Lost { answer } => Some(locale.play_lost(answer)),
+    };
+    if let Some(footer) = footer {
+        description.push_str(&format!("\n\n{}", footer));
+    }
+    // 得点はヒントを使える /wordle だけに表示する
+    if let (PlayKind::Free, Some(score)) = (kind, view.score()) {
+        description.push_str(&format!("\n{}", locale.play_score(score, view.hints.penalty())));
+    }
+
+    let embed = CreateEmbed::new()
+        .title(kind.title())
+        .color(Colour::DARK_GREEN)
+        .description(description);
+    let reply = Reply::embed(embed);
+    if view.status != PlayStatus::InProgress {
+        return reply.clear_components();
+    }
+
+    let mut buttons = vec![
+        CreateButton::new(CustomId::new(view.owner, view.revision, kind.guess_action(view)).encode())
+            .label(locale.text(Text::PlayGuessButton))
+            .style(ButtonStyle::Primary),
+    ];
+    // デイリーはメンバーどうしで結果を比べるため、ヒントは出さない
+    if let (PlayKind::Free, Some(tier)) = (kind, view.next_hint) {
+        buttons.push(
+            CreateButton::new(CustomId::new(view.owner, view.revision, Action::PlayHint { game: view.game }).encode())
+                .label(locale.play_hint_button(tier.cost()))
+                .style(ButtonStyle::Secondary),
+        );
+    }
+    reply.components(vec![CreateActionRow::Buttons(buttons)])
+}
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("wordle").add_option(
+        CreateCommandOption::new(CommandOptionType::String, "limit", "推測できる回数（省略すると6回）")
+            .add_string_choice("classic（6回）", GuessLimit::Classic.code())
+            .add_string_choice("relaxed（8回）", GuessLimit::Relaxed.code())
+            .add_string_choice("brutal（4回）", GuessLimit::Brutal.code()),
+    )
+}
+
+impl Bot {
+    // 単語リストから答えを選ぶ（よく使われる単語があればその中から。pick は候補の数から番号を選ぶ）
+    // 答えの候補（禁止された単語を除き、よく使われる単語があればその中から）
+    pub(crate) async fn answer_pool(&self, guild_id: Option<u64>) -> Vec<String> {
+        let Some(arena) = self.word_cache.read().await.words_of_length(solver::WORD_LENGTH).cloned() else {
+            return Vec::new();
+        };
+        let settings = self.settings();
+        let allowed: Vec<&str> = arena.iter().map(|(_, word)| word).filter(|word| !settings.is_banned(guild_id, word)).collect();
+        let common: Vec<&str> = allowed.iter().copied().filter(|word| solver::is_common_word(word)).collect();
+        let answers = if common.is_empty() { allowed } else { common };
+        answers.into_iter().map(str::to_string).collect()
+    }
+
+    pub(crate) async fn pick_answer(&self, guild_id: Option<u64>, pick: impl FnOnce(usize) -> usize) -> Option<SecretWord> {
+        let answers = self.answer_pool(guild_id).await;
+        if answers.is_empty() {
+            return None;
+        }
+        Some(SecretWord::new(&answers[pick(answers.len()) % answers.len()]))
+    }
+
+    // 答えを選んでゲームを始める
+    pub async fn start_play(&self, user_id: u64, guild_id: Option<u64>, limit: GuessLimit) -> Option<PlayView> {
+        let secret = self.pick_answer(guild_id, |count| RandomState::new().hash_one(SystemTime::now()) as usize % count).await?;
+        let previous = self.play_games.view(user_id).map(|view| view.game);
+        let view = self.play_games.start(user_id, secret, self.next_revision(), limit.guesses());
+        // 置き換えた前のゲームはもう続けられないため、保存したものも消す
+        if let Some(previous) = previous {
+            self.forget_play_game(previous).await;
+        }
+        self.save_play_game(user_id).await;
+        Some(view)
+    }
+
+    // 再起動などでメモリにないゲームを、ボタンのゲームIDからストレージより読み込む
+    // （そのユーザーのゲームがメモリにあれば何もしない。古いボタンはリビジョンが合わず Stale になる）
+    pub async fn resume_play(&self, user_id: u64, game: u64) {
+        if self.play_games.view(user_id).is_some() {
+            return;
+        }
+        let stored = match self.storage.load_play_game(game).await {
+            Ok(Some(value)) => serde_json::from_value::<StoredPlayGame>(value),
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to load playable game {} of {}: {:?}", game, user_id, e);
+                return;
+            }
+        };
+        match stored {
+            Ok(stored) if stored.owner == user_id => {
+                if self.play_games.restore(game, stored) {
+                    info!("Restored playable game {} of {}", game, user_id);
+                }
+            }
+            Ok(_) => warn!("Playable game {} does not belong to {}", game, user_id),
+            Err(e) => warn!("Failed to parse playable game {}: {}", game, e),
+        }
+    }
+
+    async fn save_play_game(&self, user_id: u64) {
+        save_game(&self.play_games, self.storage.as_ref(), user_id).await;
+    }
+
+    async fn forget_play_game(&self, game: u64) {
+        if let Err(e) = self.storage.delete_play_game(game).await {
+            warn!("Failed to delete playable game {}: {:?}", game, e);
+        }
+    }
+
+    // 推測が単語リストにある5文字の英単語か
+    pub(crate) async fn is_known_play_word(&self, word: &str) -> bool {
+        solver::is_valid_word(word)
+            && self.word_cache.read().await
+                .words_of_length(solver::WORD_LENGTH)
+                .is_some_and(|arena| arena.iter().any(|(_, w)| w == word))
+    }
+
+    pub async fn play_hint(&self, user_id: u64, revision: u64) -> Result<PlayView, PlayError> {
+        let view = self.play_games.hint(user_id, revision, self.next_revision())?;
+        self.save_play_game(user_id).await;
+        Ok(view)
+    }
+
+    // 推測を受け付ける（単語リストにない単語は推測に数えない）
+    pub async fn play_guess(&self, user_id: u64, guild_id: Option<u64>, revision: u64, word: &str) -> Result<PlayView, PlayError> {
+        let word = word.trim().to_uppercase();
+        if !self.is_known_play_word(&word).await {
+            return Err(PlayError::UnknownWord);
+        }
+        let view = self.play_games.guess(user_id, revision, &word, self.next_revision())?;
+        if view.status == PlayStatus::InProgress {
+            self.save_play_game(user_id).await;
+        } else {
+            self.forget_play_game(view.game).await;
+        }
+        self.record_play_result(user_id, guild_id, &view).await;
+        Ok(view)
+    }
+
+    // 終わったゲームは成績に記録する（記録に失敗しても盤面は進める）
+    pub(crate) async fn record_play_result(&self, user_id: u64, guild_id: Option<u64>, view: &PlayView) {
+        let recorded = match view.status {
+            PlayStatus::InProgress => Ok(None),
+            PlayStatus::Won => self.record_win(user_id, guild_id, view.guesses.len(), view.limit).await,
+            PlayStatus::Lost { .. } => self.record_loss(user_id, guild_id).await,
+        };
+        if let Err(e) = recorded {
+            warn!("Failed to record the result of a playable game for {}: {:?}", user_id, e);
+        }
+    }
+
+    pub(crate) async fn handle_wordle_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let user_id = command.user.id.get();
+        let limit = command.data.options().iter()
+            .find_map(|o| match o.value {
+                ResolvedValue::String(code) if o.name == "limit" => GuessLimit::parse(code),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let guild_id = command.guild_id.map(|id| id.get());
+        let Some(view) = self.start_play(user_id, guild_id, limit).await else {
+            self.responder(ctx, command, ReplyMode::NewMessage).send(Reply::content(locale.text(Text::PlayNoWords))).await;
+            return;
+        };
+        info!("Started a playable game for {}", user_id);
+        if !self.responder(ctx, command, ReplyMode::NewMessage).send(play_reply(locale, &view, PlayKind::Free)).await {
+            return;
+        }
+
+        // 自動のヒントで更新できるよう、盤面のメッセージを覚えておく（取れなければ次の操作のときに覚える）
+        if self.settings().auto_hint_minutes == 0 {
+            return;
+        }
+        match command.get_response(ctx.http()).await {
+            Ok(message) => {
+                let board = PlayBoard { channel: message.channel_id.get(), message: message.id.get(), guild: guild_id };
+                self.play_games.set_board(user_id, view.game, board);
+                self.save_play_game(user_id).await;
+            }
+            Err(e) => warn!("Could not fetch the playable game board of {}: {:?}", user_id, e),
+        }
+    }
+
+    pub(crate) async fn open_play_modal(&self, ctx: &ReplyContext, component: &ComponentInteraction, locale: Locale, id: CustomId) {
+        let word_input = CreateInputText::new(InputTextStyle::Short, "word", locale.text(Text::WordModalTitle))
+            .placeholder(locale.text(Text::WordInputPlaceholder))
+            .min_length(5)
+            .max_length(5)
+            .required(true);
+
+        let modal = CreateModal::new(CustomId::new(id.owner, id.revision, modal_action(id.action)).encode(), locale.text(Text::PlayGuessButton))
+            .components(vec![CreateActionRow::InputText(word_input)]);
+
+        self.responder(ctx, component, ReplyMode::UpdateMessage)
+            .open_modal(modal)
+            .await;
+    }
+
+    // ヒントを使って盤面を更新する（使えなければ本人にだけ理由を伝える）
+    pub(crate) async fn use_play_hint(&self, ctx: &ReplyContext, component: &ComponentInteraction, locale: Locale, id: CustomId, game: u64) {
+        self.resume_play(id.owner, game).await;
+        let board = PlayBoard { channel: component.channel_id.get(), message: component.message.id.get(), guild: component.guild_id.map(|g| g.get()) };
+        self.play_games.set_board(id.owner, game, board);
+        match self.play_hint(id.owner, id.revision).await {
+            Ok(view) => {
+                self.responder(ctx, component, ReplyMode::UpdateMessage)
+                    .send(play_reply(locale, &view, PlayKind::Free))
+                    .await;
+            }
+            Err(e) => {
+                self.responder(ctx, component, ReplyMode::Ephemeral)
+                    .send(Reply::content(locale.play_error(e)))
+                    .await;
+            }
+        }
+    }
+
+    // 推測の色を判定して盤面を更新する（受け付けられない推測は本人にだけ理由を伝える）
+    pub(crate) async fn submit_play_guess(&self, ctx: &ReplyContext, modal: &ModalInteraction, locale: Locale, id: CustomId, game: u64, word: &str) {
+        self.resume_play(id.owner, game).await;
+        if let Some(message) = &modal.message {
+            let board = PlayBoard { channel: modal.channel_id.get(), message: message.id.get(), guild: modal.guild_id.map(|g| g.get()) };
+            self.play_games.set_board(id.owner, game, board);
+        }
+        match self.play_guess(id.owner, modal.guild_id.map(|g| g.get()), id.revision, word).await {
+            Ok(view) => {
+                self.responder(ctx, modal, ReplyMode::UpdateMessage)
+                    .send(play_reply(locale, &view, PlayKind::Free))
+                    .await;
+            }
+            Err(e) => {
+                self.responder(ctx, modal, ReplyMode::Ephemeral)
+                    .send(Reply::content(locale.play_error(e)))
+                    .await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::MAX_GUESSES;
+
+    #[test]
+    fn guesses_are_colored_and_the_game_ends_on_a_win() {
+        let games = PlayGames::default();
+        games.start(1, SecretWord::new("CRANE"), 10, MAX_GUESSES);
+
+        let view = games.guess(1, 10, "react", 11).unwrap();
+        use LetterResult::*;
+        assert_eq!(view.guesses[0].results, [Yellow, Yellow, Green, Yellow, Gray]);
+        assert_eq!(view.status, PlayStatus::InProgress);
+        assert_eq!(games.guess(1, 10, "CRANE", 12), Err(PlayError::Stale));
+
+        let view = games.guess(1, 11, "CRANE", 12).unwrap();
+        assert_eq!(view.status, PlayStatus::Won);
+        assert_eq!(games.guess(1, 12, "SLATE", 13), Err(PlayError::Finished));
+        assert_eq!(games.guess(2, 0, "SLATE", 13), Err(PlayError::NotFound));
+    }
+
+    #[test]
+    fn hints_are_shown_on_the_board_and_lower_the_score() {
+        let games = PlayGames::default();
+        let view = games.start(1, SecretWord::new("TRUCK"), 0, MAX_GUESSES);
+        assert_eq!(view.next_hint, Some(HintTier::Absent));
+
+        games.guess(1, 0, "CRANE", 1).unwrap();
+        let view = games.hint(1, 1, 2).unwrap();
+        assert_eq!(view.hints.used.len(), 1);
+        assert_eq!(view.next_hint, Some(HintTier::Present));
+        assert_eq!(games.hint(1, 1, 3), Err(PlayError::Stale));
+
+        // 2回目で正解: 10 + 残り4回 × 2 - ヒント1点
+        let view = games.guess(1, 2, "TRUCK", 3).unwrap();
+        assert_eq!(view.score(), Some(17));
+        assert_eq!(view.next_hint, None);
+        assert_eq!(games.hint(1, 3, 4), Err(PlayError::Finished));
+    }
+
+    #[test]
+    fn the_answer_is_revealed_after_the_last_guess() {
+        let games = PlayGames::default();
+        games.start(1, SecretWord::new("CRANE"), 0, MAX_GUESSES);
+
+        for revision in 0..MAX_GUESSES as u64 - 1 {
+            assert_eq!(games.guess(1, revision, "MOIST", revision + 1).unwrap().status, PlayStatus::InProgress);
+        }
+        let view = games.guess(1, MAX_GUESSES as u64 - 1, "MOIST", MAX_GUESSES as u64).unwrap();
+        assert_eq!(view.status, PlayStatus::Lost { answer: "CRANE".to_string() });
+    }
+
+    #[test]
+    fn the_guess_limit_ends_the_game_and_sets_the_spare_points() {
+        let games = PlayGames::default();
+        games.start(1, SecretWord::new("CRANE"), 0, GuessLimit::Brutal.guesses());
+        for revision in 0..3 {
+            games.guess(1, revision, "MOIST", revision + 1).unwrap();
+        }
+        assert!(matches!(games.guess(1, 3, "MOIST", 4).unwrap().status, PlayStatus::Lost { .. }));
+
+        // 8回なら7回外しても続けられ、1回目で当てれば残り7回分の加点
+        games.start(2, SecretWord::new("CRANE"), 0, GuessLimit::Relaxed.guesses());
+        for revision in 0..7 {
+            assert_eq!(games.guess(2, revision, "MOIST", revision + 1).unwrap().status, PlayStatus::InProgress);
+        }
+        games.start(3, SecretWord::new("CRANE"), 0, GuessLimit::Relaxed.guesses());
+        assert_eq!(games.guess(3, 0, "CRANE", 1).unwrap().score(), Some(WIN_POINTS + 7 * POINTS_PER_SPARE_GUESS));
+    }
+
+    #[test]
+    fn stored_games_are_restored_only_when_fresh_and_missing() {
+        let games = PlayGames::default();
+        games.start(1, SecretWord::new("CRANE"), 5, MAX_GUESSES);
+        games.guess(1, 5, "MOIST", 6).unwrap();
+        let (id, stored) = games.stored(1).unwrap();
+        assert_eq!((id, stored.revision, stored.answer.as_str()), (5, 6, "CRANE"));
+
+        // メモリにゲームがあれば上書きしない
+        assert!(!games.restore(id, stored.clone()));
+
+        let restarted = PlayGames::default();
+        let expired = StoredPlayGame { started_at: unix_secs(SystemTime::now()) - GAME_TTL.as_secs() - 1, ..stored.clone() };
+        assert!(!restarted.restore(id, expired));
+        assert!(restarted.restore(id, stored));
+        let view = restarted.guess(1, 6, "CRANE", 7).unwrap();
+        assert_eq!((view.game, view.guesses.len(), view.status), (5, 2, PlayStatus::Won));
+    }
+
+    #[test]
+    fn idle_games_with_a_board_get_the_next_hint_without_a_new_revision() {
+        let games = PlayGames::default();
+        games.start(1, SecretWord::new("TRUCK"), 0, MAX_GUESSES);
+        games.start(2, SecretWord::new("CRANE"), 10, MAX_GUESSES);
+        let board = PlayBoard { channel: 3, message: 4, guild: None };
+        games.set_board(1, 0, board);
+        // 置き換えられた前のゲームIDでは記録しない
+        games.set_board(2, 9, board);
+
+        assert!(games.take_idle_hints(Duration::from_secs(60)).is_empty());
+        let hinted = games.take_idle_hints(Duration::ZERO);
+        assert_eq!(hinted.len(), 1);
+        let (view, hinted_board) = &hinted[0];
+        assert_eq!((view.owner, view.revision, view.hints.used.len(), *hinted_board), (1, 0, 1, board));
+        assert_eq!(games.stored(1).unwrap().1.board, Some(board));
+
+        // 盤面のボタンはそのまま使え、自動のヒントも得点から引く
+        let view = games.guess(1, 0, "TRUCK", 1).unwrap();
+        assert_eq!(view.score(), Some(WIN_POINTS + 5 * POINTS_PER_SPARE_GUESS - view.hints.penalty()));
+        assert!(view.hints.penalty() > 0);
+        assert!(games.take_idle_hints(Duration::ZERO).is_empty());
+    }
+}
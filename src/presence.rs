@@ -0,0 +1,57 @@
+// ボットのステータス（アクティビティ）に進行中のセッション数を表示する
+// Gateway接続時だけ使える（Webhookモードではシャードがないため何もしない）
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use arc_swap::ArcSwap;
+use serenity::all::{ActivityData, ShardMessenger};
+use tracing::info;
+
+use crate::settings::Settings;
+use crate::SessionMap;
+
+#[derive(Default)]
+pub struct Presence {
+    // シャードごとの送信先（ready で登録する）
+    shards: Mutex<HashMap<u32, ShardMessenger>>,
+    // 最後に設定した文言（変わらなければ送らない）
+    current: Mutex<Option<String>>,
+}
+
+impl Presence {
+    pub fn attach(&self, shard_id: u32, shard: ShardMessenger) {
+        self.shards.lock().unwrap_or_else(|e| e.into_inner()).insert(shard_id, shard);
+        // 再接続したシャードにも送り直す
+        *self.current.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    // 文言が変わったときだけ全シャードに設定する
+    pub fn set(&self, text: String) -> bool {
+        let shards = self.shards.lock().unwrap_or_else(|e| e.into_inner());
+        if shards.is_empty() {
+            return false;
+        }
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        if current.as_deref() == Some(text.as_str()) {
+            return false;
+        }
+
+        for shard in shards.values() {
+            shard.set_activity(Some(ActivityData::custom(text.clone())));
+        }
+        info!("Updated presence: {}", text);
+        *current = Some(text);
+        true
+    }
+}
+
+// 進行中のセッション数からステータスを更新する（言語は既定の言語）
+pub async fn refresh_presence(
+    presence: Arc<Presence>,
+    game_states: SessionMap,
+    settings: Arc<ArcSwap<Settings>>,
+) -> anyhow::Result<()> {
+    let sessions = game_states.read().await.len();
+    presence.set(settings.load().default_locale.presence(sessions));
+    Ok(())
+}
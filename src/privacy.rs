@@ -0,0 +1,56 @@
+// /privacy: 成績を記録・公開しない設定（ランキング・結果の投稿・ロール報酬からも外す）
+// 盤面の操作や提案はそのまま使える
+use serenity::all::{CommandInteraction, CommandOptionType, CreateCommand, CreateCommandOption, ResolvedValue};
+
+use crate::i18n::{self, Text};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::Bot;
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("privacy").add_option(CreateCommandOption::new(
+        CommandOptionType::Boolean,
+        "private",
+        "成績を記録・公開しない（省略すると現在の設定を表示）",
+    ))
+}
+
+impl Bot {
+    // 成績を記録しない設定を変更する（変更がなければ保存しない）
+    pub async fn set_private(&self, user_id: u64, private: bool) -> anyhow::Result<()> {
+        self.update_settings(|settings| {
+            let changed = if private {
+                settings.private_users.insert(user_id)
+            } else {
+                settings.private_users.remove(&user_id)
+            };
+            changed.then_some(())
+        })
+        .await
+        .map(|_| ())
+    }
+
+    pub(crate) async fn handle_privacy_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let user_id = command.user.id.get();
+        let private = command.data.options().into_iter().find_map(|o| match o.value {
+            ResolvedValue::Boolean(value) if o.name == "private" => Some(value),
+            _ => None,
+        });
+
+        let content = match private {
+            Some(private) => match self.set_private(user_id, private).await {
+                Ok(()) => locale.text(if private { Text::PrivacyEnabled } else { Text::PrivacyDisabled }),
+                Err(e) => {
+                    self.reporter.report("Failed to save privacy setting", &e).await;
+                    locale.text(Text::GenericError)
+                }
+            },
+            None if self.settings().is_private(user_id) => locale.text(Text::PrivacyEnabled),
+            None => locale.text(Text::PrivacyDisabled),
+        };
+
+        self.responder(ctx, command, ReplyMode::Ephemeral)
+            .send(Reply::content(content))
+            .await;
+    }
+}
@@ -0,0 +1,250 @@
+// /qht: Quordle（4つの盤面を同じ推測で同時に解く）の手助け
+// 推測と4つの盤面の結果をまとめて入力すると、盤面ごとに候補を絞り込み、
+// まだ解けていない盤面に対する情報量の合計が最も大きい単語を提案する
+// /solve と同じく毎回まとめて入力する形式のため、セッションは作らない
+use std::sync::Arc;
+
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, CreateCommand, CreateCommandOption, CreateEmbed, ResolvedValue};
+use tracing::{info, warn};
+
+use crate::i18n::{self, Locale, Text};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::results;
+use crate::solver::{self, LetterResult, WordleGuess};
+use crate::Bot;
+
+pub const BOARDS: usize = 4;
+
+// Quordle の推測回数
+const MAX_GUESSES: usize = 9;
+
+const SUGGESTIONS: usize = 5;
+
+// 盤面ごとに表示する候補の数
+const SHOWN_CANDIDATES: usize = 6;
+
+// 解けたあとの盤面の行
+const BLANK_ROW: &str = "▫️▫️▫️▫️▫️";
+
+// 入力した推測と、盤面ごとの結果（盤面が解けたあとの推測は含めない）
+#[derive(Debug, Clone)]
+pub struct QuordleHistory {
+    pub words: Vec<String>,
+    pub boards: [Vec<WordleGuess>; BOARDS],
+}
+
+// 盤面ごとの絞り込みの結果
+#[derive(Debug, Clone)]
+pub struct QuordleBoard {
+    // 解けていれば答え
+    pub solved: Option<String>,
+    pub candidates: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuordleView {
+    pub history: QuordleHistory,
+    pub boards: Vec<QuordleBoard>,
+    // おすすめの単語と、まだ解けていない盤面に対する情報量の合計（ビット）
+    pub suggestions: Vec<(String, f64)>,
+}
+
+fn is_solved(guesses: &[WordleGuess]) -> bool {
+    guesses.last().is_some_and(|g| g.results.iter().all(|r| *r == LetterResult::Green))
+}
+
+impl QuordleHistory {
+    // 「単語 結果1 結果2 結果3 結果4」をカンマ・セミコロン・改行で区切って並べたものを読む
+    // 解けた盤面の結果は読まない（_ などを書いておけばよい）。読めなかったときは、その部分を返す
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let entries: Vec<&str> = input.split([',', ';', '\n']).map(str::trim).filter(|e| !e.is_empty()).collect();
+        if entries.is_empty() || entries.len() > MAX_GUESSES {
+            return Err(input.trim().to_string());
+        }
+
+        let mut history = Self { words: Vec::new(), boards: Default::default() };
+        for entry in entries {
+            let parts: Vec<&str> = entry.split(|c: char| c == ':' || c.is_whitespace()).filter(|p| !p.is_empty()).collect();
+            let [word, patterns @ ..] = parts.as_slice() else {
+                return Err(entry.to_string());
+            };
+            if patterns.len() != BOARDS {
+                return Err(entry.to_string());
+            }
+            for (board, pattern) in history.boards.iter_mut().zip(patterns) {
+                if is_solved(board) {
+                    continue;
+                }
+                let guess = WordleGuess::parse(word, pattern)
+                    .filter(|guess| solver::is_valid_word(&guess.word))
+                    .ok_or_else(|| entry.to_string())?;
+                board.push(guess);
+            }
+            history.words.push(word.to_uppercase());
+        }
+        Ok(history)
+    }
+}
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("qht").add_option(
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "guesses",
+            "推測と4つの盤面の結果（例: crane BYXXG GBBBY XXYXX YBBBG。解けた盤面は _）",
+        )
+        .required(true),
+    )
+}
+
+fn board_field(locale: Locale, board: &QuordleBoard) -> String {
+    if let Some(answer) = &board.solved {
+        return format!("✅ **{}**", answer);
+    }
+    if board.candidates.is_empty() {
+        return locale.text(Text::NoCandidates).to_string();
+    }
+    let mut shown = board.candidates.iter().take(SHOWN_CANDIDATES).map(String::as_str).collect::<Vec<_>>().join(", ");
+    if board.candidates.len() > SHOWN_CANDIDATES {
+        shown.push_str(", …");
+    }
+    format!("{}\n{}", locale.remaining(board.candidates.len()), shown)
+}
+
+// 4つの盤面の色のマスを推測ごとに横に並べ、盤面ごとの候補とおすすめを添える
+fn quordle_embed(locale: Locale, view: &QuordleView) -> CreateEmbed {
+    let rows: Vec<Vec<String>> = view.history.boards.iter().map(|board| results::pattern_rows(board)).collect();
+    let mut description: Vec<String> = view.history.words.iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let cells: Vec<&str> = rows.iter().map(|rows| rows.get(i).map_or(BLANK_ROW, String::as_str)).collect();
+            format!("`{}` {}", word, cells.join("  "))
+        })
+        .collect();
+    if view.boards.iter().all(|board| board.solved.is_some()) {
+        description.push(format!("\n{}", locale.text(Text::QuordleSolved)));
+    }
+
+    let mut embed = CreateEmbed::new()
+        .title("🟩 Quordle Helper")
+        .color(Colour::DARK_GREEN)
+        .description(description.join("\n"));
+    for (i, board) in view.boards.iter().enumerate() {
+        embed = embed.field(locale.quordle_board(i + 1), board_field(locale, board), true);
+    }
+    if !view.suggestions.is_empty() {
+        let lines: Vec<String> = view.suggestions.iter()
+            .enumerate()
+            .map(|(i, (word, bits))| format!("{}. **{}**{}", i + 1, word, locale.quordle_bits(*bits)))
+            .collect();
+        embed = embed.field(locale.text(Text::Suggestions), lines.join("\n"), false);
+    }
+    embed
+}
+
+impl Bot {
+    // 盤面ごとに候補を絞り込み、まだ解けていない盤面をまとめて分けられる単語を提案する（単語リストがなければ Err）
+    pub async fn quordle(&self, guild_id: Option<u64>, history: QuordleHistory) -> anyhow::Result<QuordleView> {
+        let settings = self.settings();
+        let banned = settings.has_banned_words(guild_id);
+        let (arena, candidates) = {
+            let words = self.word_cache.read().await;
+            let arena = words.words_of_length(solver::WORD_LENGTH).cloned()
+                .ok_or_else(|| anyhow::anyhow!("no {}-letter words are loaded", solver::WORD_LENGTH))?;
+            let candidates: Vec<_> = history.boards.iter()
+                .map(|board| {
+                    let candidates = words.candidate_ids(board);
+                    if banned { candidates.without(|word| settings.is_banned(guild_id, word)) } else { candidates }
+                })
+                .collect();
+            (arena, candidates)
+        };
+
+        let boards: Vec<QuordleBoard> = history.boards.iter()
+            .zip(&candidates)
+            .map(|(guesses, candidates)| QuordleBoard {
+                solved: is_solved(guesses).then(|| guesses.last().map(|g| g.word.clone()).unwrap_or_default()),
+                candidates: candidates.to_strings(),
+            })
+            .collect();
+
+        // 解けた盤面と候補のない盤面は除いて、残りの盤面の情報量を合計する
+        let unsolved: Vec<_> = boards.iter()
+            .zip(candidates)
+            .filter(|(board, candidates)| board.solved.is_none() && !candidates.is_empty())
+            .map(|(_, candidates)| candidates.ids)
+            .collect();
+        let suggestions = if unsolved.is_empty() {
+            Vec::new()
+        } else {
+            let probes: Vec<solver::WordId> = arena.iter()
+                .filter(|(_, word)| !banned || !settings.is_banned(guild_id, word))
+                .map(|(id, _)| id)
+                .collect();
+            let scoring_arena = Arc::clone(&arena);
+            let ranked = tokio::task::spawn_blocking(move || {
+                let boards: Vec<&[solver::WordId]> = unsolved.iter().map(|ids| ids.as_slice()).collect();
+                solver::top_probes_for_boards(&scoring_arena, &probes, &boards, SUGGESTIONS)
+            })
+            .await?;
+            ranked.into_iter().map(|r| (arena.word(r.id).to_string(), r.score)).collect()
+        };
+
+        Ok(QuordleView { history, boards, suggestions })
+    }
+
+    pub(crate) async fn handle_qht_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let input = command.data.options().into_iter()
+            .find_map(|o| match o.value {
+                ResolvedValue::String(value) if o.name == "guesses" => Some(value),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let history = match QuordleHistory::parse(input) {
+            Ok(history) => history,
+            Err(entry) => {
+                self.responder(ctx, command, ReplyMode::Ephemeral)
+                    .send(Reply::content(locale.quordle_invalid_entry(&entry)))
+                    .await;
+                return;
+            }
+        };
+
+        // 提案の計算は時間がかかることがあるため自動で保留される
+        self.responder(ctx, command, ReplyMode::NewMessage)
+            .run(async {
+                info!("Solving {} Quordle guesses for {}", history.words.len(), command.user.id);
+                match self.quordle(command.guild_id.map(|g| g.get()), history).await {
+                    Ok(view) => Reply::embed(quordle_embed(locale, &view)),
+                    Err(e) => {
+                        warn!("Failed to suggest Quordle guesses: {:?}", e);
+                        Reply::content(locale.text(Text::SuggestionFailed))
+                    }
+                }
+            })
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LetterResult::{Gray, Green, Yellow};
+
+    #[test]
+    fn parses_four_boards_and_skips_solved_ones() {
+        let history = QuordleHistory::parse("crane BYXXG GGGGG XXXXX YXXXX\nmoist:XXGYB:_:GXXXX:XXXXY").unwrap();
+        assert_eq!(history.words, ["CRANE", "MOIST"]);
+        assert_eq!(history.boards[0].len(), 2);
+        assert_eq!(history.boards[0][0].results, [Gray, Yellow, Gray, Gray, Green]);
+        // 2つ目の盤面は CRANE で解けたので、MOIST の結果は読まない
+        assert_eq!(history.boards[1].len(), 1);
+        assert_eq!(history.boards[2][1].results, [Green, Gray, Gray, Gray, Gray]);
+
+        assert_eq!(QuordleHistory::parse("crane BYXXG GGGGG XXXXX").unwrap_err(), "crane BYXXG GGGGG XXXXX");
+        assert_eq!(QuordleHistory::parse("crane BYXXG _ XXXXX YXXXX").unwrap_err(), "crane BYXXG _ XXXXX YXXXX");
+        assert!(QuordleHistory::parse(&["crane XXXXX XXXXX XXXXX XXXXX"; 10].join(",")).is_err());
+    }
+}
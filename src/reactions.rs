@@ -0,0 +1,303 @@
+// リアクションでの色の入力（ボタンを使えないクライアント向け）
+// /guess で単語を入力すると、ボタンのない盤面に ⬜🟨🟩 をつけ、本人のリアクションを左の文字から順に色として扱う
+// リアクションのイベントはGateway接続でだけ届く（Webhookモードでは使えない）
+// 単語は今のゲームの残りの候補から自動補完する（打ち間違いを防ぎ、使える単語を知ってもらう）
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serenity::all::{
+    AutocompleteChoice,
+    CommandInteraction,
+    CommandOptionType,
+    CreateCommandOption,
+    CreateCommand,
+    CreateAutocompleteResponse,
+    Http,
+    MessageId,
+    Reaction,
+    ReactionType,
+    ResolvedValue,
+};
+use tracing::{debug, info, warn};
+
+use crate::define::MAX_AUTOCOMPLETE_CHOICES;
+use crate::i18n::{self, Locale};
+use crate::respond::{Reply, ReplyContext, ReplyMode, Responder};
+use crate::session::{BoardControls, BoardView, SessionError};
+use crate::{solver, Bot, LetterResult};
+
+// 応答の編集に使うトークンの有効期限（これを過ぎた入力待ちは捨てる）
+const PROMPT_TTL: Duration = Duration::from_secs(15 * 60);
+
+// 盤面につけるリアクション（灰色はダークテーマの ⬛ も受け付ける）
+const REACTIONS: [(&str, LetterResult); 3] = [
+    ("⬜", LetterResult::Gray),
+    ("🟨", LetterResult::Yellow),
+    ("🟩", LetterResult::Green),
+];
+
+fn reaction_result(emoji: &ReactionType) -> Option<LetterResult> {
+    let ReactionType::Unicode(emoji) = emoji else {
+        return None;
+    };
+    match emoji.as_str() {
+        "⬛" => Some(LetterResult::Gray),
+        emoji => REACTIONS.iter().find(|(e, _)| *e == emoji).map(|(_, result)| *result),
+    }
+}
+
+// リアクションを待っている盤面
+struct ReactionPrompt {
+    owner: u64,
+    revision: u64,
+    // 次に色を設定する文字の位置
+    cursor: usize,
+    locale: Locale,
+    responder: Responder,
+    created: Instant,
+}
+
+#[derive(Default)]
+pub struct ReactionPrompts {
+    prompts: Mutex<HashMap<MessageId, Arc<tokio::sync::Mutex<ReactionPrompt>>>>,
+}
+
+impl ReactionPrompts {
+    fn insert(&self, message: MessageId, prompt: ReactionPrompt) {
+        let mut prompts = self.prompts.lock().unwrap_or_else(|e| e.into_inner());
+        // 期限切れの入力待ちはここで掃除する
+        prompts.retain(|_, prompt| prompt.try_lock().map_or(true, |p| p.created.elapsed() < PROMPT_TTL));
+        prompts.insert(message, Arc::new(tokio::sync::Mutex::new(prompt)));
+    }
+
+    fn get(&self, message: MessageId) -> Option<Arc<tokio::sync::Mutex<ReactionPrompt>>> {
+        self.prompts.lock().unwrap_or_else(|e| e.into_inner()).get(&message).cloned()
+    }
+
+    fn remove(&self, message: MessageId) {
+        self.prompts.lock().unwrap_or_else(|e| e.into_inner()).remove(&message);
+    }
+}
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("guess").add_option(
+        CreateCommandOption::new(CommandOptionType::String, "word", "推測した5文字の英単語")
+            .min_length(5)
+            .max_length(5)
+            .required(true)
+            .set_autocomplete(true),
+    )
+}
+
+impl Bot {
+    // 盤面にリアクションの案内をつけた応答（ボタンはつけない）
+    fn reaction_reply(&self, locale: Locale, mut view: BoardView, cursor: usize) -> Reply {
+        if let BoardControls::LetterResults { word, .. } | BoardControls::LetterMenu { word, .. } = &view.controls {
+            if let Some(letter) = word.chars().nth(cursor) {
+                view.description.push_str(&format!("\n\n{}", locale.reaction_prompt(cursor + 1, letter)));
+            }
+        }
+        self.board_reply(locale, Ok(view)).clear_components()
+    }
+
+    // 入力中の文字で始まる単語（ゲームがあれば残りの候補から、計算済みのおすすめを先に。なければ単語リスト全体から）
+    pub async fn guess_completions(&self, user_id: u64, prefix: &str) -> Vec<String> {
+        let prefix = prefix.trim().to_uppercase();
+        let Some(state) = self.session(user_id).await else {
+            let words = self.word_cache.read().await;
+            return words.words_of_length(solver::WORD_LENGTH).map_or_else(Vec::new, |arena| {
+                arena.iter()
+                    .map(|(_, word)| word)
+                    .filter(|word| word.starts_with(&prefix))
+                    .take(MAX_AUTOCOMPLETE_CHOICES)
+                    .map(str::to_string)
+                    .collect()
+            });
+        };
+
+        let candidates = {
+            let words = self.words_for(&state).await;
+            self.filter_words_by_constraints(&words, &state)
+        };
+        let suggested = state.suggestion_cache.get(&self.suggestion_key(&state, candidates.clone())).unwrap_or_default();
+        let mut completions: Vec<String> = suggested.into_iter().filter(|word| word.starts_with(&prefix)).collect();
+        for word in candidates.words().filter(|word| word.starts_with(&prefix)) {
+            if completions.len() >= MAX_AUTOCOMPLETE_CHOICES {
+                break;
+            }
+            if !completions.iter().any(|c| c == word) {
+                completions.push(word.to_string());
+            }
+        }
+        completions.truncate(MAX_AUTOCOMPLETE_CHOICES);
+        completions
+    }
+
+    pub(crate) async fn handle_guess_autocomplete(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let prefix = command.data.autocomplete().map_or(String::new(), |o| o.value.to_string());
+        let choices: Vec<AutocompleteChoice> = self.guess_completions(command.user.id.get(), &prefix).await
+            .into_iter()
+            .map(|word| AutocompleteChoice::new(word.clone(), word))
+            .collect();
+
+        self.responder(ctx, command, ReplyMode::NewMessage)
+            .autocomplete(CreateAutocompleteResponse::new().set_choices(choices))
+            .await;
+    }
+
+    pub(crate) async fn handle_guess_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let user_id = command.user.id.get();
+        let word = command.data.options().into_iter()
+            .find_map(|o| match o.value {
+                ResolvedValue::String(value) if o.name == "word" => Some(value.trim().to_uppercase()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        if !solver::is_valid_word(&word) {
+            self.responder(ctx, command, ReplyMode::Ephemeral)
+                .send(Reply::content(locale.text(i18n::Text::EvaluateInvalidWord)))
+                .await;
+            return;
+        }
+
+        // ゲームがなければ新しく始める
+        let revision = match self.session(user_id).await {
+            Some(state) => state.revision,
+            None => self.start_session(user_id, command.guild_id.map(|g| g.get())).await.revision,
+        };
+        if let Some(nearest) = self.unknown_word(user_id, &word).await {
+            self.responder(ctx, command, ReplyMode::Ephemeral)
+                .send(Reply::content(locale.unknown_word(&word, &nearest)))
+                .await;
+            return;
+        }
+        let view = match self.submit_word(user_id, revision, &word).await {
+            Ok(view) => view,
+            Err(e) => {
+                self.responder(ctx, command, ReplyMode::Ephemeral)
+                    .send(Reply::content(locale.session_error(e)))
+                    .await;
+                return;
+            }
+        };
+
+        let revision = view.revision;
+        let mut responder = self.responder(ctx, command, ReplyMode::NewMessage);
+        if !responder.send(self.reaction_reply(locale, view, 0)).await {
+            return;
+        }
+
+        let http = ctx.http();
+        let message = match command.get_response(http).await {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Could not fetch the reaction prompt for {}: {:?}", user_id, e);
+                return;
+            }
+        };
+        self.reaction_prompts.insert(message.id, ReactionPrompt {
+            owner: user_id,
+            revision,
+            cursor: 0,
+            locale,
+            responder,
+            created: Instant::now(),
+        });
+
+        for (emoji, _) in REACTIONS {
+            if let Err(e) = message.react(http, ReactionType::Unicode(emoji.to_string())).await {
+                warn!("Could not add reactions to the prompt for {}: {:?}", user_id, e);
+                break;
+            }
+        }
+        info!("Waiting for reaction colors from {}", user_id);
+    }
+
+    // 入力待ちの盤面への本人のリアクションを、次の文字の色として反映する
+    pub(crate) async fn handle_reaction(&self, http: &Arc<Http>, reaction: &Reaction) {
+        let Some(entry) = self.reaction_prompts.get(reaction.message_id) else {
+            return;
+        };
+        let Some(result) = reaction_result(&reaction.emoji) else {
+            return;
+        };
+        let mut prompt = entry.lock().await;
+        if reaction.user_id.map(|u| u.get()) != Some(prompt.owner) {
+            return;
+        }
+
+        // 同じ色を続けて押せるよう、本人のリアクションを外す（権限がなければ本人に外してもらう）
+        if let Err(e) = reaction.delete(http).await {
+            debug!("Could not remove reaction: {:?}", e);
+        }
+
+        let (owner, locale) = (prompt.owner, prompt.locale);
+        let view = match self.set_letter(owner, prompt.revision, prompt.cursor, result).await {
+            Ok(view) => view,
+            Err(e) => {
+                // ボタンなど別の操作で盤面が変わった場合は入力を終える
+                info!("Stopped reaction input for {}: {}", owner, e);
+                self.reaction_prompts.remove(reaction.message_id);
+                return;
+            }
+        };
+        prompt.revision = view.revision;
+        prompt.cursor += 1;
+
+        let length = match &view.controls {
+            BoardControls::LetterResults { word, .. } | BoardControls::LetterMenu { word, .. } => word.chars().count(),
+            _ => 0,
+        };
+        if prompt.cursor < length {
+            let reply = self.reaction_reply(locale, view, prompt.cursor);
+            prompt.responder.send(reply).await;
+            return;
+        }
+
+        // すべての文字の色が揃ったら確定する
+        self.reaction_prompts.remove(reaction.message_id);
+        let view = match self.confirm_guess(owner, prompt.revision).await {
+            Ok(view) => view,
+            Err(e) => {
+                info!("Could not confirm reaction input for {}: {}", owner, e);
+                return;
+            }
+        };
+        let finished = view.controls == BoardControls::Finished;
+        if finished {
+            if let Some(guild_id) = reaction.guild_id {
+                self.spawn_win_tasks(http, guild_id, owner);
+            }
+        }
+        let reply = self.board_reply(locale, Ok(view)).clear_components();
+        if !prompt.responder.send(reply).await || finished {
+            return;
+        }
+
+        match self.finish_analysis(owner).await {
+            Err(SessionError::Stale) => info!("Board changed during analysis; skipping update"),
+            Err(SessionError::AnalysisRunning) => info!("Analysis for {} is already running; skipping duplicate", owner),
+            view => {
+                let reply = self.board_reply(locale, view).clear_components();
+                prompt.responder.send(reply).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reactions_map_to_colors() {
+        let unicode = |e: &str| ReactionType::Unicode(e.to_string());
+        assert_eq!(reaction_result(&unicode("🟩")), Some(LetterResult::Green));
+        assert_eq!(reaction_result(&unicode("🟨")), Some(LetterResult::Yellow));
+        assert_eq!(reaction_result(&unicode("⬛")), Some(LetterResult::Gray));
+        assert_eq!(reaction_result(&unicode("👍")), None);
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use serenity::all::{ChannelId, Colour, CreateEmbed, CreateMessage, Http};
+use tracing::{error, warn};
+
+// 同じ内容のエラーを連続して投稿しない間隔
+const DUPLICATE_SUPPRESSION: Duration = Duration::from_secs(60);
+
+// 深刻なエラーをログに記録し、設定されていればエラーチャンネルにも投稿する
+pub struct ErrorReporter {
+    channel: Option<ChannelId>,
+    http: OnceLock<Arc<Http>>,
+    recent: tokio::sync::Mutex<HashMap<String, Instant>>,
+}
+
+impl ErrorReporter {
+    pub fn new(channel: Option<ChannelId>) -> Self {
+        Self {
+            channel,
+            http: OnceLock::new(),
+            recent: tokio::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    // 接続後にHTTPクライアントを登録（それまではログのみ）
+    pub fn attach(&self, http: Arc<Http>) {
+        let _ = self.http.set(http);
+    }
+
+    pub async fn report(&self, context: &str, error: &(dyn Display + Sync)) {
+        error!("{}: {}", context, error);
+
+        let (Some(channel), Some(http)) = (self.channel, self.http.get()) else {
+            return;
+        };
+
+        {
+            let mut recent = self.recent.lock().await;
+            let key = format!("{}: {}", context, error);
+            recent.retain(|_, reported_at| reported_at.elapsed() < DUPLICATE_SUPPRESSION);
+            if recent.contains_key(&key) {
+                return;
+            }
+            recent.insert(key, Instant::now());
+        }
+
+        let mut detail = error.to_string();
+        detail.truncate(detail.char_indices().nth(4000).map_or(detail.len(), |(i, _)| i));
+
+        let embed = CreateEmbed::new()
+            .title(format!("⚠️ {}", context))
+            .description(format!("```\n{}\n```", detail))
+            .color(Colour::RED)
+            .timestamp(serenity::all::Timestamp::now());
+
+        if let Err(e) = channel.send_message(http, CreateMessage::new().embed(embed)).await {
+            warn!("Failed to post error report to channel {}: {}", channel, e);
+        }
+    }
+}
@@ -0,0 +1,430 @@
+// インタラクションへの応答（即時応答・保留・後からの編集）を共通化する
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serenity::all::{
+    ChannelId,
+    CommandInteraction,
+    ComponentInteraction,
+    CreateAttachment,
+    CreateAutocompleteResponse,
+    CreateActionRow,
+    CreateEmbed,
+    CreateInteractionResponse,
+    CreateInteractionResponseFollowup,
+    CreateInteractionResponseMessage,
+    CreateModal,
+    EditInteractionResponse,
+    EditMessage,
+    Http,
+    MessageFlags,
+    MessageId,
+    ModalInteraction,
+};
+use serenity::http::HttpError;
+use tokio::sync::oneshot;
+use tracing::info;
+
+use crate::reporting::ErrorReporter;
+
+// 1つのインタラクションへの応答経路
+// ゲートウェイではすべてRESTで送り、Webhookでは最初の応答だけを受信中のHTTPリクエストへ返す
+#[derive(Clone)]
+pub struct ReplyContext {
+    http: Arc<Http>,
+    initial: Option<Arc<Mutex<Option<oneshot::Sender<CreateInteractionResponse>>>>>,
+}
+
+impl ReplyContext {
+    pub fn gateway(http: Arc<Http>) -> Self {
+        Self { http, initial: None }
+    }
+
+    #[cfg_attr(not(feature = "webhook"), allow(dead_code))]
+    pub fn webhook(http: Arc<Http>) -> (Self, oneshot::Receiver<CreateInteractionResponse>) {
+        let (sender, receiver) = oneshot::channel();
+        let context = Self {
+            http,
+            initial: Some(Arc::new(Mutex::new(Some(sender)))),
+        };
+        (context, receiver)
+    }
+
+    pub fn http(&self) -> &Arc<Http> {
+        &self.http
+    }
+
+    // まだHTTPレスポンスとして返していなければ、最初の応答として引き渡す
+    fn take_initial(&self) -> Option<oneshot::Sender<CreateInteractionResponse>> {
+        self.initial.as_ref()?.lock().ok()?.take()
+    }
+}
+
+// Discordの3秒制限に余裕を持って間に合うよう、これを超えたら先に保留応答を返す
+pub const DEFAULT_DEFER_THRESHOLD: Duration = Duration::from_millis(2000);
+
+// インタラクションのトークンが期限切れ（15分）のときのエラーコード
+const UNKNOWN_WEBHOOK: isize = 10015;
+const INVALID_WEBHOOK_TOKEN: isize = 50027;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyMode {
+    // 新しいメッセージで応答
+    NewMessage,
+    // 本人にだけ見えるメッセージで応答
+    Ephemeral,
+    // ボタンが付いている元のメッセージを書き換える
+    UpdateMessage,
+}
+
+// 応答の内容（未指定の項目は元のメッセージのまま）
+#[derive(Debug, Clone, Default)]
+pub struct Reply {
+    content: Option<String>,
+    embed: Option<CreateEmbed>,
+    components: Option<Vec<CreateActionRow>>,
+    attachments: Vec<CreateAttachment>,
+}
+
+impl Reply {
+    pub fn content(content: impl Into<String>) -> Self {
+        Self { content: Some(content.into()), ..Default::default() }
+    }
+
+    pub fn embed(embed: CreateEmbed) -> Self {
+        Self { embed: Some(embed), ..Default::default() }
+    }
+
+    // 空の場合は既存のボタンを残す
+    pub fn components(mut self, components: Vec<CreateActionRow>) -> Self {
+        if !components.is_empty() {
+            self.components = Some(components);
+        }
+        self
+    }
+
+    // ファイルを添付する
+    pub fn attachment(mut self, attachment: CreateAttachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    // 既存のボタンをすべて取り除く
+    pub fn clear_components(mut self) -> Self {
+        self.components = Some(Vec::new());
+        self
+    }
+
+    fn into_message(self, ephemeral: bool) -> CreateInteractionResponseMessage {
+        let mut message = CreateInteractionResponseMessage::new().ephemeral(ephemeral);
+        if let Some(content) = self.content {
+            message = message.content(content);
+        }
+        if let Some(embed) = self.embed {
+            message = message.embed(embed);
+        }
+        if let Some(components) = self.components {
+            message = message.components(components);
+        }
+        message.add_files(self.attachments)
+    }
+
+    fn into_followup(self, ephemeral: bool) -> CreateInteractionResponseFollowup {
+        let mut followup = CreateInteractionResponseFollowup::new().ephemeral(ephemeral);
+        if let Some(content) = self.content {
+            followup = followup.content(content);
+        }
+        if let Some(embed) = self.embed {
+            followup = followup.embed(embed);
+        }
+        if let Some(components) = self.components {
+            followup = followup.components(components);
+        }
+        followup.add_files(self.attachments)
+    }
+
+    fn into_edit(self) -> EditInteractionResponse {
+        let mut edit = EditInteractionResponse::new();
+        if let Some(content) = self.content {
+            edit = edit.content(content);
+        }
+        if let Some(embed) = self.embed {
+            edit = edit.embed(embed);
+        }
+        if let Some(components) = self.components {
+            edit = edit.components(components);
+        }
+        self.attachments.into_iter().fold(edit, |edit, attachment| edit.new_attachment(attachment))
+    }
+
+    pub(crate) fn into_message_edit(self) -> EditMessage {
+        let mut edit = EditMessage::new();
+        if let Some(content) = self.content {
+            edit = edit.content(content);
+        }
+        if let Some(embed) = self.embed {
+            edit = edit.embed(embed);
+        }
+        if let Some(components) = self.components {
+            edit = edit.components(components);
+        }
+        self.attachments.into_iter().fold(edit, |edit, attachment| edit.new_attachment(attachment))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Target {
+    Command(CommandInteraction),
+    Component(Box<ComponentInteraction>),
+    Modal(ModalInteraction),
+}
+
+impl From<&CommandInteraction> for Target {
+    fn from(interaction: &CommandInteraction) -> Self {
+        Target::Command(interaction.clone())
+    }
+}
+
+impl From<&ComponentInteraction> for Target {
+    fn from(interaction: &ComponentInteraction) -> Self {
+        Target::Component(Box::new(interaction.clone()))
+    }
+}
+
+impl From<&ModalInteraction> for Target {
+    fn from(interaction: &ModalInteraction) -> Self {
+        Target::Modal(interaction.clone())
+    }
+}
+
+// 最初の応答か後からの編集かを管理し、失敗はErrorReporterに送る
+// 所有型なのでバックグラウンドタスクへそのまま移動できる（応答済みのものは複製して編集に使える）
+#[derive(Clone)]
+pub struct Responder {
+    context: ReplyContext,
+    reporter: Arc<ErrorReporter>,
+    target: Target,
+    mode: ReplyMode,
+    acknowledged: bool,
+    threshold: Duration,
+}
+
+impl Responder {
+    pub fn new(context: ReplyContext, reporter: Arc<ErrorReporter>, target: Target, mode: ReplyMode) -> Self {
+        Self {
+            context,
+            reporter,
+            target,
+            mode,
+            acknowledged: false,
+            threshold: DEFAULT_DEFER_THRESHOLD,
+        }
+    }
+
+    async fn create(&self, response: CreateInteractionResponse) -> serenity::Result<()> {
+        if let Some(initial) = self.context.take_initial() {
+            // 受信側がタイムアウトしていれば、以降の編集は失敗として報告される
+            let _ = initial.send(response);
+            return Ok(());
+        }
+
+        let http = &self.context.http;
+        match &self.target {
+            Target::Command(i) => i.create_response(http, response).await,
+            Target::Component(i) => i.create_response(http, response).await,
+            Target::Modal(i) => i.create_response(http, response).await,
+        }
+    }
+
+    async fn edit(&self, reply: Reply) -> serenity::Result<()> {
+        let http = &self.context.http;
+        // トークンが切れたときに元のメッセージを直接編集できるよう、宛先があれば内容を残しておく
+        let fallback = self.board_message().map(|message| (message, reply.clone()));
+        let edit = reply.into_edit();
+        let result = match &self.target {
+            Target::Command(i) => i.edit_response(http, edit).await.map(|_| ()),
+            Target::Component(i) => i.edit_response(http, edit).await.map(|_| ()),
+            Target::Modal(i) => i.edit_response(http, edit).await.map(|_| ()),
+        };
+
+        match (result, fallback) {
+            (Err(e), Some(((channel, message), reply))) if is_token_expired(&e) => {
+                info!("Interaction token expired; editing message {} directly", message);
+                channel.edit_message(http, message, reply.into_message_edit()).await.map(|_| ())
+            }
+            (result, _) => result,
+        }
+    }
+
+    // ボタンが付いている元のメッセージ（本人にだけ見えるメッセージはボットから直接編集できない）
+    fn board_message(&self) -> Option<(ChannelId, MessageId)> {
+        if self.mode != ReplyMode::UpdateMessage {
+            return None;
+        }
+        let message = match &self.target {
+            Target::Component(i) => &i.message,
+            Target::Modal(i) => i.message.as_ref()?,
+            Target::Command(_) => return None,
+        };
+        if message.flags.is_some_and(|flags| flags.contains(MessageFlags::EPHEMERAL)) {
+            return None;
+        }
+        Some((message.channel_id, message.id))
+    }
+
+    // 応答を保留する（ユーザーには読み込み中の表示、または何も表示されない）
+    pub async fn defer(&mut self) -> bool {
+        if self.acknowledged {
+            return true;
+        }
+
+        let response = match self.mode {
+            ReplyMode::NewMessage => CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new()),
+            ReplyMode::Ephemeral => CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new().ephemeral(true)),
+            ReplyMode::UpdateMessage => CreateInteractionResponse::Acknowledge,
+        };
+
+        match self.create(response).await {
+            Ok(()) => {
+                self.acknowledged = true;
+                true
+            }
+            Err(why) => {
+                self.reporter.report("Cannot defer interaction", &why).await;
+                false
+            }
+        }
+    }
+
+    // 未応答なら応答し、応答済みなら元の応答を編集する
+    pub async fn send(&mut self, reply: Reply) -> bool {
+        let context = if self.acknowledged { "Cannot edit response" } else { "Cannot respond to interaction" };
+        match self.try_send(reply).await {
+            Ok(()) => true,
+            Err(why) => {
+                self.report(context, &why).await;
+                false
+            }
+        }
+    }
+
+    // send と同じだが、失敗を報告せずに返す（呼び出し側で再試行する場合）
+    pub async fn try_send(&mut self, reply: Reply) -> serenity::Result<()> {
+        if self.acknowledged {
+            return self.edit(reply).await;
+        }
+
+        let message = reply.into_message(self.mode == ReplyMode::Ephemeral);
+        let response = match self.mode {
+            ReplyMode::UpdateMessage => CreateInteractionResponse::UpdateMessage(message),
+            ReplyMode::NewMessage | ReplyMode::Ephemeral => CreateInteractionResponse::Message(message),
+        };
+        self.create(response).await?;
+        self.acknowledged = true;
+        Ok(())
+    }
+
+    pub async fn report(&self, context: &str, error: &serenity::Error) {
+        self.reporter.report(context, error).await;
+    }
+
+    // 応答済みのインタラクションに、本人にだけ見える追加のメッセージを送る
+    pub async fn follow_up(&self, reply: Reply) -> bool {
+        let http = &self.context.http;
+        let followup = reply.into_followup(true);
+        let result = match &self.target {
+            Target::Command(i) => i.create_followup(http, followup).await,
+            Target::Component(i) => i.create_followup(http, followup).await,
+            Target::Modal(i) => i.create_followup(http, followup).await,
+        };
+        match result {
+            Ok(_) => true,
+            Err(why) => {
+                self.reporter.report("Cannot send follow-up message", &why).await;
+                false
+            }
+        }
+    }
+
+    // 処理がしきい値を超えたら自動で保留し、完了後に編集で結果を返す
+    pub async fn run<F>(&mut self, work: F) -> bool
+    where
+        F: Future<Output = Reply>,
+    {
+        tokio::pin!(work);
+
+        if !self.acknowledged {
+            tokio::select! {
+                reply = &mut work => return self.send(reply).await,
+                _ = tokio::time::sleep(self.threshold) => {
+                    if !self.defer().await {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        let reply = work.await;
+        self.send(reply).await
+    }
+
+    // run と同じく、処理がしきい値を超えたら自動で保留する（結果の送り方は呼び出し側で決める）
+    pub async fn defer_if_slow<F>(&mut self, work: F) -> F::Output
+    where
+        F: Future,
+    {
+        tokio::pin!(work);
+
+        if !self.acknowledged {
+            tokio::select! {
+                output = &mut work => return output,
+                _ = tokio::time::sleep(self.threshold) => {
+                    self.defer().await;
+                }
+            }
+        }
+
+        work.await
+    }
+
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged
+    }
+
+    // 入力中のオプションの候補を返す（オートコンプリートのみ）
+    pub async fn autocomplete(&mut self, response: CreateAutocompleteResponse) -> bool {
+        match self.create(CreateInteractionResponse::Autocomplete(response)).await {
+            Ok(()) => {
+                self.acknowledged = true;
+                true
+            }
+            Err(why) => {
+                self.reporter.report("Cannot send autocomplete choices", &why).await;
+                false
+            }
+        }
+    }
+
+    // モーダルを開く（コマンド・コンポーネントのみ）
+    pub async fn open_modal(&mut self, modal: CreateModal) -> bool {
+        match self.create(CreateInteractionResponse::Modal(modal)).await {
+            Ok(()) => {
+                self.acknowledged = true;
+                true
+            }
+            Err(why) => {
+                self.reporter.report("Cannot open modal", &why).await;
+                false
+            }
+        }
+    }
+}
+
+fn is_token_expired(error: &serenity::Error) -> bool {
+    matches!(
+        error,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+            if matches!(response.error.code, UNKNOWN_WEBHOOK | INVALID_WEBHOOK_TOKEN)
+    )
+}
@@ -0,0 +1,250 @@
+// 正解した結果を、サーバーが設定した結果チャンネルや外部のURLへ共有する
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use serenity::all::{ChannelId, CreateAllowedMentions, CreateMessage, GuildId, Http};
+use tracing::{info, warn};
+
+use crate::i18n::{self, Locale};
+use crate::play::{PlayStatus, PlayView};
+use crate::{forum, stats, Bot, GameState, LetterResult, Phase, WordleGuess};
+
+// 転送先の応答を待つ上限
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 公式のWordleの0番のお題の日（2021-06-19、1970-01-01からの日数）
+const WORDLE_EPOCH_DAY: u64 = 18797;
+
+// 推測ごとの色のマス（Unicodeのみ）
+pub fn pattern_rows(guesses: &[WordleGuess]) -> Vec<String> {
+    guesses.iter()
+        .map(|guess| guess.results.iter()
+            .map(|r| match r {
+                LetterResult::Gray => '⬜',
+                LetterResult::Yellow => '🟨',
+                LetterResult::Green => '🟩',
+            })
+            .collect())
+        .collect()
+}
+
+// Discordの外（SNSなど）に貼る共有用の文章（公式と同じ見出しとお題の番号、ハッシュタグ。カスタム絵文字は使わない）
+// お題の番号は解いた日（UTC）の公式のお題とみなし、公式と同じく3桁ごとにカンマを入れる
+pub fn social_share_text(locale: Locale, guesses: &[WordleGuess], day: u64, limit: usize) -> String {
+    let solved = guesses.last().is_some_and(|g| g.results.iter().all(|r| *r == LetterResult::Green));
+    let score = if solved && guesses.len() <= limit { guesses.len().to_string() } else { "X".to_string() };
+    let hashtags = match locale {
+        Locale::Ja => "#Wordle #ワードル",
+        Locale::En => "#Wordle",
+    };
+    format!(
+        "Wordle {} {}/{}\n\n{}\n\n{}",
+        i18n::grouped(day.saturating_sub(WORDLE_EPOCH_DAY) as usize),
+        score,
+        limit,
+        pattern_rows(guesses).join("\n"),
+        hashtags
+    )
+}
+
+// 推測ごとの色の名前
+fn color_names(guesses: &[WordleGuess]) -> Vec<Vec<&'static str>> {
+    guesses.iter()
+        .map(|guess| guess.results.iter()
+            .map(|r| match r {
+                LetterResult::Gray => "gray",
+                LetterResult::Yellow => "yellow",
+                LetterResult::Green => "green",
+            })
+            .collect())
+        .collect()
+}
+
+// 外部へ送る結果（単語は含めない。IDはJavaScriptで扱えるよう文字列にする）
+#[derive(Debug, Serialize)]
+pub struct ResultPayload {
+    // 結果の種類（"helper": ヘルパーで正解したゲーム、"daily": 終えた /daily）
+    pub kind: &'static str,
+    pub guild_id: String,
+    pub user_id: String,
+    // /daily の日（UTCのUNIXエポックからの日数。ヘルパーでは省く）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<u64>,
+    // 当てたか（ヘルパーは正解したゲームだけを送るため常に true）
+    pub solved: bool,
+    pub guesses: usize,
+    // 推測ごとの色（"gray" / "yellow" / "green"）
+    pub results: Vec<Vec<&'static str>>,
+    pub share_text: String,
+    // UNIX時刻（秒）
+    pub completed_at: u64,
+}
+
+impl ResultPayload {
+    pub fn helper(guild_id: u64, user_id: u64, state: &GameState, completed_at: u64) -> Self {
+        Self {
+            kind: "helper",
+            guild_id: guild_id.to_string(),
+            user_id: user_id.to_string(),
+            day: None,
+            solved: true,
+            guesses: state.guesses.len(),
+            results: color_names(&state.guesses),
+            share_text: state.share_grid(),
+            completed_at,
+        }
+    }
+
+    pub fn daily(guild_id: u64, user_id: u64, day: u64, view: &PlayView, completed_at: u64) -> Self {
+        let solved = view.status == PlayStatus::Won;
+        let score = if solved { view.guesses.len().to_string() } else { "X".to_string() };
+        Self {
+            kind: "daily",
+            guild_id: guild_id.to_string(),
+            user_id: user_id.to_string(),
+            day: Some(day),
+            solved,
+            guesses: view.guesses.len(),
+            results: color_names(&view.guesses),
+            share_text: format!("Daily Wordle {} {}/{}\n\n{}", day, score, view.limit, pattern_rows(&view.guesses).join("\n")),
+            completed_at,
+        }
+    }
+}
+
+impl Bot {
+    pub(crate) async fn post_result(&self, http: &Arc<Http>, guild_id: GuildId, user_id: u64) {
+        let settings = self.settings();
+        let Some(channel) = settings.results_channels.get(&guild_id.get()).copied().filter(|_| !settings.is_private(user_id)) else {
+            return;
+        };
+        let Some(state) = self.session(user_id).await.filter(|state| state.phase == Phase::Completed) else {
+            return;
+        };
+
+        // 共有用の結果には単語を含めない（メンションの通知も送らない）
+        let message = CreateMessage::new()
+            .content(format!("<@{}>\n{}", user_id, self.share_grid(&state).await))
+            .allowed_mentions(CreateAllowedMentions::new());
+
+        // フォーラムには直接投稿できないため、その日の /daily の投稿に返信する
+        let posted = match settings.results_forum(guild_id.get()) {
+            Some(forum) => {
+                let place = (guild_id.get(), stats::today(), forum);
+                forum::post_reply(&self.daily, http, self.storage.as_ref(), &settings, place, message).await.map(|_| ())
+            }
+            None => ChannelId::new(channel).send_message(http, message).await.map(|_| ()).map_err(Into::into),
+        };
+        match posted {
+            Ok(()) => info!("Posted result of {} to channel {}", user_id, channel),
+            Err(e) => self.reporter.report("Failed to post result", &e).await,
+        }
+    }
+
+    // ヘルパーで正解した結果を、サーバーが設定したURLへ送る
+    pub(crate) async fn forward_result(&self, guild_id: GuildId, user_id: u64) {
+        if self.result_webhook(guild_id, user_id).is_none() {
+            return;
+        }
+        let Some(state) = self.session(user_id).await.filter(|state| state.phase == Phase::Completed) else {
+            return;
+        };
+        self.send_result(guild_id, user_id, ResultPayload::helper(guild_id.get(), user_id, &state, unix_now())).await;
+    }
+
+    // 終えた /daily の結果を、サーバーが設定したURLへ送る
+    pub(crate) async fn forward_daily_result(&self, guild_id: GuildId, user_id: u64, day: u64, view: &PlayView) {
+        let payload = ResultPayload::daily(guild_id.get(), user_id, day, view, unix_now());
+        self.send_result(guild_id, user_id, payload).await;
+    }
+
+    // 結果の転送先（設定がない、または成績を公開しないユーザーなら None）
+    fn result_webhook(&self, guild_id: GuildId, user_id: u64) -> Option<String> {
+        let settings = self.settings();
+        settings.result_webhooks.get(&guild_id.get()).cloned().filter(|_| !settings.is_private(user_id))
+    }
+
+    // 結果をJSONでPOSTする（失敗はサーバー側の設定の問題なのでログのみ）
+    async fn send_result(&self, guild_id: GuildId, user_id: u64, payload: ResultPayload) {
+        let Some(url) = self.result_webhook(guild_id, user_id) else {
+            return;
+        };
+
+        let response = self.http_client.post(&url)
+            .timeout(WEBHOOK_TIMEOUT)
+            .json(&payload)
+            .send()
+            .await;
+        match response {
+            Ok(response) if response.status().is_success() => info!("Forwarded result of {} for guild {}", user_id, guild_id),
+            Ok(response) => warn!("Result webhook for guild {} returned {}", guild_id, response.status()),
+            Err(e) => warn!("Failed to forward result for guild {}: {}", guild_id, e.without_url()),
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WordleGuess;
+
+    #[test]
+    fn payload_has_colors_but_no_words() {
+        let mut state = GameState::new();
+        state.guesses.push(WordleGuess::parse("CRANE", "BYBBG").unwrap());
+        state.guesses.push(WordleGuess::parse("TRUCE", "GGGGG").unwrap());
+        state.phase = Phase::Completed;
+
+        let payload = ResultPayload::helper(1, 2, &state, 1_700_000_000);
+        let json = serde_json::to_string(&payload).unwrap();
+
+        assert_eq!(payload.results[0], vec!["gray", "yellow", "gray", "gray", "green"]);
+        assert!(json.contains("\"guild_id\":\"1\""));
+        assert!(!json.contains("CRANE") && !json.contains("TRUCE"));
+    }
+
+    #[test]
+    fn daily_payload_has_the_day_but_no_words() {
+        let view = PlayView {
+            owner: 2,
+            revision: 0,
+            guesses: vec![WordleGuess::parse("CRANE", "BYBBG").unwrap(), WordleGuess::parse("TRUCE", "BBBBB").unwrap()],
+            status: PlayStatus::Lost { answer: "MOIST".to_string() },
+            hints: Default::default(),
+            next_hint: None,
+            limit: 2,
+            game: 0,
+        };
+        let payload = ResultPayload::daily(1, 2, 20742, &view, 1_700_000_000);
+        let json = serde_json::to_value(&payload).unwrap();
+
+        assert_eq!(json["kind"], "daily");
+        assert!(payload.share_text.starts_with("Daily Wordle 20742 X/2"));
+        assert_eq!(json["day"], 20742);
+        assert_eq!(json["solved"], false);
+        assert_eq!(json["results"][1], serde_json::json!(["gray", "gray", "gray", "gray", "gray"]));
+        assert!(["CRANE", "TRUCE", "MOIST"].iter().all(|word| !json.to_string().contains(word)));
+        // ヘルパーの結果には日を入れない
+        assert!(!serde_json::to_string(&ResultPayload::helper(1, 2, &GameState::new(), 0)).unwrap().contains("\"day\""));
+    }
+
+    #[test]
+    fn social_share_text_has_puzzle_number_and_plain_squares() {
+        let guesses = [WordleGuess::parse("CRANE", "BYBBG").unwrap(), WordleGuess::parse("TRUCE", "GGGGG").unwrap()];
+        // 2026-10-16
+        let text = social_share_text(Locale::En, &guesses, 20742, 6);
+
+        assert_eq!(text, "Wordle 1,945 2/6\n\n⬜🟨⬜⬜🟩\n🟩🟩🟩🟩🟩\n\n#Wordle");
+        assert!(social_share_text(Locale::Ja, &guesses[..1], 20742, 6).starts_with("Wordle 1,945 X/6"));
+        // 上限を超えて当てた回数は X にし、見出しには上限の回数を出す
+        assert!(social_share_text(Locale::En, &guesses, 20742, 8).starts_with("Wordle 1,945 2/8"));
+        assert!(social_share_text(Locale::En, &guesses, 20742, 1).starts_with("Wordle 1,945 X/1"));
+    }
+}
@@ -0,0 +1,295 @@
+// 成績の節目（連続正解日数・正解数）に応じてサーバーのロールを付け外しする
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, Http, Permissions, RoleId, UserId};
+use tracing::{info, warn};
+
+use crate::settings::Settings;
+use crate::stats::{self, UserStats};
+use crate::Bot;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "threshold")]
+pub enum Milestone {
+    Streak(u32),
+    Wins(u32),
+}
+
+impl Milestone {
+    pub const KINDS: [&'static str; 2] = ["streak", "wins"];
+
+    pub fn parse(kind: &str, threshold: u32) -> Option<Self> {
+        match kind {
+            "streak" => Some(Milestone::Streak(threshold)),
+            "wins" => Some(Milestone::Wins(threshold)),
+            _ => None,
+        }
+    }
+
+    pub fn reached(&self, stats: &UserStats, today: u64) -> bool {
+        match *self {
+            Milestone::Streak(days) => stats.streak_on(today) >= days,
+            Milestone::Wins(wins) => stats.wins >= wins,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Milestone::Streak(days) => format!("{}日連続正解", days),
+            Milestone::Wins(wins) => format!("{}回正解", wins),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleReward {
+    pub role_id: u64,
+    pub milestone: Milestone,
+}
+
+// 付け外しするロール
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RolePlan {
+    pub add: Vec<RoleReward>,
+    pub remove: Vec<RoleReward>,
+}
+
+impl RolePlan {
+    pub fn is_empty(&self) -> bool {
+        self.add.is_empty() && self.remove.is_empty()
+    }
+}
+
+// 現在のロールと成績から、付け外しが必要なロールを求める
+// （連続日数が途切れた場合など、条件を満たさなくなったロールは外す）
+pub fn plan(rewards: &[RoleReward], stats: &UserStats, today: u64, current_roles: &[u64]) -> RolePlan {
+    let mut plan = RolePlan::default();
+    for reward in rewards {
+        let has_role = current_roles.contains(&reward.role_id);
+        match (reward.milestone.reached(stats, today), has_role) {
+            (true, false) => plan.add.push(reward.clone()),
+            (false, true) => plan.remove.push(reward.clone()),
+            _ => {}
+        }
+    }
+    plan
+}
+
+impl Settings {
+    pub fn role_rewards(&self, guild: u64) -> &[RoleReward] {
+        self.role_rewards.get(&guild).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl Bot {
+    // 正解したユーザーのロールを成績に合わせて更新する
+    pub(crate) async fn apply_role_rewards(&self, http: &Arc<Http>, guild_id: GuildId, user_id: u64) {
+        let settings = self.settings();
+        let rewards = settings.role_rewards(guild_id.get());
+        // 成績を公開しないユーザーには成績に応じたロールもつけない
+        if rewards.is_empty() || settings.is_private(user_id) {
+            return;
+        }
+
+        let plan = match self.role_plan(http, guild_id, user_id, rewards).await {
+            Ok((_, plan)) => plan,
+            Err(e) => {
+                warn!("Cannot compute role rewards for {}: {:?}", user_id, e);
+                return;
+            }
+        };
+
+        let member = UserId::new(user_id);
+        for reward in &plan.add {
+            let result = http.add_member_role(guild_id, member, RoleId::new(reward.role_id), Some("Wordle milestone reached")).await;
+            match result {
+                Ok(()) => info!("Granted role {} to {} ({})", reward.role_id, user_id, reward.milestone.label()),
+                Err(e) => self.reporter.report("Failed to grant reward role", &e).await,
+            }
+        }
+        for reward in &plan.remove {
+            let result = http.remove_member_role(guild_id, member, RoleId::new(reward.role_id), Some("Wordle milestone lost")).await;
+            match result {
+                Ok(()) => info!("Removed role {} from {} ({})", reward.role_id, user_id, reward.milestone.label()),
+                Err(e) => self.reporter.report("Failed to remove reward role", &e).await,
+            }
+        }
+    }
+
+    async fn role_plan(
+        &self,
+        http: &Arc<Http>,
+        guild_id: GuildId,
+        user_id: u64,
+        rewards: &[RoleReward],
+    ) -> anyhow::Result<(UserStats, RolePlan)> {
+        let stats = self.user_stats(user_id).await?;
+        let member = guild_id.member(http, UserId::new(user_id)).await?;
+        let roles: Vec<u64> = member.roles.iter().map(|r| r.get()).collect();
+        let plan = plan(rewards, &stats, stats::today(), &roles);
+        Ok((stats, plan))
+    }
+
+    // ボットが付け外しできるロールか確認する（エラーはそのままユーザーに表示する）
+    async fn check_assignable(&self, http: &Arc<Http>, guild_id: GuildId, role_id: RoleId) -> Result<(), String> {
+        if role_id.get() == guild_id.get() {
+            return Err("@everyone は指定できません".to_string());
+        }
+
+        let roles = guild_id.roles(http).await.map_err(|e| format!("ロールを取得できませんでした: {}", e))?;
+        let role = roles.get(&role_id).ok_or("ロールが見つかりません")?;
+        if role.managed {
+            return Err("連携サービスが管理するロールは指定できません".to_string());
+        }
+
+        let bot_user = http.get_current_user().await.map_err(|e| e.to_string())?;
+        let bot_member = guild_id.member(http, bot_user.id).await.map_err(|e| e.to_string())?;
+        let bot_roles: Vec<_> = bot_member.roles.iter().filter_map(|id| roles.get(id)).collect();
+
+        let can_manage = bot_roles.iter()
+            .any(|r| r.permissions.contains(Permissions::MANAGE_ROLES) || r.permissions.contains(Permissions::ADMINISTRATOR));
+        if !can_manage {
+            return Err("ボットに「ロールの管理」権限がありません".to_string());
+        }
+
+        let top_position = bot_roles.iter().map(|r| r.position).max().unwrap_or(0);
+        if role.position >= top_position {
+            return Err("ボットの最上位ロールより下にあるロールを指定してください".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn admin_rewards_add(
+        &self,
+        http: &Arc<Http>,
+        guild_id: GuildId,
+        kind: &str,
+        threshold: i64,
+        role_id: Option<RoleId>,
+    ) -> String {
+        let (Some(role_id), Ok(threshold @ 1..)) = (role_id, u32::try_from(threshold)) else {
+            return "❌ ロールと1以上の値を指定してください".to_string();
+        };
+        let Some(milestone) = Milestone::parse(kind, threshold) else {
+            return format!("❌ 種類は {} のいずれかを指定してください", Milestone::KINDS.join(" / "));
+        };
+        if let Err(message) = self.check_assignable(http, guild_id, role_id).await {
+            return format!("❌ {}", message);
+        }
+
+        let saved = self.update_settings(|settings| {
+            let rewards = settings.role_rewards.entry(guild_id.get()).or_default();
+            rewards.retain(|r| r.role_id != role_id.get());
+            rewards.push(RoleReward { role_id: role_id.get(), milestone });
+            Some(())
+        });
+        match saved.await {
+            Ok(_) => format!("✅ {} で <@&{}> を付与します", milestone.label(), role_id),
+            Err(e) => {
+                self.reporter.report("Failed to save settings", &e).await;
+                format!("❌ 設定の保存に失敗しました: {}", e)
+            }
+        }
+    }
+
+    pub(crate) async fn admin_rewards_remove(&self, guild_id: GuildId, role_id: Option<RoleId>) -> String {
+        let Some(role_id) = role_id else {
+            return "❌ ロールを指定してください".to_string();
+        };
+
+        let removed = self.update_settings(|settings| {
+            let rewards = settings.role_rewards.get_mut(&guild_id.get())?;
+            let before = rewards.len();
+            rewards.retain(|r| r.role_id != role_id.get());
+            if rewards.len() == before {
+                return None;
+            }
+            if rewards.is_empty() {
+                settings.role_rewards.remove(&guild_id.get());
+            }
+            Some(())
+        });
+        match removed.await {
+            Ok(None) => format!("<@&{}> は報酬に設定されていません", role_id),
+            Ok(Some(())) => format!("✅ <@&{}> を報酬から外しました（付与済みのロールはそのまま残ります）", role_id),
+            Err(e) => {
+                self.reporter.report("Failed to save settings", &e).await;
+                format!("❌ 設定の保存に失敗しました: {}", e)
+            }
+        }
+    }
+
+    pub(crate) fn admin_rewards_list(&self, guild_id: GuildId) -> String {
+        let settings = self.settings();
+        let rewards = settings.role_rewards(guild_id.get());
+        if rewards.is_empty() {
+            return "ロール報酬は設定されていません。".to_string();
+        }
+
+        let mut message = String::from("🏅 **ロール報酬**\n");
+        for reward in rewards {
+            message.push_str(&format!("{} → <@&{}>\n", reward.milestone.label(), reward.role_id));
+        }
+        message
+    }
+
+    // 実際には変更せず、付け外しされるロールを表示する
+    pub(crate) async fn admin_rewards_preview(&self, http: &Arc<Http>, guild_id: GuildId, user: Option<UserId>) -> String {
+        let Some(user) = user else {
+            return "❌ ユーザーを指定してください".to_string();
+        };
+        let settings = self.settings();
+        let rewards = settings.role_rewards(guild_id.get());
+
+        let (stats, plan) = match self.role_plan(http, guild_id, user.get(), rewards).await {
+            Ok(result) => result,
+            Err(e) => return format!("❌ 成績またはメンバー情報を取得できませんでした: {}", e),
+        };
+
+        let mut message = format!(
+            "🔍 **<@{}> のロール報酬（プレビュー）**\n正解数: {}回 / 連続正解: {}日\n",
+            user, stats.wins, stats.streak_on(stats::today())
+        );
+        if plan.is_empty() {
+            message.push_str("変更はありません");
+            return message;
+        }
+
+        for reward in &plan.add {
+            message.push_str(&format!("➕ <@&{}>（{}）\n", reward.role_id, reward.milestone.label()));
+        }
+        for reward in &plan.remove {
+            message.push_str(&format!("➖ <@&{}>（{}を満たさなくなった）\n", reward.role_id, reward.milestone.label()));
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_adds_reached_roles_and_removes_lost_streaks() {
+        let rewards = [
+            RoleReward { role_id: 1, milestone: Milestone::Streak(2) },
+            RoleReward { role_id: 2, milestone: Milestone::Wins(3) },
+            RoleReward { role_id: 3, milestone: Milestone::Wins(1) },
+        ];
+        let mut stats = UserStats::default();
+        stats.record_win(10, 4);
+        stats.record_win(11, 4);
+        stats.record_win(11, 4);
+
+        let changes = plan(&rewards, &stats, 11, &[3]);
+        assert_eq!(changes.add.iter().map(|r| r.role_id).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(changes.remove.is_empty());
+
+        // 2日空くと連続正解のロールは外れる
+        let changes = plan(&rewards, &stats, 13, &[1, 2, 3]);
+        assert!(changes.add.is_empty());
+        assert_eq!(changes.remove.iter().map(|r| r.role_id).collect::<Vec<_>>(), vec![1]);
+    }
+}
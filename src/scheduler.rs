@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::{info, warn};
+
+type JobFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+type JobFn = Arc<dyn Fn() -> JobFuture + Send + Sync>;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// ジョブの実行タイミング
+#[derive(Debug, Clone, Copy)]
+pub enum Schedule {
+    // 一定間隔で実行
+    Every(Duration),
+    // 毎日指定時刻（UTC）に実行
+    DailyAt { hour: u32, minute: u32 },
+}
+
+impl Schedule {
+    // 次回実行までの待ち時間を計算
+    fn delay_from(&self, now: SystemTime) -> Duration {
+        match *self {
+            Schedule::Every(interval) => interval,
+            Schedule::DailyAt { hour, minute } => {
+                let now_secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+                let target = (hour as u64 % 24) * 3600 + (minute as u64 % 60) * 60;
+                let elapsed_today = now_secs % SECONDS_PER_DAY;
+                let wait = if target > elapsed_today {
+                    target - elapsed_today
+                } else {
+                    SECONDS_PER_DAY - elapsed_today + target
+                };
+                Duration::from_secs(wait)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JobStatus {
+    pub last_run: Option<SystemTime>,
+    pub last_error: Option<String>,
+    pub run_count: u64,
+    pub failure_count: u64,
+}
+
+struct Job {
+    name: &'static str,
+    schedule: Schedule,
+    run: JobFn,
+}
+
+// 名前付きの定期ジョブを管理する簡易スケジューラー
+pub struct Scheduler {
+    jobs: Vec<Job>,
+    status: Arc<tokio::sync::RwLock<HashMap<&'static str, JobStatus>>>,
+    started: AtomicBool,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            status: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            started: AtomicBool::new(false),
+        }
+    }
+
+    pub fn register<F, Fut>(&mut self, name: &'static str, schedule: Schedule, job: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        self.jobs.push(Job {
+            name,
+            schedule,
+            run: Arc::new(move || Box::pin(job())),
+        });
+    }
+
+    // 登録順にジョブ名と実行状況を取得
+    pub async fn statuses(&self) -> Vec<(&'static str, Schedule, JobStatus)> {
+        let status = self.status.read().await;
+        self.jobs.iter()
+            .map(|job| (job.name, job.schedule, status.get(job.name).cloned().unwrap_or_default()))
+            .collect()
+    }
+
+    // 全ジョブのループを開始（再接続でreadyが複数回呼ばれても一度だけ起動する）
+    pub fn start(&self) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        for job in &self.jobs {
+            let name = job.name;
+            let schedule = job.schedule;
+            let run = Arc::clone(&job.run);
+            let status = Arc::clone(&self.status);
+
+            info!("Scheduling job '{}' ({:?})", name, schedule);
+
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(schedule.delay_from(SystemTime::now())).await;
+
+                    // パニックしてもループが止まらないよう別タスクで実行
+                    let result = match tokio::spawn((run)()).await {
+                        Ok(result) => result,
+                        Err(e) => Err(anyhow::anyhow!("job panicked: {}", e)),
+                    };
+
+                    let mut status = status.write().await;
+                    let entry = status.entry(name).or_default();
+                    entry.last_run = Some(SystemTime::now());
+                    entry.run_count += 1;
+
+                    match result {
+                        Ok(()) => entry.last_error = None,
+                        Err(e) => {
+                            entry.failure_count += 1;
+                            warn!("Job '{}' failed ({} failures so far): {:?}", name, entry.failure_count, e);
+                            entry.last_error = Some(e.to_string());
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
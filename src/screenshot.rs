@@ -0,0 +1,295 @@
+// /import と「盤面の画像を読み込む」メッセージコマンド:
+// Wordleのスクリーンショットからタイルの色を読み取り、その盤面からセッションを始める
+// 文字の読み取り（OCR）は行わないため、推測した単語は入力してもらう
+use anyhow::Context as _;
+use image::RgbImage;
+use serenity::all::{
+    Attachment,
+    CommandInteraction,
+    CommandOptionType,
+    CommandType,
+    CreateCommand,
+    CreateCommandOption,
+    ResolvedTarget,
+    ResolvedValue,
+};
+use tracing::info;
+
+use crate::i18n::{self, Text};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::solver::WORD_LENGTH;
+use crate::{Bot, LetterResult, WordleGuess};
+
+// メッセージのコンテキストメニューに表示される名前
+pub const MESSAGE_COMMAND_NAME: &str = "Import board image";
+
+// 添付ファイルの上限（それ以上は読み込まない）
+const MAX_IMAGE_BYTES: u32 = 8 * 1024 * 1024;
+
+// ライト・ダーク・ハイコントラストテーマのタイルの色
+const TILE_COLORS: [(LetterResult, [u8; 3]); 8] = [
+    (LetterResult::Green, [106, 170, 100]),
+    (LetterResult::Green, [83, 141, 78]),
+    (LetterResult::Green, [245, 121, 58]),
+    (LetterResult::Yellow, [201, 180, 88]),
+    (LetterResult::Yellow, [181, 159, 59]),
+    (LetterResult::Yellow, [133, 192, 249]),
+    (LetterResult::Gray, [120, 124, 126]),
+    (LetterResult::Gray, [58, 58, 60]),
+];
+
+// JPEGの劣化を見込んだ色の許容差（RGB空間の距離の2乗）
+const COLOR_TOLERANCE: u32 = 40 * 40;
+
+fn classify(pixel: [u8; 3]) -> Option<LetterResult> {
+    TILE_COLORS.iter()
+        .map(|(result, color)| {
+            let distance: u32 = pixel.iter().zip(color).map(|(&a, &b)| (a as i32 - b as i32).pow(2) as u32).sum();
+            (distance, *result)
+        })
+        .filter(|(distance, _)| *distance <= COLOR_TOLERANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, result)| result)
+}
+
+// 値がしきい値以上の連続区間（開始, 終了）
+fn runs(counts: &[usize], threshold: usize) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    for (i, &count) in counts.iter().chain(std::iter::once(&0)).enumerate() {
+        match (start, count >= threshold && count > 0) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                runs.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+// 区間の中央付近で最も多い色（タイルの縁や文字を避ける）
+fn cell_color(image: &RgbImage, (x0, x1): (usize, usize), (y0, y1): (usize, usize)) -> Option<LetterResult> {
+    let (w, h) = (x1 - x0, y1 - y0);
+    let mut counts = [0usize; 3];
+    for y in (y0 + h / 4)..(y1 - h / 4) {
+        for x in (x0 + w / 4)..(x1 - w / 4) {
+            match classify(image.get_pixel(x as u32, y as u32).0) {
+                Some(LetterResult::Gray) => counts[0] += 1,
+                Some(LetterResult::Yellow) => counts[1] += 1,
+                Some(LetterResult::Green) => counts[2] += 1,
+                None => {}
+            }
+        }
+    }
+
+    // 文字の部分を除いても、半分程度はタイルの色になる
+    let (index, &count) = counts.iter().enumerate().max_by_key(|(_, c)| **c)?;
+    if count * 3 < (w / 2) * (h / 2) {
+        return None;
+    }
+    Some([LetterResult::Gray, LetterResult::Yellow, LetterResult::Green][index])
+}
+
+// 盤面の行ごとの色（上から順）。キーボードなど5マスの正方形に並ばないものは無視する
+pub fn read_tile_colors(bytes: &[u8]) -> anyhow::Result<Vec<Vec<LetterResult>>> {
+    let image = image::load_from_memory(bytes).context("Failed to decode image")?.to_rgb8();
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let is_tile = |x: usize, y: usize| classify(image.get_pixel(x as u32, y as u32).0).is_some();
+
+    let row_counts: Vec<usize> = (0..height).map(|y| (0..width).filter(|&x| is_tile(x, y)).count()).collect();
+    let widest = row_counts.iter().copied().max().unwrap_or(0);
+
+    let mut rows = Vec::new();
+    for (y0, y1) in runs(&row_counts, widest / 4) {
+        // 枠線だけの細い帯は除く
+        if y1 - y0 < 4 {
+            continue;
+        }
+        let column_counts: Vec<usize> = (0..width).map(|x| (y0..y1).filter(|&y| is_tile(x, y)).count()).collect();
+        // 文字が縦に長く重なる列もあるため、しきい値は低めにする
+        let columns = runs(&column_counts, (y1 - y0) / 4);
+        if columns.len() != WORD_LENGTH {
+            continue;
+        }
+
+        // タイルはほぼ正方形
+        let square = columns.iter().all(|&(x0, x1)| {
+            let (w, h) = ((x1 - x0) as f32, (y1 - y0) as f32);
+            (w / h - 1.0).abs() < 0.3
+        });
+        if !square {
+            continue;
+        }
+
+        let colors: Option<Vec<LetterResult>> = columns.iter().map(|&column| cell_color(&image, column, (y0, y1))).collect();
+        if let Some(colors) = colors {
+            rows.push(colors);
+        }
+    }
+
+    Ok(rows)
+}
+
+// 読み取った色と入力された単語を組み合わせる（数が合わなければ None）
+pub fn pair_words(words: &str, rows: &[Vec<LetterResult>]) -> Option<Vec<WordleGuess>> {
+    let words: Vec<&str> = words.split(|c: char| c.is_whitespace() || c == ',').filter(|w| !w.is_empty()).collect();
+    if words.len() != rows.len() || !words.iter().all(|w| crate::solver::is_valid_word(w)) {
+        return None;
+    }
+
+    Some(words.iter()
+        .zip(rows)
+        .map(|(word, results)| WordleGuess { word: word.to_uppercase(), results: results.clone() })
+        .collect())
+}
+
+async fn download_tile_colors(image: &Attachment) -> anyhow::Result<Vec<Vec<LetterResult>>> {
+    let bytes = image.download().await.context("Failed to download attachment")?;
+    // 画像の解析はCPUを使うため別スレッドで行う
+    tokio::task::spawn_blocking(move || read_tile_colors(&bytes)).await?
+}
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("import")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Attachment, "image", "盤面のスクリーンショット")
+                .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "words", "推測した単語（上から順にスペース区切り）")
+                .required(true),
+        )
+}
+
+pub fn create_message_command() -> CreateCommand {
+    i18n::command(MESSAGE_COMMAND_NAME).kind(CommandType::Message)
+}
+
+fn is_image(attachment: &Attachment) -> bool {
+    attachment.content_type.as_deref().is_some_and(|t| t.starts_with("image/"))
+}
+
+impl Bot {
+    // メッセージに添付された画像の色を読み取り、修正できる状態で盤面を表示する
+    pub(crate) async fn handle_import_message_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let image = match command.data.target() {
+            Some(ResolvedTarget::Message(message)) => message.attachments.iter().find(|a| is_image(a)).cloned(),
+            _ => None,
+        };
+
+        let Some(image) = image.filter(|a| a.size <= MAX_IMAGE_BYTES) else {
+            self.responder(ctx, command, ReplyMode::Ephemeral)
+                .send(Reply::content(locale.text(Text::ImportNoImage)))
+                .await;
+            return;
+        };
+
+        self.responder(ctx, command, ReplyMode::NewMessage)
+            .run(async {
+                match download_tile_colors(&image).await {
+                    Ok(rows) if !rows.is_empty() => {
+                        info!("Read {} rows from a board image for {}", rows.len(), command.user.id);
+                        let view = self.start_import(command.user.id.get(), command.guild_id.map(|g| g.get()), rows).await;
+                        self.board_reply(locale, Ok(view))
+                    }
+                    Ok(_) => Reply::content(locale.text(Text::ImportUnreadable)),
+                    Err(e) => {
+                        info!("Could not read board image from {}: {:?}", command.user.id, e);
+                        Reply::content(locale.text(Text::ImportUnreadable))
+                    }
+                }
+            })
+            .await;
+    }
+
+    pub(crate) async fn handle_import_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let mut image: Option<&Attachment> = None;
+        let mut words = "";
+        for option in command.data.options() {
+            match (option.name, option.value) {
+                ("image", ResolvedValue::Attachment(attachment)) => image = Some(attachment),
+                ("words", ResolvedValue::String(value)) => words = value,
+                _ => {}
+            }
+        }
+        let Some(image) = image else {
+            return;
+        };
+
+        let mut responder = self.responder(ctx, command, ReplyMode::NewMessage);
+        if image.size > MAX_IMAGE_BYTES {
+            responder.send(Reply::content(locale.text(Text::ImportUnreadable))).await;
+            return;
+        }
+
+        // ダウンロードと画像の解析は時間がかかるため自動で保留される
+        responder
+            .run(async {
+                let rows = match download_tile_colors(image).await {
+                    Ok(rows) if !rows.is_empty() => rows,
+                    Ok(_) => return Reply::content(locale.text(Text::ImportUnreadable)),
+                    Err(e) => {
+                        info!("Could not read board image from {}: {:?}", command.user.id, e);
+                        return Reply::content(locale.text(Text::ImportUnreadable));
+                    }
+                };
+
+                let Some(guesses) = pair_words(words, &rows) else {
+                    return Reply::content(locale.import_word_mismatch(rows.len()));
+                };
+
+                info!("Imported {} guesses from a screenshot for {}", guesses.len(), command.user.id);
+                let view = self.import_guesses(command.user.id.get(), command.guild_id.map(|g| g.get()), guesses).await;
+                self.board_reply(locale, view)
+            })
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    fn fill(image: &mut RgbImage, x: u32, y: u32, w: u32, h: u32, color: [u8; 3]) {
+        for dy in 0..h {
+            for dx in 0..w {
+                image.put_pixel(x + dx, y + dy, Rgb(color));
+            }
+        }
+    }
+
+    #[test]
+    fn reads_board_rows_and_skips_keyboard() {
+        let mut image = RgbImage::from_pixel(400, 400, Rgb([255, 255, 255]));
+        let board = [
+            [LetterResult::Gray, LetterResult::Yellow, LetterResult::Gray, LetterResult::Gray, LetterResult::Green],
+            [LetterResult::Green, LetterResult::Green, LetterResult::Green, LetterResult::Green, LetterResult::Green],
+        ];
+        for (row, results) in board.iter().enumerate() {
+            for (col, result) in results.iter().enumerate() {
+                let color = TILE_COLORS.iter().find(|(r, _)| r == result).unwrap().1;
+                fill(&mut image, 50 + col as u32 * 60, 20 + row as u32 * 60, 50, 50, color);
+                // 文字の代わりに白い四角を置く
+                fill(&mut image, 65 + col as u32 * 60, 35 + row as u32 * 60, 20, 20, [255, 255, 255]);
+            }
+        }
+        // キーボード（縦長のキーが10個）
+        for key in 0..10 {
+            fill(&mut image, 10 + key * 38, 300, 32, 60, [120, 124, 126]);
+        }
+
+        let mut bytes = Vec::new();
+        image.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png).unwrap();
+
+        let rows = read_tile_colors(&bytes).unwrap();
+        assert_eq!(rows, board.iter().map(|r| r.to_vec()).collect::<Vec<_>>());
+
+        assert!(pair_words("crane trace", &rows).is_some());
+        assert!(pair_words("crane", &rows).is_none());
+    }
+}
@@ -0,0 +1,921 @@
+// セッション操作（Discordに依存しないゲーム進行ロジック）
+// ハンドラーはここで得た BoardView をEmbedとボタンに変換して返信する
+use std::fmt;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use tokio::sync::{watch, Mutex};
+use tracing::{info, warn};
+
+use crate::i18n::{Locale, Text};
+use crate::in_flight::{AnalysisProgress, ProgressStage};
+use crate::results;
+use crate::settings::{Difficulty, InputStyle};
+use crate::solver::{self, GuessExplanation, HardModeViolation, MAX_GUESSES};
+use crate::{Bot, GameState, LetterResult, Phase, SharedBoard, WordleGuess};
+
+// 盤面の下に表示する操作ボタン
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardControls {
+    // 既存のボタンをそのまま残す
+    Unchanged,
+    NewWord,
+    // 分析中（新しい単語ボタン。確定した直後は、分析を取り消して確定前に戻すボタンも並べる）
+    Analyzing { cancellable: bool },
+    LetterResults { word: String, results: Vec<LetterResult> },
+    // 色の入力をメニューで選ぶユーザー向けの、黄と緑をまとめて選ぶメニューと確定ボタン
+    LetterMenu { word: String, results: Vec<LetterResult> },
+    // 候補が0件のとき、疑わしい推測を修正するボタンを並べる
+    EditGuess { index: usize },
+    // 読み込んだ盤面の選択中の行のタイルと、行の切り替え・確定ボタン
+    Importing { rows: Vec<Vec<LetterResult>>, selected: usize },
+    // ゲーム終了（ボタンをすべて取り除く）
+    Finished,
+}
+
+// 盤面のEmbedの欄（見出しは盤面の言語で付ける）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardSection {
+    History,
+    // 文字ごとにわかった色（キーボードの並び）
+    Keyboard,
+    CurrentWord,
+    Candidates,
+    Suggestions,
+    // 一番のおすすめで候補がどう分かれるか
+    Elimination,
+}
+
+impl BoardSection {
+    // 候補数とおすすめは横に並べる
+    pub fn inline(self) -> bool {
+        matches!(self, BoardSection::Candidates | BoardSection::Suggestions)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardField {
+    pub section: BoardSection,
+    pub value: String,
+}
+
+// Embedの欄の値は1024文字まで
+pub const MAX_FIELD_CHARS: usize = 1024;
+
+// 単語リストにない推測に案内する近い単語の数
+const NEAREST_WORDS: usize = 5;
+
+// 行を欄の上限に収まるように分ける（長い履歴は同じ見出しの欄を続ける）
+pub fn board_fields(section: BoardSection, lines: impl IntoIterator<Item = String>) -> Vec<BoardField> {
+    let mut fields: Vec<BoardField> = Vec::new();
+    for line in lines {
+        let line: String = line.chars().take(MAX_FIELD_CHARS).collect();
+        match fields.last_mut() {
+            Some(field) if field.value.chars().count() + 1 + line.chars().count() <= MAX_FIELD_CHARS => {
+                field.value.push('\n');
+                field.value.push_str(&line);
+            }
+            _ => fields.push(BoardField { section, value: line }),
+        }
+    }
+    fields
+}
+
+// 公式のWordleと同じキーボードの並び
+pub const KEYBOARD_ROWS: [&str; 3] = ["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"];
+
+// 確定した推測から、文字ごとにわかった一番良い色（緑 > 黄 > 灰。まだ使っていない文字は None）
+pub fn keyboard_colors(guesses: &[WordleGuess]) -> [Option<LetterResult>; 26] {
+    let mut colors = [None; 26];
+    for guess in guesses {
+        for (letter, result) in guess.word.chars().zip(&guess.results) {
+            if !letter.is_ascii_alphabetic() {
+                continue;
+            }
+            let slot: &mut Option<LetterResult> = &mut colors[(letter.to_ascii_uppercase() as u8 - b'A') as usize];
+            if slot.is_none_or(|current| (current as u8) < (*result as u8)) {
+                *slot = Some(*result);
+            }
+        }
+    }
+    colors
+}
+
+#[derive(Debug, Clone)]
+pub struct BoardView {
+    // 盤面を持つセッション（所有者のユーザーID）とそのリビジョン（ボタンの custom_id に埋め込む）
+    pub session: u64,
+    pub revision: u64,
+    // 盤面を表示する言語（セッション開始時のサーバーの設定）
+    pub locale: Locale,
+    // 欄の上に出す案内（空なら出さない）
+    pub description: String,
+    pub fields: Vec<BoardField>,
+    pub controls: BoardControls,
+    // 確定済みの推測（過去の推測を編集するメニューに並べる）
+    pub history: Vec<WordleGuess>,
+    // 「なぜこの単語？」ボタンをつけるおすすめの単語（上位のみ）
+    pub explained: Vec<String>,
+}
+
+// 現在の段階では実行できない操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionError {
+    NotFound,
+    // 古い盤面のボタンからの操作
+    Stale,
+    NoWordToConfirm,
+    NotAwaitingColors,
+    AlreadySolved,
+    // 読み込んだ盤面の確認中
+    ImportPending,
+    // 読み込んだ行の数と入力された単語が合わない
+    ImportWordMismatch,
+    // 同じ盤面の分析が実行中（先に始まった分析が盤面を更新する）
+    AnalysisRunning,
+}
+
+impl SessionError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            SessionError::NotFound => "ゲーム状態が見つかりません。`/wht` で新しく始めてください。",
+            SessionError::Stale => "この盤面は古くなっています。最新のメッセージから操作してください。",
+            SessionError::NoWordToConfirm => "確定する単語がありません。先に単語を入力してください。",
+            SessionError::NotAwaitingColors => "色を変更できる単語がありません。先に単語を入力してください。",
+            SessionError::AlreadySolved => "このゲームはすでに正解しています。`/wht` で新しく始めてください。",
+            SessionError::ImportPending => "読み込んだ盤面を先に確定してください。",
+            SessionError::ImportWordMismatch => "読み込んだ行と同じ数の5文字の英単語を、上から順に入力してください。",
+            SessionError::AnalysisRunning => "最適な単語を分析中です。しばらくお待ちください。",
+        }
+    }
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl GameState {
+    pub fn new() -> Self {
+        Self {
+            guesses: Vec::new(),
+            phase: Phase::AwaitingWord,
+            revision: 0,
+            last_suggestion: String::new(),
+            locale: Locale::default(),
+            hard_mode: false,
+            difficulty: Difficulty::default(),
+            explanations: Vec::new(),
+            guild_id: None,
+            word_list_version: None,
+            shared: None,
+            candidate_cache: Default::default(),
+            suggestion_cache: Default::default(),
+            last_active: std::time::Instant::now(),
+        }
+    }
+
+    fn touch(&mut self) {
+        self.last_active = std::time::Instant::now();
+    }
+
+    // 単語を入力して色の入力待ちにする（色の入力中なら単語を差し替える）
+    pub fn enter_word(&mut self, word: String) -> Result<(), SessionError> {
+        match self.phase {
+            Phase::Completed => return Err(SessionError::AlreadySolved),
+            Phase::Importing { .. } => return Err(SessionError::ImportPending),
+            Phase::AwaitingWord | Phase::AwaitingColors { .. } => {}
+        }
+
+        let results = vec![LetterResult::Gray; word.chars().count()];
+        self.phase = Phase::AwaitingColors { word, results };
+        Ok(())
+    }
+
+    // 指定位置の文字の色を 灰→黄→緑→灰 の順に切り替える（範囲外は無視）
+    // 読み込んだ盤面の確認中は、選択中の行のタイルを切り替える
+    pub fn cycle_letter(&mut self, index: usize) -> Result<(), SessionError> {
+        let results = match &mut self.phase {
+            Phase::AwaitingColors { results, .. } => results,
+            Phase::Importing { rows, selected } => &mut rows[*selected],
+            Phase::AwaitingWord => return Err(SessionError::NotAwaitingColors),
+            Phase::Completed => return Err(SessionError::AlreadySolved),
+        };
+
+        if let Some(result) = results.get_mut(index) {
+            *result = match result {
+                LetterResult::Gray => LetterResult::Yellow,
+                LetterResult::Yellow => LetterResult::Green,
+                LetterResult::Green => LetterResult::Gray,
+            };
+        }
+        Ok(())
+    }
+
+    // 指定位置の文字の色を直接設定する（リアクションでの入力用、範囲外は無視）
+    pub fn set_letter(&mut self, index: usize, result: LetterResult) -> Result<(), SessionError> {
+        let results = match &mut self.phase {
+            Phase::AwaitingColors { results, .. } => results,
+            Phase::Importing { .. } => return Err(SessionError::ImportPending),
+            Phase::AwaitingWord => return Err(SessionError::NotAwaitingColors),
+            Phase::Completed => return Err(SessionError::AlreadySolved),
+        };
+
+        if let Some(slot) = results.get_mut(index) {
+            *slot = result;
+        }
+        Ok(())
+    }
+
+    // すべての文字の色をまとめて設定する（色の入力メニュー用。足りない分は灰、余った分は無視）
+    pub fn set_letters(&mut self, colors: &[LetterResult]) -> Result<(), SessionError> {
+        let results = match &mut self.phase {
+            Phase::AwaitingColors { results, .. } => results,
+            Phase::Importing { .. } => return Err(SessionError::ImportPending),
+            Phase::AwaitingWord => return Err(SessionError::NotAwaitingColors),
+            Phase::Completed => return Err(SessionError::AlreadySolved),
+        };
+
+        for (i, slot) in results.iter_mut().enumerate() {
+            *slot = colors.get(i).copied().unwrap_or(LetterResult::Gray);
+        }
+        Ok(())
+    }
+
+    // 読み込んだ盤面の修正する行を選ぶ（範囲外は無視）
+    pub fn select_import_row(&mut self, row: usize) -> Result<(), SessionError> {
+        let Phase::Importing { rows, selected } = &mut self.phase else {
+            return Err(SessionError::NotAwaitingColors);
+        };
+        if row < rows.len() {
+            *selected = row;
+        }
+        Ok(())
+    }
+
+    // 読み込んだ色と入力された単語を履歴として確定する（最後の行がすべて緑なら正解として終了）
+    pub fn finish_import(&mut self, words: &str) -> Result<(), SessionError> {
+        let Phase::Importing { rows, .. } = &self.phase else {
+            return Err(SessionError::NoWordToConfirm);
+        };
+        let guesses = crate::screenshot::pair_words(words, rows).ok_or(SessionError::ImportWordMismatch)?;
+
+        let solved = guesses.last().is_some_and(|g| g.results.iter().all(|r| *r == LetterResult::Green));
+        self.phase = if solved { Phase::Completed } else { Phase::AwaitingWord };
+        self.guesses = guesses;
+        Ok(())
+    }
+
+    // 現在の単語と色を履歴に確定する（すべて緑なら正解として終了）
+    pub fn confirm(&mut self) -> Result<(), SessionError> {
+        let (word, results) = match std::mem::replace(&mut self.phase, Phase::AwaitingWord) {
+            Phase::AwaitingColors { word, results } => (word, results),
+            Phase::AwaitingWord => return Err(SessionError::NoWordToConfirm),
+            Phase::Completed => {
+                self.phase = Phase::Completed;
+                return Err(SessionError::AlreadySolved);
+            }
+            importing @ Phase::Importing { .. } => {
+                self.phase = importing;
+                return Err(SessionError::ImportPending);
+            }
+        };
+
+        if results.iter().all(|r| *r == LetterResult::Green) {
+            self.phase = Phase::Completed;
+        }
+        self.guesses.push(WordleGuess { word, results });
+        Ok(())
+    }
+
+    // 確定済みの推測を履歴から外し、その色のまま色の入力に戻す（入力中の単語は破棄）
+    pub fn reopen_guess(&mut self, index: usize) -> Result<(), SessionError> {
+        match self.phase {
+            Phase::Completed => return Err(SessionError::AlreadySolved),
+            Phase::Importing { .. } => return Err(SessionError::ImportPending),
+            Phase::AwaitingWord | Phase::AwaitingColors { .. } => {}
+        }
+        if index >= self.guesses.len() {
+            return Err(SessionError::NotAwaitingColors);
+        }
+
+        let WordleGuess { word, results } = self.guesses.remove(index);
+        self.phase = Phase::AwaitingColors { word, results };
+        Ok(())
+    }
+}
+
+impl GameState {
+    // ハードモードの確認が有効な場合のみ、これまでの推測に対する違反を返す
+    pub fn hard_mode_violations(&self, word: &str) -> Vec<HardModeViolation> {
+        if !self.hard_mode {
+            return Vec::new();
+        }
+        solver::hard_mode_violations(word, &self.guesses)
+    }
+
+    // 単語を伏せた共有用の結果（色のマスのみ）
+    pub fn share_grid(&self) -> String {
+        format!("{}\n{}", self.locale.share_header(self.guesses.len()), results::pattern_rows(&self.guesses).join("\n"))
+    }
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 案内の段落をつなげる（前の段落がなければそのまま）
+fn join_paragraphs(first: &str, second: &str) -> String {
+    if first.is_empty() {
+        second.to_string()
+    } else {
+        format!("{}\n\n{}", first, second)
+    }
+}
+
+impl BoardView {
+    // 指定した欄の値（分かれている欄はつなげる）
+    pub fn field(&self, section: BoardSection) -> Option<String> {
+        let values: Vec<&str> = self.fields.iter()
+            .filter(|f| f.section == section)
+            .map(|f| f.value.as_str())
+            .collect();
+        (!values.is_empty()).then(|| values.join("\n"))
+    }
+}
+
+impl Bot {
+    // 新しいセッションを開始（既存のセッションは破棄）
+    // 盤面の言語は開始したサーバーの設定に従う
+    pub async fn start_session(&self, user_id: u64, guild_id: Option<u64>) -> BoardView {
+        let locale = self.settings().locale_for(guild_id);
+        let state = GameState {
+            revision: self.next_revision(),
+            locale,
+            guild_id,
+            difficulty: self.settings().default_difficulty,
+            ..GameState::new()
+        };
+        let revision = state.revision;
+        self.persist_session(user_id, &state).await;
+        self.insert_session(user_id, state).await;
+
+        BoardView {
+            session: user_id,
+            revision,
+            locale,
+            description: locale.text(Text::EmptyBoard).to_string(),
+            fields: Vec::new(),
+            controls: BoardControls::NewWord,
+            history: Vec::new(),
+            explained: Vec::new(),
+        }
+    }
+
+    // チャンネルで共有する盤面を開始する（セッションはチャンネルのIDで持ち、誰でも色を入力できる）
+    pub async fn start_shared_session(&self, channel_id: u64, guild_id: Option<u64>, board: SharedBoard) -> BoardView {
+        let mut view = self.start_session(channel_id, guild_id).await;
+        if let Ok(entry) = self.session_entry(channel_id).await {
+            entry.lock().await.shared = Some(board);
+        }
+        let note = if board.host_only { Text::SharedBoardHostOnly } else { Text::SharedBoard };
+        view.description = join_paragraphs(&view.description, view.locale.text(note));
+        view
+    }
+
+    // 読み込んだ推測の履歴から新しいセッションを開始し、提案を計算した盤面を返す
+    // （すでに正解している盤面は記録に数えない）
+    pub async fn import_guesses(&self, user_id: u64, guild_id: Option<u64>, guesses: Vec<WordleGuess>) -> Result<BoardView, SessionError> {
+        let solved = guesses.last().is_some_and(|g| g.results.iter().all(|r| *r == LetterResult::Green));
+        let state = GameState {
+            revision: self.next_revision(),
+            locale: self.settings().locale_for(guild_id),
+            guild_id,
+            difficulty: self.settings().default_difficulty,
+            guesses,
+            phase: if solved { Phase::Completed } else { Phase::AwaitingWord },
+            ..GameState::new()
+        };
+        self.insert_session(user_id, state.clone()).await;
+
+        self.imported_view(user_id, &state).await
+    }
+
+    // スクリーンショットから読み取った色の確認から新しいセッションを開始する
+    pub async fn start_import(&self, user_id: u64, guild_id: Option<u64>, rows: Vec<Vec<LetterResult>>) -> BoardView {
+        let state = GameState {
+            revision: self.next_revision(),
+            locale: self.settings().locale_for(guild_id),
+            guild_id,
+            difficulty: self.settings().default_difficulty,
+            phase: Phase::Importing { rows: rows.clone(), selected: 0 },
+            ..GameState::new()
+        };
+        self.insert_session(user_id, state.clone()).await;
+
+        let (description, fields) = self.update_embed_content(&state).await;
+        BoardView {
+            session: user_id,
+            revision: state.revision,
+            locale: state.locale,
+            description,
+            fields,
+            controls: BoardControls::Importing { rows, selected: 0 },
+            history: Vec::new(),
+            explained: Vec::new(),
+        }
+    }
+
+    // 読み込んだ盤面の修正する行を切り替える
+    pub async fn select_import_row(&self, user_id: u64, revision: u64, row: usize) -> Result<BoardView, SessionError> {
+        let state = self.update_session(user_id, revision, |state| state.select_import_row(row)).await?;
+
+        self.letter_view(user_id, &state).await
+    }
+
+    // 確認した色に単語を合わせて履歴とする（提案の計算は finish_analysis で行う）
+    pub async fn finish_import(&self, user_id: u64, revision: u64, words: &str) -> Result<BoardView, SessionError> {
+        let state = self.update_session(user_id, revision, |state| state.finish_import(words)).await?;
+
+        Ok(self.analyzing_view(user_id, &state).await)
+    }
+
+    // 読み込んだ盤面は記録に数えない
+    async fn imported_view(&self, user_id: u64, state: &GameState) -> Result<BoardView, SessionError> {
+        if state.phase != Phase::Completed {
+            return self.finish_analysis(user_id).await;
+        }
+        let (description, fields) = self.update_embed_content(state).await;
+        Ok(BoardView {
+            session: user_id,
+            revision: state.revision,
+            locale: state.locale,
+            description,
+            fields,
+            controls: BoardControls::Finished,
+            history: state.guesses.clone(),
+            explained: Vec::new(),
+        })
+    }
+
+    async fn session_entry(&self, user_id: u64) -> Result<Arc<Mutex<GameState>>, SessionError> {
+        if let Some(entry) = self.game_states.read().await.get(&user_id).cloned() {
+            return Ok(entry);
+        }
+
+        // 追い出された、または再起動前のセッションはストレージから復元する
+        // （取り出すと保存した行は消えるため、もう一度保存しておく）
+        let state = self.restore_session(user_id).await.ok_or(SessionError::NotFound)?;
+        self.persist_session(user_id, &state).await;
+        Ok(self.insert_session(user_id, state).await)
+    }
+
+    // セッションを登録し、上限を超えた分は最後の操作が古い順に追い出す
+    async fn insert_session(&self, user_id: u64, mut state: GameState) -> Arc<Mutex<GameState>> {
+        // 分析に使う単語リストの版を記録する
+        if state.word_list_version.is_none() {
+            state.word_list_version = self.word_versions.current();
+        }
+        let entry = Arc::new(Mutex::new(state));
+
+        let evicted = {
+            let mut states = self.game_states.write().await;
+            states.insert(user_id, Arc::clone(&entry));
+            self.metrics.record_sessions(states.len());
+
+            let mut evicted = Vec::new();
+            while states.len() > self.max_sessions {
+                // 操作中（ロック中）のセッションは追い出さない
+                let oldest = states.iter()
+                    .filter(|(id, _)| **id != user_id)
+                    .filter_map(|(id, entry)| entry.try_lock().ok().map(|state| (*id, state.last_active)))
+                    .min_by_key(|(_, last_active)| *last_active)
+                    .map(|(id, _)| id);
+
+                match oldest.and_then(|id| states.remove(&id).map(|entry| (id, entry))) {
+                    Some(removed) => evicted.push(removed),
+                    None => break,
+                }
+            }
+            evicted
+        };
+
+        if !evicted.is_empty() {
+            info!("Evicted {} sessions (limit: {})", evicted.len(), self.max_sessions);
+        }
+
+        if self.persist_sessions {
+            for (id, evicted_entry) in evicted {
+                let state = evicted_entry.lock().await.clone();
+                self.persist_session(id, &state).await;
+            }
+        }
+
+        entry
+    }
+
+    // セッションをストレージに保存する（書き込みはまとめて送られる）
+    async fn persist_session(&self, user_id: u64, state: &GameState) {
+        if !self.persist_sessions {
+            return;
+        }
+
+        let saved = match serde_json::to_value(state) {
+            Ok(value) => self.storage.save_session(user_id, value).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = saved {
+            warn!("Failed to persist session {}: {:?}", user_id, e);
+        }
+    }
+
+    async fn restore_session(&self, user_id: u64) -> Option<GameState> {
+        if !self.persist_sessions {
+            return None;
+        }
+
+        let value = match self.storage.take_session(user_id).await {
+            Ok(value) => value?,
+            Err(e) => {
+                warn!("Failed to load persisted session {}: {:?}", user_id, e);
+                return None;
+            }
+        };
+
+        match serde_json::from_value(value) {
+            Ok(state) => {
+                info!("Restored persisted session {}", user_id);
+                Some(state)
+            }
+            Err(e) => {
+                warn!("Discarding unreadable persisted session {}: {:?}", user_id, e);
+                None
+            }
+        }
+    }
+
+    // メモリ上のセッション数と上限
+    pub async fn session_usage(&self) -> (usize, usize) {
+        (self.game_states.read().await.len(), self.max_sessions)
+    }
+
+    // 対象のセッションだけをロックして状態を変更し、変更後のスナップショットを返す
+    // （描画はロックを解放してから行う）
+    // 盤面を描画したときと異なるリビジョンからの操作は古いボタンとして拒否する
+    async fn update_session<F>(&self, user_id: u64, revision: u64, update: F) -> Result<GameState, SessionError>
+    where
+        F: FnOnce(&mut GameState) -> Result<(), SessionError>,
+    {
+        let entry = self.session_entry(user_id).await?;
+        let mut state = entry.lock().await;
+        if state.revision != revision {
+            return Err(SessionError::Stale);
+        }
+        state.touch();
+        update(&mut state)?;
+        state.revision = self.next_revision();
+        // 盤面が変わるたびに保存し、再起動や異常終了のあとも古いメッセージのボタンから続けられるようにする
+        // （続けて操作されても古い状態があとから保存されないよう、ロックしたまま保存を予約する）
+        self.persist_session(user_id, &state).await;
+        Ok(state.clone())
+    }
+
+    pub(crate) fn next_revision(&self) -> u64 {
+        self.revisions.fetch_add(1, Ordering::Relaxed)
+    }
+
+    // 入力された単語をセットし、色の入力待ちにする
+    pub async fn submit_word(&self, user_id: u64, revision: u64, word: &str) -> Result<BoardView, SessionError> {
+        let word = word.to_uppercase();
+        let state = self.update_session(user_id, revision, |state| state.enter_word(word)).await?;
+
+        self.letter_view(user_id, &state).await
+    }
+
+    // 指定位置の文字の色を切り替える
+    pub async fn cycle_letter(&self, user_id: u64, revision: u64, index: usize) -> Result<BoardView, SessionError> {
+        let state = self.update_session(user_id, revision, |state| state.cycle_letter(index)).await?;
+
+        self.letter_view(user_id, &state).await
+    }
+
+    // 指定位置の文字の色を設定する
+    pub async fn set_letter(&self, user_id: u64, revision: u64, index: usize, result: LetterResult) -> Result<BoardView, SessionError> {
+        let state = self.update_session(user_id, revision, |state| state.set_letter(index, result)).await?;
+
+        self.letter_view(user_id, &state).await
+    }
+
+    // すべての文字の色をまとめて設定する
+    pub async fn set_letters(&self, user_id: u64, revision: u64, colors: Vec<LetterResult>) -> Result<BoardView, SessionError> {
+        let state = self.update_session(user_id, revision, |state| state.set_letters(&colors)).await?;
+
+        self.letter_view(user_id, &state).await
+    }
+
+    async fn letter_view(&self, user_id: u64, state: &GameState) -> Result<BoardView, SessionError> {
+        // 共有した盤面は開始した人の入力方法で表示する
+        let style = self.settings().input_style(state.shared.map_or(user_id, |board| board.host));
+        let controls = match &state.phase {
+            Phase::AwaitingColors { word, results } if style == InputStyle::Select => BoardControls::LetterMenu {
+                word: word.clone(),
+                results: results.clone(),
+            },
+            Phase::AwaitingColors { word, results } => BoardControls::LetterResults {
+                word: word.clone(),
+                results: results.clone(),
+            },
+            Phase::Importing { rows, selected } => BoardControls::Importing {
+                rows: rows.clone(),
+                selected: *selected,
+            },
+            Phase::AwaitingWord | Phase::Completed => return Err(SessionError::NotAwaitingColors),
+        };
+
+        let (description, fields) = self.update_embed_content(state).await;
+        let description = match &controls {
+            BoardControls::LetterResults { .. } => join_paragraphs(&description, state.locale.text(Text::ColorHint)),
+            BoardControls::LetterMenu { .. } => join_paragraphs(&description, state.locale.text(Text::ColorMenuHint)),
+            _ => description,
+        };
+        Ok(BoardView {
+            session: user_id,
+            revision: state.revision,
+            locale: state.locale,
+            description,
+            fields,
+            controls,
+            history: state.guesses.clone(),
+            explained: Vec::new(),
+        })
+    }
+
+    // このセッションの盤面の言語を切り替え、同じ段階の盤面を描き直す
+    // 提案を表示していた盤面は分析中の表示を返す（提案は finish_analysis で計算し直す）
+    pub async fn toggle_locale(&self, user_id: u64, revision: u64) -> Result<BoardView, SessionError> {
+        let state = self.update_session(user_id, revision, |state| {
+            state.locale = state.locale.toggled();
+            Ok(())
+        }).await?;
+
+        match state.phase {
+            Phase::AwaitingColors { .. } | Phase::Importing { .. } => self.letter_view(user_id, &state).await,
+            Phase::Completed => self.imported_view(user_id, &state).await,
+            // 提案を覚えていればその場で描き直す
+            Phase::AwaitingWord if self.has_cached_suggestions(&state).await => self.finish_analysis(user_id).await,
+            Phase::AwaitingWord => Ok(self.analyzing_view(user_id, &state).await),
+        }
+    }
+
+    // 確定済みの推測の色を修正する
+    pub async fn reopen_guess(&self, user_id: u64, revision: u64, index: usize) -> Result<BoardView, SessionError> {
+        let state = self.update_session(user_id, revision, |state| state.reopen_guess(index)).await?;
+
+        self.letter_view(user_id, &state).await
+    }
+
+    // 現在の単語と色を履歴に確定する（提案の計算は finish_analysis で行う）
+    pub async fn confirm_guess(&self, user_id: u64, revision: u64) -> Result<BoardView, SessionError> {
+        let state = self.update_session(user_id, revision, GameState::confirm).await?;
+
+        // 共有した盤面は個人の成績に数えない
+        if state.phase == Phase::Completed && state.shared.is_none() {
+            if let Err(e) = self.record_win(user_id, state.guild_id, state.guesses.len(), MAX_GUESSES).await {
+                warn!("Failed to record win for {}: {:?}", user_id, e);
+            }
+        }
+        let mut view = self.analyzing_view(user_id, &state).await;
+        // 確定した直後の分析だけは、取り消して確定前の盤面に戻せる
+        if let BoardControls::Analyzing { cancellable } = &mut view.controls {
+            *cancellable = true;
+        }
+        Ok(view)
+    }
+
+    // 実行中の分析を止め、最後に確定した推測を確定前の色の入力に戻す
+    pub async fn cancel_analysis(&self, user_id: u64, revision: u64) -> Result<BoardView, SessionError> {
+        let state = self.update_session(user_id, revision, |state| state.reopen_guess(state.guesses.len().saturating_sub(1))).await?;
+        // 盤面を戻してから計算を止める（リビジョンが変わっているため、止まった分析は盤面を上書きしない）
+        self.analyses.cancel(user_id, revision);
+
+        self.letter_view(user_id, &state).await
+    }
+
+    // 履歴を確定した直後の盤面（正解なら終了、それ以外は分析中の表示）
+    async fn analyzing_view(&self, user_id: u64, state: &GameState) -> BoardView {
+        let (basic_description, fields) = self.update_embed_content(state).await;
+
+        if state.phase == Phase::Completed {
+            return BoardView {
+                session: user_id,
+                revision: state.revision,
+                locale: state.locale,
+                description: basic_description,
+                fields,
+                controls: BoardControls::Finished,
+                history: state.guesses.clone(),
+                explained: Vec::new(),
+            };
+        }
+
+        BoardView {
+            session: user_id,
+            revision: state.revision,
+            locale: state.locale,
+            description: join_paragraphs(&basic_description, state.locale.text(Text::Analyzing)),
+            fields,
+            controls: BoardControls::Analyzing { cancellable: false },
+            history: state.guesses.clone(),
+            explained: Vec::new(),
+        }
+    }
+
+    // 単語提案を計算してセッションに保存し、最終的な盤面を返す
+    pub async fn finish_analysis(&self, user_id: u64) -> Result<BoardView, SessionError> {
+        self.finish_analysis_reporting(user_id, None).await
+    }
+
+    // finish_analysis と同じ計算を、順番待ちとスコア計算の進み具合を progress に送りながら行う
+    pub(crate) async fn finish_analysis_reporting(
+        &self,
+        user_id: u64,
+        progress: Option<watch::Sender<Option<AnalysisProgress>>>,
+    ) -> Result<BoardView, SessionError> {
+        let entry = self.session_entry(user_id).await?;
+
+        // 提案の計算には時間がかかるため、スナップショットに対して行う
+        let snapshot = entry.lock().await.clone();
+        if snapshot.phase == Phase::Completed {
+            return Err(SessionError::AlreadySolved);
+        }
+        // 同じ盤面の分析は1つだけ行う（盤面の更新はリビジョンを確かめてから行うため、結果が二重に反映されることもない）
+        let Some(mut running) = self.analyses.begin(user_id, snapshot.revision) else {
+            return Err(SessionError::AnalysisRunning);
+        };
+        if let Some(progress) = progress {
+            running = running.report_to(progress);
+        }
+        // 計算の実行枠を待つ（待っている間に取り消された、または新しい盤面の分析に譲った場合はやめる）
+        // 提案を覚えている盤面は計算しないので待たない
+        let _slot = if self.has_cached_suggestions(&snapshot).await {
+            None
+        } else {
+            let slot = tokio::select! {
+                slot = self.analysis_queue.enter(user_id, |position| running.report(ProgressStage::Queued { position })) => slot,
+                _ = running.cancelled() => None,
+            };
+            let Some(slot) = slot else {
+                return Err(SessionError::Stale);
+            };
+            Some(slot)
+        };
+        // 候補が0件なら提案の代わりに食い違っている推測を示す
+        let conflict = self.find_conflict(&snapshot).await;
+        // 提案できたときは候補数とおすすめの欄、できないときは理由の案内
+        let suggestion = match conflict {
+            Some(conflict) => Err(snapshot.locale.conflict(conflict)),
+            None => {
+                let started = std::time::Instant::now();
+                let suggestion = self.suggest_words(&snapshot, &running).await;
+                self.metrics.record_solver_latency(started.elapsed());
+                suggestion
+            }
+        };
+        let last_suggestion = match &suggestion {
+            Ok((fields, _)) => fields.iter()
+                .filter(|f| f.section == BoardSection::Suggestions)
+                .map(|f| f.value.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Err(message) => message.clone(),
+        };
+
+        {
+            let mut state = entry.lock().await;
+            if state.revision != snapshot.revision {
+                return Err(SessionError::Stale);
+            }
+            state.last_suggestion = last_suggestion;
+            // ボタンを押したときは計算し直さず、提案したときの値を使う
+            state.explanations = suggestion.as_ref().map_or_else(|_| Vec::new(), |(_, explanations)| explanations.clone());
+        }
+
+        let (mut description, mut fields) = self.update_embed_content(&snapshot).await;
+        let mut explained = Vec::new();
+        match suggestion {
+            Ok((suggestion, explanations)) => {
+                fields.extend(suggestion);
+                explained = explanations.into_iter().map(|e| e.word).collect();
+            }
+            Err(message) => description = join_paragraphs(&description, &message),
+        }
+
+        Ok(BoardView {
+            session: user_id,
+            revision: snapshot.revision,
+            locale: snapshot.locale,
+            description,
+            fields,
+            controls: match conflict {
+                Some(conflict) => BoardControls::EditGuess { index: conflict.suspect() },
+                None => BoardControls::NewWord,
+            },
+            history: snapshot.guesses,
+            explained,
+        })
+    }
+
+    // 分析中の盤面に順番待ちやスコア計算の進み具合を添える（ボタンはそのまま残す）
+    pub(crate) async fn progress_view(&self, user_id: u64, progress: AnalysisProgress) -> Result<BoardView, SessionError> {
+        let state = self.session_entry(user_id).await?.lock().await.clone();
+        if state.revision != progress.revision {
+            return Err(SessionError::Stale);
+        }
+
+        let mut view = self.analyzing_view(user_id, &state).await;
+        let note = match progress.stage {
+            ProgressStage::Queued { position } => state.locale.queue_position(position),
+            ProgressStage::Scoring { scored, total } => state.locale.scoring_progress(scored, total),
+        };
+        view.description = join_paragraphs(&view.description, &note);
+        view.controls = BoardControls::Unchanged;
+        Ok(view)
+    }
+
+    // 提案したときに計算したおすすめの単語の説明（その盤面のボタンからの操作のみ）
+    pub async fn explain_suggestion(&self, user_id: u64, revision: u64, index: usize) -> Result<GuessExplanation, SessionError> {
+        let state = self.session(user_id).await.ok_or(SessionError::NotFound)?;
+        if state.revision != revision {
+            return Err(SessionError::Stale);
+        }
+        state.explanations.get(index).cloned().ok_or(SessionError::Stale)
+    }
+
+    // 終了した盤面の推測（その盤面のボタンからの操作のみ）
+    pub async fn finished_guesses(&self, user_id: u64, revision: u64) -> Result<Vec<WordleGuess>, SessionError> {
+        let state = self.session(user_id).await.ok_or(SessionError::NotFound)?;
+        if state.revision != revision || state.phase != Phase::Completed {
+            return Err(SessionError::Stale);
+        }
+        Ok(state.guesses)
+    }
+
+    // 確定済みの推測に合う単語の数（提案の計算は行わない）
+    pub async fn remaining_candidates(&self, user_id: u64) -> Result<usize, SessionError> {
+        let state = self.session(user_id).await.ok_or(SessionError::NotFound)?;
+        let words = self.words_for(&state).await;
+        Ok(self.filter_words_by_constraints(&words, &state).len())
+    }
+
+    // 提案の難易度を変える（次の提案から反映する）
+    pub async fn set_difficulty(&self, user_id: u64, difficulty: Difficulty) -> bool {
+        let Ok(entry) = self.session_entry(user_id).await else {
+            return false;
+        };
+        entry.lock().await.difficulty = difficulty;
+        true
+    }
+
+    // ハードモードの確認を切り替える（盤面は変わらないためリビジョンは進めない）
+    pub async fn set_hard_mode(&self, user_id: u64, enabled: bool) -> bool {
+        let Ok(entry) = self.session_entry(user_id).await else {
+            return false;
+        };
+        entry.lock().await.hard_mode = enabled;
+        true
+    }
+
+    pub async fn hard_mode_violations(&self, user_id: u64, word: &str) -> Vec<HardModeViolation> {
+        self.session(user_id).await
+            .map(|state| state.hard_mode_violations(word))
+            .unwrap_or_default()
+    }
+
+    // 厳格なサーバーのセッションで、単語リストにない単語なら近い単語を返す（受け付けられれば None）
+    // 単語リストにその文字数の単語がなければ確かめない
+    pub async fn unknown_word(&self, user_id: u64, word: &str) -> Option<Vec<String>> {
+        let state = self.session(user_id).await?;
+        if !self.settings().is_strict(state.guild_id) {
+            return None;
+        }
+        let word = word.trim().to_uppercase();
+        let words = self.words_for(&state).await;
+        let arena = words.words_of_length(word.chars().count())?;
+        if arena.iter().any(|(_, w)| w == word) {
+            return None;
+        }
+        Some(solver::nearest_words(&word, arena.iter().map(|(_, w)| w), NEAREST_WORDS).into_iter().map(str::to_string).collect())
+    }
+
+    // セッションのスナップショットを取得
+    pub async fn session(&self, user_id: u64) -> Option<GameState> {
+        let entry = self.session_entry(user_id).await.ok()?;
+        let state = entry.lock().await.clone();
+        Some(state)
+    }
+}
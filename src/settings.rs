@@ -0,0 +1,488 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::emoji::{describe_chain, EmojiTier};
+use crate::i18n::Locale;
+use crate::rewards::RoleReward;
+use crate::solver::MAX_GUESSES;
+use crate::Bot;
+
+// 提案の難易度（補助の多さ）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    // よく使われる単語から3つだけ、絞り込める数の説明つきで提案する
+    Beginner,
+    #[default]
+    Standard,
+    // エントロピー順に多くの候補を、あまり使われない単語や答えの候補でない絞り込み用の単語も含めて提案する
+    Advanced,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Beginner, Difficulty::Standard, Difficulty::Advanced];
+
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Standard => "standard",
+            Difficulty::Advanced => "advanced",
+        }
+    }
+
+    // 盤面に表示するおすすめの数
+    pub fn suggestion_limit(self, suggestion_count: usize) -> usize {
+        match self {
+            Difficulty::Beginner => suggestion_count.min(3),
+            Difficulty::Standard => suggestion_count,
+            Difficulty::Advanced => MAX_SUGGESTION_COUNT,
+        }
+    }
+}
+
+// /wordle・/daily の推測回数の上限
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuessLimit {
+    // 公式と同じ6回
+    #[default]
+    Classic,
+    // ゆるめの8回
+    Relaxed,
+    // きびしめの4回
+    Brutal,
+}
+
+impl GuessLimit {
+    pub const ALL: [GuessLimit; 3] = [GuessLimit::Classic, GuessLimit::Relaxed, GuessLimit::Brutal];
+    // 一番多い上限の回数（ボタンに埋め込む推測の数に使う）
+    pub const MOST: usize = 8;
+
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            GuessLimit::Classic => "classic",
+            GuessLimit::Relaxed => "relaxed",
+            GuessLimit::Brutal => "brutal",
+        }
+    }
+
+    pub fn guesses(self) -> usize {
+        match self {
+            GuessLimit::Classic => MAX_GUESSES,
+            GuessLimit::Relaxed => 8,
+            GuessLimit::Brutal => 4,
+        }
+    }
+}
+
+// サーバーの /daily の推測回数の上限（メンバーの結果をそろえるため、変更は翌日から反映する）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyGuessLimit {
+    pub limit: GuessLimit,
+    // limit を使い始める日（UTCのUNIXエポックからの日数）と、それより前の日の上限
+    pub since: u64,
+    pub previous: GuessLimit,
+}
+
+impl DailyGuessLimit {
+    pub fn on(&self, day: u64) -> GuessLimit {
+        if day >= self.since { self.limit } else { self.previous }
+    }
+}
+
+// 色の入力方法（ユーザーごとに /input-style で選ぶ）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputStyle {
+    // 文字ごとのボタンを押すたびに 灰→黄→緑 と切り替える
+    #[default]
+    Buttons,
+    // 1つのメニューで全部の文字の黄と緑をまとめて選ぶ（選ばなかった文字は灰）
+    Select,
+}
+
+impl InputStyle {
+    pub const ALL: [InputStyle; 2] = [InputStyle::Buttons, InputStyle::Select];
+
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.code().eq_ignore_ascii_case(code.trim()))
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            InputStyle::Buttons => "buttons",
+            InputStyle::Select => "select",
+        }
+    }
+}
+
+// 再デプロイせずに変更できる設定（/admin config set で変更し、ストレージに保存する）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // おすすめとして表示する単語数
+    pub suggestion_count: usize,
+    // 単語・絵文字キャッシュを自動で再読み込みする間隔（分、0で無効）
+    pub cache_refresh_minutes: u64,
+    // /wordle の盤面に操作がないとき、ボットから次のヒントを出すまでの時間（分、0で無効）
+    pub auto_hint_minutes: u64,
+    // 言語設定のないサーバーに使う言語
+    pub default_locale: Locale,
+    // /wht で難易度を指定しなかったセッションの難易度
+    pub default_difficulty: Difficulty,
+    // サーバーごとの言語（/config language で変更する）
+    pub guild_locales: BTreeMap<u64, Locale>,
+    // サーバーごとのロール報酬（/admin rewards で変更する）
+    pub role_rewards: BTreeMap<u64, Vec<RoleReward>>,
+    // サーバーごとの結果投稿チャンネル（/config results-channel で設定した場合のみ投稿する）
+    pub results_channels: BTreeMap<u64, u64>,
+    // 結果投稿チャンネルがフォーラムのサーバー（/daily は日ごとの投稿に結果を集める）
+    pub results_forums: BTreeSet<u64>,
+    // サーバーごとの結果の転送先URL（/config results-webhook で設定した場合のみ送る）
+    pub result_webhooks: BTreeMap<u64, String>,
+    // サーバーごとの盤面のマスの表示方法の順番（/config emoji-fallback で変更する）
+    pub emoji_chains: BTreeMap<u64, Vec<EmojiTier>>,
+    // 提案・答え・候補に使わない単語（全サーバー共通。大文字）
+    pub banned_words: BTreeSet<String>,
+    // サーバーごとに追加で使わない単語（/config banned-words で変更する）
+    pub guild_banned_words: BTreeMap<u64, BTreeSet<String>>,
+    // 単語リストにない推測を受け付けないサーバー（/config strict-words で変更する）
+    pub strict_guilds: BTreeSet<u64>,
+    // 全サーバー共通の /daily の単語を使うサーバー（/config shared-daily で変更する）
+    pub shared_daily_guilds: BTreeSet<u64>,
+    // サーバーごとの /daily の推測回数の上限（/config daily-guesses で変更する。未設定は6回）
+    pub daily_guess_limits: BTreeMap<u64, DailyGuessLimit>,
+    // 成績を記録・公開しないユーザー（/privacy で変更する）
+    pub private_users: BTreeSet<u64>,
+    // ユーザーごとの色の入力方法（/input-style で変更する。未設定はボタン）
+    pub input_styles: BTreeMap<u64, InputStyle>,
+    // 機能フラグ（未設定の機能は無効）
+    pub features: BTreeMap<String, bool>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            suggestion_count: 5,
+            cache_refresh_minutes: 0,
+            auto_hint_minutes: 0,
+            default_locale: Locale::Ja,
+            default_difficulty: Difficulty::Standard,
+            guild_locales: BTreeMap::new(),
+            role_rewards: BTreeMap::new(),
+            results_channels: BTreeMap::new(),
+            results_forums: BTreeSet::new(),
+            result_webhooks: BTreeMap::new(),
+            emoji_chains: BTreeMap::new(),
+            banned_words: BTreeSet::new(),
+            guild_banned_words: BTreeMap::new(),
+            strict_guilds: BTreeSet::new(),
+            shared_daily_guilds: BTreeSet::new(),
+            daily_guess_limits: BTreeMap::new(),
+            private_users: BTreeSet::new(),
+            input_styles: BTreeMap::new(),
+            features: BTreeMap::new(),
+        }
+    }
+}
+
+// /admin config set で指定できるキー（機能フラグは feature.<名前>）
+pub const SETTING_KEYS: [&str; 7] = [
+    "suggestion_count",
+    "cache_refresh_minutes",
+    "auto_hint_minutes",
+    "default_locale",
+    "default_difficulty",
+    "banned_words",
+    "feature.<名前>",
+];
+
+pub const MAX_SUGGESTION_COUNT: usize = 25;
+
+impl Settings {
+    pub fn feature(&self, name: &str) -> bool {
+        self.features.get(name).copied().unwrap_or(false)
+    }
+
+    // キーと値の文字列から設定を変更する（エラーはそのままユーザーに表示する）
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        match key {
+            "suggestion_count" => {
+                self.suggestion_count = match value.parse() {
+                    Ok(n) if (1..=MAX_SUGGESTION_COUNT).contains(&n) => n,
+                    _ => return Err(format!("1〜{} の数値を指定してください", MAX_SUGGESTION_COUNT)),
+                };
+            }
+            "cache_refresh_minutes" => {
+                self.cache_refresh_minutes = value.parse()
+                    .map_err(|_| "0以上の数値（分）を指定してください".to_string())?;
+            }
+            "auto_hint_minutes" => {
+                self.auto_hint_minutes = value.parse()
+                    .map_err(|_| "0以上の数値（分）を指定してください".to_string())?;
+            }
+            "default_locale" => {
+                self.default_locale = Locale::parse(value).ok_or_else(locale_error)?;
+            }
+            "default_difficulty" => {
+                self.default_difficulty = Difficulty::parse(value).ok_or_else(|| {
+                    let codes: Vec<_> = Difficulty::ALL.iter().map(|d| format!("`{}`", d.code())).collect();
+                    format!("{} のいずれかを指定してください", codes.join(" / "))
+                })?;
+            }
+            // カンマ区切りで全体を置き換える（空にすると解除）
+            "banned_words" => {
+                self.banned_words = parse_banned_words(value)?.into_iter().collect();
+            }
+            _ => {
+                let Some(name) = key.strip_prefix("feature.").filter(|name| !name.is_empty()) else {
+                    return Err(format!("不明なキーです（指定できるキー: {}）", SETTING_KEYS.join(", ")));
+                };
+                let enabled = match value.to_lowercase().as_str() {
+                    "on" | "true" | "1" => true,
+                    "off" | "false" | "0" => false,
+                    _ => return Err("on / off を指定してください".to_string()),
+                };
+                self.features.insert(name.to_string(), enabled);
+            }
+        }
+        Ok(())
+    }
+
+    // 全サーバー共通の単語とサーバーの単語のどちらかに含まれるか
+    pub fn is_banned(&self, guild: Option<u64>, word: &str) -> bool {
+        let word = word.to_uppercase();
+        self.banned_words.contains(&word)
+            || guild.and_then(|guild| self.guild_banned_words.get(&guild)).is_some_and(|words| words.contains(&word))
+    }
+
+    pub fn has_banned_words(&self, guild: Option<u64>) -> bool {
+        !self.banned_words.is_empty() || guild.is_some_and(|guild| self.guild_banned_words.contains_key(&guild))
+    }
+
+    // 単語リストにない推測を受け付けないか（DMでは受け付ける）
+    pub fn is_strict(&self, guild: Option<u64>) -> bool {
+        guild.is_some_and(|guild| self.strict_guilds.contains(&guild))
+    }
+
+    // 結果投稿チャンネルがフォーラムなら、そのチャンネル
+    pub fn results_forum(&self, guild: u64) -> Option<u64> {
+        self.results_channels.get(&guild).copied().filter(|_| self.results_forums.contains(&guild))
+    }
+
+    // /daily でほかのサーバーと同じ単語を使うか
+    pub fn is_shared_daily(&self, guild: u64) -> bool {
+        self.shared_daily_guilds.contains(&guild)
+    }
+
+    // その日のサーバーの /daily の推測回数の上限
+    pub fn daily_guess_limit(&self, guild: u64, day: u64) -> GuessLimit {
+        self.daily_guess_limits.get(&guild).map_or(GuessLimit::Classic, |limit| limit.on(day))
+    }
+
+    // 成績の記録・ランキング・結果の投稿から外すユーザーか
+    pub fn is_private(&self, user: u64) -> bool {
+        self.private_users.contains(&user)
+    }
+
+    pub fn input_style(&self, user: u64) -> InputStyle {
+        self.input_styles.get(&user).copied().unwrap_or_default()
+    }
+
+    // サーバーの言語（未設定なら既定の言語）
+    pub fn locale_for(&self, guild: Option<u64>) -> Locale {
+        guild
+            .and_then(|guild| self.guild_locales.get(&guild).copied())
+            .unwrap_or(self.default_locale)
+    }
+
+    pub fn describe(&self) -> String {
+        let mut message = format!(
+            "suggestion_count: {}\ncache_refresh_minutes: {}\nauto_hint_minutes: {}\ndefault_locale: {}\ndefault_difficulty: {}\n",
+            self.suggestion_count, self.cache_refresh_minutes, self.auto_hint_minutes, self.default_locale, self.default_difficulty.code()
+        );
+        for (guild, locale) in &self.guild_locales {
+            message.push_str(&format!("locale.{}: {}\n", guild, locale));
+        }
+        for (guild, rewards) in &self.role_rewards {
+            message.push_str(&format!("role_rewards.{}: {}件\n", guild, rewards.len()));
+        }
+        for (guild, channel) in &self.results_channels {
+            let forum = if self.results_forums.contains(guild) { "（フォーラム）" } else { "" };
+            message.push_str(&format!("results_channel.{}: {}{}\n", guild, channel, forum));
+        }
+        // URLにはトークンが含まれることがあるため表示しない
+        for guild in self.result_webhooks.keys() {
+            message.push_str(&format!("result_webhook.{}: 設定済み\n", guild));
+        }
+        for (guild, chain) in &self.emoji_chains {
+            message.push_str(&format!("emoji_chain.{}: {}\n", guild, describe_chain(chain)));
+        }
+        if !self.banned_words.is_empty() {
+            message.push_str(&format!("banned_words: {}語\n", self.banned_words.len()));
+        }
+        for (guild, words) in &self.guild_banned_words {
+            message.push_str(&format!("banned_words.{}: {}語\n", guild, words.len()));
+        }
+        for guild in &self.strict_guilds {
+            message.push_str(&format!("strict_words.{}: on\n", guild));
+        }
+        for guild in &self.shared_daily_guilds {
+            message.push_str(&format!("shared_daily.{}: on\n", guild));
+        }
+        for (guild, limit) in &self.daily_guess_limits {
+            message.push_str(&format!("daily_guesses.{}: {}（{}日から）\n", guild, limit.limit.code(), limit.since));
+        }
+        for (name, enabled) in &self.features {
+            message.push_str(&format!("feature.{}: {}\n", name, if *enabled { "on" } else { "off" }));
+        }
+        message
+    }
+}
+
+// カンマ・空白区切りの単語（英字のみ、大文字にそろえる）
+pub fn parse_banned_words(value: &str) -> Result<Vec<String>, String> {
+    let words: Vec<String> = value.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|w| !w.is_empty())
+        .map(str::to_uppercase)
+        .collect();
+    match words.iter().find(|w| !w.chars().all(|c| c.is_ascii_alphabetic())) {
+        Some(word) => Err(format!("英字だけの単語を指定してください: {}", word)),
+        None => Ok(words),
+    }
+}
+
+pub fn locale_error() -> String {
+    let codes: Vec<_> = Locale::ALL.iter().map(|l| format!("`{}`", l.code())).collect();
+    format!("{} のいずれかを指定してください", codes.join(" / "))
+}
+
+impl Bot {
+    // 現在の設定（変更されても取得済みのスナップショットは変わらない）
+    pub fn settings(&self) -> Arc<Settings> {
+        self.settings.load_full()
+    }
+
+    // ストレージに保存された設定を読み込む（未保存なら既定値のまま）
+    pub async fn load_settings(&self) -> anyhow::Result<()> {
+        let Some(value) = self.storage.load_settings().await? else {
+            return Ok(());
+        };
+
+        let settings: Settings = serde_json::from_value(value).context("Failed to parse stored settings")?;
+        info!("Loaded runtime settings: {:?}", settings);
+        let _writing = self.settings_writes.lock().await;
+        self.settings.store(Arc::new(settings));
+        Ok(())
+    }
+
+    // 設定を置き換えて保存し、保存できた場合のみ反映する
+    pub async fn save_settings(&self, settings: Settings) -> anyhow::Result<()> {
+        self.update_settings(|current| {
+            *current = settings;
+            Some(())
+        })
+        .await
+        .map(|_| ())
+    }
+
+    // 現在の設定を書き換えて保存し、保存できた場合のみ反映する（update が None を返したら保存しない）
+    // 読み込みから反映までロックし、同時に変更されても片方の変更が失われないようにする
+    pub async fn update_settings<T>(&self, update: impl FnOnce(&mut Settings) -> Option<T>) -> anyhow::Result<Option<T>> {
+        let _writing = self.settings_writes.lock().await;
+        let mut settings = Settings::clone(&self.settings.load());
+        let Some(result) = update(&mut settings) else {
+            return Ok(None);
+        };
+        self.storage.save_settings(serde_json::to_value(&settings)?).await?;
+        self.settings.store(Arc::new(settings));
+        Ok(Some(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_updates_known_keys_and_features() {
+        let mut settings = Settings::default();
+
+        settings.set("suggestion_count", "8").unwrap();
+        settings.set("feature.hard-mode", "on").unwrap();
+        settings.set("default_difficulty", "Advanced").unwrap();
+        settings.set("auto_hint_minutes", "5").unwrap();
+
+        assert_eq!(settings.suggestion_count, 8);
+        assert_eq!(settings.auto_hint_minutes, 5);
+        assert!(settings.feature("hard-mode"));
+        assert_eq!(settings.default_difficulty, Difficulty::Advanced);
+        assert!(!settings.feature("unknown"));
+    }
+
+    #[test]
+    fn set_rejects_invalid_values() {
+        let mut settings = Settings::default();
+
+        assert!(settings.set("suggestion_count", "0").is_err());
+        assert!(settings.set("suggestion_count", "many").is_err());
+        assert!(settings.set("no_such_key", "1").is_err());
+        assert!(settings.set("feature.", "on").is_err());
+        assert!(settings.set("default_difficulty", "expert").is_err());
+        assert!(settings.set("auto_hint_minutes", "-1").is_err());
+        assert!(settings.set("banned_words", "crane, sl4te").is_err());
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{"suggestion_count": 3}"#).unwrap();
+        assert_eq!(settings.suggestion_count, 3);
+        assert_eq!(settings.default_locale, Locale::Ja);
+    }
+
+    #[test]
+    fn guild_locale_overrides_default() {
+        let mut settings = Settings::default();
+        settings.guild_locales.insert(1, Locale::En);
+
+        assert_eq!(settings.locale_for(Some(1)), Locale::En);
+        assert_eq!(settings.locale_for(Some(2)), Locale::Ja);
+        assert_eq!(settings.locale_for(None), Locale::Ja);
+        assert!(settings.clone().set("default_locale", "fr").is_err());
+
+        let restored: Settings = serde_json::from_value(serde_json::to_value(&settings).unwrap()).unwrap();
+        assert_eq!(restored, settings);
+    }
+
+    #[test]
+    fn banned_words_merge_global_and_guild_lists() {
+        let mut settings = Settings::default();
+        settings.set("banned_words", "crane, slate").unwrap();
+        settings.guild_banned_words.insert(1, ["TRACE".to_string()].into());
+
+        assert!(settings.is_banned(None, "crane"));
+        assert!(settings.is_banned(Some(1), "TRACE"));
+        assert!(!settings.is_banned(Some(2), "TRACE"));
+        assert!(settings.has_banned_words(Some(2)));
+
+        settings.set("banned_words", "").unwrap();
+        assert!(!settings.has_banned_words(Some(2)));
+        assert!(settings.has_banned_words(Some(1)));
+    }
+}
@@ -0,0 +1,129 @@
+// 単語・絵文字キャッシュのローカルスナップショット
+// 起動直後はここから即座に復元し、ストレージからの最新データ取得はバックグラウンドで行う
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::storage::WordRecord;
+use crate::word_cache::WordCache;
+use crate::Bot;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheSnapshot {
+    // 保存時刻（UNIX秒）
+    pub saved_at: u64,
+    pub words: Vec<WordRecord>,
+    pub emojis: HashMap<String, String>,
+}
+
+impl CacheSnapshot {
+    pub async fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        let bytes = match tokio::fs::read(path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("cannot read {}", path.display())),
+        };
+        let snapshot = serde_json::from_slice(&bytes).with_context(|| format!("cannot parse {}", path.display()))?;
+        Ok(Some(snapshot))
+    }
+
+    // 書き込み途中で落ちても壊れたファイルが残らないよう、一時ファイルから置き換える
+    pub async fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let temp = path.with_extension("tmp");
+        tokio::fs::write(&temp, serde_json::to_vec(self)?).await?;
+        tokio::fs::rename(&temp, path).await?;
+        Ok(())
+    }
+}
+
+impl Bot {
+    // スナップショットの保存先を設定（未設定なら起動時にストレージから同期的に読み込む）
+    pub fn with_cache_snapshot(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_snapshot = Some(path.into());
+        self
+    }
+
+    // スナップショットからキャッシュを復元し、復元できたかを返す（空のキャッシュだけを埋める）
+    pub(crate) async fn restore_cache_snapshot(&self) -> bool {
+        let Some(path) = &self.cache_snapshot else {
+            return false;
+        };
+
+        let snapshot = match CacheSnapshot::load(path).await {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return false,
+            Err(e) => {
+                warn!("Ignoring cache snapshot: {:?}", e);
+                return false;
+            }
+        };
+
+        let mut words = self.word_cache.write().await;
+        if words.is_empty() {
+            *words = WordCache::new(snapshot.words);
+            self.word_versions.replace(WordCache::default(), &words);
+        }
+        let mut emojis = self.emoji_cache.write().await;
+        if emojis.is_empty() {
+            *emojis = snapshot.emojis;
+        }
+
+        info!(
+            "Restored cache snapshot from {} ({} words, {} emojis, saved at {})",
+            path.display(), words.len(), emojis.len(), snapshot.saved_at
+        );
+        !words.is_empty()
+    }
+
+    // 現在のキャッシュをスナップショットとして保存
+    pub(crate) async fn save_cache_snapshot(&self) {
+        let Some(path) = &self.cache_snapshot else {
+            return;
+        };
+
+        let snapshot = CacheSnapshot {
+            saved_at: crate::startup_millis() / 1000,
+            words: self.word_cache.read().await.to_records(),
+            emojis: self.emoji_cache.read().await.clone(),
+        };
+        if snapshot.words.is_empty() {
+            return;
+        }
+
+        match snapshot.save(path).await {
+            Ok(()) => info!("Saved cache snapshot to {}", path.display()),
+            Err(e) => warn!("Failed to save cache snapshot: {:?}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn snapshot_round_trips_through_disk() {
+        let path = std::env::temp_dir().join(format!("wht-snapshot-{}/cache.json", std::process::id()));
+        assert!(CacheSnapshot::load(&path).await.unwrap().is_none());
+
+        let snapshot = CacheSnapshot {
+            saved_at: 1,
+            words: vec![WordRecord { id: 1, word: "crane".to_string() }],
+            emojis: HashMap::from([("a_green".to_string(), "<:a_green:1>".to_string())]),
+        };
+        snapshot.save(&path).await.unwrap();
+
+        let loaded = CacheSnapshot::load(&path).await.unwrap().unwrap();
+        assert_eq!(loaded.words[0].word, "crane");
+        assert_eq!(loaded.emojis["a_green"], "<:a_green:1>");
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+// /solve: これまでの推測と結果をまとめて入力し、盤面を再現して提案を受け取る
+// （「crane BYXXG, moist XXGYB」のように、1つずつボタンで入力しなくて済む）
+use serenity::all::{CommandInteraction, CommandOptionType, CreateCommand, CreateCommandOption, ResolvedValue};
+use tracing::info;
+
+use crate::i18n;
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::solver::{self, WordleGuess};
+use crate::Bot;
+
+// 一度に入力できる推測の数
+const MAX_GUESSES: usize = 10;
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("solve").add_option(
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "guesses",
+            "推測と結果（例: crane BYXXG, moist XXGYB。G: 緑、Y: 黄、B / X: 灰）",
+        )
+        .required(true),
+    )
+}
+
+// 「単語 結果」または「単語:結果」をカンマ・セミコロン・改行で区切って並べたものを読む
+// 読めなかったときは、その部分を返す
+pub fn parse_history(input: &str) -> Result<Vec<WordleGuess>, String> {
+    let entries: Vec<&str> = input.split([',', ';', '\n']).map(str::trim).filter(|e| !e.is_empty()).collect();
+    if entries.is_empty() || entries.len() > MAX_GUESSES {
+        return Err(input.trim().to_string());
+    }
+
+    entries.into_iter()
+        .map(|entry| {
+            let mut parts = entry.split(|c: char| c == ':' || c.is_whitespace()).filter(|p| !p.is_empty());
+            let guess = match (parts.next(), parts.next(), parts.next()) {
+                (Some(word), Some(pattern), None) => WordleGuess::parse(word, pattern),
+                _ => None,
+            };
+            guess.filter(|guess| solver::is_valid_word(&guess.word)).ok_or_else(|| entry.to_string())
+        })
+        .collect()
+}
+
+impl Bot {
+    pub(crate) async fn handle_solve_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let input = command.data.options().into_iter()
+            .find_map(|o| match o.value {
+                ResolvedValue::String(value) if o.name == "guesses" => Some(value),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let guesses = match parse_history(input) {
+            Ok(guesses) => guesses,
+            Err(entry) => {
+                self.responder(ctx, command, ReplyMode::Ephemeral)
+                    .send(Reply::content(locale.solve_invalid_entry(&entry)))
+                    .await;
+                return;
+            }
+        };
+
+        // 提案の計算は時間がかかることがあるため自動で保留される
+        self.responder(ctx, command, ReplyMode::NewMessage)
+            .run(async {
+                info!("Solving {} pasted guesses for {}", guesses.len(), command.user.id);
+                let view = self.import_guesses(command.user.id.get(), command.guild_id.map(|g| g.get()), guesses).await;
+                self.board_reply(locale, view)
+            })
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LetterResult::{Gray, Green, Yellow};
+
+    #[test]
+    fn parses_pasted_history() {
+        let guesses = parse_history("crane BYXXG, moist:XXGYB\nLIGHT ggggg").unwrap();
+        assert_eq!(guesses.len(), 3);
+        assert_eq!(guesses[0].word, "CRANE");
+        assert_eq!(guesses[0].results, [Gray, Yellow, Gray, Gray, Green]);
+        assert_eq!(guesses[1].results, [Gray, Gray, Green, Yellow, Gray]);
+
+        assert_eq!(parse_history("crane BYXXG, moist").unwrap_err(), "moist");
+        assert_eq!(parse_history("cranes BYXXGG").unwrap_err(), "cranes BYXXGG");
+        assert!(parse_history(" , ").is_err());
+    }
+}
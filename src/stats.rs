@@ -0,0 +1,347 @@
+// ユーザーごとの成績（遊んだ数・正解数・連続正解日数・平均推測回数・推測回数の分布）と /stats
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use serenity::all::{Colour, CommandInteraction, CreateCommand, CreateEmbed};
+
+use crate::i18n::{self, Locale, Text};
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::solver::MAX_GUESSES;
+use crate::Bot;
+
+// 分布の棒の最大の長さ（マスの数）
+const CHART_WIDTH: u32 = 12;
+// 日ごとの成績を残しておく日数（/leaderboard の週間・月間の集計に使う）
+pub const RECENT_DAYS: u64 = 31;
+
+// 期間ごとに集計した成績
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tally {
+    pub wins: u32,
+    pub losses: u32,
+    // 推測回数を記録した正解の数とその合計
+    pub counted_wins: u32,
+    pub total_guesses: u32,
+}
+
+impl Tally {
+    pub fn played(&self) -> u32 {
+        self.wins + self.losses
+    }
+
+    // 正解した割合（%。遊んだ記録がなければ None）
+    pub fn win_rate(&self) -> Option<f64> {
+        let played = self.played();
+        (played > 0).then(|| self.wins as f64 * 100.0 / played as f64)
+    }
+
+    // 正解までの平均推測回数（記録がなければ None）
+    pub fn average_guesses(&self) -> Option<f64> {
+        (self.counted_wins > 0).then(|| self.total_guesses as f64 / self.counted_wins as f64)
+    }
+
+    fn add(&mut self, other: &Tally) {
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.counted_wins += other.counted_wins;
+        self.total_guesses += other.total_guesses;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserStats {
+    pub wins: u32,
+    // 1日1回以上正解した日が続いている日数
+    pub current_streak: u32,
+    pub best_streak: u32,
+    // 最後に正解した日（UTCのUNIXエポックからの日数）
+    pub last_win_day: Option<u64>,
+    // 推測回数を記録した正解の数とその合計（記録を始める前の正解は平均に含めない）
+    pub counted_wins: u32,
+    pub total_guesses: u32,
+    // 当てられずに終わったゲームの数（/wordle で最後の推測まで外れたもの）
+    pub losses: u32,
+    // 推測回数ごとの正解の数（添字は推測回数 - 1。記録を始める前の正解は含めない）
+    // 上限が6回のゲーム（ヘルパーを含む）だけを数える
+    pub distribution: Vec<u32>,
+    // 上限が6回でないゲームの推測回数ごとの正解の数（キーは上限の回数）
+    pub limit_distributions: BTreeMap<usize, Vec<u32>>,
+    // ゲームを終えたサーバー（サーバーごとのランキングに使う。記録を始める前の成績では空）
+    pub guilds: BTreeSet<u64>,
+    // 直近 RECENT_DAYS 日の日ごとの成績（キーは日。記録を始める前の成績は含めない）
+    pub recent: BTreeMap<u64, Tally>,
+}
+
+impl UserStats {
+    // 上限が6回のゲームの正解を記録する
+    pub fn record_win(&mut self, today: u64, guesses: usize) {
+        self.record_win_within(today, guesses, MAX_GUESSES);
+    }
+
+    // 正解を記録する（前日にも正解していれば連続日数を伸ばし、空いていれば1からやり直す）
+    // 推測回数の分布は、推測できる回数の上限ごとに分けて数える
+    pub fn record_win_within(&mut self, today: u64, guesses: usize, limit: usize) {
+        self.wins += 1;
+        self.counted_wins += 1;
+        self.total_guesses += guesses as u32;
+        if guesses > 0 {
+            let distribution = if limit == MAX_GUESSES {
+                &mut self.distribution
+            } else {
+                self.limit_distributions.entry(limit).or_default()
+            };
+            if distribution.len() < guesses {
+                distribution.resize(guesses, 0);
+            }
+            distribution[guesses - 1] += 1;
+        }
+        self.current_streak = match self.last_win_day {
+            Some(day) if day == today => self.current_streak.max(1),
+            Some(day) if day + 1 == today => self.current_streak + 1,
+            _ => 1,
+        };
+        self.best_streak = self.best_streak.max(self.current_streak);
+        self.last_win_day = Some(today);
+
+        let day = self.day(today);
+        day.wins += 1;
+        day.counted_wins += 1;
+        day.total_guesses += guesses as u32;
+    }
+
+    // 当てられずに終わったゲームを記録する（連続正解日数は正解した日で数えるため変えない）
+    pub fn record_loss(&mut self, today: u64) {
+        self.losses += 1;
+        self.day(today).losses += 1;
+    }
+
+    // 今日の集計（古くなった日はここで捨てる）
+    fn day(&mut self, today: u64) -> &mut Tally {
+        self.recent.retain(|day, _| day + RECENT_DAYS > today);
+        self.recent.entry(today).or_default()
+    }
+
+    // これまでの成績の集計
+    pub fn total(&self) -> Tally {
+        Tally { wins: self.wins, losses: self.losses, counted_wins: self.counted_wins, total_guesses: self.total_guesses }
+    }
+
+    // first_day 以降の成績の集計（None ならこれまでのすべて）
+    pub fn tally_since(&self, first_day: Option<u64>) -> Tally {
+        let Some(first_day) = first_day else {
+            return self.total();
+        };
+        let mut tally = Tally::default();
+        for day in self.recent.range(first_day..).map(|(_, day)| day) {
+            tally.add(day);
+        }
+        tally
+    }
+
+    // そのサーバーで遊んだか（サーバーを記録する前の成績は、コマンドを登録したサーバーのものとみなす）
+    pub fn played_in(&self, guild_id: u64, home_guild_id: u64) -> bool {
+        self.guilds.contains(&guild_id) || (self.guilds.is_empty() && guild_id == home_guild_id)
+    }
+
+    pub fn games_played(&self) -> u32 {
+        self.total().played()
+    }
+
+    pub fn win_rate(&self) -> Option<f64> {
+        self.total().win_rate()
+    }
+
+    pub fn average_guesses(&self) -> Option<f64> {
+        self.total().average_guesses()
+    }
+
+    // 今日から見た連続日数（前日までに正解していなければ途切れている）
+    pub fn streak_on(&self, today: u64) -> u32 {
+        match self.last_win_day {
+            Some(day) if day + 1 >= today => self.current_streak,
+            _ => 0,
+        }
+    }
+}
+
+pub fn today() -> u64 {
+    crate::startup_millis() / (24 * 60 * 60 * 1000)
+}
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("stats")
+}
+
+// 推測回数の分布の棒グラフ（一番多い回数の棒を CHART_WIDTH マスにそろえる。上限の回数までは0件でも行を出す）
+pub fn distribution_chart(distribution: &[u32], limit: usize) -> String {
+    let max = distribution.iter().copied().max().unwrap_or(0).max(1);
+    (0..distribution.len().max(limit))
+        .map(|i| {
+            let count = distribution.get(i).copied().unwrap_or(0);
+            let bar = "🟩".repeat(count.saturating_mul(CHART_WIDTH).div_ceil(max) as usize);
+            format!("`{}` {} {}", i + 1, if bar.is_empty() { "▫️" } else { bar.as_str() }, count)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn stats_embed(locale: Locale, name: &str, stats: &UserStats, today: u64) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title(locale.stats_title(name))
+        .color(Colour::DARK_GREEN)
+        .description(locale.stats_summary(stats, today));
+    if stats.distribution.iter().any(|&count| count > 0) {
+        embed = embed.field(locale.text(Text::StatsDistribution), distribution_chart(&stats.distribution, MAX_GUESSES), false);
+    }
+    for (&limit, distribution) in &stats.limit_distributions {
+        if distribution.iter().any(|&count| count > 0) {
+            embed = embed.field(locale.limited_distribution(limit), distribution_chart(distribution, limit), false);
+        }
+    }
+    embed
+}
+
+impl Bot {
+    pub async fn user_stats(&self, user_id: u64) -> anyhow::Result<UserStats> {
+        let Some(value) = self.storage.load_user_stats(user_id).await? else {
+            return Ok(UserStats::default());
+        };
+        serde_json::from_value(value).context("Failed to parse stored user stats")
+    }
+
+    // 正解を記録して更新後の成績を返す（成績を記録しないユーザーは None。limit は推測できる回数の上限）
+    pub async fn record_win(&self, user_id: u64, guild_id: Option<u64>, guesses: usize, limit: usize) -> anyhow::Result<Option<UserStats>> {
+        let today = today();
+        self.update_stats(user_id, &|stats: &mut UserStats| {
+            stats.guilds.extend(guild_id);
+            stats.record_win_within(today, guesses, limit);
+        })
+        .await
+    }
+
+    // 当てられずに終わったゲームを記録する（成績を記録しないユーザーは None）
+    pub async fn record_loss(&self, user_id: u64, guild_id: Option<u64>) -> anyhow::Result<Option<UserStats>> {
+        let today = today();
+        self.update_stats(user_id, &|stats: &mut UserStats| {
+            stats.guilds.extend(guild_id);
+            stats.record_loss(today);
+        })
+        .await
+    }
+
+    // 読み込みから保存まではストレージ側で1つずつ行うため、同時に終わっても記録が失われない
+    async fn update_stats(&self, user_id: u64, update: &(dyn Fn(&mut UserStats) + Send + Sync)) -> anyhow::Result<Option<UserStats>> {
+        if self.settings().is_private(user_id) {
+            return Ok(None);
+        }
+        let updated = self.storage
+            .update_user_stats(user_id, &|stored| {
+                let mut stats: UserStats = match stored {
+                    Some(value) => serde_json::from_value(value).context("Failed to parse stored user stats")?,
+                    None => UserStats::default(),
+                };
+                update(&mut stats);
+                Ok(serde_json::to_value(&stats)?)
+            })
+            .await?;
+        Ok(Some(serde_json::from_value(updated)?))
+    }
+
+    // 自分の成績を本人にだけ表示する（成績を記録しない設定でも、それまでの記録は本人には見せる）
+    pub(crate) async fn handle_stats_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let locale = self.locale_for(command.guild_id);
+        let user = &command.user;
+
+        let reply = async {
+            match self.user_stats(user.id.get()).await {
+                Ok(stats) if stats.games_played() == 0 => Reply::content(locale.text(Text::StatsEmpty)),
+                Ok(stats) => Reply::embed(stats_embed(locale, user.display_name(), &stats, today())),
+                Err(e) => {
+                    self.reporter.report("Failed to load user stats", &e).await;
+                    Reply::content(locale.text(Text::GenericError))
+                }
+            }
+        };
+        self.responder(ctx, command, ReplyMode::Ephemeral).run(reply).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streak_grows_on_consecutive_days_and_resets_after_a_gap() {
+        let mut stats = UserStats::default();
+
+        stats.record_win(10, 3);
+        stats.record_win(10, 4);
+        stats.record_win(11, 5);
+        assert_eq!((stats.wins, stats.current_streak), (3, 2));
+        assert_eq!(stats.average_guesses(), Some(4.0));
+
+        stats.record_win(13, 4);
+        assert_eq!((stats.current_streak, stats.best_streak), (1, 2));
+        assert_eq!(stats.streak_on(14), 1);
+        assert_eq!(stats.streak_on(15), 0);
+    }
+
+    #[test]
+    fn losses_count_as_played_and_wins_fill_the_distribution() {
+        let mut stats = UserStats::default();
+        stats.record_win(1, 3);
+        stats.record_win(1, 3);
+        stats.record_win(2, 5);
+        stats.record_loss(2);
+
+        assert_eq!(stats.games_played(), 4);
+        assert_eq!(stats.win_rate(), Some(75.0));
+        assert_eq!(stats.distribution, vec![0, 0, 2, 0, 1]);
+        // 負けても正解した日の連続は途切れない
+        assert_eq!(stats.current_streak, 2);
+
+        // 一番多い回数の棒が最大の長さになり、6回目までは行を出す
+        let chart = distribution_chart(&stats.distribution, MAX_GUESSES);
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[2], format!("`3` {} 2", "🟩".repeat(CHART_WIDTH as usize)));
+        assert_eq!(lines[4], format!("`5` {} 1", "🟩".repeat(CHART_WIDTH as usize / 2)));
+        assert_eq!(lines[0], "`1` ▫️ 0");
+    }
+
+    #[test]
+    fn wins_under_other_limits_have_their_own_distribution() {
+        let mut stats = UserStats::default();
+        stats.record_win(1, 3);
+        stats.record_win_within(1, 7, 8);
+        stats.record_win_within(1, 2, 4);
+
+        assert_eq!(stats.distribution, vec![0, 0, 1]);
+        assert_eq!(stats.limit_distributions[&8], vec![0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(stats.limit_distributions[&4], vec![0, 1]);
+        assert_eq!((stats.wins, stats.current_streak), (3, 1));
+        // 上限が8回の分布は8行
+        assert_eq!(distribution_chart(&stats.limit_distributions[&8], 8).lines().count(), 8);
+    }
+
+    #[test]
+    fn recent_days_are_tallied_by_period_and_old_days_are_dropped() {
+        let mut stats = UserStats::default();
+        stats.record_win(100, 4);
+        stats.record_loss(120);
+        stats.record_win(125, 2);
+        stats.record_win(125, 3);
+
+        assert_eq!(stats.tally_since(Some(119)), Tally { wins: 2, losses: 1, counted_wins: 2, total_guesses: 5 });
+        assert_eq!(stats.tally_since(Some(121)).played(), 2);
+        assert_eq!(stats.tally_since(None), stats.total());
+
+        // RECENT_DAYS を過ぎた日は集計から消え、これまでの合計にだけ残る
+        stats.record_loss(100 + RECENT_DAYS);
+        assert_eq!(stats.tally_since(Some(0)).played(), 4);
+        assert_eq!(stats.games_played(), 5);
+    }
+}
@@ -0,0 +1,45 @@
+use serenity::all::{CommandInteraction, CreateCommand};
+
+use crate::i18n;
+use crate::respond::{Reply, ReplyContext, ReplyMode};
+use crate::Bot;
+
+pub fn create_command() -> CreateCommand {
+    i18n::command("status")
+}
+
+impl Bot {
+    pub(crate) async fn handle_status_command(&self, ctx: &ReplyContext, command: &CommandInteraction) {
+        let (sessions, max_sessions) = self.session_usage().await;
+        let (word_count, word_bytes) = {
+            let words = self.word_cache.read().await;
+            (words.len(), words.heap_bytes())
+        };
+        let emoji_count = self.emoji_cache.read().await.len();
+
+        let mut message = String::from("📊 **稼働状況**\n");
+        message.push_str(&format!(
+            "セッション: {} / {}（{}%）\n",
+            sessions,
+            max_sessions,
+            sessions * 100 / max_sessions
+        ));
+        message.push_str(&format!(
+            "上限超過時: {}\n",
+            if self.persist_sessions { "古いセッションをストレージに退避" } else { "古いセッションを破棄" }
+        ));
+        message.push_str(&format!(
+            "単語キャッシュ: {}件（{:.1} KiB） / 絵文字: {}件\n",
+            word_count,
+            word_bytes as f64 / 1024.0,
+            emoji_count
+        ));
+        if let Some(version) = self.word_versions.current() {
+            message.push_str(&format!("単語リストの版: `{}`\n", version));
+        }
+
+        self.responder(ctx, command, ReplyMode::Ephemeral)
+            .send(Reply::content(message))
+            .await;
+    }
+}
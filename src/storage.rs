@@ -0,0 +1,893 @@
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use serenity::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, warn};
+
+use crate::supabase::SupabaseClient;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordRecord {
+    pub id: i32,
+    pub word: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmojiRecord {
+    pub emoji_name: String,
+    pub emoji_id: i64,
+    pub discord_format: String,
+}
+
+// 定期的に読み込み直すテーブル
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CachedTable {
+    Words,
+    Emojis,
+}
+
+impl CachedTable {
+    // テーブル名と、行の追加で増えていく列
+    fn columns(self) -> (&'static str, &'static str) {
+        match self {
+            CachedTable::Words => ("words", "id"),
+            CachedTable::Emojis => ("emojis", "emoji_id"),
+        }
+    }
+}
+
+// 保存されている成績（なければ None）から新しい成績を作る
+pub type StatsUpdate<'a> = &'a (dyn Fn(Option<serde_json::Value>) -> anyhow::Result<serde_json::Value> + Send + Sync);
+
+// 単語・絵文字データの取得元（本番はSupabase、テストはインメモリ）
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn fetch_words(&self) -> anyhow::Result<Vec<WordRecord>>;
+
+    async fn fetch_emojis(&self) -> anyhow::Result<Vec<EmojiRecord>>;
+
+    // テーブルの中身の指紋（件数と最大のID）。前回と同じなら全件の取得を省く
+    // 指紋を出せない実装は None を返し、毎回読み込み直す
+    async fn table_fingerprint(&self, _table: CachedTable) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    // 単語を追加（既存の単語は無視）
+    async fn insert_words(&self, words: &[String]) -> anyhow::Result<()>;
+
+    // メモリから追い出したセッションを保存（同じユーザーの既存データは上書き）
+    async fn save_session(&self, user_id: u64, state: serde_json::Value) -> anyhow::Result<()>;
+
+    // 保存したセッションを取り出して削除
+    async fn take_session(&self, user_id: u64) -> anyhow::Result<Option<serde_json::Value>>;
+
+    // 実行時に変更できる設定（未保存なら None）
+    async fn load_settings(&self) -> anyhow::Result<Option<serde_json::Value>>;
+
+    async fn save_settings(&self, settings: serde_json::Value) -> anyhow::Result<()>;
+
+    // ユーザーごとの成績（未保存なら None）
+    async fn load_user_stats(&self, user_id: u64) -> anyhow::Result<Option<serde_json::Value>>;
+
+    async fn save_user_stats(&self, user_id: u64, stats: serde_json::Value) -> anyhow::Result<()>;
+
+    // 全ユーザーの成績（ランキング用）
+    async fn list_user_stats(&self) -> anyhow::Result<Vec<(u64, serde_json::Value)>>;
+
+    // 単語リストの版を記録する（同じ版がすでにあれば何もしない）
+    async fn record_wordlist_version(&self, version: &str, words: &[String]) -> anyhow::Result<()>;
+
+    // 記録した版の単語リスト（なければ None）
+    async fn load_wordlist_version(&self, version: &str) -> anyhow::Result<Option<Vec<String>>>;
+
+    // メンバーが終えたその日の /daily の結果を記録する（新しく記録したら true、すでにあれば何もせず false）
+    async fn record_daily_result(&self, guild_id: u64, day: u64, user_id: u64, result: serde_json::Value) -> anyhow::Result<bool>;
+
+    // 記録したその日の /daily の結果（なければ None）
+    async fn load_daily_result(&self, guild_id: u64, day: u64, user_id: u64) -> anyhow::Result<Option<serde_json::Value>>;
+
+    // 記録したその日のサーバーの /daily の結果すべて（ユーザーIDと結果）
+    async fn load_daily_results(&self, guild_id: u64, day: u64) -> anyhow::Result<Vec<(u64, serde_json::Value)>>;
+
+    // その日の /daily のフォーラムの投稿を記録する（新しく記録したら true、すでにあれば何もせず false）
+    async fn record_daily_post(&self, guild_id: u64, day: u64, thread_id: u64) -> anyhow::Result<bool>;
+
+    // 記録したその日の /daily のフォーラムの投稿（なければ None）
+    async fn load_daily_post(&self, guild_id: u64, day: u64) -> anyhow::Result<Option<u64>>;
+
+    // 遊んでいる /wordle のゲームを保存（同じゲームの既存データは上書き。started_at は始めた時刻のUNIX秒）
+    async fn save_play_game(&self, game_id: u64, started_at: u64, state: serde_json::Value) -> anyhow::Result<()>;
+
+    // 保存した /wordle のゲーム（なければ None）
+    async fn load_play_game(&self, game_id: u64) -> anyhow::Result<Option<serde_json::Value>>;
+
+    // 終わった /wordle のゲームを削除
+    async fn delete_play_game(&self, game_id: u64) -> anyhow::Result<()>;
+
+    // started_before より前に始めた /wordle のゲームを削除（期限切れの掃除）
+    async fn delete_play_games_before(&self, started_before: u64) -> anyhow::Result<()>;
+
+    // 成績を読み込んで書き換える（同じユーザーの更新が同時に起きても片方が失われないようにする）
+    // 既定では読み込みと保存を続けて行うだけなので、同時に更新されうる実装では上書きする
+    async fn update_user_stats(&self, user_id: u64, update: StatsUpdate<'_>) -> anyhow::Result<serde_json::Value> {
+        let stats = update(self.load_user_stats(user_id).await?)?;
+        self.save_user_stats(user_id, stats.clone()).await?;
+        Ok(stats)
+    }
+
+    // 複数のセッション・成績をまとめて保存（既定では1件ずつ保存する）
+    async fn save_sessions(&self, sessions: &[(u64, serde_json::Value)]) -> anyhow::Result<()> {
+        for (user_id, state) in sessions {
+            self.save_session(*user_id, state.clone()).await?;
+        }
+        Ok(())
+    }
+
+    async fn save_user_stats_batch(&self, stats: &[(u64, serde_json::Value)]) -> anyhow::Result<()> {
+        for (user_id, data) in stats {
+            self.save_user_stats(*user_id, data.clone()).await?;
+        }
+        Ok(())
+    }
+}
+
+pub struct SupabaseStorage {
+    client: Arc<SupabaseClient>,
+}
+
+// 一意制約がない場合に、読み込み・更新をやり直す回数
+const STATS_WRITE_RETRIES: u32 = 3;
+
+// PostgRESTが on_conflict に使える一意制約がないと返すエラーか
+fn missing_conflict_target(status: reqwest::StatusCode, body: &str) -> bool {
+    #[derive(Deserialize)]
+    struct PostgrestError {
+        code: Option<String>,
+    }
+
+    status == reqwest::StatusCode::BAD_REQUEST
+        && serde_json::from_str::<PostgrestError>(body).is_ok_and(|e| e.code.as_deref() == Some("42P10"))
+}
+
+impl SupabaseStorage {
+    pub fn new(client: Arc<SupabaseClient>) -> Self {
+        Self { client }
+    }
+
+    // 成績を user_id で upsert する（同じ行への同時の書き込みはデータベース側で1つずつ適用される）
+    async fn upsert_user_stats(&self, stats: &[(u64, serde_json::Value)]) -> anyhow::Result<()> {
+        let body: Vec<serde_json::Value> = stats.iter()
+            .map(|(user_id, data)| serde_json::json!({ "user_id": user_id, "data": data }))
+            .collect();
+
+        let request = self.client
+            .post("/rest/v1/user_stats?on_conflict=user_id")
+            .header("Prefer", "resolution=merge-duplicates")
+            .json(&body);
+        let response = self.client.send(request).await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let error_text = response.text().await.unwrap_or_default();
+        if !missing_conflict_target(status, &error_text) {
+            return Err(anyhow::anyhow!("Supabase stats save failed: {}", error_text));
+        }
+
+        // user_id に一意制約がない古いテーブルでは、1行ずつ更新と追加を試す
+        warn!("user_stats has no unique constraint on user_id; falling back to update-or-insert");
+        for (user_id, data) in stats {
+            self.write_user_stats_row(*user_id, data).await?;
+        }
+        Ok(())
+    }
+
+    // 既存の行を更新し、なければ追加する（追加が他の書き込みと衝突したら更新からやり直す）
+    async fn write_user_stats_row(&self, user_id: u64, data: &serde_json::Value) -> anyhow::Result<()> {
+        for attempt in 1..=STATS_WRITE_RETRIES {
+            let request = self.client
+                .request(reqwest::Method::PATCH, &format!("/rest/v1/user_stats?user_id=eq.{}", user_id))
+                .header("Prefer", "return=representation")
+                .json(&serde_json::json!({ "data": data }));
+            let response = self.client.send(request).await?;
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(anyhow::anyhow!("Supabase stats update failed: {}", error_text));
+            }
+            let updated: Vec<serde_json::Value> = response.json().await
+                .map_err(|e| e.without_url())
+                .context("Failed to parse JSON response")?;
+            if !updated.is_empty() {
+                return Ok(());
+            }
+
+            let request = self.client
+                .post("/rest/v1/user_stats")
+                .json(&serde_json::json!({ "user_id": user_id, "data": data }));
+            let response = self.client.send(request).await?;
+            match response.status() {
+                status if status.is_success() => return Ok(()),
+                reqwest::StatusCode::CONFLICT => {
+                    info!("Stats row for {} was created concurrently (attempt {})", user_id, attempt);
+                    tokio::time::sleep(std::time::Duration::from_millis(50 * attempt as u64)).await;
+                }
+                _ => {
+                    let error_text = response.text().await.unwrap_or_default();
+                    return Err(anyhow::anyhow!("Supabase stats insert failed: {}", error_text));
+                }
+            }
+        }
+
+        Err(anyhow::anyhow!("Supabase stats save for {} kept conflicting", user_id))
+    }
+}
+
+#[async_trait]
+impl Storage for SupabaseStorage {
+    async fn fetch_words(&self) -> anyhow::Result<Vec<WordRecord>> {
+        let mut all_words = Vec::new();
+        let mut offset = 0;
+        let limit = 1000; // 1回のリクエストで取得する件数
+
+        loop {
+            let path = format!("/rest/v1/words?select=id,word&limit={}&offset={}", limit, offset);
+
+            info!("Fetching words (offset: {})", offset);
+
+            let response = self.client.send(self.client.get(&path)).await?;
+
+            info!("Response status: {}", response.status());
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                warn!("Error response body: {}", error_text);
+                return Err(anyhow::anyhow!("Supabase request failed: {}", error_text));
+            }
+
+            let response_text = response.text().await
+                .context("Failed to read response text")?;
+
+            let words: Vec<WordRecord> = serde_json::from_str(&response_text)
+                .context("Failed to parse JSON response")?;
+
+            let fetched_count = words.len();
+            info!("Fetched {} words in this batch", fetched_count);
+
+            all_words.extend(words);
+
+            // 取得した件数がlimitより少ない場合、全件取得完了
+            if fetched_count < limit {
+                break;
+            }
+
+            offset += limit;
+        }
+
+        Ok(all_words)
+    }
+
+    async fn fetch_emojis(&self) -> anyhow::Result<Vec<EmojiRecord>> {
+        let request = self.client.get("/rest/v1/emojis?select=emoji_name,emoji_id,discord_format");
+
+        let response = self.client.send(request).await?;
+        let emojis: Vec<EmojiRecord> = response.json().await.map_err(|e| e.without_url())?;
+
+        Ok(emojis)
+    }
+
+    async fn table_fingerprint(&self, table: CachedTable) -> anyhow::Result<Option<String>> {
+        let (name, column) = table.columns();
+        let path = format!("/rest/v1/{}?select={}&order={}.desc&limit=1", name, column, column);
+        let request = self.client.get(&path).header("Prefer", "count=exact");
+
+        let response = self.client.send(request).await?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase count of {} failed: {}", name, error_text));
+        }
+
+        // 件数は Content-Range（0-0/123）の最後に入る
+        let count = response.headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|range| range.to_str().ok())
+            .and_then(|range| range.rsplit_once('/'))
+            .map(|(_, count)| count.to_string());
+        let rows: Vec<serde_json::Value> = response.json().await.map_err(|e| e.without_url())?;
+        let last = rows.first().and_then(|row| row.get(column)).map(|id| id.to_string()).unwrap_or_default();
+
+        Ok(count.map(|count| format!("{}:{}", count, last)))
+    }
+
+    async fn insert_words(&self, words: &[String]) -> anyhow::Result<()> {
+        let body: Vec<serde_json::Value> = words.iter()
+            .map(|word| serde_json::json!({ "word": word }))
+            .collect();
+
+        let request = self.client
+            .post("/rest/v1/words?on_conflict=word")
+            .header("Prefer", "resolution=ignore-duplicates")
+            .json(&body);
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase insert failed: {}", error_text));
+        }
+
+        Ok(())
+    }
+
+    async fn save_session(&self, user_id: u64, state: serde_json::Value) -> anyhow::Result<()> {
+        let request = self.client
+            .post("/rest/v1/sessions?on_conflict=user_id")
+            .header("Prefer", "resolution=merge-duplicates")
+            .json(&serde_json::json!({ "user_id": user_id, "state": state }));
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase session save failed: {}", error_text));
+        }
+
+        Ok(())
+    }
+
+    async fn take_session(&self, user_id: u64) -> anyhow::Result<Option<serde_json::Value>> {
+        #[derive(Deserialize)]
+        struct SessionRecord {
+            state: serde_json::Value,
+        }
+
+        // 削除した行をそのまま返してもらう
+        let request = self.client
+            .delete(&format!("/rest/v1/sessions?user_id=eq.{}", user_id))
+            .header("Prefer", "return=representation");
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase session load failed: {}", error_text));
+        }
+
+        let records: Vec<SessionRecord> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(records.into_iter().next().map(|r| r.state))
+    }
+
+    async fn load_settings(&self) -> anyhow::Result<Option<serde_json::Value>> {
+        #[derive(Deserialize)]
+        struct SettingsRecord {
+            data: serde_json::Value,
+        }
+
+        // 設定は id = 1 の1行だけを使う
+        let response = self.client.send(self.client.get("/rest/v1/settings?select=data&id=eq.1")).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase settings load failed: {}", error_text));
+        }
+
+        let records: Vec<SettingsRecord> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(records.into_iter().next().map(|r| r.data))
+    }
+
+    async fn save_settings(&self, settings: serde_json::Value) -> anyhow::Result<()> {
+        let request = self.client
+            .post("/rest/v1/settings?on_conflict=id")
+            .header("Prefer", "resolution=merge-duplicates")
+            .json(&serde_json::json!({ "id": 1, "data": settings }));
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase settings save failed: {}", error_text));
+        }
+
+        Ok(())
+    }
+
+    async fn load_user_stats(&self, user_id: u64) -> anyhow::Result<Option<serde_json::Value>> {
+        #[derive(Deserialize)]
+        struct StatsRecord {
+            data: serde_json::Value,
+        }
+
+        let path = format!("/rest/v1/user_stats?select=data&user_id=eq.{}", user_id);
+        let response = self.client.send(self.client.get(&path)).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase stats load failed: {}", error_text));
+        }
+
+        let records: Vec<StatsRecord> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(records.into_iter().next().map(|r| r.data))
+    }
+
+    async fn save_user_stats(&self, user_id: u64, stats: serde_json::Value) -> anyhow::Result<()> {
+        self.upsert_user_stats(&[(user_id, stats)]).await
+    }
+
+    async fn list_user_stats(&self) -> anyhow::Result<Vec<(u64, serde_json::Value)>> {
+        #[derive(Deserialize)]
+        struct StatsRecord {
+            user_id: u64,
+            data: serde_json::Value,
+        }
+
+        let response = self.client.send(self.client.get("/rest/v1/user_stats?select=user_id,data")).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase stats list failed: {}", error_text));
+        }
+
+        let records: Vec<StatsRecord> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(records.into_iter().map(|r| (r.user_id, r.data)).collect())
+    }
+
+    async fn record_wordlist_version(&self, version: &str, words: &[String]) -> anyhow::Result<()> {
+        let request = self.client
+            .post("/rest/v1/wordlist_versions?on_conflict=version")
+            .header("Prefer", "resolution=ignore-duplicates")
+            .json(&serde_json::json!({ "version": version, "word_count": words.len(), "words": words }));
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase word list version save failed: {}", error_text));
+        }
+
+        Ok(())
+    }
+
+    async fn load_wordlist_version(&self, version: &str) -> anyhow::Result<Option<Vec<String>>> {
+        #[derive(Deserialize)]
+        struct VersionRecord {
+            words: Vec<String>,
+        }
+
+        let path = format!("/rest/v1/wordlist_versions?select=words&version=eq.{}", version);
+        let response = self.client.send(self.client.get(&path)).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase word list version load failed: {}", error_text));
+        }
+
+        let records: Vec<VersionRecord> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(records.into_iter().next().map(|r| r.words))
+    }
+
+    async fn record_daily_result(&self, guild_id: u64, day: u64, user_id: u64, result: serde_json::Value) -> anyhow::Result<bool> {
+        // 重複を無視したときは、追加した行だけが返るので空になる
+        let request = self.client
+            .post("/rest/v1/daily_results?on_conflict=guild_id,day,user_id")
+            .header("Prefer", "resolution=ignore-duplicates,return=representation")
+            .json(&serde_json::json!({ "guild_id": guild_id, "day": day, "user_id": user_id, "data": result }));
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase daily result save failed: {}", error_text));
+        }
+
+        let inserted: Vec<serde_json::Value> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(!inserted.is_empty())
+    }
+
+    async fn load_daily_result(&self, guild_id: u64, day: u64, user_id: u64) -> anyhow::Result<Option<serde_json::Value>> {
+        #[derive(Deserialize)]
+        struct DailyResultRecord {
+            data: serde_json::Value,
+        }
+
+        let path = format!("/rest/v1/daily_results?select=data&guild_id=eq.{}&day=eq.{}&user_id=eq.{}", guild_id, day, user_id);
+        let response = self.client.send(self.client.get(&path)).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase daily result load failed: {}", error_text));
+        }
+
+        let records: Vec<DailyResultRecord> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(records.into_iter().next().map(|r| r.data))
+    }
+
+    async fn load_daily_results(&self, guild_id: u64, day: u64) -> anyhow::Result<Vec<(u64, serde_json::Value)>> {
+        #[derive(Deserialize)]
+        struct DailyResultRecord {
+            user_id: u64,
+            data: serde_json::Value,
+        }
+
+        let path = format!("/rest/v1/daily_results?select=user_id,data&guild_id=eq.{}&day=eq.{}", guild_id, day);
+        let response = self.client.send(self.client.get(&path)).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase daily results load failed: {}", error_text));
+        }
+
+        let records: Vec<DailyResultRecord> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(records.into_iter().map(|r| (r.user_id, r.data)).collect())
+    }
+
+    async fn record_daily_post(&self, guild_id: u64, day: u64, thread_id: u64) -> anyhow::Result<bool> {
+        // 重複を無視したときは、追加した行だけが返るので空になる
+        let request = self.client
+            .post("/rest/v1/daily_posts?on_conflict=guild_id,day")
+            .header("Prefer", "resolution=ignore-duplicates,return=representation")
+            .json(&serde_json::json!({ "guild_id": guild_id, "day": day, "thread_id": thread_id }));
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase daily post save failed: {}", error_text));
+        }
+
+        let inserted: Vec<serde_json::Value> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(!inserted.is_empty())
+    }
+
+    async fn load_daily_post(&self, guild_id: u64, day: u64) -> anyhow::Result<Option<u64>> {
+        #[derive(Deserialize)]
+        struct DailyPostRecord {
+            thread_id: u64,
+        }
+
+        let path = format!("/rest/v1/daily_posts?select=thread_id&guild_id=eq.{}&day=eq.{}", guild_id, day);
+        let response = self.client.send(self.client.get(&path)).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase daily post load failed: {}", error_text));
+        }
+
+        let records: Vec<DailyPostRecord> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(records.into_iter().next().map(|r| r.thread_id))
+    }
+
+    async fn save_play_game(&self, game_id: u64, started_at: u64, state: serde_json::Value) -> anyhow::Result<()> {
+        let request = self.client
+            .post("/rest/v1/play_games?on_conflict=game_id")
+            .header("Prefer", "resolution=merge-duplicates")
+            .json(&serde_json::json!({ "game_id": game_id, "started_at": started_at, "state": state }));
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase game save failed: {}", error_text));
+        }
+
+        Ok(())
+    }
+
+    async fn load_play_game(&self, game_id: u64) -> anyhow::Result<Option<serde_json::Value>> {
+        #[derive(Deserialize)]
+        struct GameRecord {
+            state: serde_json::Value,
+        }
+
+        let path = format!("/rest/v1/play_games?select=state&game_id=eq.{}", game_id);
+        let response = self.client.send(self.client.get(&path)).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase game load failed: {}", error_text));
+        }
+
+        let records: Vec<GameRecord> = response.json().await
+            .map_err(|e| e.without_url())
+            .context("Failed to parse JSON response")?;
+
+        Ok(records.into_iter().next().map(|r| r.state))
+    }
+
+    async fn delete_play_game(&self, game_id: u64) -> anyhow::Result<()> {
+        let request = self.client.delete(&format!("/rest/v1/play_games?game_id=eq.{}", game_id));
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase game delete failed: {}", error_text));
+        }
+
+        Ok(())
+    }
+
+    async fn delete_play_games_before(&self, started_before: u64) -> anyhow::Result<()> {
+        let request = self.client.delete(&format!("/rest/v1/play_games?started_at=lt.{}", started_before));
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase game delete failed: {}", error_text));
+        }
+
+        Ok(())
+    }
+
+    // 配列で送ると1回のリクエストでまとめて保存される
+    async fn save_sessions(&self, sessions: &[(u64, serde_json::Value)]) -> anyhow::Result<()> {
+        let body: Vec<serde_json::Value> = sessions.iter()
+            .map(|(user_id, state)| serde_json::json!({ "user_id": user_id, "state": state }))
+            .collect();
+
+        let request = self.client
+            .post("/rest/v1/sessions?on_conflict=user_id")
+            .header("Prefer", "resolution=merge-duplicates")
+            .json(&body);
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Supabase session save failed: {}", error_text));
+        }
+
+        Ok(())
+    }
+
+    async fn save_user_stats_batch(&self, stats: &[(u64, serde_json::Value)]) -> anyhow::Result<()> {
+        self.upsert_user_stats(stats).await
+    }
+}
+
+// テストやローカル開発用のインメモリ実装
+#[derive(Default)]
+pub struct MemoryStorage {
+    words: tokio::sync::RwLock<Vec<WordRecord>>,
+    emojis: tokio::sync::RwLock<Vec<EmojiRecord>>,
+    sessions: tokio::sync::RwLock<HashMap<u64, serde_json::Value>>,
+    settings: tokio::sync::RwLock<Option<serde_json::Value>>,
+    user_stats: tokio::sync::RwLock<HashMap<u64, serde_json::Value>>,
+    wordlist_versions: tokio::sync::RwLock<HashMap<String, Vec<String>>>,
+    daily_results: tokio::sync::RwLock<HashMap<(u64, u64, u64), serde_json::Value>>,
+    daily_posts: tokio::sync::RwLock<HashMap<(u64, u64), u64>>,
+    // ゲームIDごとの始めた時刻と状態
+    play_games: tokio::sync::RwLock<HashMap<u64, (u64, serde_json::Value)>>,
+    unavailable: AtomicBool,
+}
+
+impl MemoryStorage {
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let words = words.into_iter()
+            .enumerate()
+            .map(|(i, word)| WordRecord { id: i as i32 + 1, word: word.into() })
+            .collect();
+
+        Self {
+            words: tokio::sync::RwLock::new(words),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_emojis(self, emojis: Vec<EmojiRecord>) -> Self {
+        Self {
+            emojis: tokio::sync::RwLock::new(emojis),
+            ..self
+        }
+    }
+
+    // trueにすると全リクエストが失敗する（DB障害のシミュレーション）
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.unavailable.store(unavailable, Ordering::SeqCst);
+    }
+
+    fn check_available(&self) -> anyhow::Result<()> {
+        if self.unavailable.load(Ordering::SeqCst) {
+            return Err(anyhow::anyhow!("storage is unavailable"));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn fetch_words(&self) -> anyhow::Result<Vec<WordRecord>> {
+        self.check_available()?;
+        Ok(self.words.read().await.clone())
+    }
+
+    async fn fetch_emojis(&self) -> anyhow::Result<Vec<EmojiRecord>> {
+        self.check_available()?;
+        Ok(self.emojis.read().await.clone())
+    }
+
+    async fn table_fingerprint(&self, table: CachedTable) -> anyhow::Result<Option<String>> {
+        self.check_available()?;
+        let (count, last) = match table {
+            CachedTable::Words => {
+                let words = self.words.read().await;
+                (words.len(), words.iter().map(|w| w.id as i64).max())
+            }
+            CachedTable::Emojis => {
+                let emojis = self.emojis.read().await;
+                (emojis.len(), emojis.iter().map(|e| e.emoji_id).max())
+            }
+        };
+        Ok(Some(format!("{}:{}", count, last.map(|id| id.to_string()).unwrap_or_default())))
+    }
+
+    async fn insert_words(&self, words: &[String]) -> anyhow::Result<()> {
+        self.check_available()?;
+
+        let mut stored = self.words.write().await;
+        for word in words {
+            if !stored.iter().any(|w| w.word.eq_ignore_ascii_case(word)) {
+                let id = stored.len() as i32 + 1;
+                stored.push(WordRecord { id, word: word.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    async fn save_session(&self, user_id: u64, state: serde_json::Value) -> anyhow::Result<()> {
+        self.check_available()?;
+        self.sessions.write().await.insert(user_id, state);
+        Ok(())
+    }
+
+    async fn take_session(&self, user_id: u64) -> anyhow::Result<Option<serde_json::Value>> {
+        self.check_available()?;
+        Ok(self.sessions.write().await.remove(&user_id))
+    }
+
+    async fn load_settings(&self) -> anyhow::Result<Option<serde_json::Value>> {
+        self.check_available()?;
+        Ok(self.settings.read().await.clone())
+    }
+
+    async fn save_settings(&self, settings: serde_json::Value) -> anyhow::Result<()> {
+        self.check_available()?;
+        *self.settings.write().await = Some(settings);
+        Ok(())
+    }
+
+    async fn load_user_stats(&self, user_id: u64) -> anyhow::Result<Option<serde_json::Value>> {
+        self.check_available()?;
+        Ok(self.user_stats.read().await.get(&user_id).cloned())
+    }
+
+    async fn save_user_stats(&self, user_id: u64, stats: serde_json::Value) -> anyhow::Result<()> {
+        self.check_available()?;
+        self.user_stats.write().await.insert(user_id, stats);
+        Ok(())
+    }
+
+    async fn record_wordlist_version(&self, version: &str, words: &[String]) -> anyhow::Result<()> {
+        self.check_available()?;
+        self.wordlist_versions.write().await.entry(version.to_string()).or_insert_with(|| words.to_vec());
+        Ok(())
+    }
+
+    async fn load_wordlist_version(&self, version: &str) -> anyhow::Result<Option<Vec<String>>> {
+        self.check_available()?;
+        Ok(self.wordlist_versions.read().await.get(version).cloned())
+    }
+
+    async fn record_daily_result(&self, guild_id: u64, day: u64, user_id: u64, result: serde_json::Value) -> anyhow::Result<bool> {
+        self.check_available()?;
+        let mut daily_results = self.daily_results.write().await;
+        if daily_results.contains_key(&(guild_id, day, user_id)) {
+            return Ok(false);
+        }
+        daily_results.insert((guild_id, day, user_id), result);
+        Ok(true)
+    }
+
+    async fn load_daily_result(&self, guild_id: u64, day: u64, user_id: u64) -> anyhow::Result<Option<serde_json::Value>> {
+        self.check_available()?;
+        Ok(self.daily_results.read().await.get(&(guild_id, day, user_id)).cloned())
+    }
+
+    async fn load_daily_results(&self, guild_id: u64, day: u64) -> anyhow::Result<Vec<(u64, serde_json::Value)>> {
+        self.check_available()?;
+        Ok(self.daily_results.read().await.iter()
+            .filter(|((guild, d, _), _)| *guild == guild_id && *d == day)
+            .map(|((_, _, user_id), result)| (*user_id, result.clone()))
+            .collect())
+    }
+
+    async fn record_daily_post(&self, guild_id: u64, day: u64, thread_id: u64) -> anyhow::Result<bool> {
+        self.check_available()?;
+        let mut daily_posts = self.daily_posts.write().await;
+        if daily_posts.contains_key(&(guild_id, day)) {
+            return Ok(false);
+        }
+        daily_posts.insert((guild_id, day), thread_id);
+        Ok(true)
+    }
+
+    async fn load_daily_post(&self, guild_id: u64, day: u64) -> anyhow::Result<Option<u64>> {
+        self.check_available()?;
+        Ok(self.daily_posts.read().await.get(&(guild_id, day)).copied())
+    }
+
+    async fn save_play_game(&self, game_id: u64, started_at: u64, state: serde_json::Value) -> anyhow::Result<()> {
+        self.check_available()?;
+        self.play_games.write().await.insert(game_id, (started_at, state));
+        Ok(())
+    }
+
+    async fn load_play_game(&self, game_id: u64) -> anyhow::Result<Option<serde_json::Value>> {
+        self.check_available()?;
+        Ok(self.play_games.read().await.get(&game_id).map(|(_, state)| state.clone()))
+    }
+
+    async fn delete_play_game(&self, game_id: u64) -> anyhow::Result<()> {
+        self.check_available()?;
+        self.play_games.write().await.remove(&game_id);
+        Ok(())
+    }
+
+    async fn delete_play_games_before(&self, started_before: u64) -> anyhow::Result<()> {
+        self.check_available()?;
+        self.play_games.write().await.retain(|_, (started_at, _)| *started_at >= started_before);
+        Ok(())
+    }
+
+    async fn update_user_stats(&self, user_id: u64, update: StatsUpdate<'_>) -> anyhow::Result<serde_json::Value> {
+        self.check_available()?;
+        let mut user_stats = self.user_stats.write().await;
+        let stats = update(user_stats.get(&user_id).cloned())?;
+        user_stats.insert(user_id, stats.clone());
+        Ok(stats)
+    }
+
+    async fn list_user_stats(&self) -> anyhow::Result<Vec<(u64, serde_json::Value)>> {
+        self.check_available()?;
+        Ok(self.user_stats.read().await.iter().map(|(id, stats)| (*id, stats.clone())).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_missing_unique_constraint() {
+        let body = r#"{"code":"42P10","message":"there is no unique or exclusion constraint matching the ON CONFLICT specification"}"#;
+        assert!(missing_conflict_target(reqwest::StatusCode::BAD_REQUEST, body));
+        assert!(!missing_conflict_target(reqwest::StatusCode::BAD_REQUEST, r#"{"code":"23502"}"#));
+        assert!(!missing_conflict_target(reqwest::StatusCode::UNAUTHORIZED, body));
+    }
+}
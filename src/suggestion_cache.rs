@@ -0,0 +1,152 @@
+// 計算したおすすめの単語のLRU（セッションごと）
+// 推測の並びごとに提案を覚えておき、盤面を描き直すときや、推測を色の入力に戻して同じ色で確定し直したときに
+// スコアを計算し直さずに済むようにする
+// セッションの複製とは中身を共有する（分析は複製した状態で行うため）
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::settings::Difficulty;
+use crate::solver::WordleGuess;
+use crate::word_cache::Candidates;
+
+// セッションごとに覚えておく提案の数
+const SUGGESTION_CACHE_CAPACITY: usize = 8;
+
+// 提案の順位を決める条件（推測の並び・候補・難易度・ハードモード・表示する数が同じなら同じ提案になる）
+#[derive(Debug, Clone)]
+pub struct SuggestionKey {
+    history: u64,
+    candidates: Candidates,
+    difficulty: Difficulty,
+    hard_mode: bool,
+    pub limit: usize,
+}
+
+// 推測の並びの指紋
+fn history_hash(guesses: &[WordleGuess]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for guess in guesses {
+        guess.word.hash(&mut hasher);
+        guess.results.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+impl SuggestionKey {
+    pub fn new(guesses: &[WordleGuess], candidates: Candidates, difficulty: Difficulty, hard_mode: bool, limit: usize) -> Self {
+        Self { history: history_hash(guesses), candidates, difficulty, hard_mode, limit }
+    }
+
+    fn matches(&self, other: &SuggestionKey) -> bool {
+        // 単語リストが置き換わったり、禁止した単語で候補が変わったりしたら使わない
+        self.history == other.history
+            && self.difficulty == other.difficulty
+            && self.hard_mode == other.hard_mode
+            && self.limit == other.limit
+            && Arc::ptr_eq(&self.candidates.arena, &other.candidates.arena)
+            && (Arc::ptr_eq(&self.candidates.ids, &other.candidates.ids) || self.candidates.ids == other.candidates.ids)
+    }
+}
+
+struct CachedSuggestions {
+    key: SuggestionKey,
+    words: Vec<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct SuggestionCache {
+    // 最近使ったものが後ろ
+    entries: Arc<Mutex<VecDeque<CachedSuggestions>>>,
+}
+
+impl fmt::Debug for SuggestionCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = self.entries.lock().unwrap_or_else(|e| e.into_inner()).len();
+        f.debug_struct("SuggestionCache").field("entries", &len).finish()
+    }
+}
+
+impl SuggestionCache {
+    pub fn get(&self, key: &SuggestionKey) -> Option<Vec<String>> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let index = entries.iter().position(|cached| cached.key.matches(key))?;
+        let cached = entries.remove(index).expect("position is in range");
+        let words = cached.words.clone();
+        entries.push_back(cached);
+        Some(words)
+    }
+
+    pub fn contains(&self, key: &SuggestionKey) -> bool {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().any(|cached| cached.key.matches(key))
+    }
+
+    pub fn insert(&self, key: SuggestionKey, words: Vec<String>) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|cached| !cached.key.matches(&key));
+        entries.push_back(CachedSuggestions { key, words });
+        while entries.len() > SUGGESTION_CACHE_CAPACITY {
+            entries.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{LetterResult, WordArena};
+
+    fn guess(word: &str, results: [LetterResult; 5]) -> WordleGuess {
+        WordleGuess { word: word.to_string(), results: results.to_vec() }
+    }
+
+    fn key(arena: &Arc<WordArena>, guesses: &[WordleGuess], ids: &[usize], difficulty: Difficulty) -> SuggestionKey {
+        let all: Vec<_> = arena.ids().collect();
+        let candidates = Candidates { arena: Arc::clone(arena), ids: Arc::new(ids.iter().map(|&i| all[i]).collect()) };
+        SuggestionKey::new(guesses, candidates, difficulty, false, 10)
+    }
+
+    #[test]
+    fn suggestions_are_reused_only_for_the_same_conditions() {
+        use LetterResult::*;
+        let arena = Arc::new(WordArena::new(["CRANE", "TRACE", "CRATE"]));
+        let first = [guess("SLOTH", [Gray, Gray, Gray, Yellow, Gray])];
+        let cache = SuggestionCache::default();
+        cache.insert(key(&arena, &first, &[0, 1], Difficulty::Standard), vec!["TRACE".to_string()]);
+
+        // 複製とは中身を共有する
+        let shared = cache.clone();
+        assert_eq!(shared.get(&key(&arena, &first, &[0, 1], Difficulty::Standard)), Some(vec!["TRACE".to_string()]));
+        assert!(!cache.contains(&key(&arena, &first, &[0, 1, 2], Difficulty::Standard)));
+        assert!(!cache.contains(&key(&arena, &first, &[0, 1], Difficulty::Advanced)));
+        let recolored = [guess("SLOTH", [Gray, Gray, Gray, Green, Gray])];
+        assert!(!cache.contains(&key(&arena, &recolored, &[0, 1], Difficulty::Standard)));
+
+        // 同じ単語でも別の単語リストなら使わない
+        let reloaded = Arc::new(WordArena::new(["CRANE", "TRACE", "CRATE"]));
+        assert!(!cache.contains(&key(&reloaded, &first, &[0, 1], Difficulty::Standard)));
+    }
+
+    #[test]
+    fn earlier_histories_are_kept_until_the_capacity_is_exceeded() {
+        use LetterResult::*;
+        let arena = Arc::new(WordArena::new(["CRANE", "TRACE", "CRATE"]));
+        let words = ["SLOTH", "PUDGY", "BLIMP", "FJORD", "WALTZ", "NYMPH", "GHOST", "QUICK", "VIXEN"];
+        let histories: Vec<_> = words.iter().map(|w| vec![guess(w, [Gray; 5])]).collect();
+        let cache = SuggestionCache::default();
+        for history in &histories {
+            cache.insert(key(&arena, history, &[0], Difficulty::Standard), vec![history[0].word.clone()]);
+        }
+
+        // 色の入力に戻して確定し直した推測の並びは、残っていれば計算し直さない
+        assert!(!cache.contains(&key(&arena, &histories[0], &[0], Difficulty::Standard)));
+        assert_eq!(cache.get(&key(&arena, &histories[1], &[0], Difficulty::Standard)), Some(vec!["PUDGY".to_string()]));
+        cache.insert(key(&arena, &histories[0], &[0], Difficulty::Standard), Vec::new());
+        // 使ったものは追い出されない
+        assert!(cache.contains(&key(&arena, &histories[1], &[0], Difficulty::Standard)));
+        assert!(!cache.contains(&key(&arena, &histories[2], &[0], Difficulty::Standard)));
+    }
+}
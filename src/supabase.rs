@@ -0,0 +1,123 @@
+// SupabaseのREST APIへのリクエスト
+// キーはここだけで持ち、認証ヘッダーもここで一括してつける（/admin rotate-key で実行中に差し替えられる）
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use reqwest::{Method, RequestBuilder, Response};
+use tokio::sync::Semaphore;
+
+pub struct SupabaseClient {
+    http: reqwest::Client,
+    url: String,
+    key: ArcSwap<String>,
+    // 同時に送るリクエスト数の上限（単語の一括取得などで接続を使い切らないように）
+    in_flight: Semaphore,
+}
+
+// 上限を設定しない場合の同時リクエスト数
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 16;
+
+// キーがログに出ないよう、Debug では伏せる
+impl std::fmt::Debug for SupabaseClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SupabaseClient")
+            .field("url", &self.url)
+            .field("key", &key_hint(&self.key.load()))
+            .finish()
+    }
+}
+
+// キーの末尾4文字だけを見せる（どのキーが使われているかの確認用）
+pub fn key_hint(key: &str) -> String {
+    let tail: String = key.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+    format!("…{}", tail)
+}
+
+impl SupabaseClient {
+    pub fn new(http: reqwest::Client, url: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            http,
+            url: url.into().trim_end_matches('/').to_string(),
+            key: ArcSwap::from_pointee(key.into()),
+            in_flight: Semaphore::new(DEFAULT_MAX_CONCURRENT_REQUESTS),
+        }
+    }
+
+    pub fn with_max_concurrent_requests(mut self, max: usize) -> Self {
+        self.in_flight = Semaphore::new(max.max(1));
+        self
+    }
+
+    // 以降のリクエストで使うキーを差し替え、元のキーを返す
+    pub fn rotate_key(&self, key: &str) -> anyhow::Result<Arc<String>> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(anyhow::anyhow!("key is empty"));
+        }
+        Ok(self.key.swap(Arc::new(key.to_string())))
+    }
+
+    // 差し替えたキーを元に戻す（新しいキーが使えなかった場合）
+    pub fn restore_key(&self, key: Arc<String>) {
+        self.key.store(key);
+    }
+
+    pub fn key_hint(&self) -> String {
+        key_hint(&self.key.load())
+    }
+
+    // path は "/rest/v1/..." の形式で、プロジェクトのURLに続けて送る
+    pub fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let key = self.key.load();
+        self.http
+            .request(method, format!("{}{}", self.url, path))
+            .header("apikey", key.as_str())
+            .header("Authorization", format!("Bearer {}", key))
+    }
+
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.request(Method::GET, path)
+    }
+
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.request(Method::POST, path)
+    }
+
+    pub fn delete(&self, path: &str) -> RequestBuilder {
+        self.request(Method::DELETE, path)
+    }
+
+    // 送信エラーからURLを取り除いて返す（エラーはそのままログやエラーチャンネルに出るため）
+    pub async fn send(&self, request: RequestBuilder) -> anyhow::Result<Response> {
+        // 応答の本文を読む前に枠を返すが、接続はプールに戻るまで再利用されない
+        let _permit = self.in_flight.acquire().await?;
+        request.send().await
+            .map_err(|e| anyhow::Error::new(e.without_url()).context("Failed to send request to Supabase"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(request: RequestBuilder, name: &str) -> String {
+        let request = request.build().unwrap();
+        request.headers()[name].to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn rotated_key_is_used_for_later_requests() {
+        let client = SupabaseClient::new(reqwest::Client::new(), "https://example.supabase.co/", "old-key-1234");
+        assert_eq!(header(client.get("/rest/v1/words"), "apikey"), "old-key-1234");
+
+        let previous = client.rotate_key(" new-key-5678 ").unwrap();
+        assert_eq!(previous.as_str(), "old-key-1234");
+        assert_eq!(header(client.get("/rest/v1/words"), "authorization"), "Bearer new-key-5678");
+        assert!(client.rotate_key("  ").is_err());
+        assert!(!format!("{:?}", client).contains("new-key"));
+
+        client.restore_key(previous);
+        assert_eq!(client.key_hint(), "…1234");
+        assert_eq!(client.get("/rest/v1/words").build().unwrap().url().as_str(), "https://example.supabase.co/rest/v1/words");
+    }
+}
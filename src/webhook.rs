@@ -0,0 +1,161 @@
+// HTTPのInteractions Endpointでインタラクションを受け取るモード（ゲートウェイ接続なし）
+// 署名を検証し、PINGに応答したうえで、ゲートウェイと同じ振り分け処理に渡す
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context as _;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serenity::all::{Http, Interaction};
+use tracing::{info, warn};
+
+use crate::respond::ReplyContext;
+use crate::{Bot, BotConfig};
+
+// Discordは3秒以内の応答を要求する（Responderはそれより前に保留応答を返す）
+const RESPONSE_TIMEOUT: Duration = Duration::from_millis(2800);
+
+#[derive(Clone)]
+struct WebhookState {
+    bot: Bot,
+    http: Arc<Http>,
+    public_key: Arc<Vec<u8>>,
+}
+
+pub async fn serve_webhook(config: BotConfig, addr: SocketAddr) -> anyhow::Result<()> {
+    let public_key = config.discord_public_key.as_deref()
+        .context("'DISCORD_PUBLIC_KEY' is required for the interactions endpoint")?;
+    let public_key = decode_hex(public_key).context("'DISCORD_PUBLIC_KEY' must be a hex-encoded key")?;
+
+    let bot = Bot::from_config(&config);
+
+    // 応答の編集にはアプリケーションIDが必要（ゲートウェイでは接続時に設定される）
+    let http = Arc::new(Http::new(&config.discord_token));
+    let application = http.get_current_application_info().await
+        .context("Failed to fetch application info")?;
+    http.set_application_id(application.id);
+
+    bot.initialize(&http).await;
+
+    let state = WebhookState { bot: bot.clone(), http, public_key: Arc::new(public_key) };
+    let app = Router::new()
+        .route("/interactions", post(interactions))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(addr).await
+        .with_context(|| format!("Failed to bind {}", addr))?;
+    info!("Serving interactions endpoint on http://{}/interactions", addr);
+
+    // 終了時は未送信の書き込みを送ってから抜ける
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                warn!("Failed to listen for shutdown signal: {:?}", e);
+                std::future::pending::<()>().await;
+            }
+            info!("Shutdown signal received");
+        })
+        .await?;
+    bot.flush_writes().await;
+    Ok(())
+}
+
+async fn interactions(State(state): State<WebhookState>, headers: HeaderMap, body: Bytes) -> Response {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    if !verify_signature(&state.public_key, header("X-Signature-Ed25519"), header("X-Signature-Timestamp"), &body) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let interaction: Interaction = match serde_json::from_slice(&body) {
+        Ok(interaction) => interaction,
+        Err(e) => {
+            warn!("Failed to parse interaction payload: {}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    if let Interaction::Ping(_) = interaction {
+        return Json(serde_json::json!({ "type": 1 })).into_response();
+    }
+
+    // 最初の応答はHTTPレスポンスとして返し、以降の編集はRESTで行う
+    let (ctx, initial) = ReplyContext::webhook(Arc::clone(&state.http));
+    let bot = state.bot.clone();
+    tokio::spawn(async move { bot.handle_interaction(ctx, interaction).await });
+
+    match tokio::time::timeout(RESPONSE_TIMEOUT, initial).await {
+        Ok(Ok(response)) => Json(response).into_response(),
+        Ok(Err(_)) => {
+            warn!("Interaction finished without responding");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+        Err(_) => {
+            warn!("Interaction was not answered within {:?}", RESPONSE_TIMEOUT);
+            StatusCode::SERVICE_UNAVAILABLE.into_response()
+        }
+    }
+}
+
+// Discordの署名（タイムスタンプ + 本文 に対するEd25519署名）を検証
+fn verify_signature(public_key: &[u8], signature: &str, timestamp: &str, body: &[u8]) -> bool {
+    let Some(signature) = decode_hex(signature) else {
+        return false;
+    };
+
+    let mut message = Vec::with_capacity(timestamp.len() + body.len());
+    message.extend_from_slice(timestamp.as_bytes());
+    message.extend_from_slice(body);
+
+    UnparsedPublicKey::new(&ED25519, public_key).verify(&message, &signature).is_ok()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim();
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn accepts_only_valid_signatures() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = key_pair.public_key().as_ref();
+
+        let body = br#"{"type":1}"#;
+        let signature = to_hex(key_pair.sign(b"1700000000{\"type\":1}").as_ref());
+
+        assert!(verify_signature(public_key, &signature, "1700000000", body));
+        assert!(!verify_signature(public_key, &signature, "1700000001", body));
+        assert!(!verify_signature(public_key, &signature, "1700000000", br#"{"type":2}"#));
+        assert!(!verify_signature(public_key, "not-hex", "1700000000", body));
+    }
+
+    #[test]
+    fn decodes_hex_strings() {
+        assert_eq!(decode_hex("00ff10"), Some(vec![0x00, 0xff, 0x10]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
@@ -0,0 +1,320 @@
+// 単語キャッシュ（読み込み時に文字数ごとに分けておく）
+// 文字数ごとの前計算（単語に含まれる文字のビットマスクと文字ごとの数）は、その文字数が初めて使われたときに作る
+// （4〜8文字のモードを加えても、起動時の計算が文字数の分だけ増えないように）
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tracing::debug;
+
+use crate::solver::{self, GuessConstraint, LetterResult, WordArena, WordId, WordleGuess};
+use crate::storage::WordRecord;
+
+// 文字数ごとの前計算
+pub struct LengthTables {
+    // 単語ごとの、含まれる文字の集合（A = 1ビット目）
+    pub letter_masks: Vec<u32>,
+    // 単語ごとの文字ごとの数（推測の条件と比べるときに数え直さない）
+    pub letter_counts: Vec<[u8; 26]>,
+}
+
+impl LengthTables {
+    // 推測の条件（GuessConstraint::compile で作ったもの）をすべて満たすか
+    fn is_possible(&self, arena: &WordArena, id: WordId, constraints: &[GuessConstraint]) -> bool {
+        let word = arena.word(id).as_bytes();
+        constraints.iter().all(|constraint| constraint.matches(word, &self.letter_counts[id as usize]))
+    }
+}
+
+struct Partition {
+    // 大文字にした単語（英字だけのもの）
+    words: Arc<WordArena>,
+    tables: OnceLock<Arc<LengthTables>>,
+}
+
+// 推測に矛盾しない単語（WordId の並び。表示するときに単語に戻す）
+#[derive(Debug, Clone, Default)]
+pub struct Candidates {
+    pub arena: Arc<WordArena>,
+    pub ids: Arc<Vec<WordId>>,
+}
+
+impl Candidates {
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.ids.iter().map(|&id| self.arena.word(id))
+    }
+
+    pub fn to_strings(&self) -> Vec<String> {
+        self.words().map(str::to_string).collect()
+    }
+
+    // 条件に合う単語を除く（除く単語がなければ同じ並びを共有する）
+    pub fn without(self, excluded: impl Fn(&str) -> bool) -> Self {
+        if !self.words().any(&excluded) {
+            return self;
+        }
+        let ids = self.ids.iter().copied().filter(|&id| !excluded(self.arena.word(id))).collect();
+        Self { arena: self.arena, ids: Arc::new(ids) }
+    }
+}
+
+#[derive(Default)]
+pub struct WordCache {
+    // 読み込んだままの単語（連続したバッファに詰める）とそのID
+    all: WordArena,
+    record_ids: Vec<i32>,
+    partitions: HashMap<usize, Partition>,
+}
+
+fn letter_bit(letter: char) -> u32 {
+    match letter.to_ascii_uppercase() {
+        c @ 'A'..='Z' => 1 << (c as u32 - 'A' as u32),
+        _ => 0,
+    }
+}
+
+fn letter_mask(word: &str) -> u32 {
+    word.chars().fold(0, |mask, c| mask | letter_bit(c))
+}
+
+// 推測から、答えに必ず含まれる文字と含まれない文字を集める
+fn required_and_excluded(guesses: &[WordleGuess]) -> (u32, u32) {
+    let mut required = 0;
+    let mut excluded = 0;
+    for guess in guesses {
+        let mut present = 0;
+        let mut gray = 0;
+        for (letter, result) in guess.word.chars().zip(&guess.results) {
+            match result {
+                LetterResult::Green | LetterResult::Yellow => present |= letter_bit(letter),
+                LetterResult::Gray => gray |= letter_bit(letter),
+            }
+        }
+        required |= present;
+        // 同じ推測で緑・黄にもなっている文字は、数が多すぎただけなので除外しない
+        excluded |= gray & !present;
+    }
+    (required, excluded & !required)
+}
+
+impl WordCache {
+    pub fn new(records: Vec<WordRecord>) -> Self {
+        let mut by_length: HashMap<usize, Vec<String>> = HashMap::new();
+        for record in &records {
+            let word = record.word.to_uppercase();
+            if !word.chars().all(|c| c.is_ascii_alphabetic()) {
+                continue;
+            }
+            by_length.entry(word.len()).or_default().push(word);
+        }
+        let partitions = by_length.into_iter()
+            .map(|(length, words)| (length, Partition { words: Arc::new(WordArena::new(words)), tables: OnceLock::new() }))
+            .collect();
+        Self {
+            all: records.iter().map(|r| r.word.as_str()).collect(),
+            record_ids: records.iter().map(|r| r.id).collect(),
+            partitions,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.all.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.all.is_empty()
+    }
+
+    // 読み込んだままの単語（大文字・小文字や文字数はそのまま）
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.all.iter().map(|(_, word)| word)
+    }
+
+    // スナップショットや版の記録に使う
+    pub fn to_records(&self) -> Vec<WordRecord> {
+        self.record_ids.iter()
+            .zip(self.iter())
+            .map(|(&id, word)| WordRecord { id, word: word.to_string() })
+            .collect()
+    }
+
+    // 単語の文字列が使っているバイト数（/status に表示する）
+    pub fn heap_bytes(&self) -> usize {
+        self.all.heap_bytes()
+            + self.record_ids.capacity() * std::mem::size_of::<i32>()
+            + self.partitions.values().map(|p| p.words.heap_bytes()).sum::<usize>()
+    }
+
+    // 指定した文字数の単語（大文字）
+    pub fn words_of_length(&self, length: usize) -> Option<&Arc<WordArena>> {
+        self.partitions.get(&length).map(|p| &p.words)
+    }
+
+    // 指定した文字数の前計算（初めて使われたときに作る）
+    pub fn tables(&self, length: usize) -> Option<Arc<LengthTables>> {
+        let partition = self.partitions.get(&length)?;
+        let tables = partition.tables.get_or_init(|| {
+            debug!("Building tables for {}-letter words ({} words)", length, partition.words.len());
+            Arc::new(LengthTables {
+                letter_masks: partition.words.iter().map(|(_, w)| letter_mask(w)).collect(),
+                letter_counts: partition.words.iter().map(|(_, w)| solver::letter_counts(w.as_bytes())).collect(),
+            })
+        });
+        Some(Arc::clone(tables))
+    }
+
+    // 推測に矛盾しない単語の WordId。文字の集合で絞ってから位置を確かめる
+    pub fn candidate_ids(&self, guesses: &[WordleGuess]) -> Candidates {
+        let length = guesses.first().map_or(solver::WORD_LENGTH, |g| g.word.chars().count());
+        let (Some(arena), Some(tables)) = (self.words_of_length(length), self.tables(length)) else {
+            return Candidates::default();
+        };
+        let (required, excluded) = required_and_excluded(guesses);
+        // 推測の条件は1回だけ作る（英字でない推測が混ざっていれば文字列で確かめる）
+        let constraints = GuessConstraint::compile(guesses);
+
+        let ids = arena.iter()
+            .zip(&tables.letter_masks)
+            .filter(|(_, mask)| *mask & required == required && *mask & excluded == 0)
+            .filter(|((id, word), _)| match &constraints {
+                Some(constraints) => tables.is_possible(arena, *id, constraints),
+                None => solver::is_word_possible(word, guesses),
+            })
+            .map(|((id, _), _)| id)
+            .collect();
+        Candidates { arena: Arc::clone(arena), ids: Arc::new(ids) }
+    }
+}
+
+// セッションごとに覚えておく候補の数
+const CANDIDATE_CACHE_CAPACITY: usize = 8;
+
+struct CachedCandidates {
+    guesses: Vec<WordleGuess>,
+    candidates: Candidates,
+}
+
+impl CachedCandidates {
+    // 同じ単語リストの、推測の並びの先頭部分か（単語リストが置き換わったら使わない）
+    fn is_prefix_of(&self, arena: &Arc<WordArena>, guesses: &[WordleGuess]) -> bool {
+        Arc::ptr_eq(&self.candidates.arena, arena)
+            && self.guesses.len() <= guesses.len()
+            && self.guesses.iter().zip(guesses).all(|(a, b)| a.word == b.word && a.results == b.results)
+    }
+}
+
+// 推測の並び（制約の先頭部分）ごとの候補のLRU（セッションごと）
+// 推測を色の入力に戻して確定し直しても、辞書全体を絞り込み直さずに済むようにする
+// セッションの複製とは中身を共有する（分析は複製した状態で行うため）
+#[derive(Clone, Default)]
+pub struct CandidateCache {
+    // 最近使ったものが後ろ
+    entries: Arc<Mutex<VecDeque<CachedCandidates>>>,
+}
+
+impl fmt::Debug for CandidateCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = self.entries.lock().unwrap_or_else(|e| e.into_inner()).len();
+        f.debug_struct("CandidateCache").field("entries", &len).finish()
+    }
+}
+
+impl CandidateCache {
+    // 推測に矛盾しない単語（同じ推測の並びは覚えておき、先頭部分が一致するものがあればその候補から絞る）
+    pub fn candidates(&self, words: &WordCache, guesses: &[WordleGuess]) -> Candidates {
+        let length = guesses.first().map_or(solver::WORD_LENGTH, |g| g.word.chars().count());
+        // 推測がないときは辞書全体になるため覚えない
+        let Some(arena) = words.words_of_length(length).filter(|_| !guesses.is_empty()) else {
+            return words.candidate_ids(guesses);
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(i) = entries.iter().position(|e| e.guesses.len() == guesses.len() && e.is_prefix_of(arena, guesses)) {
+            let entry = entries.remove(i).expect("position is in range");
+            let candidates = entry.candidates.clone();
+            entries.push_back(entry);
+            return candidates;
+        }
+
+        let prefix = entries.iter()
+            .filter(|e| e.is_prefix_of(arena, guesses))
+            .max_by_key(|e| e.guesses.len());
+        let candidates = match prefix {
+            Some(prefix) => {
+                // 先頭部分の推測はすでに満たしているため、残りの推測だけを確かめる
+                let rest = &guesses[prefix.guesses.len()..];
+                let tables = words.tables(length);
+                let constraints = GuessConstraint::compile(rest);
+                let ids = prefix.candidates.ids.iter()
+                    .copied()
+                    .filter(|&id| match (&tables, &constraints) {
+                        (Some(tables), Some(constraints)) => tables.is_possible(arena, id, constraints),
+                        _ => solver::is_word_possible(arena.word(id), rest),
+                    })
+                    .collect();
+                Candidates { arena: Arc::clone(arena), ids: Arc::new(ids) }
+            }
+            None => words.candidate_ids(guesses),
+        };
+
+        entries.push_back(CachedCandidates {
+            guesses: guesses.to_vec(),
+            candidates: candidates.clone(),
+        });
+        while entries.len() > CANDIDATE_CACHE_CAPACITY {
+            entries.pop_front();
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(words: &[&str]) -> WordCache {
+        WordCache::new(words.iter().enumerate().map(|(i, w)| WordRecord { id: i as i32, word: w.to_string() }).collect())
+    }
+
+    #[test]
+    fn partitions_by_length_and_matches_the_solver_filter() {
+        let words = ["crane", "slate", "trace", "crate", "react", "cater", "moist", "grasp", "tree", "planet"];
+        let cache = cache(&words);
+        assert_eq!(cache.words_of_length(4).unwrap().word(0), "TREE");
+        assert_eq!(cache.words_of_length(6).unwrap().len(), 1);
+
+        let guesses = [WordleGuess {
+            word: "CRANE".to_string(),
+            results: vec![LetterResult::Gray, LetterResult::Green, LetterResult::Green, LetterResult::Gray, LetterResult::Gray],
+        }];
+        assert_eq!(cache.candidate_ids(&guesses).to_strings(), ["GRASP"]);
+        assert_eq!(cache.candidate_ids(&guesses).to_strings(), solver::filter_candidates(words, &guesses));
+        assert_eq!(cache.candidate_ids(&[]).len(), 8);
+    }
+
+    #[test]
+    fn candidate_cache_reuses_prefixes() {
+        use LetterResult::{Gray, Green};
+        let words = cache(&["crane", "grasp", "brash", "trash", "moist"]);
+        let guess = |word: &str, results: [LetterResult; 5]| WordleGuess { word: word.to_string(), results: results.to_vec() };
+        let guesses = [guess("CRANE", [Gray, Green, Green, Gray, Gray]), guess("TRASH", [Gray, Green, Green, Green, Gray])];
+
+        let lru = CandidateCache::default();
+        let narrowed = lru.candidates(&words, &guesses[..1]);
+        assert_eq!(narrowed.to_strings(), ["GRASP", "BRASH", "TRASH"]);
+        assert_eq!(lru.candidates(&words, &guesses).to_strings(), ["GRASP"]);
+
+        // 同じ推測の並びは同じ結果を返し、置き換えた単語リストとは混ざらない
+        assert!(Arc::ptr_eq(&narrowed.ids, &lru.candidates(&words, &guesses[..1]).ids));
+        let reloaded = cache(&["crane", "grasp"]);
+        assert_eq!(lru.candidates(&reloaded, &guesses[..1]).to_strings(), ["GRASP"]);
+    }
+}
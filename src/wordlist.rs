@@ -0,0 +1,163 @@
+// 単語リストのバージョン管理
+// 単語リストの内容から版（ハッシュ）を決め、セッションには開始時の版を記録する
+// リストが更新されても、以前の版で始めたゲームの分析はその版の単語で行う
+use std::collections::VecDeque;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::RwLockReadGuard;
+use tracing::{info, warn};
+
+use crate::storage::{Storage, WordRecord};
+use crate::word_cache::WordCache;
+use crate::{Bot, GameState};
+
+// メモリに残しておく過去の版の数（それより古い版はストレージから読み込む）
+const RETAINED_VERSIONS: usize = 3;
+
+// 再起動やRustの版によって変わらないハッシュ（FNV-1a）
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+// 単語の並び順や大文字・小文字によらない版
+pub fn version_of<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    let mut words: Vec<String> = words.into_iter().map(str::to_lowercase).collect();
+    words.sort_unstable();
+    words.dedup();
+
+    let hash = fnv1a(words.iter().flat_map(|word| word.bytes().chain(std::iter::once(b'\n'))));
+    format!("{:016x}", hash)
+}
+
+#[derive(Default)]
+pub struct WordListVersions {
+    // 現在の単語キャッシュの版（読み込み前は None）
+    current: Mutex<Option<String>>,
+    // 置き換えられた過去の版（新しいものが後ろ）
+    retained: Mutex<VecDeque<(String, Arc<WordCache>)>>,
+}
+
+impl WordListVersions {
+    pub fn current(&self) -> Option<String> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    // 新しい単語リストを読み込んだときに呼ぶ（置き換えられるリストは過去の版として残す）
+    // 版が変わったら新しい版を返す
+    pub fn replace(&self, previous: WordCache, words: &WordCache) -> Option<String> {
+        let version = version_of(words.iter());
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        if current.as_deref() == Some(version.as_str()) {
+            return None;
+        }
+
+        if let Some(old) = current.take().filter(|_| !previous.is_empty()) {
+            let mut retained = self.retained.lock().unwrap_or_else(|e| e.into_inner());
+            retained.retain(|(v, _)| *v != old && *v != version);
+            retained.push_back((old, Arc::new(previous)));
+            while retained.len() > RETAINED_VERSIONS {
+                retained.pop_front();
+            }
+        }
+        *current = Some(version.clone());
+        Some(version)
+    }
+
+    fn retained(&self, version: &str) -> Option<Arc<WordCache>> {
+        self.retained.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|(v, _)| v == version)
+            .map(|(_, words)| Arc::clone(words))
+    }
+
+    fn retain(&self, version: String, words: Arc<WordCache>) {
+        let mut retained = self.retained.lock().unwrap_or_else(|e| e.into_inner());
+        retained.push_back((version, words));
+        while retained.len() > RETAINED_VERSIONS {
+            retained.pop_front();
+        }
+    }
+}
+
+// 分析に使う単語リスト（現在のキャッシュか、過去の版）
+pub(crate) enum WordList<'a> {
+    Current(RwLockReadGuard<'a, WordCache>),
+    Past(Arc<WordCache>),
+}
+
+impl Deref for WordList<'_> {
+    type Target = WordCache;
+
+    fn deref(&self) -> &WordCache {
+        match self {
+            WordList::Current(words) => words,
+            WordList::Past(words) => words,
+        }
+    }
+}
+
+// 新しい版をストレージに記録する（記録できなくても読み込みは続ける）
+pub(crate) async fn record_version(storage: &dyn Storage, version: &str, words: &WordCache) {
+    let list: Vec<String> = words.iter().map(str::to_lowercase).collect();
+    match storage.record_wordlist_version(version, &list).await {
+        Ok(()) => info!("Word list version {} ({} words)", version, list.len()),
+        Err(e) => warn!("Failed to record word list version {}: {:?}", version, e),
+    }
+}
+
+impl Bot {
+    // セッションを始めたときの版の単語リスト（見つからなければ現在のリストを使う）
+    pub(crate) async fn words_for(&self, state: &GameState) -> WordList<'_> {
+        let current = self.word_versions.current();
+        let Some(version) = state.word_list_version.as_deref().filter(|v| current.as_deref() != Some(*v)) else {
+            return WordList::Current(self.word_cache.read().await);
+        };
+
+        if let Some(words) = self.word_versions.retained(version) {
+            return WordList::Past(words);
+        }
+        match self.storage.load_wordlist_version(version).await {
+            Ok(Some(list)) => {
+                let words = Arc::new(WordCache::new(list.into_iter()
+                    .enumerate()
+                    .map(|(i, word)| WordRecord { id: i as i32 + 1, word })
+                    .collect()));
+                info!("Loaded word list version {} for an older game", version);
+                self.word_versions.retain(version.to_string(), Arc::clone(&words));
+                WordList::Past(words)
+            }
+            Ok(None) => {
+                warn!("Word list version {} is unknown; using the current list", version);
+                WordList::Current(self.word_cache.read().await)
+            }
+            Err(e) => {
+                warn!("Failed to load word list version {}: {:?}", version, e);
+                WordList::Current(self.word_cache.read().await)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(words: &[&str]) -> WordCache {
+        WordCache::new(words.iter().enumerate().map(|(i, w)| WordRecord { id: i as i32, word: w.to_string() }).collect())
+    }
+
+    #[test]
+    fn version_ignores_order_and_case_and_keeps_replaced_lists() {
+        let old = ["crane", "slate"];
+        let new = ["crane", "slate", "trace"];
+        assert_eq!(version_of(old), version_of(["SLATE", "crane"]));
+        assert_ne!(version_of(old), version_of(new));
+
+        let versions = WordListVersions::default();
+        assert_eq!(versions.replace(WordCache::default(), &cache(&old)), Some(version_of(old)));
+        assert_eq!(versions.replace(cache(&old), &cache(&old)), None);
+        assert_eq!(versions.replace(cache(&old), &cache(&new)), Some(version_of(new)));
+        assert_eq!(versions.retained(&version_of(old)).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,293 @@
+// セッションと成績の書き込みをまとめて送るキュー（write-behind）
+// 保存はメモリ上の待ち行列に入れるだけで、件数か経過時間が上限に達したときにまとめてストレージに送る
+// 送れなかった書き込みは次の機会に再送する（少なくとも1回は届く）。読み込みは未送信の値を優先する
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use serenity::async_trait;
+use tracing::{info, warn};
+
+use crate::storage::{CachedTable, EmojiRecord, StatsUpdate, Storage, WordRecord};
+
+pub const DEFAULT_BATCH_SIZE: usize = 50;
+pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key {
+    Session(u64),
+    Stats(u64),
+}
+
+pub struct WriteBehindStorage {
+    inner: Arc<dyn Storage>,
+    // 未送信の書き込み（同じ行への書き込みは最新の値だけを残す）
+    pending: Mutex<HashMap<Key, Value>>,
+    batch_size: AtomicUsize,
+    flush_interval_ms: AtomicU64,
+    last_flush: Mutex<Instant>,
+    // 送信は同時に1つだけ（送信中の値を読み込みや取り出しと競合させない）
+    flushing: tokio::sync::Mutex<()>,
+    // 成績の読み込みから書き込みまでを1つずつ行う（同じユーザーの正解が同時に記録されても失われない）
+    stats_updates: tokio::sync::Mutex<()>,
+}
+
+impl WriteBehindStorage {
+    pub fn new(inner: Arc<dyn Storage>) -> Self {
+        Self {
+            inner,
+            pending: Mutex::new(HashMap::new()),
+            batch_size: AtomicUsize::new(DEFAULT_BATCH_SIZE),
+            flush_interval_ms: AtomicU64::new(DEFAULT_FLUSH_INTERVAL.as_millis() as u64),
+            last_flush: Mutex::new(Instant::now()),
+            flushing: tokio::sync::Mutex::new(()),
+            stats_updates: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    // 何件たまったら、または何秒経ったら送るか
+    pub fn configure(&self, batch_size: usize, flush_interval: Duration) {
+        self.batch_size.store(batch_size.max(1), Ordering::Relaxed);
+        self.flush_interval_ms.store(flush_interval.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    fn pending_value(&self, key: Key) -> Option<Value> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).get(&key).cloned()
+    }
+
+    async fn enqueue(&self, key: Key, value: Value) {
+        let queued = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.insert(key, value);
+            pending.len()
+        };
+        if queued >= self.batch_size.load(Ordering::Relaxed) {
+            if let Err(e) = self.flush().await {
+                warn!("Failed to flush queued writes; will retry: {:?}", e);
+            }
+        }
+    }
+
+    // 前回の送信から間隔が空いていれば送る（定期ジョブから呼ぶ）
+    pub async fn flush_if_due(&self) -> anyhow::Result<()> {
+        let interval = Duration::from_millis(self.flush_interval_ms.load(Ordering::Relaxed));
+        let due = self.last_flush.lock().unwrap_or_else(|e| e.into_inner()).elapsed() >= interval;
+        if !due || self.pending_count() == 0 {
+            return Ok(());
+        }
+        self.flush().await
+    }
+
+    // 未送信の書き込みをすべて送る（失敗した分は残して次回に再送する）
+    pub async fn flush(&self) -> anyhow::Result<()> {
+        let _flushing = self.flushing.lock().await;
+        *self.last_flush.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+
+        let snapshot: Vec<(Key, Value)> = self.pending.lock().unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(key, value)| (*key, value.clone()))
+            .collect();
+        if snapshot.is_empty() {
+            return Ok(());
+        }
+
+        let mut sessions = Vec::new();
+        let mut stats = Vec::new();
+        for (key, value) in &snapshot {
+            match key {
+                Key::Session(id) => sessions.push((*id, value.clone())),
+                Key::Stats(id) => stats.push((*id, value.clone())),
+            }
+        }
+
+        let mut errors = Vec::new();
+        let mut sent = Vec::new();
+        if !sessions.is_empty() {
+            match self.inner.save_sessions(&sessions).await {
+                Ok(()) => sent.extend(sessions.into_iter().map(|(id, value)| (Key::Session(id), value))),
+                Err(e) => errors.push(e),
+            }
+        }
+        if !stats.is_empty() {
+            match self.inner.save_user_stats_batch(&stats).await {
+                Ok(()) => sent.extend(stats.into_iter().map(|(id, value)| (Key::Stats(id), value))),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        // 送信中に新しい値が書き込まれた行は残す
+        {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            for (key, value) in &sent {
+                if pending.get(key) == Some(value) {
+                    pending.remove(key);
+                }
+            }
+        }
+        info!("Flushed {} queued writes ({} failed batches)", sent.len(), errors.len());
+
+        match errors.into_iter().next() {
+            Some(e) => Err(e.context("Some queued writes could not be saved")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for WriteBehindStorage {
+    async fn fetch_words(&self) -> anyhow::Result<Vec<WordRecord>> {
+        self.inner.fetch_words().await
+    }
+
+    async fn fetch_emojis(&self) -> anyhow::Result<Vec<EmojiRecord>> {
+        self.inner.fetch_emojis().await
+    }
+
+    async fn table_fingerprint(&self, table: CachedTable) -> anyhow::Result<Option<String>> {
+        self.inner.table_fingerprint(table).await
+    }
+
+    async fn insert_words(&self, words: &[String]) -> anyhow::Result<()> {
+        self.inner.insert_words(words).await
+    }
+
+    async fn save_session(&self, user_id: u64, state: Value) -> anyhow::Result<()> {
+        self.enqueue(Key::Session(user_id), state).await;
+        Ok(())
+    }
+
+    async fn take_session(&self, user_id: u64) -> anyhow::Result<Option<Value>> {
+        // 送信中の値があとから保存されないよう、送信が終わるのを待つ
+        let _flushing = self.flushing.lock().await;
+        let queued = self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&Key::Session(user_id));
+        // 以前に保存された行も消しておく
+        let stored = self.inner.take_session(user_id).await;
+        match queued {
+            Some(state) => Ok(Some(state)),
+            None => stored,
+        }
+    }
+
+    async fn load_settings(&self) -> anyhow::Result<Option<Value>> {
+        self.inner.load_settings().await
+    }
+
+    async fn save_settings(&self, settings: Value) -> anyhow::Result<()> {
+        self.inner.save_settings(settings).await
+    }
+
+    async fn load_user_stats(&self, user_id: u64) -> anyhow::Result<Option<Value>> {
+        if let Some(stats) = self.pending_value(Key::Stats(user_id)) {
+            return Ok(Some(stats));
+        }
+        self.inner.load_user_stats(user_id).await
+    }
+
+    async fn save_user_stats(&self, user_id: u64, stats: Value) -> anyhow::Result<()> {
+        self.enqueue(Key::Stats(user_id), stats).await;
+        Ok(())
+    }
+
+    async fn record_wordlist_version(&self, version: &str, words: &[String]) -> anyhow::Result<()> {
+        self.inner.record_wordlist_version(version, words).await
+    }
+
+    async fn load_wordlist_version(&self, version: &str) -> anyhow::Result<Option<Vec<String>>> {
+        self.inner.load_wordlist_version(version).await
+    }
+
+    // 記録したかどうかで成績に数えるかを決めるため、まとめずにすぐ書き込む
+    async fn record_daily_result(&self, guild_id: u64, day: u64, user_id: u64, result: Value) -> anyhow::Result<bool> {
+        self.inner.record_daily_result(guild_id, day, user_id, result).await
+    }
+
+    async fn load_daily_result(&self, guild_id: u64, day: u64, user_id: u64) -> anyhow::Result<Option<Value>> {
+        self.inner.load_daily_result(guild_id, day, user_id).await
+    }
+
+    async fn load_daily_results(&self, guild_id: u64, day: u64) -> anyhow::Result<Vec<(u64, Value)>> {
+        self.inner.load_daily_results(guild_id, day).await
+    }
+
+    async fn record_daily_post(&self, guild_id: u64, day: u64, thread_id: u64) -> anyhow::Result<bool> {
+        self.inner.record_daily_post(guild_id, day, thread_id).await
+    }
+
+    async fn load_daily_post(&self, guild_id: u64, day: u64) -> anyhow::Result<Option<u64>> {
+        self.inner.load_daily_post(guild_id, day).await
+    }
+
+    // /wordle のゲームは推測のたびに保存し、再起動の直前の推測も失わないようすぐ書き込む
+    async fn save_play_game(&self, game_id: u64, started_at: u64, state: Value) -> anyhow::Result<()> {
+        self.inner.save_play_game(game_id, started_at, state).await
+    }
+
+    async fn load_play_game(&self, game_id: u64) -> anyhow::Result<Option<Value>> {
+        self.inner.load_play_game(game_id).await
+    }
+
+    async fn delete_play_game(&self, game_id: u64) -> anyhow::Result<()> {
+        self.inner.delete_play_game(game_id).await
+    }
+
+    async fn delete_play_games_before(&self, started_before: u64) -> anyhow::Result<()> {
+        self.inner.delete_play_games_before(started_before).await
+    }
+
+    async fn update_user_stats(&self, user_id: u64, update: StatsUpdate<'_>) -> anyhow::Result<Value> {
+        let _updating = self.stats_updates.lock().await;
+        let stats = update(self.load_user_stats(user_id).await?)?;
+        self.enqueue(Key::Stats(user_id), stats.clone()).await;
+        Ok(stats)
+    }
+
+    async fn list_user_stats(&self) -> anyhow::Result<Vec<(u64, Value)>> {
+        let mut stats: HashMap<u64, Value> = self.inner.list_user_stats().await?.into_iter().collect();
+        let pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        for (key, value) in pending.iter() {
+            if let Key::Stats(id) = key {
+                stats.insert(*id, value.clone());
+            }
+        }
+        Ok(stats.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn writes_are_batched_and_retried_until_saved() {
+        let memory = Arc::new(MemoryStorage::new(["crane"]));
+        let queue = WriteBehindStorage::new(Arc::clone(&memory) as Arc<dyn Storage>);
+        queue.configure(3, Duration::from_secs(60));
+
+        queue.save_user_stats(1, json!({ "wins": 1 })).await.unwrap();
+        queue.save_user_stats(1, json!({ "wins": 2 })).await.unwrap();
+        // 未送信でも読み込みには最新の値が返る
+        assert_eq!(memory.load_user_stats(1).await.unwrap(), None);
+        assert_eq!(queue.load_user_stats(1).await.unwrap(), Some(json!({ "wins": 2 })));
+
+        // 送信に失敗しても書き込みは残る
+        memory.set_unavailable(true);
+        assert!(queue.flush().await.is_err());
+        assert_eq!(queue.pending_count(), 1);
+
+        // 件数が上限に達すると送られる
+        memory.set_unavailable(false);
+        queue.save_user_stats(2, json!({ "wins": 1 })).await.unwrap();
+        queue.save_session(3, json!({ "guesses": [] })).await.unwrap();
+        assert_eq!(queue.pending_count(), 0);
+        assert_eq!(memory.load_user_stats(1).await.unwrap(), Some(json!({ "wins": 2 })));
+        assert_eq!(queue.take_session(3).await.unwrap(), Some(json!({ "guesses": [] })));
+    }
+}
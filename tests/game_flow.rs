@@ -0,0 +1,1046 @@
+// Discordやデータベースを使わずにゲームの流れを検証する統合テスト
+use std::sync::Arc;
+
+use discord_bot::custom_id::PlayedWords;
+use discord_bot::i18n::Locale;
+use discord_bot::permissions::PermissionConfig;
+use discord_bot::quordle::QuordleHistory;
+use discord_bot::session::{self, BoardControls, BoardSection, SessionError};
+use discord_bot::settings::{DailyGuessLimit, Difficulty, GuessLimit, InputStyle, Settings};
+use discord_bot::storage::{MemoryStorage, Storage};
+use discord_bot::solver::{HardModeViolation, MAX_GUESSES};
+use discord_bot::{Bot, LetterResult, Phase, SharedBoard, WordleGuess};
+use serenity::all::GuildId;
+
+const USER: u64 = 42;
+
+const WORDS: [&str; 8] = ["crane", "slate", "trace", "crate", "react", "cater", "plant", "moist"];
+
+async fn bot_with_storage(storage: Arc<MemoryStorage>) -> Bot {
+    let bot = Bot::new(GuildId::new(1), storage, PermissionConfig::default());
+    let _ = bot.load_word_cache().await;
+    bot
+}
+
+async fn bot_with_words() -> Bot {
+    bot_with_storage(Arc::new(MemoryStorage::new(WORDS))).await
+}
+
+// 最新の盤面のリビジョン（ボタンに埋め込まれている値）
+async fn revision(bot: &Bot) -> u64 {
+    bot.session(USER).await.map_or(0, |state| state.revision)
+}
+
+#[tokio::test]
+async fn start_session_shows_new_word_button() {
+    let bot = bot_with_words().await;
+
+    let view = bot.start_session(USER, None).await;
+
+    assert_eq!(view.controls, BoardControls::NewWord);
+    let state = bot.session(USER).await.expect("session should exist");
+    assert!(state.guesses.is_empty());
+    assert_eq!(state.phase, Phase::AwaitingWord);
+}
+
+#[tokio::test]
+async fn submitted_word_starts_as_all_gray() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+
+    let view = bot.submit_word(USER, revision(&bot).await, "crane").await.expect("session should exist");
+
+    assert_eq!(
+        view.controls,
+        BoardControls::LetterResults {
+            word: "CRANE".to_string(),
+            results: vec![LetterResult::Gray; 5],
+        }
+    );
+    let state = bot.session(USER).await.unwrap();
+    assert!(matches!(state.phase, Phase::AwaitingColors { .. }));
+}
+
+#[tokio::test]
+async fn letters_can_be_set_one_by_one_like_reactions() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+    bot.submit_word(USER, revision(&bot).await, "crane").await.unwrap();
+
+    let colors = [LetterResult::Yellow, LetterResult::Green, LetterResult::Green, LetterResult::Gray, LetterResult::Green];
+    for (index, color) in colors.into_iter().enumerate() {
+        bot.set_letter(USER, revision(&bot).await, index, color).await.unwrap();
+    }
+    // 古いリビジョンからの入力は拒否する
+    assert_eq!(bot.set_letter(USER, 0, 0, LetterResult::Gray).await.unwrap_err(), SessionError::Stale);
+
+    let view = bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+    // 確定した推測は過去の推測のメニューに並ぶ
+    assert_eq!(view.history.len(), 1);
+    let state = bot.session(USER).await.unwrap();
+    assert_eq!(state.guesses[0].results, colors.to_vec());
+}
+
+#[tokio::test]
+async fn menu_input_sets_every_letter_at_once() {
+    let bot = bot_with_words().await;
+    bot.set_input_style(USER, InputStyle::Select).await.unwrap();
+    bot.start_session(USER, None).await;
+
+    let view = bot.submit_word(USER, revision(&bot).await, "crane").await.unwrap();
+    assert_eq!(
+        view.controls,
+        BoardControls::LetterMenu {
+            word: "CRANE".to_string(),
+            results: vec![LetterResult::Gray; 5],
+        }
+    );
+
+    let colors = vec![LetterResult::Green, LetterResult::Gray, LetterResult::Yellow, LetterResult::Gray, LetterResult::Green];
+    let view = bot.set_letters(USER, revision(&bot).await, colors.clone()).await.unwrap();
+    assert_eq!(view.controls, BoardControls::LetterMenu { word: "CRANE".to_string(), results: colors.clone() });
+
+    bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+    assert_eq!(bot.session(USER).await.unwrap().guesses[0].results, colors);
+
+    // ボタンに戻すと次の単語からボタンで入力する
+    bot.set_input_style(USER, InputStyle::Buttons).await.unwrap();
+    assert!(bot.settings().input_styles.is_empty());
+}
+
+#[tokio::test]
+async fn letter_colors_cycle_gray_yellow_green() {
+    fn results(state: discord_bot::GameState) -> Vec<LetterResult> {
+        match state.phase {
+            Phase::AwaitingColors { results, .. } => results,
+            phase => panic!("unexpected phase: {:?}", phase),
+        }
+    }
+
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+    bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap();
+
+    let expected = [LetterResult::Yellow, LetterResult::Green, LetterResult::Gray];
+    for result in expected {
+        bot.cycle_letter(USER, revision(&bot).await, 1).await.unwrap();
+        assert_eq!(results(bot.session(USER).await.unwrap())[1], result);
+    }
+
+    // 範囲外の位置は無視される
+    bot.cycle_letter(USER, revision(&bot).await, 9).await.expect("session should exist");
+    assert_eq!(results(bot.session(USER).await.unwrap()), vec![LetterResult::Gray; 5]);
+}
+
+#[tokio::test]
+async fn confirmed_guess_narrows_suggestions() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+    bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap();
+
+    // 正解 TRACE に対する結果: 黄 緑 緑 灰 緑
+    for (index, clicks) in [(0, 1), (1, 2), (2, 2), (4, 2)] {
+        for _ in 0..clicks {
+            bot.cycle_letter(USER, revision(&bot).await, index).await.unwrap();
+        }
+    }
+
+    let loading = bot.confirm_guess(USER, revision(&bot).await).await.expect("session should exist");
+    assert!(loading.description.contains("分析中"));
+
+    let view = bot.finish_analysis(USER).await.expect("session should exist");
+    assert_eq!(view.controls, BoardControls::NewWord);
+    assert!(view.field(BoardSection::Suggestions).unwrap().contains("**TRACE**"));
+    assert!(view.field(BoardSection::History).unwrap().contains("**1回目:**"));
+    assert!(view.field(BoardSection::Candidates).is_some());
+
+    let state = bot.session(USER).await.unwrap();
+    assert_eq!(state.guesses.len(), 1);
+    assert_eq!(state.guesses[0].word, "CRANE");
+    assert_eq!(state.phase, Phase::AwaitingWord);
+    assert!(state.last_suggestion.contains("TRACE"));
+}
+
+#[tokio::test]
+async fn cancelled_analysis_returns_to_the_colors_before_confirming() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+    bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap();
+    bot.cycle_letter(USER, revision(&bot).await, 1).await.unwrap();
+
+    let analyzing = bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+    assert_eq!(analyzing.controls, BoardControls::Analyzing { cancellable: true });
+
+    let view = bot.cancel_analysis(USER, analyzing.revision).await.unwrap();
+    assert!(matches!(view.controls, BoardControls::LetterResults { ref word, ref results }
+        if word == "CRANE" && results[1] == LetterResult::Yellow));
+    assert!(bot.session(USER).await.unwrap().guesses.is_empty());
+
+    // 同じ盤面のボタンをもう一度押しても何も変わらない
+    assert_eq!(bot.cancel_analysis(USER, analyzing.revision).await.unwrap_err(), SessionError::Stale);
+}
+
+#[tokio::test]
+async fn contradictory_guesses_point_to_the_guess_to_fix() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+
+    // CRANE はすべて灰色なのに、SLATE の A を黄色にする
+    bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap();
+    bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+    bot.finish_analysis(USER).await.unwrap();
+    bot.submit_word(USER, revision(&bot).await, "SLATE").await.unwrap();
+    bot.cycle_letter(USER, revision(&bot).await, 2).await.unwrap();
+    bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+
+    let view = bot.finish_analysis(USER).await.unwrap();
+    assert_eq!(view.controls, BoardControls::EditGuess { index: 1 });
+    assert!(view.description.contains("A の色が矛盾"));
+    assert_eq!(view.field(BoardSection::Suggestions), None);
+
+    // 修正ボタンでその推測の色の入力に戻る
+    let view = bot.reopen_guess(USER, view.revision, 1).await.unwrap();
+    assert!(matches!(view.controls, BoardControls::LetterResults { ref word, ref results }
+        if word == "SLATE" && results[2] == LetterResult::Yellow));
+    assert_eq!(bot.session(USER).await.unwrap().guesses.len(), 1);
+}
+
+#[tokio::test]
+async fn unavailable_storage_falls_back_to_default_openers() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    storage.set_unavailable(true);
+    let bot = bot_with_storage(storage).await;
+
+    bot.start_session(USER, None).await;
+    let view = bot.finish_analysis(USER).await.expect("session should exist");
+
+    assert!(view.field(BoardSection::Suggestions).unwrap().contains("**SLATE**"));
+}
+
+#[tokio::test]
+async fn interactions_without_session_are_rejected() {
+    let bot = bot_with_words().await;
+
+    assert_eq!(bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap_err(), SessionError::NotFound);
+    assert_eq!(bot.cycle_letter(USER, revision(&bot).await, 0).await.unwrap_err(), SessionError::NotFound);
+    assert_eq!(bot.confirm_guess(USER, revision(&bot).await).await.unwrap_err(), SessionError::NotFound);
+    assert_eq!(bot.finish_analysis(USER).await.unwrap_err(), SessionError::NotFound);
+}
+
+#[tokio::test]
+async fn confirm_without_word_is_rejected() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+
+    assert_eq!(bot.confirm_guess(USER, revision(&bot).await).await.unwrap_err(), SessionError::NoWordToConfirm);
+    assert_eq!(bot.cycle_letter(USER, revision(&bot).await, 0).await.unwrap_err(), SessionError::NotAwaitingColors);
+    assert!(bot.session(USER).await.unwrap().guesses.is_empty());
+}
+
+#[tokio::test]
+async fn all_green_guess_completes_the_game() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+    bot.submit_word(USER, revision(&bot).await, "TRACE").await.unwrap();
+    for index in 0..5 {
+        bot.cycle_letter(USER, revision(&bot).await, index).await.unwrap();
+        bot.cycle_letter(USER, revision(&bot).await, index).await.unwrap();
+    }
+
+    let view = bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+
+    assert_eq!(view.controls, BoardControls::Finished);
+    assert_eq!(bot.session(USER).await.unwrap().phase, Phase::Completed);
+    assert_eq!(bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap_err(), SessionError::AlreadySolved);
+
+    // 共有用の結果は色のマスだけで、単語は含まない
+    let grid = bot.session(USER).await.unwrap().share_grid();
+    assert!(grid.ends_with("🟩🟩🟩🟩🟩"), "{}", grid);
+    assert!(!grid.contains("TRACE"), "{}", grid);
+
+    // 正解は成績に記録される
+    let stats = bot.user_stats(USER).await.unwrap();
+    assert_eq!((stats.wins, stats.current_streak), (1, 1));
+
+    // 画像の保存は終了した盤面のボタンからのみ
+    assert_eq!(bot.finished_guesses(USER, view.revision).await.unwrap()[0].word, "TRACE");
+    assert_eq!(bot.finished_guesses(USER, view.revision - 1).await.unwrap_err(), SessionError::Stale);
+}
+
+#[tokio::test]
+async fn clicks_from_outdated_board_are_rejected() {
+    let bot = bot_with_words().await;
+    let first = bot.start_session(USER, None).await;
+    let view = bot.submit_word(USER, first.revision, "CRANE").await.unwrap();
+    assert_ne!(view.revision, first.revision);
+
+    // 古い盤面のボタンからの操作は状態を変更しない
+    assert_eq!(bot.submit_word(USER, first.revision, "SLATE").await.unwrap_err(), SessionError::Stale);
+    bot.cycle_letter(USER, view.revision, 0).await.unwrap();
+    assert_eq!(bot.cycle_letter(USER, view.revision, 0).await.unwrap_err(), SessionError::Stale);
+
+    // 新しいセッションを始めても以前のメッセージのボタンは使えない
+    let restarted = bot.start_session(USER, None).await;
+    assert_ne!(restarted.revision, view.revision);
+    assert_eq!(bot.confirm_guess(USER, view.revision).await.unwrap_err(), SessionError::Stale);
+}
+
+#[tokio::test]
+async fn oldest_session_is_evicted_and_restored_from_storage() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await.with_session_limit(1, true);
+    const OTHER: u64 = 7;
+
+    let first = bot.start_session(USER, None).await;
+    bot.submit_word(USER, first.revision, "CRANE").await.unwrap();
+    bot.start_session(OTHER, None).await;
+
+    assert_eq!(bot.session_usage().await, (1, 1));
+
+    // 追い出されたセッションは次の操作で復元され、今度は OTHER が追い出される
+    let state = bot.session(USER).await.expect("session should be restored");
+    assert!(matches!(state.phase, Phase::AwaitingColors { ref word, .. } if word == "CRANE"));
+    assert_eq!(bot.session_usage().await, (1, 1));
+    assert!(bot.session(OTHER).await.is_some());
+}
+
+#[tokio::test]
+async fn persisted_sessions_continue_after_a_restart() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await.with_session_limit(10, true);
+    bot.start_session(USER, None).await;
+    let view = bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap();
+    bot.flush_writes().await;
+
+    // 再起動したボットでも、古いメッセージのボタンからそのまま続けられる
+    let restarted = bot_with_storage(Arc::clone(&storage)).await.with_session_limit(10, true);
+    let view = restarted.cycle_letter(USER, view.revision, 0).await.unwrap();
+    assert!(matches!(view.controls, BoardControls::LetterResults { ref word, ref results }
+        if word == "CRANE" && results[0] == LetterResult::Yellow));
+}
+
+#[tokio::test]
+async fn evicted_session_is_dropped_without_persistence() {
+    let bot = bot_with_words().await.with_session_limit(1, false);
+
+    bot.start_session(USER, None).await;
+    bot.start_session(7, None).await;
+
+    assert!(bot.session(USER).await.is_none());
+}
+
+#[tokio::test]
+async fn saved_settings_apply_immediately_and_survive_reload() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await;
+
+    let mut settings = Settings::clone(&bot.settings());
+    settings.set("suggestion_count", "2").unwrap();
+    bot.save_settings(settings).await.unwrap();
+
+    bot.start_session(USER, None).await;
+    let view = bot.finish_analysis(USER).await.unwrap();
+    let suggestions = view.field(BoardSection::Suggestions).unwrap();
+    assert!(suggestions.contains("🥈"), "{}", suggestions);
+    assert!(!suggestions.contains("🥉"), "{}", suggestions);
+
+    // 別のインスタンスでも保存した設定が読み込まれる
+    let restarted = bot_with_storage(storage).await;
+    restarted.load_settings().await.unwrap();
+    assert_eq!(restarted.settings().suggestion_count, 2);
+}
+
+#[tokio::test]
+async fn board_uses_the_guild_locale() {
+    let bot = bot_with_words().await;
+
+    let mut settings = Settings::clone(&bot.settings());
+    settings.guild_locales.insert(1, Locale::En);
+    bot.save_settings(settings).await.unwrap();
+
+    let view = bot.start_session(USER, Some(1)).await;
+    assert_eq!(view.locale, Locale::En);
+    assert!(view.description.contains("No guesses yet"), "{}", view.description);
+
+    let view = bot.submit_word(USER, view.revision, "crane").await.unwrap();
+    assert!(view.field(BoardSection::CurrentWord).is_some());
+    assert_eq!(Locale::En.section_name(BoardSection::CurrentWord), "Current word");
+
+    // 設定のないサーバーでは既定の言語のまま
+    let view = bot.start_session(USER, Some(2)).await;
+    assert_eq!(view.locale, Locale::Ja);
+}
+
+#[tokio::test]
+async fn remaining_candidates_counts_without_suggesting() {
+    let bot = bot_with_words().await;
+    assert_eq!(bot.remaining_candidates(USER).await.unwrap_err(), SessionError::NotFound);
+
+    bot.start_session(USER, None).await;
+    assert_eq!(bot.remaining_candidates(USER).await.unwrap(), WORDS.len());
+
+    bot.submit_word(USER, revision(&bot).await, "MOIST").await.unwrap();
+    bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+
+    // M・O・I・S・T を含まないのは CRANE だけ
+    assert_eq!(bot.remaining_candidates(USER).await.unwrap(), 1);
+    assert!(bot.session(USER).await.unwrap().last_suggestion.is_empty());
+}
+
+#[tokio::test]
+async fn hard_mode_check_warns_only_when_enabled() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+
+    // CRANE の R を緑にして確定
+    bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap();
+    bot.cycle_letter(USER, revision(&bot).await, 1).await.unwrap();
+    bot.cycle_letter(USER, revision(&bot).await, 1).await.unwrap();
+    bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+
+    assert!(bot.hard_mode_violations(USER, "SLATE").await.is_empty());
+
+    assert!(bot.set_hard_mode(USER, true).await);
+    assert_eq!(
+        bot.hard_mode_violations(USER, "SLATE").await,
+        vec![HardModeViolation::MissingGreen { letter: 'R', position: 1 }],
+    );
+    assert!(bot.hard_mode_violations(USER, "TRACE").await.is_empty());
+}
+
+#[tokio::test]
+async fn hard_mode_suggests_only_words_that_use_the_hints() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+    bot.set_difficulty(USER, Difficulty::Advanced).await;
+    bot.set_hard_mode(USER, true).await;
+
+    // MOIST の T を黄色にして確定（上級では候補でない単語も提案に入りうる）
+    bot.submit_word(USER, revision(&bot).await, "MOIST").await.unwrap();
+    bot.cycle_letter(USER, revision(&bot).await, 4).await.unwrap();
+    bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+
+    let view = bot.finish_analysis(USER).await.unwrap();
+    let suggestions = view.field(BoardSection::Suggestions).unwrap();
+    assert!(suggestions.contains("ハードモード"), "{}", suggestions);
+    let words: Vec<&str> = suggestions.split("**").skip(1).step_by(2).collect();
+    assert!(!words.is_empty(), "{}", suggestions);
+    for word in words {
+        assert!(bot.hard_mode_violations(USER, word).await.is_empty(), "{} in {}", word, suggestions);
+    }
+}
+
+#[tokio::test]
+async fn imported_colors_can_be_fixed_before_confirming() {
+    let bot = bot_with_words().await;
+    let gray = vec![LetterResult::Gray; 5];
+    let view = bot.start_import(USER, None, vec![gray.clone(), gray]).await;
+    assert!(matches!(view.controls, BoardControls::Importing { selected: 0, .. }));
+
+    // 読み込み中は単語を入力できない
+    assert_eq!(bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap_err(), SessionError::ImportPending);
+
+    // 2行目の1文字目を黄色に直す
+    bot.select_import_row(USER, revision(&bot).await, 1).await.unwrap();
+    let view = bot.cycle_letter(USER, revision(&bot).await, 0).await.unwrap();
+    let BoardControls::Importing { rows, selected: 1 } = view.controls else { panic!("expected import controls") };
+    assert_eq!(rows[1][0], LetterResult::Yellow);
+
+    assert_eq!(
+        bot.finish_import(USER, revision(&bot).await, "MOIST").await.unwrap_err(),
+        SessionError::ImportWordMismatch,
+    );
+
+    let view = bot.finish_import(USER, revision(&bot).await, "moist, plant").await.unwrap();
+    assert!(view.description.contains("分析中"));
+    let state = bot.session(USER).await.unwrap();
+    assert_eq!(state.phase, Phase::AwaitingWord);
+    assert_eq!(state.guesses[1].word, "PLANT");
+    assert_eq!(state.guesses[1].results[0], LetterResult::Yellow);
+}
+
+#[tokio::test]
+async fn concurrent_wins_by_the_same_user_are_all_recorded() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await.with_write_batching(1, std::time::Duration::from_secs(60));
+
+    let tasks: Vec<_> = (0..10)
+        .map(|i| {
+            let bot = bot.clone();
+            tokio::spawn(async move { bot.record_win(USER, None, 3 + i % 2, MAX_GUESSES).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    let stats = bot.user_stats(USER).await.unwrap();
+    assert_eq!((stats.wins, stats.counted_wins, stats.total_guesses), (10, 10, 35));
+    // 件数の上限が1なので、すべてストレージまで届いている
+    assert!(storage.load_user_stats(USER).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn games_keep_the_word_list_they_started_with() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await;
+
+    let view = bot.start_session(USER, None).await;
+    let view = bot.submit_word(USER, view.revision, "MOIST").await.unwrap();
+    bot.confirm_guess(USER, view.revision).await.unwrap();
+    let before = bot.remaining_candidates(USER).await.unwrap();
+
+    // 単語リストが更新されても、更新前に始めたゲームの候補は変わらない
+    storage.insert_words(&["bulky".to_string()]).await.unwrap();
+    bot.load_word_cache().await.unwrap();
+    assert_eq!(bot.remaining_candidates(USER).await.unwrap(), before);
+
+    // 新しく始めたゲームは新しいリストを使う
+    bot.start_session(USER, None).await;
+    let view = bot.submit_word(USER, revision(&bot).await, "MOIST").await.unwrap();
+    bot.confirm_guess(USER, view.revision).await.unwrap();
+    assert_eq!(bot.remaining_candidates(USER).await.unwrap(), before + 1);
+}
+
+#[tokio::test]
+async fn cache_refresh_reloads_only_tables_that_changed() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await;
+
+    // 前回の指紋がないうちは両方読み込み、変わっていなければ取得を省く
+    let refreshed = bot.refresh_caches().await.unwrap();
+    assert!(refreshed.words && refreshed.emojis);
+    let refreshed = bot.refresh_caches().await.unwrap();
+    assert!(!refreshed.words && !refreshed.emojis);
+
+    bot.start_session(USER, None).await;
+    let view = bot.submit_word(USER, revision(&bot).await, "MOIST").await.unwrap();
+    bot.confirm_guess(USER, view.revision).await.unwrap();
+    let before = bot.remaining_candidates(USER).await.unwrap();
+
+    // 追加された単語は再起動しなくても新しいゲームの候補に入る
+    storage.insert_words(&["bulky".to_string()]).await.unwrap();
+    let refreshed = bot.refresh_caches().await.unwrap();
+    assert!(refreshed.words && !refreshed.emojis);
+
+    bot.start_session(USER, None).await;
+    let view = bot.submit_word(USER, revision(&bot).await, "MOIST").await.unwrap();
+    bot.confirm_guess(USER, view.revision).await.unwrap();
+    assert_eq!(bot.remaining_candidates(USER).await.unwrap(), before + 1);
+}
+
+#[test]
+fn keyboard_shows_the_best_color_found_for_each_letter() {
+    use LetterResult::*;
+    let guesses = [
+        WordleGuess { word: "CRANE".to_string(), results: vec![Gray, Yellow, Gray, Gray, Yellow] },
+        WordleGuess { word: "RESET".to_string(), results: vec![Green, Yellow, Gray, Gray, Gray] },
+    ];
+    let colors = session::keyboard_colors(&guesses);
+    let color = |letter: u8| colors[(letter - b'A') as usize];
+
+    assert_eq!(color(b'R'), Some(Green));
+    // 同じ推測で黄と灰になった文字は黄
+    assert_eq!(color(b'E'), Some(Yellow));
+    assert_eq!(color(b'C'), Some(Gray));
+    assert_eq!(color(b'Q'), None);
+    assert_eq!(session::KEYBOARD_ROWS.concat().len(), 26);
+}
+
+#[tokio::test]
+async fn confirmed_guesses_add_a_keyboard_to_the_board() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+    let view = bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap();
+    assert!(view.field(BoardSection::Keyboard).is_none());
+
+    bot.confirm_guess(USER, view.revision).await.unwrap();
+    let view = bot.finish_analysis(USER).await.unwrap();
+    let keyboard = view.field(BoardSection::Keyboard).unwrap();
+    assert_eq!(keyboard.lines().count(), 3);
+    assert!(keyboard.contains("`Q`") && !keyboard.contains("`C`"), "{}", keyboard);
+}
+
+#[test]
+fn long_history_is_split_into_fields_within_the_limit() {
+    let lines = (1..=12).map(|i| format!("**{}回目:** {}", i, "<:green_a:123456789012345678>".repeat(5)));
+    let fields = session::board_fields(BoardSection::History, lines);
+
+    assert!(fields.len() > 1);
+    assert!(fields.iter().all(|f| f.value.chars().count() <= session::MAX_FIELD_CHARS));
+    assert_eq!(fields.iter().map(|f| f.value.lines().count()).sum::<usize>(), 12);
+}
+
+#[tokio::test]
+async fn toggling_the_locale_redraws_only_this_session() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+    bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap();
+
+    let view = bot.toggle_locale(USER, revision(&bot).await).await.unwrap();
+    assert_eq!(view.locale, Locale::En);
+    assert!(matches!(view.controls, BoardControls::LetterResults { .. }));
+    assert!(view.description.contains("Click each letter"), "{}", view.description);
+
+    // 提案を表示する段階では、確定した直後に計算した提案を切り替えた言語で描き直す
+    bot.confirm_guess(USER, view.revision).await.unwrap();
+    bot.finish_analysis(USER).await.unwrap();
+    let view = bot.toggle_locale(USER, revision(&bot).await).await.unwrap();
+    assert_eq!(view.locale, Locale::Ja);
+    assert!(!view.description.contains("分析中"), "{}", view.description);
+    assert_eq!(view.controls, BoardControls::NewWord);
+    assert!(view.field(BoardSection::Suggestions).is_some());
+
+    // サーバーの設定は変わらない
+    assert_eq!(bot.start_session(USER, None).await.locale, Locale::Ja);
+}
+
+#[tokio::test]
+async fn difficulty_changes_how_many_suggestions_are_explained() {
+    let bot = bot_with_words().await;
+
+    bot.start_session(USER, None).await;
+    assert!(bot.set_difficulty(USER, Difficulty::Beginner).await);
+    let view = bot.finish_analysis(USER).await.unwrap();
+    let suggestions = view.field(BoardSection::Suggestions).unwrap();
+    assert_eq!(suggestions.lines().filter(|l| l.contains("**")).count(), 3, "{}", suggestions);
+    assert!(suggestions.contains("平均"), "{}", suggestions);
+
+    // どの難易度でも、単語ごとに情報量と残る候補数の期待値を添える
+    for difficulty in [Difficulty::Standard, Difficulty::Advanced] {
+        bot.set_difficulty(USER, difficulty).await;
+        let view = bot.finish_analysis(USER).await.unwrap();
+        let suggestions = view.field(BoardSection::Suggestions).unwrap();
+        assert!(suggestions.contains("ビット") && suggestions.contains("平均"), "{}", suggestions);
+    }
+
+    // サーバー全体の既定値は新しいセッションに使う
+    let mut settings = Settings::clone(&bot.settings());
+    settings.set("default_difficulty", "advanced").unwrap();
+    bot.save_settings(settings).await.unwrap();
+    bot.start_session(USER, None).await;
+    assert_eq!(bot.session(USER).await.unwrap().difficulty, Difficulty::Advanced);
+}
+
+#[tokio::test]
+async fn top_suggestions_keep_their_explanations() {
+    let bot = bot_with_words().await;
+    bot.start_session(USER, None).await;
+    bot.submit_word(USER, revision(&bot).await, "CRANE").await.unwrap();
+    bot.confirm_guess(USER, revision(&bot).await).await.unwrap();
+
+    let view = bot.finish_analysis(USER).await.unwrap();
+    assert!(!view.explained.is_empty() && view.explained.len() <= 3);
+
+    let explanation = bot.explain_suggestion(USER, view.revision, 0).await.unwrap();
+    assert_eq!(explanation.word, view.explained[0]);
+    assert!(explanation.untested_letters.iter().all(|c| !"CRANE".contains(*c)));
+    assert!(explanation.expected_remaining <= explanation.candidates as f64);
+
+    // 一番のおすすめの文字ごとの内訳は、どの位置でも残りの候補の合計になる
+    let map = view.field(BoardSection::Elimination).unwrap();
+    assert!(map.contains(&format!("**{}**", explanation.word)), "{}", map);
+    assert_eq!(explanation.splits.len(), 5);
+    assert!(explanation.splits.iter().all(|s| s.green + s.yellow + s.gray == explanation.candidates));
+
+    // 盤面が変わったあとの古いボタンは使えない
+    bot.submit_word(USER, view.revision, "SLATE").await.unwrap();
+    assert_eq!(bot.explain_suggestion(USER, view.revision, 0).await.unwrap_err(), SessionError::Stale);
+}
+
+#[tokio::test]
+async fn banned_words_are_left_out_of_candidates_and_suggestions() {
+    let bot = bot_with_words().await;
+
+    let mut settings = Settings::clone(&bot.settings());
+    settings.set("banned_words", "moist").unwrap();
+    settings.guild_banned_words.insert(1, ["TRACE".to_string()].into());
+    bot.save_settings(settings).await.unwrap();
+
+    bot.start_session(USER, Some(1)).await;
+    assert_eq!(bot.remaining_candidates(USER).await.unwrap(), WORDS.len() - 2);
+    let view = bot.finish_analysis(USER).await.unwrap();
+    let suggestions = view.field(BoardSection::Suggestions).unwrap();
+    assert!(!suggestions.contains("TRACE") && !suggestions.contains("MOIST"), "{}", suggestions);
+
+    // ほかのサーバーでは全サーバー共通の単語だけを除く
+    bot.start_session(USER, Some(2)).await;
+    assert_eq!(bot.remaining_candidates(USER).await.unwrap(), WORDS.len() - 1);
+}
+
+#[tokio::test]
+async fn strict_servers_reject_words_outside_the_list_with_nearby_words() {
+    let bot = bot_with_words().await;
+    let mut settings = Settings::clone(&bot.settings());
+    settings.strict_guilds.insert(1);
+    bot.save_settings(settings).await.unwrap();
+
+    bot.start_session(USER, Some(1)).await;
+    assert_eq!(bot.unknown_word(USER, "crane").await, None);
+    let nearest = bot.unknown_word(USER, "CRAME").await.unwrap();
+    assert_eq!(nearest[..2], ["CRANE", "CRATE"]);
+
+    // 厳格でないサーバーでは、単語リストにない単語も受け付ける
+    bot.start_session(USER, Some(2)).await;
+    assert_eq!(bot.unknown_word(USER, "CRAME").await, None);
+}
+
+#[tokio::test]
+async fn shared_boards_live_in_the_channel_and_are_not_counted_as_wins() {
+    const CHANNEL: u64 = 7;
+    let bot = bot_with_words().await;
+    let board = SharedBoard { host: USER, host_only: true };
+
+    let view = bot.start_shared_session(CHANNEL, None, board).await;
+    assert_eq!(view.session, CHANNEL);
+    assert!(view.description.contains("開始した人だけ"), "{}", view.description);
+    assert_eq!(bot.session(CHANNEL).await.unwrap().shared, Some(board));
+    assert!(bot.session(USER).await.is_none());
+
+    let revision = bot.session(CHANNEL).await.unwrap().revision;
+    bot.submit_word(CHANNEL, revision, "TRACE").await.unwrap();
+    for index in 0..5 {
+        for _ in 0..2 {
+            let revision = bot.session(CHANNEL).await.unwrap().revision;
+            bot.cycle_letter(CHANNEL, revision, index).await.unwrap();
+        }
+    }
+    let revision = bot.session(CHANNEL).await.unwrap().revision;
+    assert_eq!(bot.confirm_guess(CHANNEL, revision).await.unwrap().controls, BoardControls::Finished);
+
+    // 共有した盤面の正解は、開始した人の成績にもチャンネルの成績にも数えない
+    assert_eq!(bot.user_stats(USER).await.unwrap().wins, 0);
+    assert_eq!(bot.user_stats(CHANNEL).await.unwrap().wins, 0);
+}
+
+#[tokio::test]
+async fn wins_of_private_users_are_not_recorded() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await;
+
+    bot.record_win(USER, None, 3, MAX_GUESSES).await.unwrap();
+    bot.set_private(USER, true).await.unwrap();
+    assert!(bot.settings().is_private(USER));
+
+    // 非公開にしたあとの正解は記録しない
+    assert_eq!(bot.record_win(USER, None, 4, MAX_GUESSES).await.unwrap(), None);
+    assert_eq!(bot.user_stats(USER).await.unwrap().wins, 1);
+
+    // 公開に戻すと再び記録する
+    bot.set_private(USER, false).await.unwrap();
+    assert_eq!(bot.record_win(USER, None, 4, MAX_GUESSES).await.unwrap().map(|stats| stats.wins), Some(2));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn settings_changed_at_the_same_time_are_all_kept() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await;
+
+    let updates: Vec<_> = (0..20u64)
+        .map(|user| {
+            let bot = bot.clone();
+            tokio::spawn(async move { bot.set_private(user, true).await })
+        })
+        .collect();
+    for update in updates {
+        update.await.unwrap().unwrap();
+    }
+
+    // 後から保存した変更が、先に保存した変更を上書きしない
+    assert!((0..20).all(|user| bot.settings().is_private(user)));
+    let stored: Settings = serde_json::from_value(storage.load_settings().await.unwrap().unwrap()).unwrap();
+    assert!((0..20).all(|user| stored.is_private(user)));
+}
+
+#[tokio::test]
+async fn finished_playable_games_are_recorded_in_stats() {
+    let bot = bot_with_words().await;
+    let mut revision = bot.start_play(USER, None, GuessLimit::Classic).await.unwrap().revision;
+
+    // 答えはわからないので、当てるか6回外れて終わるまで単語リストの順に推測する
+    let mut guesses = 0;
+    for word in WORDS {
+        match bot.play_guess(USER, None, revision, word).await {
+            Ok(view) => {
+                revision = view.revision;
+                guesses += 1;
+            }
+            Err(_) => break,
+        }
+    }
+
+    let stats = bot.user_stats(USER).await.unwrap();
+    assert_eq!(stats.games_played(), 1);
+    if stats.wins == 1 {
+        assert_eq!(stats.distribution[guesses - 1], 1);
+    } else {
+        assert_eq!((stats.losses, guesses), (1, 6));
+        assert!(stats.distribution.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn playable_games_continue_from_the_button_after_a_restart() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await;
+    // 1語だけを残せば、答えは必ずその単語になる
+    let mut settings = Settings::clone(&bot.settings());
+    settings.set("banned_words", "crane,slate,trace,crate,react,cater,plant").unwrap();
+    bot.save_settings(settings).await.unwrap();
+
+    let view = bot.start_play(USER, None, GuessLimit::Relaxed).await.unwrap();
+    let view = bot.play_guess(USER, None, view.revision, "crane").await.unwrap();
+    assert!(storage.load_play_game(view.game).await.unwrap().is_some());
+
+    // 再起動したボットは、ボタンのゲームIDから読み込んで続ける（他のユーザーのゲームは読み込まない）
+    let restarted = bot_with_storage(Arc::clone(&storage)).await;
+    restarted.resume_play(USER + 1, view.game).await;
+    assert!(restarted.play_guess(USER + 1, None, view.revision, "moist").await.is_err());
+    restarted.resume_play(USER, view.game).await;
+    let resumed = restarted.play_guess(USER, None, view.revision, "moist").await.unwrap();
+    assert_eq!((resumed.guesses.len(), resumed.limit), (2, 8));
+
+    // 終わったゲームは保存から消える
+    assert!(storage.load_play_game(view.game).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn relaxed_limits_give_eight_guesses_and_a_separate_distribution() {
+    let bot = bot_with_words().await;
+    const GUILD: u64 = 7;
+    let today = discord_bot::stats::today();
+    let mut settings = Settings::clone(&bot.settings());
+    settings.daily_guess_limits.insert(GUILD, DailyGuessLimit { limit: GuessLimit::Relaxed, since: today, previous: GuessLimit::Classic });
+    bot.save_settings(settings).await.unwrap();
+
+    // 単語リストの8語を順に推測すれば、8回のうちに必ず当たる
+    let view = bot.start_play(USER, None, GuessLimit::Relaxed).await.unwrap();
+    let daily = bot.start_daily(GUILD, 1, USER).await.unwrap().unwrap();
+    assert_eq!((view.limit, daily.limit), (8, 8));
+    let (mut revision, mut daily_revision) = (view.revision, daily.revision);
+    for word in WORDS {
+        if let Ok(view) = bot.play_guess(USER, None, revision, word).await {
+            revision = view.revision;
+        }
+        if let Ok(view) = bot.daily_guess(GUILD, USER, daily_revision, word).await {
+            daily_revision = view.revision;
+        }
+    }
+
+    let stats = bot.user_stats(USER).await.unwrap();
+    assert_eq!((stats.wins, stats.losses), (2, 0));
+    assert_eq!(stats.limit_distributions[&8].iter().sum::<u32>(), 2);
+    assert!(stats.distribution.is_empty());
+    // 切り替える前の日は前の上限のまま
+    assert_eq!(bot.settings().daily_guess_limit(GUILD, today - 1), GuessLimit::Classic);
+}
+
+#[tokio::test]
+async fn banned_words_are_never_picked_as_answers() {
+    let bot = bot_with_words().await;
+    const GUILD: u64 = 7;
+
+    // 1語だけを残せば、答えは必ずその単語になる
+    let mut settings = Settings::clone(&bot.settings());
+    settings.set("banned_words", "crane,slate,trace,crate,react,cater,plant").unwrap();
+    bot.save_settings(settings).await.unwrap();
+
+    let view = bot.start_play(USER, Some(GUILD), GuessLimit::Classic).await.unwrap();
+    bot.play_guess(USER, Some(GUILD), view.revision, "moist").await.unwrap();
+    let view = bot.start_daily(GUILD, 1, USER).await.unwrap().unwrap();
+    bot.daily_guess(GUILD, USER, view.revision, "moist").await.unwrap();
+    let stats = bot.user_stats(USER).await.unwrap();
+    assert_eq!((stats.wins, stats.distribution[0]), (2, 2));
+
+    // サーバーで残りの1語も禁止すると、答えを選べない
+    let mut settings = Settings::clone(&bot.settings());
+    settings.guild_banned_words.insert(GUILD, ["MOIST".to_string()].into());
+    bot.save_settings(settings).await.unwrap();
+    assert!(bot.start_play(USER + 1, Some(GUILD), GuessLimit::Classic).await.is_none());
+    assert!(bot.start_play(USER + 1, None, GuessLimit::Classic).await.is_some());
+}
+
+#[tokio::test]
+async fn daily_answer_stays_the_same_after_a_restart_with_other_words() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await;
+    const GUILD: u64 = 7;
+
+    // 1語だけを候補にしてその日のデイリーを始める
+    let mut settings = Settings::clone(&bot.settings());
+    settings.set("banned_words", "crane,slate,trace,crate,react,cater,plant").unwrap();
+    bot.save_settings(settings).await.unwrap();
+    bot.start_daily(GUILD, 1, USER).await.unwrap().unwrap();
+
+    // 再起動後に候補が変わっても（その単語が候補から外れても）、その日は最初に記録した候補から同じ単語を選ぶ
+    let restarted = bot_with_storage(Arc::clone(&storage)).await;
+    let mut settings = Settings::default();
+    settings.set("banned_words", "moist").unwrap();
+    restarted.save_settings(settings).await.unwrap();
+    let view = restarted.start_daily(GUILD, 1, USER + 1).await.unwrap().unwrap();
+    restarted.daily_guess(GUILD, USER + 1, view.revision, "moist").await.unwrap();
+    assert_eq!(restarted.user_stats(USER + 1).await.unwrap().wins, 1);
+}
+
+#[tokio::test]
+async fn shared_daily_uses_the_same_word_in_every_opted_in_guild() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    let bot = bot_with_storage(Arc::clone(&storage)).await;
+    let day = discord_bot::stats::today();
+
+    let mut settings = Settings::clone(&bot.settings());
+    settings.shared_daily_guilds.extend(1..=20);
+    bot.save_settings(settings).await.unwrap();
+    for guild in 1..=20 {
+        bot.start_daily(guild, 1, USER).await.unwrap().unwrap();
+    }
+
+    // 共有する全サーバーの候補は、共通の候補から選んだ同じ1語になる
+    let shared = storage.load_wordlist_version(&format!("daily-{}", day)).await.unwrap().unwrap();
+    assert_eq!(shared.len(), WORDS.len());
+    let first = storage.load_wordlist_version(&format!("daily-1-{}", day)).await.unwrap().unwrap();
+    assert_eq!(first.len(), 1);
+    for guild in 2..=20 {
+        assert_eq!(storage.load_wordlist_version(&format!("daily-{}-{}", guild, day)).await.unwrap().unwrap(), first);
+    }
+
+    // 共有をやめたサーバーも、その日は記録した単語のまま遊ぶ
+    let mut settings = Settings::clone(&bot.settings());
+    settings.shared_daily_guilds.clear();
+    bot.save_settings(settings).await.unwrap();
+    let restarted = bot_with_storage(Arc::clone(&storage)).await;
+    let view = restarted.start_daily(1, 1, USER + 1).await.unwrap().unwrap();
+    restarted.daily_guess(1, USER + 1, view.revision, &first[0]).await.unwrap();
+    assert_eq!(restarted.user_stats(USER + 1).await.unwrap().wins, 1);
+}
+
+#[tokio::test]
+async fn daily_puzzle_is_played_once_per_member() {
+    let bot = bot_with_words().await;
+    const GUILD: u64 = 7;
+    let mut revision = bot.start_daily(GUILD, 1, USER).await.unwrap().unwrap().revision;
+
+    for word in WORDS {
+        match bot.daily_guess(GUILD, USER, revision, word).await {
+            Ok(view) => revision = view.revision,
+            Err(_) => break,
+        }
+    }
+
+    // 終えたメンバーは次の日まで遊べないが、ほかのメンバーは同じデイリーを始められる
+    assert!(bot.start_daily(GUILD, 1, USER).await.unwrap().is_none());
+    assert!(bot.start_daily(GUILD, 1, USER + 1).await.unwrap().is_some());
+    let stats = bot.user_stats(USER).await.unwrap();
+    assert_eq!(stats.games_played(), 1);
+    assert!(stats.guilds.contains(&GUILD));
+}
+
+#[tokio::test]
+async fn daily_puzzle_continues_from_the_button_after_a_restart() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    const GUILD: u64 = 7;
+    let before = bot_with_storage(Arc::clone(&storage)).await;
+    // 1回目で当たらなかったメンバーのゲームを途中まで進める
+    let mut progressed = None;
+    for (user, word) in [(USER, "crane"), (USER + 1, "slate")] {
+        let started = before.start_daily(GUILD, 1, user).await.unwrap().unwrap();
+        let view = before.daily_guess(GUILD, user, started.revision, word).await.unwrap();
+        if before.start_daily(GUILD, 1, user).await.unwrap().is_some() {
+            progressed = Some((user, view));
+            break;
+        }
+    }
+    let (user, view) = progressed.unwrap();
+
+    // 再起動後は、ボタンに埋め込んだ推測から同じ盤面を作り直して続けられる
+    let after = bot_with_storage(storage).await;
+    let played = PlayedWords::new(view.guesses.iter().map(|g| g.word.as_str())).unwrap();
+    let today = discord_bot::stats::today();
+    assert!(after.restore_daily(GUILD, today - 1, 1, user, view.revision, played).await.is_err());
+    after.restore_daily(GUILD, today, 1, user, view.revision, played).await.unwrap();
+    let restored = after.start_daily(GUILD, 1, user).await.unwrap().unwrap();
+    assert_eq!((restored.revision, &restored.guesses), (view.revision, &view.guesses));
+    let next = after.daily_guess(GUILD, user, view.revision, "moist").await.unwrap();
+    assert_eq!(next.guesses.len(), 2);
+}
+
+#[tokio::test]
+async fn finished_daily_is_not_replayed_or_counted_twice_after_a_restart() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    const GUILD: u64 = 7;
+    let before = bot_with_storage(Arc::clone(&storage)).await;
+    let started = before.start_daily(GUILD, 1, USER).await.unwrap().unwrap();
+    // 始めたときの盤面のボタン（推測はまだ埋め込まれていない）
+    let played = PlayedWords::new(std::iter::empty::<&str>()).unwrap();
+
+    let mut revision = started.revision;
+    for word in WORDS {
+        match before.daily_guess(GUILD, USER, revision, word).await {
+            Ok(view) => revision = view.revision,
+            Err(_) => break,
+        }
+    }
+    assert_eq!(before.user_stats(USER).await.unwrap().games_played(), 1);
+    before.flush_writes().await;
+
+    // 再起動後は、始め直すことも古いボタンから遊び直すこともできない
+    let after = bot_with_storage(Arc::clone(&storage)).await;
+    assert!(after.start_daily(GUILD, 1, USER).await.unwrap().is_none());
+    let today = discord_bot::stats::today();
+    assert!(after.restore_daily(GUILD, today, 1, USER, started.revision, played).await.is_err());
+    assert_eq!(after.user_stats(USER).await.unwrap().games_played(), 1);
+}
+
+#[tokio::test]
+async fn daily_finished_on_two_instances_is_counted_once() {
+    let storage = Arc::new(MemoryStorage::new(WORDS));
+    const GUILD: u64 = 7;
+    // 再起動の前後で同じメンバーの盤面が両方に残っていても、成績に数えるのは最初に記録した結果だけ
+    let bots = [bot_with_storage(Arc::clone(&storage)).await, bot_with_storage(Arc::clone(&storage)).await];
+    let mut revisions = Vec::new();
+    for bot in &bots {
+        revisions.push(bot.start_daily(GUILD, 1, USER).await.unwrap().unwrap().revision);
+    }
+    for (bot, mut revision) in bots.iter().zip(revisions) {
+        for word in WORDS {
+            match bot.daily_guess(GUILD, USER, revision, word).await {
+                Ok(view) => revision = view.revision,
+                Err(_) => break,
+            }
+        }
+        bot.flush_writes().await;
+    }
+    assert_eq!(bot_with_storage(storage).await.user_stats(USER).await.unwrap().games_played(), 1);
+}
+
+#[tokio::test]
+async fn quordle_boards_are_narrowed_separately_from_the_same_guesses() {
+    let bot = bot_with_words().await;
+    let history = QuordleHistory::parse("crane GGGGG YGGBG BBBBB BBGGB").unwrap();
+    let view = bot.quordle(None, history).await.unwrap();
+
+    assert_eq!(view.boards[0].solved.as_deref(), Some("CRANE"));
+    assert_eq!(view.boards[1].candidates, ["TRACE"]);
+    assert_eq!(view.boards[2].candidates, ["MOIST"]);
+    assert_eq!(view.boards[3].candidates, ["PLANT"]);
+    // 残りの盤面は候補が1つずつなので、どれかの答えを先に提案する
+    assert!(["TRACE", "MOIST", "PLANT"].contains(&view.suggestions[0].0.as_str()));
+
+    // すべて解けたら提案しない
+    let history = QuordleHistory::parse("crane GGGGG YGGBG BBBBB BBGGB, trace _ GGGGG YBBBB YBGBB, moist _ _ GGGGG BBBBG, plant _ _ _ GGGGG").unwrap();
+    let view = bot.quordle(None, history).await.unwrap();
+    assert!(view.boards.iter().all(|board| board.solved.is_some()));
+    assert!(view.suggestions.is_empty());
+}
+
+#[tokio::test]
+async fn guess_autocomplete_offers_the_remaining_candidates() {
+    let bot = bot_with_words().await;
+    // ゲームがなければ単語リスト全体から
+    assert_eq!(bot.guess_completions(USER, "cr").await, ["CRANE", "CRATE"]);
+
+    let moist = WordleGuess { word: "MOIST".to_string(), results: vec![LetterResult::Gray; 5] };
+    bot.import_guesses(USER, None, vec![moist]).await.unwrap();
+    assert_eq!(bot.guess_completions(USER, "").await, ["CRANE"]);
+    assert!(bot.guess_completions(USER, "s").await.is_empty());
+}
@@ -0,0 +1,135 @@
+// 終わったゲームの振り返り（腕前と運）
+// 推測ごとに、その時点の候補に対する推測のエントロピーを、候補の中で最も良い推測のエントロピーと比べる（腕前）
+// 実際に絞り込めた量（ビット）と、推測のエントロピー（期待値）の差を運とみなす
+use serde::Serialize;
+
+use crate::constraints::word_matches_result;
+use crate::intern::{WordArena, WordId};
+use crate::scoring::guess_entropy as entropy;
+use crate::{LetterResult, WordleGuess};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepAnalysis {
+    pub word: String,
+    pub candidates_before: usize,
+    pub candidates_after: usize,
+    // 推測のエントロピー（ビット）と、候補の中で最も良い推測のエントロピー
+    pub entropy: f64,
+    pub best_entropy: f64,
+    // 実際に絞り込めた量（log2(前の候補数 / 後の候補数)）
+    pub actual_bits: f64,
+}
+
+impl StepAnalysis {
+    // 最も良い推測に対する割合（0〜1。候補が1つ以下なら1）
+    pub fn skill(&self) -> f64 {
+        if self.best_entropy <= 0.0 {
+            1.0
+        } else {
+            (self.entropy / self.best_entropy).min(1.0)
+        }
+    }
+
+    // 期待より多く絞り込めた量（ビット。正なら運が良い）
+    pub fn luck(&self) -> f64 {
+        self.actual_bits - self.entropy
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameAnalysis {
+    pub guesses: usize,
+    pub solved: bool,
+    pub steps: Vec<StepAnalysis>,
+}
+
+impl GameAnalysis {
+    pub fn average_skill(&self) -> Option<f64> {
+        average(self.scored_steps().map(StepAnalysis::skill))
+    }
+
+    pub fn total_luck(&self) -> f64 {
+        self.scored_steps().map(StepAnalysis::luck).sum()
+    }
+
+    // 候補が1つ以下になってからの推測（答えを入れるだけ）は評価しない
+    fn scored_steps(&self) -> impl Iterator<Item = &StepAnalysis> {
+        self.steps.iter().filter(|step| step.candidates_before > 1)
+    }
+}
+
+pub fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+fn best_entropy(arena: &WordArena, candidates: &[WordId]) -> f64 {
+    candidates.iter()
+        .map(|&id| entropy(arena, arena.word(id), candidates))
+        .fold(0.0, f64::max)
+}
+
+// 複数のゲームを振り返る（1手目の最も良いエントロピーはどのゲームでも同じなので1回だけ計算する）
+pub fn analyze_games(arena: &WordArena, games: &[Vec<WordleGuess>]) -> Vec<GameAnalysis> {
+    let all: Vec<WordId> = arena.ids().collect();
+    let mut first_best = None;
+
+    games.iter()
+        .map(|guesses| {
+            let mut candidates = all.clone();
+            let steps = guesses.iter()
+                .enumerate()
+                .map(|(i, guess)| {
+                    let before = candidates.len();
+                    let entropy = entropy(arena, &guess.word, &candidates);
+                    let best = if i == 0 {
+                        *first_best.get_or_insert_with(|| best_entropy(arena, &all))
+                    } else {
+                        best_entropy(arena, &candidates)
+                    };
+                    candidates.retain(|&id| word_matches_result(arena.word(id), &guess.word, &guess.results));
+                    StepAnalysis {
+                        word: guess.word.clone(),
+                        candidates_before: before,
+                        candidates_after: candidates.len(),
+                        entropy,
+                        best_entropy: best,
+                        actual_bits: if before > 0 { (before as f64 / candidates.len().max(1) as f64).log2() } else { 0.0 },
+                    }
+                })
+                .collect();
+
+            GameAnalysis {
+                guesses: guesses.len(),
+                solved: guesses.last().is_some_and(|g| g.results.iter().all(|r| *r == LetterResult::Green)),
+                steps,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LetterResult::{Gray, Green, Yellow};
+
+    #[test]
+    fn scores_each_guess_against_the_best_available() {
+        let arena = WordArena::new(["CRANE", "TRACE", "CRATE", "GRACE", "FUZZY"]);
+        let game = vec![
+            WordleGuess { word: "FUZZY".to_string(), results: vec![Gray; 5] },
+            WordleGuess { word: "CRANE".to_string(), results: vec![Yellow, Green, Green, Gray, Green] },
+            WordleGuess { word: "TRACE".to_string(), results: vec![Green; 5] },
+        ];
+        let analysis = &analyze_games(&arena, &[game])[0];
+
+        assert!(analysis.solved);
+        assert_eq!(analysis.steps[0].candidates_after, 4);
+        // FUZZY は候補を分けない悪い推測
+        assert!(analysis.steps[0].skill() < 0.5);
+        // TRACE と GRACE が残る
+        assert_eq!(analysis.steps[1].candidates_after, 2);
+        assert_eq!(analysis.steps[2].candidates_after, 1);
+        assert!(analysis.average_skill().unwrap() < 1.0);
+    }
+}
@@ -0,0 +1,156 @@
+// ターミナルで使うオフライン版のWordleヘルパー（Discordなしで動作確認や利用ができる）
+//
+//   cargo run -p wordle-solver --bin wordle-cli [-- --words <単語リストのパス>]
+//
+// 「CRANE YGGBG」のように推測した単語と結果（G: 緑、Y: 黄、B: 灰）を入力すると、
+// 残りの候補とおすすめの単語を表示する。
+//
+// --bench を付けると、単語リストを1万語以上に増やして制約チェックの速さを計測する
+// （文字数を配列で数える版と HashMap で数える版、単語ごとの文字数と推測の条件を前もって作っておく版の比較）。
+use std::io::{self, BufRead, Write};
+use std::time::{Duration, Instant};
+
+use wordle_solver::{
+    bundled_words, filter_candidates, letter_counts, parse_word_list, rank_words, simulate_guess_pattern, word_matches_result,
+    word_matches_result_by_map, GuessConstraint, LetterResult, WordleGuess,
+};
+
+const SHOWN_SUGGESTIONS: usize = 10;
+
+// ベンチマークで使う単語数の下限と、答えにする単語の数
+const BENCH_MIN_WORDS: usize = 10_000;
+const BENCH_ANSWERS: usize = 20;
+
+fn main() -> io::Result<()> {
+    let words = load_words()?;
+    if std::env::args().any(|arg| arg == "--bench") {
+        bench(&words);
+        return Ok(());
+    }
+    println!("{}語を読み込みました。「単語 結果」（例: CRANE YGGBG）を入力してください。", words.len());
+    println!("コマンド: undo（1つ戻す） / reset（最初から） / quit（終了）");
+
+    let mut guesses: Vec<WordleGuess> = Vec::new();
+    print_suggestions(&words, &guesses);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (None, _) => continue,
+            (Some("quit" | "exit"), _) => break,
+            (Some("reset"), _) => guesses.clear(),
+            (Some("undo"), _) => {
+                guesses.pop();
+            }
+            (Some(word), Some(pattern)) => match WordleGuess::parse(word, pattern) {
+                Some(guess) => guesses.push(guess),
+                None => {
+                    println!("入力が正しくありません（例: CRANE YGGBG）");
+                    continue;
+                }
+            },
+            (Some(_), None) => {
+                println!("結果のパターンも入力してください（例: CRANE YGGBG）");
+                continue;
+            }
+        }
+
+        print_suggestions(&words, &guesses);
+    }
+
+    Ok(())
+}
+
+// --words で指定されたファイル、なければ同梱の単語リストを使う
+fn load_words() -> io::Result<Vec<String>> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--words" {
+            let path = args.next()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "--words requires a path"))?;
+            return Ok(parse_word_list(&std::fs::read_to_string(path)?));
+        }
+    }
+    Ok(bundled_words())
+}
+
+fn bench(words: &[String]) {
+    if words.is_empty() {
+        println!("単語リストが空です");
+        return;
+    }
+    let list: Vec<&str> = words.iter().map(String::as_str).cycle().take(words.len().max(BENCH_MIN_WORDS)).collect();
+
+    // 答えを決めて、よく使われる開始単語の結果を作る
+    let guesses: Vec<WordleGuess> = words.iter()
+        .step_by((words.len() / BENCH_ANSWERS).max(1))
+        .flat_map(|answer| ["CRANE", "SLATE", "SPEED"].map(|guess| WordleGuess {
+            word: guess.to_string(),
+            results: simulate_guess_pattern(guess, answer).into_iter()
+                .map(|p| match p {
+                    2 => LetterResult::Green,
+                    1 => LetterResult::Yellow,
+                    _ => LetterResult::Gray,
+                })
+                .collect(),
+        }))
+        .collect();
+
+    let measure = |matches: fn(&str, &str, &[LetterResult]) -> bool| -> (Duration, usize) {
+        let started = Instant::now();
+        let kept = guesses.iter()
+            .map(|guess| list.iter().filter(|word| matches(word, &guess.word, &guess.results)).count())
+            .sum();
+        (started.elapsed(), kept)
+    };
+    let (by_array, kept) = measure(word_matches_result);
+    let (by_map, kept_by_map) = measure(word_matches_result_by_map);
+    assert_eq!(kept, kept_by_map, "the two implementations disagree");
+
+    // 単語キャッシュと同じく、文字数は読み込んだときに数え、推測の条件は推測ごとに1回だけ作る
+    let counts: Vec<[u8; 26]> = list.iter().map(|word| letter_counts(word.as_bytes())).collect();
+    let started = Instant::now();
+    let kept_packed: usize = guesses.iter()
+        .map(|guess| {
+            let constraint = GuessConstraint::new(&guess.word, &guess.results).expect("guesses are uppercase");
+            list.iter().zip(&counts).filter(|(word, counts)| constraint.matches(word.as_bytes(), counts)).count()
+        })
+        .sum();
+    let packed = started.elapsed();
+    assert_eq!(kept, kept_packed, "the precomputed version disagrees");
+
+    let checks = list.len() * guesses.len();
+    println!("{}語 × {}通りの結果（{}回の判定、残った単語 {}）", list.len(), guesses.len(), checks, kept);
+    println!("  配列:    {:>8.1} ms", by_array.as_secs_f64() * 1000.0);
+    println!("  HashMap: {:>8.1} ms", by_map.as_secs_f64() * 1000.0);
+    println!("  前計算:  {:>8.1} ms", packed.as_secs_f64() * 1000.0);
+    println!("  {:.1}倍（前計算は {:.1}倍）", by_map.as_secs_f64() / by_array.as_secs_f64().max(f64::EPSILON), by_map.as_secs_f64() / packed.as_secs_f64().max(f64::EPSILON));
+}
+
+fn print_suggestions(words: &[String], guesses: &[WordleGuess]) {
+    for (i, guess) in guesses.iter().enumerate() {
+        let pattern: String = guess.results.iter().map(|r| r.to_char()).collect();
+        println!("  {}回目: {} {}", i + 1, guess.word, pattern);
+    }
+
+    let candidates = filter_candidates(words.iter().map(String::as_str), guesses);
+    println!("候補数: {}語", candidates.len());
+
+    if candidates.is_empty() {
+        println!("候補が見つかりません。入力した結果を見直してください（undo で1つ戻せます）");
+        return;
+    }
+
+    for (i, scored) in rank_words(&candidates, guesses.len()).iter().take(SHOWN_SUGGESTIONS).enumerate() {
+        println!("  {:>2}. {} ({:.2})", i + 1, scored.word, scored.score);
+    }
+}
@@ -0,0 +1,141 @@
+// 候補が0件になったときに、どの推測の結果が食い違っているかを探す
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constraints::filter_candidates;
+use crate::pattern::{LetterResult, WordleGuess};
+
+// 推測の番号は0始まり（first < second）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Conflict {
+    // 同じ位置が別々の文字で緑になっている
+    Position { position: usize, first: usize, second: usize },
+    // 同じ文字の色が2つの推測で両立しない
+    Letter { letter: char, first: usize, second: usize },
+    // 推測同士は矛盾しないが、この推測を加えると単語リストに当てはまる単語がなくなる
+    NoMatchingWord { guess: usize },
+}
+
+impl Conflict {
+    // 修正を勧める推測（後から入力した方を疑う）
+    pub fn suspect(&self) -> usize {
+        match *self {
+            Conflict::Position { second, .. } | Conflict::Letter { second, .. } => second,
+            Conflict::NoMatchingWord { guess } => guess,
+        }
+    }
+}
+
+// 1つの推測から分かる文字ごとの情報
+struct Facts {
+    letters: Vec<char>,
+    results: Vec<LetterResult>,
+    min: HashMap<char, usize>,
+    // 灰色が含まれる文字はちょうど min 個
+    exact: HashMap<char, usize>,
+}
+
+impl Facts {
+    fn new(guess: &WordleGuess) -> Self {
+        let letters: Vec<char> = guess.word.to_uppercase().chars().collect();
+        let mut min: HashMap<char, usize> = HashMap::new();
+        for (&letter, result) in letters.iter().zip(&guess.results) {
+            if *result != LetterResult::Gray {
+                *min.entry(letter).or_insert(0) += 1;
+            }
+        }
+        let exact = letters.iter().zip(&guess.results)
+            .filter(|(_, r)| **r == LetterResult::Gray)
+            .map(|(&letter, _)| (letter, min.get(&letter).copied().unwrap_or(0)))
+            .collect();
+
+        Self { letters, results: guess.results.clone(), min, exact }
+    }
+
+    fn green(&self, position: usize) -> Option<char> {
+        (self.results.get(position) == Some(&LetterResult::Green)).then(|| self.letters[position])
+    }
+
+    // この推測と比べて、other のどの文字が両立しないか
+    fn contradicting_letter(&self, other: &Facts) -> Option<char> {
+        // 緑の位置に、相手は同じ文字を黄色・灰色で置いている
+        let misplaced = (0..self.letters.len()).find_map(|i| {
+            let letter = self.green(i)?;
+            (other.letters.get(i) == Some(&letter) && other.green(i).is_none()).then_some(letter)
+        });
+        // 必要な数が、相手の分かっている上限を超えている
+        misplaced.or_else(|| self.letters.iter().copied().find(|letter| {
+            let needed = self.min.get(letter).copied().unwrap_or(0);
+            other.exact.get(letter).is_some_and(|&allowed| needed > allowed)
+        }))
+    }
+}
+
+fn pair_conflict(a: &Facts, b: &Facts, first: usize, second: usize) -> Option<Conflict> {
+    let position = (0..a.letters.len().min(b.letters.len()))
+        .find(|&i| matches!((a.green(i), b.green(i)), (Some(x), Some(y)) if x != y));
+    if let Some(position) = position {
+        return Some(Conflict::Position { position, first, second });
+    }
+
+    a.contradicting_letter(b)
+        .or_else(|| b.contradicting_letter(a))
+        .map(|letter| Conflict::Letter { letter, first, second })
+}
+
+// 推測同士の矛盾を優先し、なければ単語リストで当てはまる単語がなくなる推測を返す
+// すべての推測に当てはまる単語があれば None
+pub fn find_conflict<'a, I>(words: I, guesses: &[WordleGuess]) -> Option<Conflict>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let facts: Vec<Facts> = guesses.iter().map(Facts::new).collect();
+    for second in 1..facts.len() {
+        for first in 0..second {
+            if let Some(conflict) = pair_conflict(&facts[first], &facts[second], first, second) {
+                return Some(conflict);
+            }
+        }
+    }
+
+    let mut candidates = filter_candidates(words, &[]);
+    for (i, guess) in guesses.iter().enumerate() {
+        candidates = filter_candidates(candidates.iter().map(String::as_str), std::slice::from_ref(guess));
+        if candidates.is_empty() {
+            return Some(Conflict::NoMatchingWord { guess: i });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guesses(list: &[(&str, &str)]) -> Vec<WordleGuess> {
+        list.iter().map(|(w, p)| WordleGuess::parse(w, p).unwrap()).collect()
+    }
+
+    #[test]
+    fn finds_the_conflicting_pair_or_guess() {
+        let words = ["CRANE", "TRACE", "SLATE", "MOIST"];
+
+        // 1文字目の緑が C と S で食い違う
+        let history = guesses(&[("CRANE", "GBBBB"), ("MOIST", "BBBBB"), ("SLATE", "GBBBB")]);
+        assert_eq!(find_conflict(words, &history), Some(Conflict::Position { position: 0, first: 0, second: 2 }));
+
+        // 灰色だった A が後で黄色になっている
+        let history = guesses(&[("CRANE", "BBBBB"), ("SLATE", "BBYBB")]);
+        let conflict = find_conflict(words, &history).unwrap();
+        assert_eq!(conflict, Conflict::Letter { letter: 'A', first: 0, second: 1 });
+        assert_eq!(conflict.suspect(), 1);
+
+        // 推測同士は矛盾しないが、単語リストに当てはまる単語がない
+        let history = guesses(&[("CRANE", "BBBBB"), ("MOIST", "BBBBY")]);
+        assert_eq!(find_conflict(words, &history), Some(Conflict::NoMatchingWord { guess: 1 }));
+
+        let history = guesses(&[("CRANE", "YGGBG")]);
+        assert_eq!(find_conflict(words, &history), None);
+    }
+}
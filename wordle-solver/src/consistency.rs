@@ -0,0 +1,120 @@
+// 制約のチェック（word_matches_result）と結果の計算（simulate_guess_pattern / pattern_code）の突き合わせ
+// 重複した文字の扱いをそれぞれ別に実装しているため、無作為に選んだ単語の組で食い違いがないか確かめる
+// （候補が推測の結果に合うと判定されるのは、その候補が答えなら同じ結果になるときだけのはず）
+use crate::constraints::{word_matches_result, word_matches_result_by_map};
+use crate::intern::{WordArena, WordId};
+use crate::pattern::{pattern_code, simulate_guess_pattern, LetterResult};
+
+pub const DEFAULT_CONSISTENCY_SAMPLES: usize = 2000;
+
+// 報告に含める食い違いの数（それ以上は数だけ数える）
+const MAX_REPORTED: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub guess: String,
+    pub answer: String,
+    pub candidate: String,
+    // 答えに対する推測の結果（G / Y / B）
+    pub pattern: String,
+    // 制約のチェックで候補に残るか（配列で数える版と HashMap の版）
+    pub matches: bool,
+    pub matches_by_map: bool,
+    // 候補が答えだったとき同じ結果になるか
+    pub reproduces: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    pub seed: u64,
+    pub checked: usize,
+    pub divergence_count: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.divergence_count == 0
+    }
+}
+
+// 依存を増やさないための簡単な乱数（xorshift64*）
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 32) as usize % n
+    }
+}
+
+fn to_results(pattern: &[u8]) -> Vec<LetterResult> {
+    pattern.iter()
+        .map(|p| match p {
+            2 => LetterResult::Green,
+            1 => LetterResult::Yellow,
+            _ => LetterResult::Gray,
+        })
+        .collect()
+}
+
+// 推測と答えを無作為に選び、答えそのものと無作為な候補の2つで突き合わせる
+pub fn check_consistency(arena: &WordArena, samples: usize, seed: u64) -> ConsistencyReport {
+    let mut report = ConsistencyReport { seed, ..Default::default() };
+    if arena.is_empty() {
+        return report;
+    }
+
+    let mut rng = Rng::new(seed);
+    let pick = |rng: &mut Rng| arena.word(rng.below(arena.len()) as WordId);
+    for _ in 0..samples {
+        let (guess, answer, other) = (pick(&mut rng), pick(&mut rng), pick(&mut rng));
+        let pattern = simulate_guess_pattern(guess, answer);
+        let results = to_results(&pattern);
+        let code = pattern.iter().fold(0, |code, &p| code * 3 + p as u32);
+
+        for candidate in [answer, other] {
+            let matches = word_matches_result(candidate, guess, &results);
+            let matches_by_map = word_matches_result_by_map(candidate, guess, &results);
+            let reproduces = simulate_guess_pattern(guess, candidate) == pattern && pattern_code(guess, candidate) == code;
+            report.checked += 1;
+            if matches == reproduces && matches == matches_by_map {
+                continue;
+            }
+
+            report.divergence_count += 1;
+            if report.divergences.len() < MAX_REPORTED {
+                report.divergences.push(Divergence {
+                    guess: guess.to_string(),
+                    answer: answer.to_string(),
+                    candidate: candidate.to_string(),
+                    pattern: results.iter().map(|r| r.to_char()).collect(),
+                    matches,
+                    matches_by_map,
+                    reproduces,
+                });
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_words_are_consistent() {
+        let arena = WordArena::new(crate::bundled_words());
+        let report = check_consistency(&arena, 500, 42);
+        assert_eq!(report.checked, 1000);
+        assert!(report.is_consistent(), "{:?}", report.divergences);
+        assert_eq!(check_consistency(&WordArena::default(), 10, 1).checked, 0);
+    }
+}
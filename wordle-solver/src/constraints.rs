@@ -0,0 +1,267 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{LetterResult, WordleGuess, WORD_LENGTH};
+
+// 英字のみで構成された5文字の単語か
+pub fn is_valid_word(word: &str) -> bool {
+    word.len() == WORD_LENGTH && word.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+// 制約に基づいて可能な単語をフィルタリング（結果は大文字）
+pub fn filter_candidates<'a, I>(words: I, guesses: &[WordleGuess]) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    words.into_iter()
+        .map(|word| word.to_uppercase())
+        // 5文字の単語のみを対象とする
+        .filter(|word| is_valid_word(word) && is_word_possible(word, guesses))
+        .collect()
+}
+
+// 単語が制約を満たすかチェック
+pub fn is_word_possible(word: &str, guesses: &[WordleGuess]) -> bool {
+    guesses.iter().all(|guess| word_matches_result(word, &guess.word, &guess.results))
+}
+
+// 単語が特定の推測結果と一致するかチェック
+// 大文字の英字だけなら [u8; 26] で文字数を数えてまとめて比較する（それ以外は HashMap で数える）
+pub fn word_matches_result(candidate: &str, guess: &str, results: &[LetterResult]) -> bool {
+    let candidate_bytes = candidate.as_bytes();
+    if !candidate_bytes.iter().all(u8::is_ascii_uppercase) {
+        return word_matches_result_by_map(candidate, guess, results);
+    }
+    match GuessConstraint::new(guess, results) {
+        Some(constraint) => constraint.matches(candidate_bytes, &letter_counts(candidate_bytes)),
+        None => word_matches_result_by_map(candidate, guess, results),
+    }
+}
+
+// 大文字の英字だけの単語の文字ごとの数（単語リストを読み込んだときに単語ごとに数えておける）
+pub fn letter_counts(word: &[u8]) -> [u8; 26] {
+    let mut counts = [0u8; 26];
+    for &c in word {
+        if c.is_ascii_uppercase() {
+            counts[(c - b'A') as usize] += 1;
+        }
+    }
+    counts
+}
+
+// 1つの推測結果から作った、候補が満たすべき条件
+// 推測ごとに1回だけ作り、単語ごとには文字の位置と数の比較だけを行う（単語ごとに確保も数え直しもしない）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuessConstraint {
+    // 位置ごとの推測の文字と結果
+    positions: Vec<(u8, LetterResult)>,
+    // 緑・黄の数が最小数、灰があればその数が最大数になる
+    min_required: [u8; 26],
+    max_allowed: [u8; 26],
+}
+
+impl GuessConstraint {
+    // 大文字の英字だけの推測から作る（それ以外や長さの合わない結果は None）
+    pub fn new(guess: &str, results: &[LetterResult]) -> Option<Self> {
+        let guess_bytes = guess.as_bytes();
+        if guess_bytes.len() != results.len() || !guess_bytes.iter().all(u8::is_ascii_uppercase) {
+            return None;
+        }
+
+        let mut min_required = [0u8; 26];
+        let mut has_gray = [false; 26];
+        for (&g, result) in guess_bytes.iter().zip(results) {
+            let letter = (g - b'A') as usize;
+            match result {
+                LetterResult::Green | LetterResult::Yellow => min_required[letter] += 1,
+                LetterResult::Gray => has_gray[letter] = true,
+            }
+        }
+        let mut max_allowed = [u8::MAX; 26];
+        for letter in 0..26 {
+            if has_gray[letter] {
+                max_allowed[letter] = min_required[letter];
+            }
+        }
+
+        Some(Self {
+            positions: guess_bytes.iter().copied().zip(results.iter().copied()).collect(),
+            min_required,
+            max_allowed,
+        })
+    }
+
+    // 推測の並びから作る（1つでも作れない推測があれば None）
+    pub fn compile(guesses: &[WordleGuess]) -> Option<Vec<Self>> {
+        guesses.iter().map(|guess| Self::new(&guess.word, &guess.results)).collect()
+    }
+
+    // 大文字の英字だけの単語と、その letter_counts が条件を満たすか
+    pub fn matches(&self, word: &[u8], counts: &[u8; 26]) -> bool {
+        if word.len() != self.positions.len() {
+            return false;
+        }
+        for (&c, &(g, result)) in word.iter().zip(&self.positions) {
+            match result {
+                LetterResult::Green if c != g => return false,
+                LetterResult::Yellow if c == g => return false,
+                _ => {}
+            }
+        }
+
+        // 分岐なしで26文字分をまとめて比較する（自動ベクトル化される）
+        counts.iter()
+            .zip(&self.min_required)
+            .zip(&self.max_allowed)
+            .fold(true, |ok, ((&count, &min), &max)| ok & (count >= min) & (count <= max))
+    }
+}
+
+// 任意の文字に対応する版（HashMapで数える。ベンチマークの比較にも使う）
+pub fn word_matches_result_by_map(candidate: &str, guess: &str, results: &[LetterResult]) -> bool {
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+    let guess_chars: Vec<char> = guess.chars().collect();
+
+    if candidate_chars.len() != guess_chars.len() || guess_chars.len() != results.len() {
+        return false;
+    }
+
+    // 緑色の制約をチェック
+    for (i, result) in results.iter().enumerate() {
+        if matches!(result, LetterResult::Green) && candidate_chars[i] != guess_chars[i] {
+            return false;
+        }
+    }
+
+    // 各文字の最小必要数と最大許可数を計算
+    let mut min_required: HashMap<char, usize> = HashMap::new();
+    let mut max_allowed: HashMap<char, usize> = HashMap::new();
+    let mut forbidden_positions: HashMap<char, HashSet<usize>> = HashMap::new();
+
+    // 推測結果を分析
+    for (i, result) in results.iter().enumerate() {
+        let letter = guess_chars[i];
+        match result {
+            LetterResult::Green => {
+                *min_required.entry(letter).or_insert(0) += 1;
+            }
+            LetterResult::Yellow => {
+                *min_required.entry(letter).or_insert(0) += 1;
+                forbidden_positions.entry(letter).or_default().insert(i);
+            }
+            LetterResult::Gray => {
+                // この文字が他の場所で緑や黄色になっていない場合、単語に含まれない
+                let letter_used_elsewhere = results.iter().enumerate().any(|(j, r)| {
+                    j != i && guess_chars[j] == letter && matches!(r, LetterResult::Green | LetterResult::Yellow)
+                });
+
+                if letter_used_elsewhere {
+                    // 他の場所で使われている場合は、その分だけ許可
+                    let used_count = results.iter().enumerate()
+                        .filter(|(j, r)| *j != i && guess_chars[*j] == letter && matches!(r, LetterResult::Green | LetterResult::Yellow))
+                        .count();
+                    max_allowed.insert(letter, used_count);
+                } else {
+                    // 完全に含まれない
+                    max_allowed.insert(letter, 0);
+                }
+            }
+        }
+    }
+
+    // 候補単語の文字数をカウント
+    let mut candidate_counts: HashMap<char, usize> = HashMap::new();
+    for &ch in &candidate_chars {
+        *candidate_counts.entry(ch).or_insert(0) += 1;
+    }
+
+    // 最小必要数をチェック
+    for (letter, min_count) in &min_required {
+        if candidate_counts.get(letter).unwrap_or(&0) < min_count {
+            return false;
+        }
+    }
+
+    // 最大許可数をチェック
+    for (letter, max_count) in &max_allowed {
+        if candidate_counts.get(letter).unwrap_or(&0) > max_count {
+            return false;
+        }
+    }
+
+    // 禁止位置をチェック
+    for (letter, positions) in &forbidden_positions {
+        for &pos in positions {
+            if pos < candidate_chars.len() && candidate_chars[pos] == *letter {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LetterResult::{Gray, Green, Yellow};
+
+    fn guess(word: &str, results: [LetterResult; 5]) -> WordleGuess {
+        WordleGuess { word: word.to_string(), results: results.to_vec() }
+    }
+
+    #[test]
+    fn green_and_yellow_constraints() {
+        let results = [Yellow, Green, Green, Gray, Green];
+        assert!(word_matches_result("TRACE", "CRANE", &results));
+        assert!(word_matches_result("GRACE", "CRANE", &results));
+        assert!(!word_matches_result("CRATE", "CRANE", &results));
+    }
+
+    #[test]
+    fn gray_duplicate_caps_letter_count() {
+        // SPEED -> ABIDE: Eは1つだけ
+        let results = [Gray, Gray, Yellow, Gray, Yellow];
+        assert!(word_matches_result("ABIDE", "SPEED", &results));
+        assert!(!word_matches_result("EERIE", "SPEED", &results));
+    }
+
+    #[test]
+    fn array_counts_agree_with_the_map_version() {
+        let words = crate::bundled_words();
+        for guess in ["CRANE", "SPEED", "EERIE", "LLAMA"] {
+            for answer in words.iter().step_by(7) {
+                let results: Vec<LetterResult> = crate::simulate_guess_pattern(guess, answer).into_iter()
+                    .map(|p| [Gray, Yellow, Green][p as usize])
+                    .collect();
+                for candidate in words.iter().step_by(5) {
+                    assert_eq!(
+                        word_matches_result(candidate, guess, &results),
+                        word_matches_result_by_map(candidate, guess, &results),
+                        "{} / {} -> {:?}", candidate, guess, results,
+                    );
+                }
+            }
+        }
+        // 小文字は HashMap の版で判定する
+        assert!(word_matches_result("trace", "crane", &[Yellow, Green, Green, Gray, Green]));
+    }
+
+    #[test]
+    fn compiled_constraints_reject_non_uppercase_guesses() {
+        let guesses = [guess("CRANE", [Yellow, Green, Green, Gray, Green])];
+        let constraints = GuessConstraint::compile(&guesses).unwrap();
+        assert!(constraints[0].matches(b"TRACE", &letter_counts(b"TRACE")));
+        assert!(!constraints[0].matches(b"TRACES", &letter_counts(b"TRACES")));
+
+        assert_eq!(GuessConstraint::compile(&[guess("crane", [Gray; 5])]), None);
+        assert_eq!(GuessConstraint::new("CRANE", &[Gray; 4]), None);
+    }
+
+    #[test]
+    fn filter_keeps_only_consistent_five_letter_words() {
+        let guesses = [guess("CRANE", [Yellow, Green, Green, Gray, Green])];
+        let words = ["trace", "crate", "grace", "traces", "tr4ce"];
+
+        assert_eq!(filter_candidates(words, &guesses), vec!["TRACE".to_string(), "GRACE".to_string()]);
+    }
+}
@@ -0,0 +1,87 @@
+// ハードモードのルール（判明した緑は同じ位置に、黄色は必ず使う）に反していないかの確認
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pattern::{LetterResult, WordleGuess};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HardModeViolation {
+    // 緑だった文字が同じ位置にない（position は0始まり）
+    MissingGreen { letter: char, position: usize },
+    // 黄色（または緑）だった文字が必要な数だけ含まれていない
+    MissingLetter { letter: char, count: usize },
+}
+
+// 過去の推測結果に対する違反を返す（違反がなければ空）
+pub fn hard_mode_violations(word: &str, guesses: &[WordleGuess]) -> Vec<HardModeViolation> {
+    let word: Vec<char> = word.to_uppercase().chars().collect();
+    let mut violations = Vec::new();
+
+    for guess in guesses {
+        let letters: Vec<char> = guess.word.to_uppercase().chars().collect();
+
+        let mut required: HashMap<char, usize> = HashMap::new();
+        for (i, (&letter, result)) in letters.iter().zip(&guess.results).enumerate() {
+            match result {
+                LetterResult::Green => {
+                    *required.entry(letter).or_insert(0) += 1;
+                    let violation = HardModeViolation::MissingGreen { letter, position: i };
+                    if word.get(i) != Some(&letter) && !violations.contains(&violation) {
+                        violations.push(violation);
+                    }
+                }
+                LetterResult::Yellow => *required.entry(letter).or_insert(0) += 1,
+                LetterResult::Gray => {}
+            }
+        }
+
+        // 推測の文字順で報告する
+        for &letter in &letters {
+            let Some(&count) = required.get(&letter) else { continue };
+            // 緑の位置を直せば満たされる文字は重ねて報告しない
+            if violations.iter().any(|v| matches!(v, HardModeViolation::MissingGreen { letter: l, .. } if *l == letter)) {
+                continue;
+            }
+            let present = word.iter().filter(|&&c| c == letter).count();
+            if present >= count {
+                continue;
+            }
+            match violations.iter_mut().find(|v| matches!(v, HardModeViolation::MissingLetter { letter: l, .. } if *l == letter)) {
+                Some(HardModeViolation::MissingLetter { count: existing, .. }) => *existing = (*existing).max(count),
+                _ => violations.push(HardModeViolation::MissingLetter { letter, count }),
+            }
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn greens_must_stay_and_yellows_must_be_reused() {
+        // CRANE: R が緑、E が黄色
+        let guesses = vec![WordleGuess::parse("CRANE", "BGBBY").unwrap()];
+
+        assert!(hard_mode_violations("FREED", &guesses).is_empty());
+        assert_eq!(
+            hard_mode_violations("SLATE", &guesses),
+            vec![HardModeViolation::MissingGreen { letter: 'R', position: 1 }],
+        );
+        assert_eq!(
+            hard_mode_violations("TRUCK", &guesses),
+            vec![HardModeViolation::MissingLetter { letter: 'E', count: 1 }],
+        );
+
+        // 同じ文字が2つ判明していれば2つ必要
+        let guesses = vec![WordleGuess::parse("EERIE", "YYBBB").unwrap()];
+        assert!(hard_mode_violations("THEME", &guesses).is_empty());
+        assert_eq!(
+            hard_mode_violations("CRANE", &guesses),
+            vec![HardModeViolation::MissingLetter { letter: 'E', count: 2 }],
+        );
+    }
+}
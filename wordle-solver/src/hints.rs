@@ -0,0 +1,112 @@
+// 正解を知っている側（ボットが出題するゲーム）が出す段階的なヒント
+// 灰色の文字 → 黄色の文字 → 緑の位置 の順に情報が増え、その分だけ得点から差し引く
+use serde::{Deserialize, Serialize};
+
+use crate::pattern::{LetterResult, WordleGuess};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HintTier {
+    // 正解に含まれない、まだ試していない文字
+    Absent,
+    // 正解に含まれる、まだ見つかっていない文字（位置は明かさない）
+    Present,
+    // まだ緑になっていない位置の文字
+    Position,
+}
+
+impl HintTier {
+    pub const ALL: [HintTier; 3] = [HintTier::Absent, HintTier::Present, HintTier::Position];
+
+    // 最終得点から差し引く点数
+    pub fn cost(self) -> u32 {
+        match self {
+            HintTier::Absent => 1,
+            HintTier::Present => 2,
+            HintTier::Position => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hint {
+    pub tier: HintTier,
+    pub letter: char,
+    // Position のときのみ
+    pub position: Option<usize>,
+}
+
+// ゲームごとのヒントの使用状況（同じヒントを二度出さない）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintState {
+    pub used: Vec<Hint>,
+}
+
+impl HintState {
+    // 次に出せるヒント（使うたびに段階が上がる。その段階で出せるものがなければ上、次に下の段階を探す）
+    pub fn next(&self, secret: &str, guesses: &[WordleGuess]) -> Option<Hint> {
+        let start = self.used.len().min(HintTier::ALL.len() - 1);
+        let order = HintTier::ALL[start..].iter().chain(HintTier::ALL[..start].iter().rev());
+        order.copied().find_map(|tier| self.candidate(tier, secret, guesses))
+    }
+
+    // ヒントを使い、そのヒントを返す
+    pub fn take(&mut self, secret: &str, guesses: &[WordleGuess]) -> Option<Hint> {
+        let hint = self.next(secret, guesses)?;
+        self.used.push(hint);
+        Some(hint)
+    }
+
+    pub fn penalty(&self) -> u32 {
+        self.used.iter().map(|h| h.tier.cost()).sum()
+    }
+
+    fn revealed(&self, letter: char) -> bool {
+        self.used.iter().any(|h| h.letter == letter && h.tier != HintTier::Position)
+    }
+
+    fn candidate(&self, tier: HintTier, secret: &str, guesses: &[WordleGuess]) -> Option<Hint> {
+        let secret: Vec<char> = secret.to_uppercase().chars().collect();
+        let tried = |letter: char| guesses.iter().any(|g| g.word.to_uppercase().contains(letter));
+
+        match tier {
+            HintTier::Absent => ('A'..='Z')
+                .find(|&c| !secret.contains(&c) && !tried(c) && !self.revealed(c))
+                .map(|letter| Hint { tier, letter, position: None }),
+            HintTier::Present => secret.iter()
+                .copied()
+                .find(|&c| !tried(c) && !self.revealed(c))
+                .map(|letter| Hint { tier, letter, position: None }),
+            HintTier::Position => {
+                let solved = |i: usize| {
+                    guesses.iter().any(|g| g.results.get(i) == Some(&LetterResult::Green))
+                        || self.used.iter().any(|h| h.position == Some(i))
+                };
+                (0..secret.len())
+                    .find(|&i| !solved(i))
+                    .map(|i| Hint { tier, letter: secret[i], position: Some(i) })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_escalate_and_accumulate_penalty() {
+        // 正解 TRUCK に対して CRANE（R が緑）を推測済み
+        let guesses = vec![WordleGuess::parse("CRANE", "YGBBB").unwrap()];
+        let mut state = HintState::default();
+
+        // 未使用で正解にない文字 → 未発見の文字 → 緑でない位置 の順
+        let hints: Vec<_> = (0..3).map(|_| state.take("TRUCK", &guesses).unwrap()).collect();
+        assert_eq!(hints[0], Hint { tier: HintTier::Absent, letter: 'B', position: None });
+        assert_eq!(hints[1], Hint { tier: HintTier::Present, letter: 'T', position: None });
+        assert_eq!(hints[2], Hint { tier: HintTier::Position, letter: 'T', position: Some(0) });
+
+        // 以降は位置のヒントを続け、緑の位置は飛ばす
+        assert_eq!(state.take("TRUCK", &guesses).unwrap().position, Some(2));
+        assert_eq!(state.penalty(), 1 + 2 + 3 + 3);
+    }
+}
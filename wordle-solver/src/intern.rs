@@ -0,0 +1,77 @@
+// 単語をインデックス（WordId）で扱うための格納庫
+// 候補やスコアは WordId で持ち、表示するときだけ文字列に戻す（スコア計算中に String を複製しない）
+// 単語は1つの連続したバッファに詰めて、開始位置と長さで取り出す（単語ごとにヒープを確保しない）
+pub type WordId = u32;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordArena {
+    buffer: String,
+    // 単語ごとのバッファ内の開始位置と長さ（バイト）
+    spans: Vec<(u32, u32)>,
+}
+
+impl WordArena {
+    pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Self {
+        let mut arena = Self::default();
+        for word in words {
+            arena.push(word.as_ref());
+        }
+        arena.buffer.shrink_to_fit();
+        arena.spans.shrink_to_fit();
+        arena
+    }
+
+    fn push(&mut self, word: &str) -> WordId {
+        let id = WordId::try_from(self.spans.len()).expect("too many words for WordId");
+        let start = u32::try_from(self.buffer.len()).expect("word arena is too large");
+        self.buffer.push_str(word);
+        self.spans.push((start, word.len() as u32));
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn word(&self, id: WordId) -> &str {
+        let (start, len) = self.spans[id as usize];
+        &self.buffer[start as usize..(start + len) as usize]
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = WordId> {
+        0..self.spans.len() as WordId
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (WordId, &str)> {
+        self.ids().map(|id| (id, self.word(id)))
+    }
+
+    // 単語の文字列が使っているバイト数（バッファと位置の表）
+    pub fn heap_bytes(&self) -> usize {
+        self.buffer.capacity() + self.spans.capacity() * std::mem::size_of::<(u32, u32)>()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for WordArena {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_map_back_to_words() {
+        let arena: WordArena = ["CRANE", "SLATE", "planet"].into_iter().collect();
+        assert_eq!(arena.ids().collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(arena.word(1), "SLATE");
+        assert_eq!(arena.word(2), "planet");
+        assert_eq!(arena.iter().next(), Some((0, "CRANE")));
+    }
+}
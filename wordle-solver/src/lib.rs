@@ -0,0 +1,75 @@
+// Wordleの制約フィルタリング・パターン計算・スコアリング
+// Discordやデータベースに依存しないため、CLIやWebからも再利用できる
+mod analysis;
+mod conflict;
+mod consistency;
+mod constraints;
+mod hard_mode;
+mod hints;
+mod intern;
+mod nearest;
+mod openers;
+mod pattern;
+mod scoring;
+mod secret;
+mod strategies;
+
+pub use analysis::{analyze_games, average, GameAnalysis, StepAnalysis};
+pub use conflict::{find_conflict, Conflict};
+pub use consistency::{check_consistency, ConsistencyReport, Divergence, DEFAULT_CONSISTENCY_SAMPLES};
+pub use constraints::{
+    filter_candidates, is_valid_word, is_word_possible, letter_counts, word_matches_result, word_matches_result_by_map, GuessConstraint,
+};
+pub use hard_mode::{hard_mode_violations, HardModeViolation};
+pub use hints::{Hint, HintState, HintTier};
+pub use intern::{WordArena, WordId};
+pub use nearest::nearest_words;
+pub use openers::{rank_openers, score_opener, OpenerScore};
+pub use pattern::{pattern_code, simulate_guess_pattern, LetterResult, WordleGuess};
+pub use scoring::{
+    calculate_information_gain, calculate_word_score, expected_remaining, explain_guess, guess_entropy, guess_metrics, GuessExplanation, LetterSplit, is_common_word, rank_by_entropy,
+    rank_by_entropy_until, rank_probes_until, rank_word_ids, rank_word_ids_until, rank_words, top_probes_for_boards, top_probes_until, top_word_ids_until, RankedWord,
+    WordScore,
+};
+pub use secret::SecretWord;
+pub use strategies::{evaluate as evaluate_strategy, play as play_strategy, sample_answers, Strategy, StrategyReport, SuggestionStrategy, MAX_GUESSES};
+
+pub const WORD_LENGTH: usize = 5;
+
+// 候補が見つからない場合に使う一般的な開始単語
+pub const FALLBACK_WORDS: [&str; 5] = ["SLATE", "CRANE", "AUDIO", "ARISE", "OUTER"];
+
+// 同梱の単語リスト（オフラインで使うCLI向け。ボットはデータベースの単語を使う）
+pub const BUNDLED_WORDS: &str = include_str!("../data/words.txt");
+
+pub fn bundled_words() -> Vec<String> {
+    parse_word_list(BUNDLED_WORDS)
+}
+
+// 1行1単語のテキストから5文字の単語を読み込む（空行と # で始まる行は無視）
+pub fn parse_word_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_uppercase)
+        .filter(|word| is_valid_word(word))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_words_are_valid_and_include_openers() {
+        let words = bundled_words();
+        assert!(words.len() > 500);
+        assert!(words.iter().all(|w| is_valid_word(w)));
+        assert!(FALLBACK_WORDS.iter().all(|w| words.iter().any(|word| word == w)));
+    }
+
+    #[test]
+    fn word_list_skips_comments_and_invalid_lines() {
+        assert_eq!(parse_word_list("# header\ncrane\n\nabc\n slate \n"), vec!["CRANE", "SLATE"]);
+    }
+}
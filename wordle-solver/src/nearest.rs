@@ -0,0 +1,37 @@
+// 単語リストにない推測に近い単語（打ち間違いを直す候補）
+// 違う位置の文字が少ない順に並べ、同じなら文字を並べ替えただけの単語（CRNAE → CRANE など）を先にする
+use std::cmp::Reverse;
+
+use crate::constraints::letter_counts;
+
+// 大文字の word に近い同じ文字数の単語を、近い順に limit 個まで返す（近さが同じなら words の順）
+pub fn nearest_words<'a>(word: &str, words: impl IntoIterator<Item = &'a str>, limit: usize) -> Vec<&'a str> {
+    let word = word.as_bytes();
+    let counts = letter_counts(word);
+    let mut scored: Vec<_> = words.into_iter()
+        .filter(|other| other.len() == word.len())
+        .enumerate()
+        .map(|(i, other)| {
+            let differing = other.bytes().zip(word).filter(|(a, b)| a != *b).count();
+            let shared: usize = letter_counts(other.as_bytes()).iter().zip(&counts).map(|(a, b)| *a.min(b) as usize).sum();
+            ((differing, Reverse(shared), i), other)
+        })
+        .collect();
+    scored.sort_unstable_by_key(|(key, _)| *key);
+    scored.into_iter().take(limit).map(|(_, other)| other).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typos_are_matched_by_position_then_by_shared_letters() {
+        let words = ["SLATE", "CRATE", "CRANE", "TRACE", "NACRE", "CRANES"];
+        // 1文字違いが先で、同じ数だけ違うなら共通する文字が多い方
+        assert_eq!(nearest_words("CRAME", words, 2), ["CRATE", "CRANE"]);
+        assert_eq!(nearest_words("CRNAE", words, 1), ["CRANE"]);
+        assert!(!nearest_words("CRANE", words, 10).contains(&"CRANES"));
+        assert!(nearest_words("CRANE", words, 0).is_empty());
+    }
+}
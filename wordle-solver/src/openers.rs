@@ -0,0 +1,69 @@
+// 最初の推測（開始単語）としての評価
+// 単語リストの全単語を答えの候補とみなし、1回目に入れたときの情報量を比べる
+use serde::Serialize;
+
+use crate::pattern::pattern_code;
+use crate::WORD_LENGTH;
+
+// 色の組み合わせの数（3^5）
+const PATTERN_COUNT: usize = 243;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenerScore {
+    pub word: String,
+    // 結果の色の分布のエントロピー（ビット）
+    pub entropy: f64,
+    // 結果を見たあとに残る候補数の期待値
+    pub expected_remaining: f64,
+}
+
+// 1つの単語を開始単語として評価する
+pub fn score_opener(word: &str, answers: &[String]) -> OpenerScore {
+    let word = word.to_uppercase();
+    let mut counts = [0usize; PATTERN_COUNT];
+    for answer in answers {
+        if word.chars().count() == WORD_LENGTH && answer.chars().count() == WORD_LENGTH {
+            counts[pattern_code(&word, answer) as usize] += 1;
+        }
+    }
+
+    let total = answers.len() as f64;
+    let (entropy, expected_remaining) = counts.iter()
+        .filter(|&&count| count > 0)
+        .fold((0.0, 0.0), |(entropy, expected), &count| {
+            let probability = count as f64 / total;
+            (entropy - probability * probability.log2(), expected + probability * count as f64)
+        });
+
+    OpenerScore { word, entropy, expected_remaining }
+}
+
+// 単語リストの全単語を評価し、エントロピーの高い順に並べる（同点は単語順）
+pub fn rank_openers(words: &[String]) -> Vec<OpenerScore> {
+    let mut scores: Vec<OpenerScore> = words.iter().map(|word| score_opener(word, words)).collect();
+    scores.sort_by(|a, b| b.entropy.total_cmp(&a.entropy).then_with(|| a.word.cmp(&b.word)));
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openers_are_scored_by_how_they_split_the_answers() {
+        let words: Vec<String> = ["CRANE", "TRACE", "CRATE", "FUZZY"].iter().map(|w| w.to_string()).collect();
+
+        // FUZZY は3語を区別できない（1語と3語の組に分かれる）
+        let fuzzy = score_opener("fuzzy", &words);
+        assert_eq!(fuzzy.word, "FUZZY");
+        assert_eq!(fuzzy.expected_remaining, (1.0 + 9.0) / 4.0);
+
+        // CRANE はすべての答えを別々の色の組み合わせに分ける（同点は単語順）
+        let ranked = rank_openers(&words);
+        assert_eq!(ranked[0].word, "CRANE");
+        assert_eq!(ranked.last().unwrap().word, "FUZZY");
+        assert_eq!(ranked[0].entropy, 2.0);
+        assert_eq!(ranked[0].expected_remaining, 1.0);
+        assert!(ranked.windows(2).all(|pair| pair[0].entropy >= pair[1].entropy));
+    }
+}
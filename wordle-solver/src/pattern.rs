@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordleGuess {
+    pub word: String,
+    pub results: Vec<LetterResult>, // 0: gray, 1: yellow, 2: green
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LetterResult {
+    Gray = 0,
+    Yellow = 1,
+    Green = 2,
+}
+
+impl LetterResult {
+    // 1文字の表記から変換（G: 緑、Y: 黄、B / X / -: 灰。数字の 2 / 1 / 0 も可）
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'G' | '2' => Some(LetterResult::Green),
+            'Y' | '1' => Some(LetterResult::Yellow),
+            'B' | 'X' | '-' | '0' => Some(LetterResult::Gray),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            LetterResult::Green => 'G',
+            LetterResult::Yellow => 'Y',
+            LetterResult::Gray => 'B',
+        }
+    }
+}
+
+impl WordleGuess {
+    // "CRANE" と "YGGBG" のような単語とパターンの組から推測を作る
+    pub fn parse(word: &str, pattern: &str) -> Option<Self> {
+        let results = pattern.chars().map(LetterResult::from_char).collect::<Option<Vec<_>>>()?;
+        if word.chars().count() != results.len() || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        Some(Self { word: word.to_uppercase(), results })
+    }
+}
+
+// 推測結果のパターンをシミュレート
+pub fn simulate_guess_pattern(guess: &str, answer: &str) -> Vec<u8> {
+    let guess_chars: Vec<char> = guess.chars().collect();
+    let answer_chars: Vec<char> = answer.chars().collect();
+    let mut pattern = vec![0u8; guess_chars.len()]; // 0: gray, 1: yellow, 2: green
+
+    // まず緑を判定
+    for i in 0..guess_chars.len() {
+        if i < answer_chars.len() && guess_chars[i] == answer_chars[i] {
+            pattern[i] = 2; // green
+        }
+    }
+
+    // 次に黄色を判定
+    let mut answer_counts: HashMap<char, usize> = HashMap::new();
+    for (i, &ch) in answer_chars.iter().enumerate() {
+        if i >= guess_chars.len() || guess_chars[i] != ch {
+            *answer_counts.entry(ch).or_insert(0) += 1;
+        }
+    }
+
+    for i in 0..guess_chars.len() {
+        if pattern[i] == 0 { // まだ判定されていない
+            let ch = guess_chars[i];
+            if let Some(count) = answer_counts.get_mut(&ch).filter(|count| **count > 0) {
+                pattern[i] = 1; // yellow
+                *count -= 1;
+            }
+        }
+    }
+
+    pattern
+}
+
+// 結果の色の組み合わせを1つの数にする（各文字 0: 灰, 1: 黄, 2: 緑 の3進数。先頭の文字が上の桁）
+// 大文字の英字だけなら Vec を作らずに計算する
+pub fn pattern_code(guess: &str, answer: &str) -> u32 {
+    let (guess_bytes, answer_bytes) = (guess.as_bytes(), answer.as_bytes());
+    if guess_bytes.len() != answer_bytes.len() || !guess_bytes.iter().chain(answer_bytes).all(u8::is_ascii_uppercase) {
+        return simulate_guess_pattern(guess, answer).iter().fold(0, |code, &p| code * 3 + p as u32);
+    }
+
+    // 緑以外の答えの文字を数える
+    let mut answer_counts = [0u8; 26];
+    for (&g, &a) in guess_bytes.iter().zip(answer_bytes) {
+        if g != a {
+            answer_counts[(a - b'A') as usize] += 1;
+        }
+    }
+
+    guess_bytes.iter().zip(answer_bytes).fold(0, |code, (&g, &a)| {
+        let count = &mut answer_counts[(g - b'A') as usize];
+        let p = if g == a {
+            2
+        } else if *count > 0 {
+            *count -= 1;
+            1
+        } else {
+            0
+        };
+        code * 3 + p
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_greens_and_yellows() {
+        assert_eq!(simulate_guess_pattern("CRANE", "TRACE"), vec![1, 2, 2, 0, 2]);
+    }
+
+    #[test]
+    fn duplicate_letters_are_only_counted_once() {
+        // SPEED の E は1つだけ ABIDE に含まれる
+        assert_eq!(simulate_guess_pattern("SPEED", "ABIDE"), vec![0, 0, 1, 0, 1]);
+        assert_eq!(simulate_guess_pattern("LLAMA", "HELLO"), vec![1, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn pattern_code_matches_the_simulated_pattern() {
+        let code = |pattern: Vec<u8>| pattern.iter().fold(0, |code, &p| code * 3 + p as u32);
+        for (guess, answer) in [("CRANE", "TRACE"), ("SPEED", "ABIDE"), ("LLAMA", "HELLO"), ("crane", "trace")] {
+            assert_eq!(pattern_code(guess, answer), code(simulate_guess_pattern(guess, answer)));
+        }
+    }
+
+    #[test]
+    fn parses_word_and_pattern() {
+        let guess = WordleGuess::parse("crane", "ygGb-").unwrap();
+        assert_eq!(guess.word, "CRANE");
+        assert_eq!(
+            guess.results,
+            vec![LetterResult::Yellow, LetterResult::Green, LetterResult::Green, LetterResult::Gray, LetterResult::Gray]
+        );
+
+        assert!(WordleGuess::parse("CRANE", "YGG").is_none());
+        assert!(WordleGuess::parse("CRANE", "YGGBZ").is_none());
+        assert!(WordleGuess::parse("CR4NE", "YGGBB").is_none());
+    }
+}
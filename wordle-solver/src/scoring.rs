@@ -0,0 +1,557 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::OnceLock;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::intern::{WordArena, WordId};
+use crate::pattern::{pattern_code, WordleGuess};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WordScore {
+    pub word: String,
+    pub score: f64,
+}
+
+// WordId で表したスコア（表示するときに WordArena で単語に戻す）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankedWord {
+    pub id: WordId,
+    pub score: f64,
+}
+
+// スコアを計算する単語がこれより少なければ、スレッドに分けずに計算する
+const PARALLEL_THRESHOLD: usize = 256;
+
+// スコアを計算した単語（大きいほど良い。スコアが同じなら tie が true の方、それも同じなら先に並んでいた方）
+#[derive(Debug, Clone, Copy)]
+struct Scored {
+    index: usize,
+    score: f64,
+    tie: bool,
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.total_cmp(&other.score)
+            .then(self.tie.cmp(&other.tie))
+            .then(other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Scored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored {}
+
+// 上位 limit 個だけを残す（ヒープの先頭は、残している中で一番悪いもの）
+fn push_bounded(heap: &mut BinaryHeap<Reverse<Scored>>, scored: Scored, limit: usize) {
+    if heap.len() < limit {
+        heap.push(Reverse(scored));
+    } else if heap.peek().is_some_and(|Reverse(worst)| scored > *worst) {
+        heap.pop();
+        heap.push(Reverse(scored));
+    }
+}
+
+// count 個の単語のスコアをCPUの数のスレッドに分けて計算し、上位 limit 個を良い順に返す（取り消されたら None）
+// 全体を並べ替えずに、スレッドごとに上位だけを残してから合わせる
+// cancelled にはそれまでにスコアを計算した単語数を渡す（スレッドをまたいで数える）
+fn top_scores(
+    count: usize,
+    limit: usize,
+    score: &(dyn Fn(usize) -> Scored + Sync),
+    cancelled: &(dyn Fn(usize) -> bool + Sync),
+) -> Option<Vec<Scored>> {
+    let scored = AtomicUsize::new(0);
+    let stopped = AtomicBool::new(false);
+    let run = |start: usize, end: usize| -> Option<BinaryHeap<Reverse<Scored>>> {
+        let mut heap = BinaryHeap::with_capacity(limit.min(end - start) + 1);
+        for i in start..end {
+            // ほかのスレッドが取り消しに気づいたら、こちらもすぐにやめる
+            if stopped.load(AtomicOrdering::Relaxed) || cancelled(scored.fetch_add(1, AtomicOrdering::Relaxed)) {
+                stopped.store(true, AtomicOrdering::Relaxed);
+                return None;
+            }
+            push_bounded(&mut heap, score(i), limit);
+        }
+        Some(heap)
+    };
+
+    let threads = if count < PARALLEL_THRESHOLD {
+        1
+    } else {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    };
+    let heaps: Vec<Option<BinaryHeap<Reverse<Scored>>>> = if threads == 1 {
+        vec![run(0, count)]
+    } else {
+        let chunk = count.div_ceil(threads);
+        let run = &run;
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..count).step_by(chunk)
+                .map(|start| scope.spawn(move || run(start, (start + chunk).min(count))))
+                .collect();
+            handles.into_iter().map(|handle| handle.join().expect("scoring thread panicked")).collect()
+        })
+    };
+
+    let mut top = BinaryHeap::with_capacity(limit.min(count) + 1);
+    for heap in heaps {
+        for Reverse(scored) in heap? {
+            push_bounded(&mut top, scored, limit);
+        }
+    }
+    let mut top: Vec<Scored> = top.into_iter().map(|Reverse(scored)| scored).collect();
+    top.sort_unstable_by(|a, b| b.cmp(a));
+    Some(top)
+}
+
+// 候補の単語をスコア順（降順）に並べる
+pub fn rank_words(possible_words: &[String], guess_count: usize) -> Vec<WordScore> {
+    let words: Vec<&str> = possible_words.iter().map(String::as_str).collect();
+    rank_indices(&words, guess_count, words.len(), &|_| false).unwrap_or_default().into_iter()
+        .map(|(i, score)| WordScore { word: possible_words[i].clone(), score })
+        .collect()
+}
+
+// 候補の WordId をスコア順（降順）に並べる（文字列は複製しない）
+pub fn rank_word_ids(arena: &WordArena, ids: &[WordId], guess_count: usize) -> Vec<RankedWord> {
+    rank_word_ids_until(arena, ids, guess_count, |_| false).unwrap_or_default()
+}
+
+// rank_word_ids と同じ順位を、単語ごとに cancelled を確かめながら計算する（取り消されたら None）
+// cancelled にはそれまでにスコアを計算した単語数を渡す（進み具合の表示に使える。スレッドに分けて計算するため、大きい数が先に届くこともある）
+pub fn rank_word_ids_until(arena: &WordArena, ids: &[WordId], guess_count: usize, cancelled: impl Fn(usize) -> bool + Sync) -> Option<Vec<RankedWord>> {
+    top_word_ids_until(arena, ids, guess_count, ids.len(), cancelled)
+}
+
+// rank_word_ids_until の上位 limit 個だけを返す（表示する分だけなら全体を並べ替えない）
+pub fn top_word_ids_until(
+    arena: &WordArena,
+    ids: &[WordId],
+    guess_count: usize,
+    limit: usize,
+    cancelled: impl Fn(usize) -> bool + Sync,
+) -> Option<Vec<RankedWord>> {
+    let words: Vec<&str> = ids.iter().map(|&id| arena.word(id)).collect();
+    let ranked = rank_indices(&words, guess_count, limit, &cancelled)?.into_iter()
+        .map(|(i, score)| RankedWord { id: ids[i], score })
+        .collect();
+    Some(ranked)
+}
+
+fn rank_indices(words: &[&str], guess_count: usize, limit: usize, cancelled: &(dyn Fn(usize) -> bool + Sync)) -> Option<Vec<(usize, f64)>> {
+    let score = |i: usize| Scored { index: i, score: word_score(words[i], words, guess_count), tie: false };
+    let top = top_scores(words.len(), limit, &score, cancelled)?;
+    Some(top.into_iter().map(|scored| (scored.index, scored.score)).collect())
+}
+
+// 候補をエントロピー（結果の色の分布から得られる情報量、ビット）の降順に並べる
+pub fn rank_by_entropy(arena: &WordArena, ids: &[WordId]) -> Vec<RankedWord> {
+    rank_by_entropy_until(arena, ids, |_| false).unwrap_or_default()
+}
+
+// rank_by_entropy と同じ順位を、単語ごとに cancelled を確かめながら計算する（取り消されたら None）
+pub fn rank_by_entropy_until(arena: &WordArena, ids: &[WordId], cancelled: impl Fn(usize) -> bool + Sync) -> Option<Vec<RankedWord>> {
+    let score = |i: usize| Scored { index: i, score: guess_entropy(arena, arena.word(ids[i]), ids), tie: false };
+    let top = top_scores(ids.len(), ids.len(), &score, &cancelled)?;
+    Some(top.into_iter().map(|scored| RankedWord { id: ids[scored.index], score: scored.score }).collect())
+}
+
+// 推測に使える単語（答えの候補でないものも含む）を、候補に対するエントロピーの降順に並べる
+// _IGHT で SIGHT・LIGHT・MIGHT・NIGHT が残っているときの SMELT のように、答えではないが候補を分けられる単語も選べる
+// 情報量が同じなら答えの候補を先にする（当たる可能性がある分だけ得をする）
+pub fn rank_probes_until(
+    arena: &WordArena,
+    guesses: &[WordId],
+    candidates: &[WordId],
+    cancelled: impl Fn(usize) -> bool + Sync,
+) -> Option<Vec<RankedWord>> {
+    top_probes_until(arena, guesses, candidates, guesses.len(), cancelled)
+}
+
+// rank_probes_until の上位 limit 個だけを返す
+pub fn top_probes_until(
+    arena: &WordArena,
+    guesses: &[WordId],
+    candidates: &[WordId],
+    limit: usize,
+    cancelled: impl Fn(usize) -> bool + Sync,
+) -> Option<Vec<RankedWord>> {
+    let mut is_candidate = vec![false; arena.len()];
+    for &id in candidates {
+        is_candidate[id as usize] = true;
+    }
+
+    let score = |i: usize| {
+        let id = guesses[i];
+        Scored { index: i, score: guess_entropy(arena, arena.word(id), candidates), tie: is_candidate[id as usize] }
+    };
+    let top = top_scores(guesses.len(), limit, &score, &cancelled)?;
+    Some(top.into_iter().map(|scored| RankedWord { id: guesses[scored.index], score: scored.score }).collect())
+}
+
+// 複数の盤面（Quordle など）を同じ推測で解くときに、盤面ごとの候補に対するエントロピーの合計で推測を並べ、上位 limit 個を返す
+// boards にはまだ解けていない盤面の候補を渡す。合計が同じなら、どれかの盤面の答えの候補を先にする
+pub fn top_probes_for_boards(arena: &WordArena, guesses: &[WordId], boards: &[&[WordId]], limit: usize) -> Vec<RankedWord> {
+    let mut is_candidate = vec![false; arena.len()];
+    for &id in boards.iter().flat_map(|board| board.iter()) {
+        is_candidate[id as usize] = true;
+    }
+
+    let score = |i: usize| {
+        let id = guesses[i];
+        let word = arena.word(id);
+        let score = boards.iter().map(|board| guess_entropy(arena, word, board)).sum();
+        Scored { index: i, score, tie: is_candidate[id as usize] }
+    };
+    let top = top_scores(guesses.len(), limit, &score, &|_| false).expect("scoring is never cancelled");
+    top.into_iter().map(|scored| RankedWord { id: guesses[scored.index], score: scored.score }).collect()
+}
+
+fn pattern_counts(arena: &WordArena, guess: &str, candidates: &[WordId]) -> HashMap<u32, usize> {
+    let mut groups: HashMap<u32, usize> = HashMap::new();
+    for &candidate in candidates {
+        *groups.entry(pattern_code(guess, arena.word(candidate))).or_insert(0) += 1;
+    }
+    groups
+}
+
+fn entropy_of(counts: &HashMap<u32, usize>, total: usize) -> f64 {
+    counts.values()
+        .map(|&count| {
+            let probability = count as f64 / total as f64;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+fn expected_remaining_of(counts: &HashMap<u32, usize>, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let squares: usize = counts.values().map(|&count| count * count).sum();
+    squares as f64 / total as f64
+}
+
+// 推測のエントロピー（ビット）
+pub fn guess_entropy(arena: &WordArena, guess: &str, candidates: &[WordId]) -> f64 {
+    entropy_of(&pattern_counts(arena, guess, candidates), candidates.len())
+}
+
+// 推測したあとに残る候補数の期待値
+pub fn expected_remaining(arena: &WordArena, guess: &str, candidates: &[WordId]) -> f64 {
+    expected_remaining_of(&pattern_counts(arena, guess, candidates), candidates.len())
+}
+
+// 推測のエントロピーと残る候補数の期待値（結果の色の分布を1回だけ数える）
+pub fn guess_metrics(arena: &WordArena, guess: &str, candidates: &[WordId]) -> (f64, f64) {
+    let counts = pattern_counts(arena, guess, candidates);
+    (entropy_of(&counts, candidates.len()), expected_remaining_of(&counts, candidates.len()))
+}
+
+// 提案した単語を選んだ理由（「なぜこの単語？」ボタンで表示する）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuessExplanation {
+    pub word: String,
+    // これまでの推測で使っていない文字（重複は除く）
+    pub untested_letters: Vec<char>,
+    // 推測する前の候補数と、推測したあとに残る候補数の期待値
+    pub candidates: usize,
+    pub expected_remaining: f64,
+    pub entropy: f64,
+    // 候補に含まれる（この単語自体が答えになりうる）
+    pub can_be_answer: bool,
+    // 文字の位置ごとに、残りの候補が緑・黄・灰のどれになるか
+    #[serde(default)]
+    pub splits: Vec<LetterSplit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LetterSplit {
+    pub letter: char,
+    pub green: usize,
+    pub yellow: usize,
+    pub gray: usize,
+}
+
+// 色の組み合わせごとの候補数から、位置ごとの色の内訳を数える
+fn letter_splits(guess: &str, counts: &HashMap<u32, usize>) -> Vec<LetterSplit> {
+    let letters: Vec<char> = guess.chars().collect();
+    let mut splits: Vec<LetterSplit> = letters.iter()
+        .map(|&letter| LetterSplit { letter, green: 0, yellow: 0, gray: 0 })
+        .collect();
+    for (&code, &count) in counts {
+        let mut code = code;
+        // 3進数の下の桁が最後の文字
+        for split in splits.iter_mut().rev() {
+            match code % 3 {
+                2 => split.green += count,
+                1 => split.yellow += count,
+                _ => split.gray += count,
+            }
+            code /= 3;
+        }
+    }
+    splits
+}
+
+// 結果の色の分布を1回だけ数え、エントロピーと残る候補数の期待値の両方に使う
+pub fn explain_guess(arena: &WordArena, guess: &str, candidates: &[WordId], guesses: &[WordleGuess]) -> GuessExplanation {
+    let counts = pattern_counts(arena, guess, candidates);
+    let mut untested_letters: Vec<char> = Vec::new();
+    for letter in guess.chars() {
+        if !untested_letters.contains(&letter) && !guesses.iter().any(|g| g.word.contains(letter)) {
+            untested_letters.push(letter);
+        }
+    }
+
+    GuessExplanation {
+        word: guess.to_string(),
+        untested_letters,
+        candidates: candidates.len(),
+        expected_remaining: expected_remaining_of(&counts, candidates.len()),
+        entropy: entropy_of(&counts, candidates.len()),
+        can_be_answer: candidates.iter().any(|&id| arena.word(id) == guess),
+        splits: letter_splits(guess, &counts),
+    }
+}
+
+// 同梱の単語リスト（よく使われる単語）に含まれるか
+pub fn is_common_word(word: &str) -> bool {
+    static COMMON: OnceLock<HashSet<String>> = OnceLock::new();
+    COMMON.get_or_init(|| crate::bundled_words().into_iter().collect())
+        .contains(&word.to_uppercase())
+}
+
+// 単語のスコアを計算
+pub fn calculate_word_score(word: &str, possible_words: &[String], guess_count: usize) -> f64 {
+    let words: Vec<&str> = possible_words.iter().map(String::as_str).collect();
+    word_score(word, &words, guess_count)
+}
+
+fn word_score(word: &str, possible_words: &[&str], guess_count: usize) -> f64 {
+    let mut score = 0.0;
+
+    // 1. 文字の多様性スコア
+    let unique_chars: HashSet<char> = word.chars().collect();
+    score += unique_chars.len() as f64 * 2.0;
+
+    // 2. 頻出文字スコア
+    let common_letters = "EAIOTRNSLCUDPMHGBFYWKVXZJQ";
+    for ch in word.chars() {
+        if let Some(pos) = common_letters.find(ch) {
+            score += (26 - pos) as f64 * 0.1;
+        }
+    }
+
+    // 3. 母音と子音のバランス
+    let vowels = "AEIOU";
+    let vowel_count = word.chars().filter(|&c| vowels.contains(c)).count();
+    let consonant_count = word.len() - vowel_count;
+    // 理想的なバランスに近いほど高スコア
+    let balance_score = 5.0 - (vowel_count as f64 - 2.0).abs() - (consonant_count as f64 - 3.0).abs();
+    score += balance_score.max(0.0);
+
+    // 4. 既知の制約からの情報量
+    let info_gain = information_gain(word, possible_words);
+    score += info_gain;
+
+    // 5. ゲームの進行に応じた重み調整
+    if guess_count == 0 {
+        // 最初の推測：多様性と一般的な文字を重視
+        score += unique_chars.len() as f64 * 3.0;
+    } else if guess_count >= 3 {
+        // 後半：絞り込みを重視、情報ゲインを強化
+        score += info_gain * 2.0;
+
+        // 可能性の高い単語により高いスコアを与える
+        if possible_words.len() <= 50 {
+            score += 5.0;
+        }
+    }
+
+    score
+}
+
+// 情報ゲインを計算
+pub fn calculate_information_gain(word: &str, possible_words: &[String]) -> f64 {
+    let words: Vec<&str> = possible_words.iter().map(String::as_str).collect();
+    information_gain(word, &words)
+}
+
+fn information_gain(word: &str, possible_words: &[&str]) -> f64 {
+    if possible_words.len() <= 1 {
+        return 0.0;
+    }
+
+    let mut pattern_groups: HashMap<u32, usize> = HashMap::new();
+
+    for possible_word in possible_words {
+        *pattern_groups.entry(pattern_code(word, possible_word)).or_insert(0) += 1;
+    }
+
+    // エントロピーベースの情報ゲイン計算
+    let total = possible_words.len() as f64;
+    let mut entropy = 0.0;
+
+    for &count in pattern_groups.values() {
+        if count > 0 {
+            let probability = count as f64 / total;
+            entropy -= probability * probability.log2();
+        }
+    }
+
+    // 最大エントロピーで正規化
+    let max_entropy = (pattern_groups.len() as f64).log2();
+    if max_entropy > 0.0 {
+        entropy / max_entropy * 10.0 // スケーリング
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn single_candidate_has_no_information_gain() {
+        assert_eq!(calculate_information_gain("CRANE", &words(&["CRANE"])), 0.0);
+    }
+
+    #[test]
+    fn ranking_is_sorted_by_score() {
+        let candidates = words(&["CRANE", "SLATE", "FUZZY", "TRACE", "MOIST"]);
+        let ranked = rank_words(&candidates, 0);
+
+        assert_eq!(ranked.len(), candidates.len());
+        assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert_eq!(ranked.last().unwrap().word, "FUZZY");
+
+        // WordId で並べても同じ順番になる
+        let arena = WordArena::new(candidates.clone());
+        let ids: Vec<WordId> = arena.ids().collect();
+        let by_id: Vec<&str> = rank_word_ids(&arena, &ids, 0).iter().map(|r| arena.word(r.id)).collect();
+        assert_eq!(by_id, ranked.iter().map(|r| r.word.as_str()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn top_words_match_the_full_ranking_across_threads() {
+        // スレッドに分けて計算する数の単語
+        let letters = b"ABCDEFGHIJKLMNOPQRST";
+        let list: Vec<String> = (0..PARALLEL_THRESHOLD + 50)
+            .map(|i| [i % 20, i / 20 % 20, (i * 7) % 20, (i * 3 + 1) % 20, (i + 5) % 20].iter().map(|&j| letters[j] as char).collect())
+            .collect();
+        let arena = WordArena::new(list);
+        let ids: Vec<WordId> = arena.ids().collect();
+
+        // スコアの合計は計算の順番で末尾の桁が変わることがあるため、単語の順番で比べる
+        let order = |ranked: &[RankedWord]| ranked.iter().map(|r| r.id).collect::<Vec<_>>();
+        let full = rank_word_ids(&arena, &ids, 1);
+        assert_eq!(full.len(), ids.len());
+        assert!(full.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert_eq!(order(&top_word_ids_until(&arena, &ids, 1, 10, |_| false).unwrap()), order(&full[..10]));
+        let probes = rank_probes_until(&arena, &ids, &ids[..40], |_| false).unwrap();
+        assert_eq!(order(&top_probes_until(&arena, &ids, &ids[..40], 5, |_| false).unwrap()), order(&probes[..5]));
+
+        // 途中で取り消すと結果を返さない
+        assert_eq!(top_word_ids_until(&arena, &ids, 1, 10, |scored| scored >= 100), None);
+    }
+
+    #[test]
+    fn entropy_ranking_prefers_the_most_splitting_guess() {
+        let arena = WordArena::new(["CRANE", "TRACE", "CRATE", "GRACE", "BRACE"]);
+        let ids: Vec<WordId> = arena.ids().collect();
+        let ranked = rank_by_entropy(&arena, &ids);
+
+        assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        // 残る候補数の期待値はエントロピーが大きいほど小さい
+        let best = arena.word(ranked[0].id);
+        let worst = arena.word(ranked[4].id);
+        assert!(expected_remaining(&arena, best, &ids) <= expected_remaining(&arena, worst, &ids));
+        assert!(is_common_word("crane"));
+
+        let guesses = [WordleGuess { word: "CRATE".to_string(), results: vec![] }];
+        let explanation = explain_guess(&arena, "BRINE", &ids, &guesses);
+        assert_eq!(explanation.untested_letters, ['B', 'I', 'N']);
+        assert!(!explanation.can_be_answer);
+        assert_eq!(explanation.expected_remaining, expected_remaining(&arena, "BRINE", &ids));
+        assert_eq!(guess_metrics(&arena, "BRINE", &ids), (explanation.entropy, explanation.expected_remaining));
+        // R はすべての候補で2文字目にある。E はすべての候補で最後にある
+        assert_eq!(explanation.splits[1], LetterSplit { letter: 'R', green: 5, yellow: 0, gray: 0 });
+        assert_eq!(explanation.splits[4].green, 5);
+        assert_eq!(explanation.splits[0], LetterSplit { letter: 'B', green: 1, yellow: 0, gray: 4 });
+        assert!(!is_common_word("XYLYL"));
+    }
+
+    #[test]
+    fn cancelled_ranking_stops_without_a_result() {
+        let arena = WordArena::new(["CRANE", "TRACE", "CRATE", "GRACE", "BRACE"]);
+        let ids: Vec<WordId> = arena.ids().collect();
+
+        assert_eq!(rank_word_ids_until(&arena, &ids, 0, |_| true), None);
+        assert_eq!(rank_by_entropy_until(&arena, &ids, |scored| scored == 2), None);
+
+        // 取り消されなければ通常の順位と同じ（計算済みの単語数は0から順に渡される）
+        let checks = std::sync::Mutex::new(Vec::new());
+        let ranked = rank_by_entropy_until(&arena, &ids, |scored| {
+            checks.lock().unwrap().push(scored);
+            false
+        });
+        assert_eq!(ranked, Some(rank_by_entropy(&arena, &ids)));
+        assert_eq!(checks.into_inner().unwrap(), (0..ids.len()).collect::<Vec<_>>());
+        assert_eq!(rank_word_ids_until(&arena, &ids, 0, |_| false), Some(rank_word_ids(&arena, &ids, 0)));
+    }
+
+    #[test]
+    fn probes_outside_the_candidates_can_split_them_better() {
+        let arena = WordArena::new(["SIGHT", "LIGHT", "MIGHT", "NIGHT", "SMELT", "FIGHT"]);
+        let all: Vec<WordId> = arena.ids().collect();
+        let candidates: Vec<WordId> = all[..4].to_vec();
+
+        // 候補のどれを推測しても残りの3語は区別できないが、SMELT なら4語すべてを分けられる
+        let ranked = rank_probes_until(&arena, &all, &candidates, |_| false).unwrap();
+        assert_eq!(arena.word(ranked[0].id), "SMELT");
+        assert_eq!(ranked[0].score, 2.0);
+
+        // 候補が1つなら、どの単語も情報量は0なので答えの候補を先にする
+        let ranked = rank_probes_until(&arena, &all, &candidates[1..2], |_| false).unwrap();
+        assert_eq!(arena.word(ranked[0].id), "LIGHT");
+        assert_eq!(rank_probes_until(&arena, &all, &candidates, |_| true), None);
+    }
+
+    #[test]
+    fn probes_for_boards_add_up_the_information_of_every_board() {
+        let arena = WordArena::new(["SIGHT", "LIGHT", "MIGHT", "NIGHT", "SMELT", "CRANE", "TRACE"]);
+        let all: Vec<WordId> = arena.ids().collect();
+        let (first, second) = (&all[..4], &all[5..7]);
+
+        // SMELT は1つ目の盤面を分け、TRACE は2つ目の盤面を分けたうえで答えの候補でもある
+        let ranked = top_probes_for_boards(&arena, &all, &[first, second], 3);
+        assert_eq!(arena.word(ranked[0].id), "SMELT");
+        assert_eq!(ranked[0].score, 2.0 + guess_entropy(&arena, "SMELT", second));
+        assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        // 解けた盤面を除くと、残りの盤面だけで選ぶ
+        let ranked = top_probes_for_boards(&arena, &all, &[second], 1);
+        assert_eq!(ranked.len(), 1);
+        assert!(["CRANE", "TRACE"].contains(&arena.word(ranked[0].id)));
+    }
+}
@@ -0,0 +1,105 @@
+// ボットが出題するゲームの正解
+// Debug / Display では伏せ字になり、ログやエラー報告から答えが漏れない
+// メモリ上でも平文では持たず、比較はハッシュで行う
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::sync::OnceLock;
+
+// 比較用のハッシュの鍵（プロセスごとに変わる）
+fn digest_state() -> &'static RandomState {
+    static STATE: OnceLock<RandomState> = OnceLock::new();
+    STATE.get_or_init(RandomState::new)
+}
+
+fn digest(word: &str) -> u64 {
+    digest_state().hash_one(word.to_uppercase())
+}
+
+#[derive(Clone)]
+pub struct SecretWord {
+    // 正解ごとの鍵でマスクした文字列
+    masked: Vec<u8>,
+    key: u64,
+    digest: u64,
+}
+
+impl SecretWord {
+    pub fn new(word: &str) -> Self {
+        let word = word.to_uppercase();
+        let key = RandomState::new().hash_one(&word);
+        Self {
+            masked: mask(word.as_bytes(), key),
+            key,
+            digest: digest(&word),
+        }
+    }
+
+    // 推測が正解と一致するか（大文字・小文字は区別しない）
+    pub fn matches(&self, guess: &str) -> bool {
+        digest(guess) == self.digest
+    }
+
+    pub fn len(&self) -> usize {
+        self.masked.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.masked.is_empty()
+    }
+
+    // 色の判定などで平文が必要な場合のみ使う（結果をログに出さないこと）
+    pub fn reveal(&self) -> String {
+        String::from_utf8(mask(&self.masked, self.key)).unwrap_or_default()
+    }
+}
+
+fn mask(bytes: &[u8], key: u64) -> Vec<u8> {
+    let key = key.to_le_bytes();
+    bytes.iter().zip(key.iter().cycle()).map(|(b, k)| b ^ k).collect()
+}
+
+impl PartialEq for SecretWord {
+    fn eq(&self, other: &Self) -> bool {
+        self.digest == other.digest
+    }
+}
+
+impl Eq for SecretWord {}
+
+impl Hash for SecretWord {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.digest.hash(state);
+    }
+}
+
+impl fmt::Debug for SecretWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretWord(<redacted>)")
+    }
+}
+
+impl fmt::Display for SecretWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&"*".repeat(self.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_is_redacted_but_comparable() {
+        let secret = SecretWord::new("crane");
+
+        assert_eq!(format!("{:?}", secret), "SecretWord(<redacted>)");
+        assert_eq!(secret.to_string(), "*****");
+        assert!(!secret.masked.windows(5).any(|w| w == b"CRANE"));
+
+        assert!(secret.matches("CRANE"));
+        assert!(!secret.matches("SLATE"));
+        assert_eq!(secret, SecretWord::new("Crane"));
+        assert_eq!(secret.reveal(), "CRANE");
+    }
+}
@@ -0,0 +1,187 @@
+// 推測の選び方（戦略）の比較
+// 答えを決めて最後まで解かせ、かかった推測の回数と時間を戦略ごとに集計する
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::intern::{WordArena, WordId};
+use crate::pattern::pattern_code;
+use crate::scoring::rank_word_ids;
+
+// 公式のWordleで使える推測の回数
+pub const MAX_GUESSES: usize = 6;
+
+// 候補の中から次の推測を選ぶ方法（独自のヒューリスティクスを実装すれば play で同じように解かせられる）
+pub trait SuggestionStrategy {
+    fn name(&self) -> &str;
+    fn choose(&self, arena: &WordArena, candidates: &[WordId], guess_count: usize) -> Option<WordId>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // 結果の色の分布のエントロピーが最大の単語
+    Entropy,
+    // 最悪の場合に残る候補数が最小の単語
+    Minimax,
+    // ボットが提案に使っているスコア（文字の多様性・頻度・情報ゲイン）
+    Heuristic,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [Strategy::Entropy, Strategy::Minimax, Strategy::Heuristic];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::Entropy => "entropy",
+            Strategy::Minimax => "minimax",
+            Strategy::Heuristic => "heuristic",
+        }
+    }
+
+    // 候補の中から次の推測を選ぶ（同点は先の単語）
+    pub fn choose(&self, arena: &WordArena, candidates: &[WordId], guess_count: usize) -> Option<WordId> {
+        if candidates.len() <= 2 {
+            return candidates.first().copied();
+        }
+        match self {
+            Strategy::Entropy => best_by(candidates, |guess| {
+                let total = candidates.len() as f64;
+                pattern_groups(arena, guess, candidates).values()
+                    .map(|&count| {
+                        let probability = count as f64 / total;
+                        -probability * probability.log2()
+                    })
+                    .sum()
+            }),
+            Strategy::Minimax => best_by(candidates, |guess| {
+                -(pattern_groups(arena, guess, candidates).values().copied().max().unwrap_or(0) as f64)
+            }),
+            Strategy::Heuristic => rank_word_ids(arena, candidates, guess_count).first().map(|ranked| ranked.id),
+        }
+    }
+}
+
+impl SuggestionStrategy for Strategy {
+    fn name(&self) -> &str {
+        Strategy::name(self)
+    }
+
+    fn choose(&self, arena: &WordArena, candidates: &[WordId], guess_count: usize) -> Option<WordId> {
+        Strategy::choose(self, arena, candidates, guess_count)
+    }
+}
+
+fn pattern_groups(arena: &WordArena, guess: WordId, candidates: &[WordId]) -> HashMap<u32, usize> {
+    let mut groups = HashMap::new();
+    for &candidate in candidates {
+        *groups.entry(pattern_code(arena.word(guess), arena.word(candidate))).or_insert(0) += 1;
+    }
+    groups
+}
+
+fn best_by(candidates: &[WordId], score: impl Fn(WordId) -> f64) -> Option<WordId> {
+    candidates.iter()
+        .map(|&id| (id, score(id)))
+        .fold(None, |best: Option<(WordId, f64)>, (id, s)| match best {
+            Some((_, best_score)) if best_score >= s => best,
+            _ => Some((id, s)),
+        })
+        .map(|(id, _)| id)
+}
+
+// 答えが分かるまでの推測の回数（最初の推測を決めていればそれを使う）
+// 推測は常に候補から選ぶため、候補は毎回減って必ず解ける
+pub fn play<S: SuggestionStrategy + ?Sized>(strategy: &S, arena: &WordArena, answer: WordId, opener: Option<WordId>) -> usize {
+    let mut candidates: Vec<WordId> = arena.ids().collect();
+    let mut guesses = 0;
+    loop {
+        let guess = match opener.filter(|_| guesses == 0) {
+            Some(opener) => opener,
+            None => strategy.choose(arena, &candidates, guesses).unwrap_or(answer),
+        };
+        guesses += 1;
+        if guess == answer {
+            return guesses;
+        }
+        let pattern = pattern_code(arena.word(guess), arena.word(answer));
+        candidates.retain(|&c| c != guess && pattern_code(arena.word(guess), arena.word(c)) == pattern);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyReport {
+    pub strategy: Strategy,
+    pub opener: Option<String>,
+    pub games: usize,
+    pub average_guesses: f64,
+    pub worst_guesses: usize,
+    // MAX_GUESSES 回までに解けなかった答えの数
+    pub failures: usize,
+    pub elapsed: Duration,
+}
+
+// 答えの候補ごとに解かせて集計する（最初の推測はどの答えでも同じなので1回だけ計算する）
+pub fn evaluate(strategy: Strategy, arena: &WordArena, answers: &[WordId]) -> StrategyReport {
+    let started = Instant::now();
+    let all: Vec<WordId> = arena.ids().collect();
+    let opener = strategy.choose(arena, &all, 0);
+
+    let counts: Vec<usize> = answers.iter().map(|&answer| play(&strategy, arena, answer, opener)).collect();
+    StrategyReport {
+        strategy,
+        opener: opener.map(|id| arena.word(id).to_string()),
+        games: counts.len(),
+        average_guesses: counts.iter().sum::<usize>() as f64 / counts.len().max(1) as f64,
+        worst_guesses: counts.iter().copied().max().unwrap_or(0),
+        failures: counts.iter().filter(|&&n| n > MAX_GUESSES).count(),
+        elapsed: started.elapsed(),
+    }
+}
+
+// 単語リストから答えを等間隔に選ぶ（同じリストなら毎回同じ答えで比べられる）
+pub fn sample_answers(arena: &WordArena, count: usize) -> Vec<WordId> {
+    let step = (arena.len() / count.max(1)).max(1);
+    arena.ids().step_by(step).take(count).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_strategy_solves_every_sampled_answer() {
+        let arena = WordArena::new(crate::bundled_words());
+        let answers = sample_answers(&arena, 20);
+        assert_eq!(answers.len(), 20);
+
+        for strategy in Strategy::ALL {
+            let report = evaluate(strategy, &arena, &answers);
+            assert_eq!(report.games, 20, "{:?}", strategy);
+            assert!(report.average_guesses >= 1.0 && report.worst_guesses <= 10, "{:?}", report);
+            assert!(report.opener.is_some());
+        }
+    }
+
+    // 候補の先頭を選ぶだけの戦略
+    struct FirstCandidate;
+
+    impl SuggestionStrategy for FirstCandidate {
+        fn name(&self) -> &str {
+            "first"
+        }
+
+        fn choose(&self, _: &WordArena, candidates: &[WordId], _: usize) -> Option<WordId> {
+            candidates.first().copied()
+        }
+    }
+
+    #[test]
+    fn custom_strategies_can_be_played_like_the_built_in_ones() {
+        let arena = WordArena::new(crate::bundled_words());
+        let strategies: [&dyn SuggestionStrategy; 2] = [&FirstCandidate, &Strategy::Entropy];
+        for answer in sample_answers(&arena, 5) {
+            for strategy in strategies {
+                assert!(play(strategy, &arena, answer, None) >= 1, "{}", strategy.name());
+            }
+        }
+    }
+}